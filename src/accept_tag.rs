@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Dfa;
+
+/// Additional information attached to an accept state, like the kind of token a lexer recognizes when it ends up in that state.
+/// When accept states with different tags are merged, the tag with the higher priority wins.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AcceptTag {
+    pub kind: String,
+    pub priority: i32,
+}
+
+impl AcceptTag {
+    pub fn new(kind: &str, priority: i32) -> AcceptTag {
+        AcceptTag { kind: kind.to_string(), priority }
+    }
+
+    /// Returns the tag with the higher priority. If both have the same priority, the kind that comes first alphabetically wins,
+    /// so that the result does not depend on the order of the arguments.
+    pub fn merge(first: &AcceptTag, second: &AcceptTag) -> AcceptTag {
+        if (second.priority, &first.kind) > (first.priority, &second.kind) {
+            return second.clone();
+        }
+        first.clone()
+    }
+}
+
+impl Dfa {
+    /// Tags the state, which also makes it an accept state.
+    pub fn set_accept_tag(&mut self, state: &str, tag: AcceptTag) {
+        self.accept_states.insert(state.to_string());
+        self.accept_tags.insert(state.to_string(), tag);
+    }
+
+    pub fn get_accept_tag(&self, state: &str) -> Option<&AcceptTag> {
+        self.accept_tags.get(state)
+    }
+
+    /// Works like `check` but reports the tag of the accept state the DFA ends up in.
+    /// Returns None if the input is rejected or the accept state has no tag.
+    pub fn check_tagged(&self, input: &str) -> Option<&AcceptTag> {
+        let (is_accepted, traversed_states) = self.check(input);
+        if !is_accepted {
+            return None;
+        }
+        self.accept_tags.get(traversed_states.last().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod accept_tag_tests {
+    use crate::{AcceptTag, Dfa};
+
+    /// Creates a DFA classifying the words "if", "i" and "f" with two equivalent states for "f".
+    fn create_example_dfa() -> Dfa {
+        let mut dfa = Dfa::from_table(
+            &["q0", "i", "if", "f"],
+            &['i', 'f'],
+            &[&[Some(1), Some(3)], &[None, Some(2)], &[None, None], &[None, None]],
+            0,
+            &[],
+        ).unwrap();
+        dfa.set_accept_tag("i", AcceptTag::new("identifier", 0));
+        dfa.set_accept_tag("if", AcceptTag::new("keyword", 1));
+        dfa.set_accept_tag("f", AcceptTag::new("identifier", 0));
+        dfa
+    }
+
+    #[test]
+    fn test_check_tagged() {
+        let dfa = create_example_dfa();
+        assert_eq!(dfa.check_tagged("if"), Some(&AcceptTag::new("keyword", 1)));
+        assert_eq!(dfa.check_tagged("f"), Some(&AcceptTag::new("identifier", 0)));
+        assert_eq!(dfa.check_tagged("fi"), None);
+    }
+
+    #[test]
+    fn test_minimize_keeps_tags() {
+        let mut dfa = create_example_dfa();
+        dfa.minimize();
+        assert_eq!(dfa.check_tagged("if"), Some(&AcceptTag::new("keyword", 1)), "states with different tags must not be merged");
+        assert_eq!(dfa.check_tagged("i"), Some(&AcceptTag::new("identifier", 0)));
+        assert_eq!(dfa.check_tagged("f"), Some(&AcceptTag::new("identifier", 0)));
+    }
+
+    #[test]
+    fn test_merge() {
+        let keyword = AcceptTag::new("keyword", 1);
+        let identifier = AcceptTag::new("identifier", 0);
+        assert_eq!(AcceptTag::merge(&keyword, &identifier), keyword);
+        assert_eq!(AcceptTag::merge(&identifier, &keyword), keyword);
+        assert_eq!(AcceptTag::merge(&AcceptTag::new("b", 0), &AcceptTag::new("a", 0)), AcceptTag::new("a", 0));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Nfa;
+
+impl Nfa {
+    /// Checks whether the NFA accepts every word over the symbols used by its transitions, using the
+    /// antichain algorithm of De Wulf, Doyen, Henzinger and Raskin.
+    /// Like the subset construction, it explores the sets of states the NFA can be in, searching for one without accept state.
+    /// However, a set only needs to be explored if no subset of it has been explored yet, since a set of states
+    /// accepts at least the words its subsets accept. This keeps the explored sets small in practice.
+    pub fn is_universal(&self) -> bool {
+        let alphabet = self.get_input_symbols();
+        let start: BTreeSet<&str> = vec![self.start_state.as_str()].into_iter().collect();
+        let mut antichain: Vec<BTreeSet<&str>> = vec![start.clone()];
+        let mut sets_to_visit = VecDeque::new();
+        sets_to_visit.push_back(start);
+        while let Some(states) = sets_to_visit.pop_front() {
+            if !states.iter().any(|state| self.accept_states.contains(*state)) {
+                return false;
+            }
+            for input in &alphabet {
+                let next_states = self.get_successors(&states, *input);
+                if antichain.iter().any(|explored_states| explored_states.is_subset(&next_states)) {
+                    continue;
+                }
+                antichain.retain(|explored_states| !next_states.is_subset(explored_states));
+                antichain.push(next_states.clone());
+                sets_to_visit.push_back(next_states);
+            }
+        }
+        true
+    }
+
+    /// Checks whether every word accepted by this NFA is also accepted by the other one, using the antichain algorithm.
+    /// Pairs of a state of this NFA and a set of states of the other NFA are explored, searching for an accepting state paired with
+    /// a set without accept state. A pair only needs to be explored if no pair with the same state and a subset of its set has been.
+    pub fn is_subset_of(&self, other: &Nfa) -> bool {
+        let alphabet = self.get_input_symbols();
+        let start: (&str, BTreeSet<&str>) = (self.start_state.as_str(), vec![other.start_state.as_str()].into_iter().collect());
+        let mut antichain = vec![start.clone()];
+        let mut pairs_to_visit = VecDeque::new();
+        pairs_to_visit.push_back(start);
+        while let Some((state, other_states)) = pairs_to_visit.pop_front() {
+            if self.accept_states.contains(state) && !other_states.iter().any(|other_state| other.accept_states.contains(*other_state)) {
+                return false;
+            }
+            for input in &alphabet {
+                let next_other_states = other.get_successors(&other_states, *input);
+                let next_states = self.transitions.iter()
+                    .filter(|transition| transition.state == state && transition.input == *input)
+                    .map(|transition| transition.next_state.as_str());
+                for next_state in next_states {
+                    let is_subsumed = antichain.iter()
+                        .any(|(explored_state, explored_states)| *explored_state == next_state && explored_states.is_subset(&next_other_states));
+                    if is_subsumed {
+                        continue;
+                    }
+                    antichain.retain(|(explored_state, explored_states)| *explored_state != next_state || !next_other_states.is_subset(explored_states));
+                    antichain.push((next_state, next_other_states.clone()));
+                    pairs_to_visit.push_back((next_state, next_other_states.clone()));
+                }
+            }
+        }
+        true
+    }
+
+    fn get_successors<'a>(&'a self, states: &BTreeSet<&str>, input: char) -> BTreeSet<&'a str> {
+        self.transitions.iter()
+            .filter(|transition| transition.input == input && states.contains(transition.state.as_str()))
+            .map(|transition| transition.next_state.as_str())
+            .collect()
+    }
+
+    fn get_input_symbols(&self) -> Vec<char> {
+        self.transitions.iter().map(|transition| transition.input).collect::<HashSet<char>>().into_iter().sorted().collect()
+    }
+}
+
+#[cfg(test)]
+mod antichain_tests {
+    use crate::Nfa;
+
+    /// Accepts all words over 'a' and 'b' whose second to last symbol is 'a'.
+    fn create_example_nfa() -> Nfa {
+        let mut nfa = Nfa::new("second to last is a", "q0");
+        nfa.add_transition("q0", 'a', "q0");
+        nfa.add_transition("q0", 'b', "q0");
+        nfa.add_transition("q0", 'a', "q1");
+        nfa.add_transition("q1", 'a', "q2");
+        nfa.add_transition("q1", 'b', "q2");
+        nfa.add_accept_state("q2");
+        nfa
+    }
+
+    #[test]
+    fn test_is_universal() {
+        assert!(!create_example_nfa().is_universal());
+        let mut universal_nfa = Nfa::new("anything", "q0");
+        universal_nfa.add_transition("q0", 'a', "q0");
+        universal_nfa.add_transition("q0", 'b', "q1");
+        universal_nfa.add_transition("q1", 'a', "q0");
+        universal_nfa.add_transition("q1", 'b', "q1");
+        universal_nfa.add_transition("q1", 'b', "q0");
+        universal_nfa.add_accept_state("q0");
+        universal_nfa.add_accept_state("q1");
+        assert!(universal_nfa.is_universal());
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        // Accepts the words ending with "ab", which all have 'a' as second to last symbol.
+        let mut ending_with_ab = Nfa::new("ending with ab", "p0");
+        ending_with_ab.add_transition("p0", 'a', "p0");
+        ending_with_ab.add_transition("p0", 'b', "p0");
+        ending_with_ab.add_transition("p0", 'a', "p1");
+        ending_with_ab.add_transition("p1", 'b', "p2");
+        ending_with_ab.add_accept_state("p2");
+        assert!(ending_with_ab.is_subset_of(&create_example_nfa()));
+        assert!(!create_example_nfa().is_subset_of(&ending_with_ab));
+    }
+}
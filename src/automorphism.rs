@@ -0,0 +1,95 @@
+use std::collections::{BTreeSet, HashMap};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Computes all [automorphisms](https://en.wikipedia.org/wiki/Automorphism) of the DFA, i.e. all renamings of the states onto themselves
+    /// that keep the start state, the accept states and the transitions. Every automorphism maps each state to its image.
+    ///
+    /// Since the start state is kept, a state reached by a word is mapped to the state reached by the same word. Hence, every automorphism
+    /// is the identity on the reachable states, and only unreachable parts of the DFA, like those of a catalog of automata
+    /// sharing their states, can be symmetric. The automorphisms are searched by backtracking, where each state may only be mapped
+    /// to a state with the same acceptance and the same symbols on its outgoing transitions.
+    pub fn get_automorphisms(&self) -> Vec<HashMap<String, String>> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let mut images = HashMap::new();
+        images.insert(dfa.start_state.clone(), dfa.start_state.clone());
+        let mut automorphisms = Vec::new();
+        if dfa.is_consistent_image(&dfa.start_state, &images) {
+            dfa.find_automorphisms(&states, &mut images, &mut automorphisms);
+        }
+        automorphisms
+    }
+
+    /// Returns the number of automorphisms (see `get_automorphisms`), which is the order of the automorphism group.
+    pub fn get_automorphism_group_order(&self) -> usize {
+        self.get_automorphisms().len()
+    }
+
+    fn find_automorphisms(&self, states: &[String], images: &mut HashMap<String, String>, automorphisms: &mut Vec<HashMap<String, String>>) {
+        let state = match states.iter().find(|state| !images.contains_key(*state)) {
+            Some(state) => state,
+            None => {
+                automorphisms.push(images.clone());
+                return;
+            }
+        };
+        let used_images: BTreeSet<String> = images.values().cloned().collect();
+        for image in states.iter().filter(|image| !used_images.contains(*image)) {
+            let is_accept_state = self.accept_states.contains(state);
+            if self.accept_states.contains(image) != is_accept_state || self.get_outgoing_symbols(image) != self.get_outgoing_symbols(state) {
+                continue;
+            }
+            images.insert(state.clone(), image.clone());
+            if self.is_consistent_image(state, images) {
+                self.find_automorphisms(states, images, automorphisms);
+            }
+            images.remove(state);
+        }
+    }
+
+    /// Checks that every transition between the state and already mapped states has a counterpart between their images.
+    fn is_consistent_image(&self, state: &str, images: &HashMap<String, String>) -> bool {
+        self.transitions.iter()
+            .filter(|transition| transition.state == state || transition.next_state == state)
+            .filter(|transition| images.contains_key(&transition.state) && images.contains_key(&transition.next_state))
+            .all(|transition| {
+                self.get_transition(&images[&transition.state], &transition.input)
+                    .map_or(false, |image_transition| image_transition.next_state == images[&transition.next_state])
+            })
+    }
+
+    fn get_outgoing_symbols(&self, state: &str) -> BTreeSet<char> {
+        self.transitions.iter().filter(|transition| transition.state == state).map(|transition| transition.input).collect()
+    }
+}
+
+#[cfg(test)]
+mod automorphism_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_get_automorphisms() {
+        // Only q0 and q1 are reachable. The unreachable states u0 and u1 form a cycle that can be rotated, just like u2 and u3.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "u0", "u1", "u2", "u3"],
+            &['a'],
+            &[&[Some(1)], &[Some(0)], &[Some(3)], &[Some(2)], &[Some(5)], &[Some(4)]],
+            0,
+            &[1],
+        ).unwrap();
+        let automorphisms = dfa.get_automorphisms();
+        // The two cycles can be rotated and swapped, which results in 8 symmetries.
+        assert_eq!(automorphisms.len(), 8);
+        for automorphism in &automorphisms {
+            assert_eq!(automorphism["q0"], "q0");
+            assert_eq!(automorphism["q1"], "q1");
+        }
+        let reachable_only = Dfa::from_table(&["q0", "q1"], &['a'], &[&[Some(1)], &[Some(0)]], 0, &[1]).unwrap();
+        assert_eq!(reachable_only.get_automorphism_group_order(), 1);
+    }
+}
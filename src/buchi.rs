@@ -0,0 +1,502 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{fresh_state_name, Dfa, ParityAutomaton, ParityGame, Player, Transition};
+use crate::model_checker_export::{render_never_claim, render_smv};
+
+/// # [Büchi automaton](https://en.wikipedia.org/wiki/B%C3%BCchi_automaton)
+/// A deterministic automaton that reads infinite words. A word is accepted if the run visits accept states infinitely often.
+/// Like in a DFA, a missing transition leads into an implicit error state, so the word is rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuchiAutomaton {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<Transition>,
+}
+
+/// Where an ω-regular language is placed in the safety-progress classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OmegaLanguageClass {
+    /// Every rejected word has a finite bad prefix, after which no continuation is accepted.
+    Safety,
+    /// Every accepted word has a finite good prefix, after which every continuation is accepted.
+    CoSafety,
+    /// Both a safety and a co-safety language, so every word is decided after a finite prefix.
+    SafetyAndCoSafety,
+    Neither,
+}
+
+impl fmt::Display for OmegaLanguageClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OmegaLanguageClass::Safety => write!(f, "safety"),
+            OmegaLanguageClass::CoSafety => write!(f, "co-safety"),
+            OmegaLanguageClass::SafetyAndCoSafety => write!(f, "safety and co-safety"),
+            OmegaLanguageClass::Neither => write!(f, "neither safety nor co-safety"),
+        }
+    }
+}
+
+/// An ultimately periodic infinite word, which is the prefix followed by infinitely many repetitions of the cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lasso {
+    pub prefix: String,
+    pub cycle: String,
+}
+
+impl fmt::Display for Lasso {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})^ω", self.prefix, self.cycle)
+    }
+}
+
+/// A state of the product of a system DFA and a Büchi automaton, i.e. the pair of the states both are in.
+type ProductState<'a> = (&'a str, &'a str);
+
+impl BuchiAutomaton {
+    /// Creates a Büchi automaton without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> BuchiAutomaton {
+        BuchiAutomaton {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.transitions.push(Transition { state: state.to_string(), input, next_state: next_state.to_string() });
+    }
+
+    pub fn get_transition(&self, state: &str, input: &char) -> Option<&Transition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    /// Checks whether the ultimately periodic word, which is the prefix followed by infinitely many repetitions of the cycle, is accepted.
+    /// Since the automaton is deterministic, the states at the beginning of the repetitions eventually repeat, too.
+    /// The word is accepted if an accept state is visited in between. An empty cycle does not form an infinite word and is rejected.
+    pub fn check_lasso(&self, prefix: &str, cycle: &str) -> bool {
+        if cycle.is_empty() {
+            return false;
+        }
+        let mut state = match self.run(&self.start_state, prefix) {
+            Some((state, _)) => state,
+            None => return false,
+        };
+        let mut repetition_starts: Vec<String> = Vec::new();
+        let mut visits_accept_state = Vec::new();
+        while !repetition_starts.contains(&state) {
+            repetition_starts.push(state.clone());
+            match self.run(&state, cycle) {
+                Some((next_state, is_accept_state_visited)) => {
+                    visits_accept_state.push(is_accept_state_visited);
+                    state = next_state;
+                }
+                None => return false,
+            }
+        }
+        let loop_start = repetition_starts.iter().position(|start| *start == state).unwrap();
+        visits_accept_state[loop_start..].iter().any(|is_visited| *is_visited)
+    }
+
+    /// Reads the word starting in the state. Returns the reached state and whether an accept state was entered on the way,
+    /// or None if a transition is missing.
+    fn run(&self, state: &str, word: &str) -> Option<(String, bool)> {
+        let mut state = state.to_string();
+        let mut is_accept_state_visited = false;
+        for char in word.chars() {
+            state = self.get_transition(&state, &char)?.next_state.clone();
+            is_accept_state_visited |= self.accept_states.contains(&state);
+        }
+        Some((state, is_accept_state_visited))
+    }
+
+    /// Classifies the language as safety, co-safety, both or neither (see `OmegaLanguageClass`).
+    ///
+    /// The language is a safety language if no run avoids accept states forever while it could still be accepted,
+    /// i.e. if there is no reachable cycle of rejecting live states. Live states are those from which an accepting run exists.
+    /// The language is a co-safety language if no accepting run avoids the universal states forever, from which every run is accepting.
+    pub fn classify(&self) -> OmegaLanguageClass {
+        match (self.is_safety(), self.is_co_safety()) {
+            (true, true) => OmegaLanguageClass::SafetyAndCoSafety,
+            (true, false) => OmegaLanguageClass::Safety,
+            (false, true) => OmegaLanguageClass::CoSafety,
+            (false, false) => OmegaLanguageClass::Neither,
+        }
+    }
+
+    pub fn is_safety(&self) -> bool {
+        let (automaton, _) = self.complete();
+        let reachable_states = automaton.get_reachable_states();
+        let live_states = automaton.get_live_states();
+        let rejecting_live_states = reachable_states.iter()
+            .filter(|state| live_states.contains(*state) && !automaton.accept_states.contains(*state))
+            .cloned()
+            .collect();
+        automaton.get_states_reaching_cycle_within(&rejecting_live_states).is_empty()
+    }
+
+    pub fn is_co_safety(&self) -> bool {
+        let (automaton, _) = self.complete();
+        let reachable_states = automaton.get_reachable_states();
+        let universal_states = automaton.get_universal_states();
+        let non_universal_states: HashSet<String> = reachable_states.difference(&universal_states).cloned().collect();
+        !non_universal_states.iter()
+            .filter(|state| automaton.accept_states.contains(*state))
+            .any(|state| automaton.is_on_cycle_within(state, &non_universal_states))
+    }
+
+    /// Returns a complete DFA accepting exactly the bad prefixes of the language, i.e. the finite words after which
+    /// no continuation is accepted. Such a DFA can serve as a runtime monitor. Returns None if the language is no safety
+    /// language, since then some rejected words have no bad prefix, so the monitor would miss violations.
+    pub fn get_bad_prefix_dfa(&self) -> Option<Dfa> {
+        if !self.is_safety() {
+            return None;
+        }
+        let (automaton, alphabet) = self.complete();
+        let live_states = automaton.get_live_states();
+        let mut dfa = Dfa {
+            alphabet: Some(alphabet),
+            ..Dfa::with_parts(
+                format!("bad prefixes of {}", self.name),
+                automaton.start_state.clone(),
+                automaton.get_reachable_states().difference(&live_states).cloned().collect(),
+                automaton.transitions,
+            )
+        };
+        dfa.remove_inaccessible_states();
+        Some(dfa)
+    }
+
+    /// Turns the automaton into an equivalent parity automaton, in which accept states have priority 2 and all others priority 1.
+    pub fn to_parity_automaton(&self) -> ParityAutomaton {
+        let mut automaton = ParityAutomaton::new(&self.name, &self.start_state);
+        for state in self.get_all_states() {
+            automaton.set_priority(&state, if self.accept_states.contains(&state) { 2 } else { 1 });
+        }
+        for transition in &self.transitions {
+            automaton.add_transition(&transition.state, transition.input, &transition.next_state);
+        }
+        automaton
+    }
+
+    /// Turns the automaton into a parity game in which Even chooses the next state and wins if accept states are visited
+    /// infinitely often (see `to_parity_automaton`).
+    pub fn to_parity_game(&self) -> ParityGame {
+        self.to_parity_automaton().to_parity_game()
+    }
+
+    /// Checks whether the automaton accepts no infinite word at all, which is the case if Even loses the game from the start state.
+    pub fn is_empty(&self) -> bool {
+        self.to_parity_game().solve()[&self.start_state] == Player::Odd
+    }
+
+    /// Model checks the system against this automaton, which must accept exactly the violations of the property, i.e. the negated property.
+    /// The system is a DFA whose infinite runs are its behaviors, and its accept states express fairness: only runs that visit them
+    /// infinitely often are considered, so a system in which every state accepts has no fairness constraints.
+    ///
+    /// Builds the product of both and searches it for a reachable cycle that visits a fair state of the system and an accept state
+    /// of this automaton. Such a cycle is a fair behavior of the system violating the property, and it is returned as a lasso.
+    /// Returns None if the system satisfies the property.
+    pub fn find_counterexample(&self, system: &Dfa) -> Option<Lasso> {
+        let mut system = system.clone();
+        system.materialize_missing_transitions();
+        let inputs = system.get_alphabet().into_iter().sorted().collect_vec();
+        let successors = |(system_state, state): ProductState<'_>| -> Vec<(char, ProductState<'_>)> {
+            inputs.iter()
+                .filter_map(|input| {
+                    let system_transition = system.get_transition(system_state, input)?;
+                    let transition = self.get_transition(state, input)?;
+                    Some((*input, (system_transition.next_state.as_str(), transition.next_state.as_str())))
+                })
+                .collect()
+        };
+        let start = (system.start_state.as_str(), self.start_state.as_str());
+        // The reachable product states in breath first order, with the product states leading into each of them.
+        let mut reachable_states = vec![start];
+        let mut predecessors: HashMap<ProductState, Vec<ProductState>> = HashMap::new();
+        let mut visited_states = HashSet::new();
+        visited_states.insert(start);
+        let mut index = 0;
+        while index < reachable_states.len() {
+            let product_state = reachable_states[index];
+            for (_, next_state) in successors(product_state) {
+                predecessors.entry(next_state).or_default().push(product_state);
+                if visited_states.insert(next_state) {
+                    reachable_states.push(next_state);
+                }
+            }
+            index += 1;
+        }
+        for accept_state in reachable_states.iter().filter(|(_, state)| self.accept_states.contains(*state)) {
+            // The strongly connected component of the accept state consists of the states it reaches and is reached from.
+            // It is empty unless the accept state lies on a cycle.
+            let reached_states = get_reached_product_states(*accept_state, |product_state| {
+                successors(product_state).into_iter().map(|(_, next_state)| next_state).collect()
+            });
+            let reaching_states = get_reached_product_states(*accept_state, |product_state| {
+                predecessors.get(&product_state).cloned().unwrap_or_default()
+            });
+            let component: HashSet<ProductState> = reached_states.intersection(&reaching_states).cloned().collect();
+            let fair_state = match component.iter().filter(|(system_state, _)| system.accept_states.contains(*system_state)).sorted().next() {
+                Some(fair_state) => *fair_state,
+                None => continue,
+            };
+            let prefix = match *accept_state == start {
+                true => String::new(),
+                false => find_product_path(start, |product_state| product_state == *accept_state, &visited_states, successors)?,
+            };
+            let cycle = match fair_state == *accept_state {
+                true => find_product_path(fair_state, |product_state| product_state == fair_state, &component, successors)?,
+                false => {
+                    let to_fair_state = find_product_path(*accept_state, |product_state| product_state == fair_state, &component, successors)?;
+                    let back = find_product_path(fair_state, |product_state| product_state == *accept_state, &component, successors)?;
+                    format!("{}{}", to_fair_state, back)
+                }
+            };
+            return Some(Lasso { prefix, cycle });
+        }
+        None
+    }
+
+    /// Renders the automaton as a NuSMV or nuXmv model like `Dfa::to_smv`, with the accept states as a fairness constraint (`JUSTICE`),
+    /// so that the model checker only considers runs visiting them infinitely often.
+    pub fn to_smv(&self) -> String {
+        render_smv(&self.name, &self.start_state, &self.accept_states, &self.transitions, true)
+    }
+
+    /// Renders the automaton as a Promela never claim for the model checker Spin, observing the global variable `input`.
+    /// Accept states get labels starting with "accept", so the automaton should accept the violations of the property to check.
+    pub fn to_promela_never_claim(&self) -> String {
+        render_never_claim(&self.name, &self.start_state, &self.accept_states, &self.transitions, true)
+    }
+
+    /// Returns an equivalent automaton in which every state has a transition for every symbol, together with the symbols.
+    /// Missing transitions lead into a new rejecting sink state.
+    fn complete(&self) -> (BuchiAutomaton, HashSet<char>) {
+        let alphabet: HashSet<char> = self.transitions.iter().map(|transition| transition.input).collect();
+        let mut states = self.get_all_states();
+        let sink_state = fresh_state_name("sink", &states);
+        states.insert(sink_state.clone());
+        let mut automaton = self.clone();
+        for state in states.iter().sorted() {
+            for input in alphabet.iter().sorted() {
+                if self.get_transition(state, input).is_none() {
+                    automaton.add_transition(state, *input, &sink_state);
+                }
+            }
+        }
+        (automaton, alphabet)
+    }
+
+    fn get_all_states(&self) -> HashSet<String> {
+        let mut states: HashSet<String> = self.transitions.iter()
+            .flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()])
+            .collect();
+        states.insert(self.start_state.clone());
+        states
+    }
+
+    fn get_successors<'a>(&'a self, state: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.transitions.iter().filter(move |transition| transition.state == state).map(|transition| &transition.next_state)
+    }
+
+    /// Returns the states that can be reached from the start state, using the breath first algorithm.
+    fn get_reachable_states(&self) -> HashSet<String> {
+        let mut visited_states = HashSet::new();
+        let mut states_to_visit = VecDeque::new();
+        visited_states.insert(self.start_state.clone());
+        states_to_visit.push_back(self.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            for next_state in self.get_successors(&state) {
+                if visited_states.insert(next_state.clone()) {
+                    states_to_visit.push_back(next_state.clone());
+                }
+            }
+        }
+        visited_states
+    }
+
+    /// Returns the states that can reach one of the given states, using the breath first algorithm on the reversed transitions.
+    fn get_states_reaching(&self, states: &HashSet<String>) -> HashSet<String> {
+        let mut visited_states = states.clone();
+        let mut states_to_visit: VecDeque<String> = states.iter().cloned().collect();
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in self.transitions.iter().filter(|transition| transition.next_state == state) {
+                if visited_states.insert(transition.state.clone()) {
+                    states_to_visit.push_back(transition.state.clone());
+                }
+            }
+        }
+        visited_states
+    }
+
+    /// Checks whether the state can return to itself without leaving the given states.
+    fn is_on_cycle_within(&self, state: &str, states: &HashSet<String>) -> bool {
+        let mut visited_states = HashSet::new();
+        let mut states_to_visit: VecDeque<&String> = self.get_successors(state).filter(|next| states.contains(*next)).collect();
+        while let Some(current_state) = states_to_visit.pop_front() {
+            if current_state == state {
+                return true;
+            }
+            if visited_states.insert(current_state) {
+                states_to_visit.extend(self.get_successors(current_state).filter(|next| states.contains(*next)));
+            }
+        }
+        false
+    }
+
+    /// Returns the given states from which a cycle within the given states can be reached without leaving them.
+    /// States without successors among the given states are removed until none are left, and the remaining ones are returned.
+    fn get_states_reaching_cycle_within(&self, states: &HashSet<String>) -> HashSet<String> {
+        let mut remaining_states = states.clone();
+        loop {
+            let dead_ends = remaining_states.iter()
+                .filter(|state| !self.get_successors(state).any(|next| remaining_states.contains(next)))
+                .cloned()
+                .collect_vec();
+            if dead_ends.is_empty() {
+                return remaining_states;
+            }
+            for state in dead_ends {
+                remaining_states.remove(&state);
+            }
+        }
+    }
+
+    /// Returns the states from which an accepting run exists, i.e. those that can reach an accept state lying on a cycle.
+    fn get_live_states(&self) -> HashSet<String> {
+        let all_states = self.get_all_states();
+        let recurring_accept_states = self.accept_states.iter()
+            .filter(|state| self.is_on_cycle_within(state, &all_states))
+            .cloned()
+            .collect();
+        self.get_states_reaching(&recurring_accept_states)
+    }
+
+    /// Returns the states from which every run is accepting, i.e. those that cannot reach a cycle of rejecting states.
+    /// The automaton must be complete.
+    fn get_universal_states(&self) -> HashSet<String> {
+        let rejecting_states = self.get_all_states().difference(&self.accept_states).cloned().collect();
+        let non_universal_states = self.get_states_reaching(&self.get_states_reaching_cycle_within(&rejecting_states));
+        self.get_all_states().difference(&non_universal_states).cloned().collect()
+    }
+}
+
+/// Searches a non-empty path from the product state to a product state fulfilling the goal, without leaving the given product states,
+/// using the breath first algorithm. Returns the word of the shortest such path, or None if there is none.
+fn find_product_path<'a>(
+    start: ProductState<'a>,
+    is_goal: impl Fn(ProductState<'a>) -> bool,
+    within: &HashSet<ProductState<'a>>,
+    successors: impl Fn(ProductState<'a>) -> Vec<(char, ProductState<'a>)>,
+) -> Option<String> {
+    let mut visited_states = HashSet::new();
+    let mut states_to_visit = VecDeque::new();
+    states_to_visit.push_back((String::new(), start));
+    while let Some((word, product_state)) = states_to_visit.pop_front() {
+        for (input, next_state) in successors(product_state) {
+            let next_word = format!("{}{}", word, input);
+            if within.contains(&next_state) && is_goal(next_state) {
+                return Some(next_word);
+            }
+            if within.contains(&next_state) && visited_states.insert(next_state) {
+                states_to_visit.push_back((next_word, next_state));
+            }
+        }
+    }
+    None
+}
+
+/// Returns the product states reached from the product state by a non-empty path, or by a non-empty reversed path if the map
+/// of predecessors is used as the successors.
+fn get_reached_product_states<'a>(start: ProductState<'a>, successors: impl Fn(ProductState<'a>) -> Vec<ProductState<'a>>) -> HashSet<ProductState<'a>> {
+    let mut reached_states = HashSet::new();
+    let mut states_to_visit = vec![start];
+    while let Some(product_state) = states_to_visit.pop() {
+        for next_state in successors(product_state) {
+            if reached_states.insert(next_state) {
+                states_to_visit.push(next_state);
+            }
+        }
+    }
+    reached_states
+}
+
+#[cfg(test)]
+mod buchi_tests {
+    use crate::{BuchiAutomaton, Dfa, Lasso, OmegaLanguageClass};
+
+    #[test]
+    fn test_check_lasso() {
+        // Accepts the words over 'a' and 'b' with infinitely many 'b's.
+        let mut automaton = BuchiAutomaton::new("infinitely often b", "q0");
+        automaton.add_transition("q0", 'a', "q0");
+        automaton.add_transition("q0", 'b', "q1");
+        automaton.add_transition("q1", 'a', "q0");
+        automaton.add_transition("q1", 'b', "q1");
+        automaton.add_accept_state("q1");
+        assert!(automaton.check_lasso("aaa", "ab"));
+        assert!(!automaton.check_lasso("bbb", "a"));
+        assert_eq!(automaton.classify(), OmegaLanguageClass::Neither);
+        assert!(automaton.to_smv().ends_with("DEFINE\n    accepting := state in {s1};\nJUSTICE\n    accepting;\n"));
+        assert!(automaton.to_promela_never_claim().contains("accept_s1: /* q1 */\n    if\n    :: (input == 'a') -> goto T_s0\n"));
+    }
+
+    #[test]
+    fn test_classify() {
+        // Never reads 'b'.
+        let mut never_b = BuchiAutomaton::new("never b", "q0");
+        never_b.add_transition("q0", 'a', "q0");
+        never_b.add_transition("q0", 'b', "q1");
+        never_b.add_transition("q1", 'a', "q1");
+        never_b.add_accept_state("q0");
+        assert_eq!(never_b.classify(), OmegaLanguageClass::Safety);
+        let monitor = never_b.get_bad_prefix_dfa().unwrap();
+        assert!(monitor.check("aab").0);
+        assert!(monitor.check("aaba").0);
+        assert!(!monitor.check("aaa").0);
+        // Eventually reads 'b'.
+        let mut eventually_b = BuchiAutomaton::new("eventually b", "q0");
+        eventually_b.add_transition("q0", 'a', "q0");
+        eventually_b.add_transition("q0", 'b', "q1");
+        eventually_b.add_transition("q1", 'a', "q1");
+        eventually_b.add_transition("q1", 'b', "q1");
+        eventually_b.add_accept_state("q1");
+        assert_eq!(eventually_b.classify(), OmegaLanguageClass::CoSafety);
+        assert!(eventually_b.get_bad_prefix_dfa().is_none());
+    }
+
+    #[test]
+    fn test_find_counterexample() {
+        // A system that requests with 'r' and is then granted with 'g', waits with 'w' or requests again. It is fair if it becomes idle infinitely often.
+        let system = Dfa::from_table(&["idle", "waiting"], &['g', 'r', 'w'], &[&[None, Some(1), None], &[Some(0), Some(1), Some(1)]], 0, &[0]).unwrap();
+        // The violations of "no second request before a grant".
+        let mut double_request = BuchiAutomaton::new("double request", "q0");
+        double_request.add_transition("q0", 'g', "q0");
+        double_request.add_transition("q0", 'r', "q1");
+        double_request.add_transition("q0", 'w', "q0");
+        double_request.add_transition("q1", 'g', "q0");
+        double_request.add_transition("q1", 'r', "violated");
+        double_request.add_transition("q1", 'w', "q1");
+        for input in &['g', 'r', 'w'] {
+            double_request.add_transition("violated", *input, "violated");
+        }
+        double_request.add_accept_state("violated");
+        let lasso = double_request.find_counterexample(&system).unwrap();
+        assert_eq!(lasso, Lasso { prefix: "rr".to_string(), cycle: "gr".to_string() });
+        assert_eq!(lasso.to_string(), "rr(gr)^ω");
+        assert!(double_request.check_lasso(&lasso.prefix, &lasso.cycle));
+        // Without requests while waiting, the property holds.
+        let system = Dfa::from_table(&["idle", "waiting"], &['g', 'r', 'w'], &[&[None, Some(1), None], &[Some(0), None, Some(1)]], 0, &[0]).unwrap();
+        assert_eq!(double_request.find_counterexample(&system), None);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::{Dfa, MissingTransitionPolicy, TaggedDfa};
+
+/// The spans of a match within a text and of the groups within that match. Spans are byte ranges into the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures {
+    overall: Range<usize>,
+    groups: Vec<Option<Range<usize>>>,
+}
+
+impl Captures {
+    /// Returns the span of the whole match.
+    pub fn get_match(&self) -> Range<usize> {
+        self.overall.clone()
+    }
+
+    /// Returns the span of a group, or None if the group did not participate in the match.
+    pub fn get_group(&self, group: usize) -> Option<Range<usize>> {
+        self.groups.get(group).cloned().flatten()
+    }
+
+    pub fn get_group_count(&self) -> usize {
+        self.groups.len()
+    }
+}
+
+impl Dfa {
+    /// Searches the text for a substring accepted by the DFA, using leftmost-longest semantics: Of all matches starting at
+    /// the leftmost possible position, the longest one is returned. As a DFA has no groups, only the overall match is reported.
+    pub fn captures(&self, text: &str) -> Option<Captures> {
+        let alphabet = self.get_alphabet();
+        let starts = text.char_indices().map(|(start, _)| start).chain(std::iter::once(text.len()));
+        for start in starts {
+            if let Some(end) = self.find_longest_match_end(text, start, &alphabet) {
+                return Some(Captures { overall: start..end, groups: Vec::new() });
+            }
+        }
+        None
+    }
+
+    /// Runs the DFA on the text from the start position and returns the end of the longest accepted substring, if any.
+    pub(crate) fn find_longest_match_end(&self, text: &str, start: usize, alphabet: &HashSet<char>) -> Option<usize> {
+        let mut current_state = &self.start_state;
+        let mut longest_match_end = if self.accept_states.contains(current_state) { Some(start) } else { None };
+        for (offset, char) in text[start..].char_indices() {
+            match self.get_transition(current_state, &char) {
+                Some(transition) => current_state = &transition.next_state,
+                None if self.missing_transitions == MissingTransitionPolicy::SelfLoop && alphabet.contains(&char) => {}
+                None => break,
+            }
+            if self.accept_states.contains(current_state) {
+                longest_match_end = Some(start + offset + char.len_utf8());
+            }
+        }
+        longest_match_end
+    }
+}
+
+impl TaggedDfa {
+    /// Searches the text for a substring accepted by the tagged DFA, using leftmost-longest semantics like `Dfa::captures`.
+    /// The group `i` is extracted from the registers `2 * i` and `2 * i + 1` of the longest match.
+    pub fn captures(&self, text: &str) -> Option<Captures> {
+        let register_count = self.get_register_count();
+        let starts = text.char_indices().map(|(start, _)| start).chain(std::iter::once(text.len()));
+        for start in starts {
+            if let Some((end, registers)) = self.find_longest_match(text, start, register_count) {
+                let groups = registers.chunks(2)
+                    .map(|bounds| match bounds {
+                        [Some(group_start), Some(group_end)] => Some(*group_start..*group_end),
+                        _ => None,
+                    })
+                    .collect();
+                return Some(Captures { overall: start..end, groups });
+            }
+        }
+        None
+    }
+
+    /// Runs the tagged DFA on the text from the start position once, like `TaggedDfa::check`. Whenever an accept state is reached,
+    /// the registers are completed by its final tags and kept, so the last snapshot belongs to the longest match.
+    /// Returns the end of that match and its registers, which hold positions within the whole text.
+    fn find_longest_match(&self, text: &str, start: usize, register_count: usize) -> Option<(usize, Vec<Option<usize>>)> {
+        let mut registers = vec![None; register_count];
+        let mut current_state = &self.start_state;
+        let snapshot = |state: &str, registers: &[Option<usize>], end: usize| {
+            self.accept_states.get(state).map(|final_tags| {
+                let mut registers = registers.to_vec();
+                for register in final_tags {
+                    registers[*register] = Some(end);
+                }
+                (end, registers)
+            })
+        };
+        let mut longest_match = snapshot(current_state, &registers, start);
+        for (offset, char) in text[start..].char_indices() {
+            let transition = match self.get_transition(current_state, &char) {
+                Some(transition) => transition,
+                None => break,
+            };
+            for register in &transition.tags {
+                registers[*register] = Some(start + offset);
+            }
+            current_state = &transition.next_state;
+            if let Some(snapshot) = snapshot(current_state, &registers, start + offset + char.len_utf8()) {
+                longest_match = Some(snapshot);
+            }
+        }
+        longest_match
+    }
+}
+
+#[cfg(test)]
+mod captures_tests {
+    use crate::{Dfa, TaggedDfa};
+
+    #[test]
+    fn test_dfa_captures() {
+        // Accepts non-empty sequences of digits.
+        let dfa = Dfa::from_table(&["q0", "q1"], &['0', '1'], &[&[Some(1), Some(1)], &[Some(1), Some(1)]], 0, &[1]).unwrap();
+        let captures = dfa.captures("ab 0110 10").unwrap();
+        assert_eq!(captures.get_match(), 3..7, "should find the leftmost match and extend it as far as possible");
+        assert_eq!(captures.get_group_count(), 0);
+        assert!(dfa.captures("abc").is_none());
+    }
+
+    #[test]
+    fn test_tagged_dfa_captures() {
+        // Matches "key=value" pairs over lowercase letters. Group 0 is the key, group 1 the value.
+        let mut dfa = TaggedDfa::new("key value pairs", "start");
+        for letter in 'a'..='z' {
+            dfa.add_transition("start", letter, &[0], "key");
+            dfa.add_transition("key", letter, &[], "key");
+            dfa.add_transition("equals", letter, &[2], "value");
+            dfa.add_transition("value", letter, &[], "value");
+        }
+        dfa.add_transition("key", '=', &[1], "equals");
+        dfa.add_accept_state("value", &[3]);
+        let text = "set ä=b x=yz;";
+        let captures = dfa.captures(text).unwrap();
+        assert_eq!(&text[captures.get_match()], "x=yz");
+        assert_eq!(&text[captures.get_group(0).unwrap()], "x");
+        assert_eq!(&text[captures.get_group(1).unwrap()], "yz");
+        assert_eq!(captures.get_group(2), None);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use itertools::Itertools;
+
+/// A symbol of a context-free grammar.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
+pub enum GrammarSymbol {
+    Terminal(char),
+    Nonterminal(String),
+}
+
+impl fmt::Display for GrammarSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarSymbol::Terminal(terminal) => write!(f, "{}", terminal),
+            GrammarSymbol::Nonterminal(nonterminal) => write!(f, "{}", nonterminal),
+        }
+    }
+}
+
+/// Splits the body of a production into symbols. Nonterminals are uppercase ASCII letters, optionally followed by digits,
+/// underscores and apostrophes, like "S", "A1" or "B'". Every other character is a terminal.
+pub(crate) fn parse_symbols(body: &str) -> Vec<GrammarSymbol> {
+    let mut symbols = Vec::new();
+    let mut chars = body.chars().peekable();
+    while let Some(char) = chars.next() {
+        if !char.is_ascii_uppercase() {
+            symbols.push(GrammarSymbol::Terminal(char));
+            continue;
+        }
+        let mut nonterminal = char.to_string();
+        while let Some(next_char) = chars.peek().filter(|next_char| next_char.is_ascii_digit() || **next_char == '_' || **next_char == '\'') {
+            nonterminal.push(*next_char);
+            chars.next();
+        }
+        symbols.push(GrammarSymbol::Nonterminal(nonterminal));
+    }
+    symbols
+}
+
+/// # [Context-free grammar](https://en.wikipedia.org/wiki/Context-free_grammar)
+/// Productions replace a nonterminal by a sequence of terminals and nonterminals, which may be empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    pub(crate) start_symbol: String,
+    pub(crate) productions: Vec<(String, Vec<GrammarSymbol>)>,
+}
+
+/// A parse tree, also called derivation tree. Leaves are terminals or nonterminals that were replaced by the empty word,
+/// and the children of a nonterminal are the body of the production applied to it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseTree {
+    pub symbol: GrammarSymbol,
+    pub children: Vec<ParseTree>,
+}
+
+impl Cfg {
+    /// Creates a grammar without productions, which generates nothing.
+    pub fn new(start_symbol: &str) -> Cfg {
+        Cfg { start_symbol: start_symbol.to_string(), productions: Vec::new() }
+    }
+
+    /// Adds a production, e.g. `add_production("S", "aSb")` for S → aSb. Nonterminals in the body are uppercase ASCII letters,
+    /// optionally followed by digits, underscores and apostrophes. All other characters are terminals. An empty body stands for ε.
+    pub fn add_production(&mut self, head: &str, body: &str) {
+        self.productions.push((head.to_string(), parse_symbols(body)));
+    }
+
+    /// Returns up to `limit` distinct parse trees of the word, e.g. to show that a grammar is ambiguous.
+    /// Each parse tree corresponds to exactly one leftmost derivation (see `ParseTree::get_leftmost_derivation`).
+    /// Trees are searched exhaustively by splitting the word among the symbols of the productions. Derivations in which a nonterminal
+    /// derives the same part of the word again, like with the productions S → S or S → SS and S → ε, are left out, as there would be infinitely many.
+    pub fn get_parse_trees(&self, word: &str, limit: usize) -> Vec<ParseTree> {
+        let word = word.chars().collect_vec();
+        let start_symbol = GrammarSymbol::Nonterminal(self.start_symbol.clone());
+        self.get_symbol_parse_trees(&start_symbol, &word, 0, word.len(), limit, &mut HashSet::new())
+    }
+
+    fn get_symbol_parse_trees(
+        &self,
+        symbol: &GrammarSymbol,
+        word: &[char],
+        start: usize,
+        end: usize,
+        limit: usize,
+        expanding: &mut HashSet<(String, usize, usize)>,
+    ) -> Vec<ParseTree> {
+        let nonterminal = match symbol {
+            GrammarSymbol::Terminal(terminal) => {
+                return match end == start + 1 && word[start] == *terminal {
+                    true => vec![ParseTree { symbol: symbol.clone(), children: Vec::new() }],
+                    false => Vec::new(),
+                };
+            }
+            GrammarSymbol::Nonterminal(nonterminal) => nonterminal,
+        };
+        let key = (nonterminal.clone(), start, end);
+        if !expanding.insert(key.clone()) {
+            return Vec::new();
+        }
+        let mut trees = Vec::new();
+        for (_, body) in self.productions.iter().filter(|(head, _)| head == nonterminal) {
+            for children in self.get_sequence_parse_trees(body, word, start, end, limit - trees.len(), expanding) {
+                trees.push(ParseTree { symbol: symbol.clone(), children });
+            }
+            if trees.len() >= limit {
+                break;
+            }
+        }
+        expanding.remove(&key);
+        trees
+    }
+
+    /// Returns up to `limit` ways to derive the part of the word between start and end from the sequence of symbols.
+    fn get_sequence_parse_trees(
+        &self,
+        symbols: &[GrammarSymbol],
+        word: &[char],
+        start: usize,
+        end: usize,
+        limit: usize,
+        expanding: &mut HashSet<(String, usize, usize)>,
+    ) -> Vec<Vec<ParseTree>> {
+        let (first_symbol, other_symbols) = match symbols.split_first() {
+            Some(split) => split,
+            None if start == end => return vec![Vec::new()],
+            None => return Vec::new(),
+        };
+        let mut sequences = Vec::new();
+        for middle in start..=end {
+            let first_trees = self.get_symbol_parse_trees(first_symbol, word, start, middle, limit, expanding);
+            if first_trees.is_empty() {
+                continue;
+            }
+            let other_sequences = self.get_sequence_parse_trees(other_symbols, word, middle, end, limit, expanding);
+            for (first_tree, other_trees) in first_trees.iter().cartesian_product(other_sequences.iter()) {
+                sequences.push(vec![first_tree.clone()].into_iter().chain(other_trees.iter().cloned()).collect());
+                if sequences.len() >= limit {
+                    return sequences;
+                }
+            }
+        }
+        sequences
+    }
+}
+
+impl ParseTree {
+    /// Returns the word derived by the tree, which consists of the terminals at its leaves.
+    pub fn get_yield(&self) -> String {
+        match &self.symbol {
+            GrammarSymbol::Terminal(terminal) => terminal.to_string(),
+            GrammarSymbol::Nonterminal(_) => self.children.iter().map(ParseTree::get_yield).collect(),
+        }
+    }
+
+    /// Returns the sentential forms of the leftmost derivation corresponding to the tree, starting with its root symbol
+    /// and ending with the derived word.
+    pub fn get_leftmost_derivation(&self) -> Vec<String> {
+        let mut frontier = vec![self];
+        let mut derivation = vec![self.symbol.to_string()];
+        while let Some(index) = frontier.iter().position(|tree| matches!(tree.symbol, GrammarSymbol::Nonterminal(_))) {
+            let tree = frontier.remove(index);
+            for (offset, child) in tree.children.iter().enumerate() {
+                frontier.insert(index + offset, child);
+            }
+            derivation.push(frontier.iter().map(|tree| tree.symbol.to_string()).join(""));
+        }
+        derivation
+    }
+
+    /// Renders the tree in the [DOT language](https://graphviz.org/doc/info/lang.html) of Graphviz. Nonterminals are drawn as ellipses,
+    /// terminals as boxes and empty productions as a leaf labeled ε. Nodes are numbered in preorder.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph \"parse tree\" {\n    ordering=out;\n");
+        self.render_dot(&mut dot, &mut 0);
+        dot + "}\n"
+    }
+
+    fn render_dot(&self, dot: &mut String, next_index: &mut usize) -> usize {
+        let index = *next_index;
+        *next_index += 1;
+        let shape = match self.symbol {
+            GrammarSymbol::Terminal(_) => "box",
+            GrammarSymbol::Nonterminal(_) => "ellipse",
+        };
+        *dot += &format!("    n{} [label=\"{}\", shape={}];\n", index, self.symbol.to_string().replace('\\', "\\\\").replace('"', "\\\""), shape);
+        if self.children.is_empty() && matches!(self.symbol, GrammarSymbol::Nonterminal(_)) {
+            *dot += &format!("    n{} [label=\"ε\", shape=plaintext];\n    n{} -> n{};\n", *next_index, index, *next_index);
+            *next_index += 1;
+        }
+        for child in &self.children {
+            let child_index = child.render_dot(dot, next_index);
+            *dot += &format!("    n{} -> n{};\n", index, child_index);
+        }
+        index
+    }
+
+    fn fmt_with_prefix(&self, f: &mut fmt::Formatter<'_>, prefix: &str) -> fmt::Result {
+        let children = match (&self.symbol, self.children.is_empty()) {
+            (GrammarSymbol::Nonterminal(_), true) => vec![ParseTree { symbol: GrammarSymbol::Terminal('ε'), children: Vec::new() }],
+            _ => self.children.clone(),
+        };
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            writeln!(f, "{}{}{}", prefix, if is_last { "└─ " } else { "├─ " }, child.symbol)?;
+            child.fmt_with_prefix(f, &format!("{}{}", prefix, if is_last { "   " } else { "│  " }))?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws the tree as text with one symbol per line, like the `tree` command does for directories.
+impl fmt::Display for ParseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.symbol)?;
+        self.fmt_with_prefix(f, "")
+    }
+}
+
+#[cfg(test)]
+mod cfg_tests {
+    use crate::Cfg;
+
+    #[test]
+    fn test_get_parse_trees() {
+        // An ambiguous grammar for sums: E → E+E | a.
+        let mut cfg = Cfg::new("E");
+        cfg.add_production("E", "E+E");
+        cfg.add_production("E", "a");
+        let trees = cfg.get_parse_trees("a+a+a", 10);
+        assert_eq!(trees.len(), 2, "both ways to put the parentheses");
+        assert!(trees.iter().all(|tree| tree.get_yield() == "a+a+a"));
+        assert_eq!(cfg.get_parse_trees("a+a+a", 1).len(), 1);
+        assert!(cfg.get_parse_trees("a+", 10).is_empty());
+        let tree = &cfg.get_parse_trees("a+a", 1)[0];
+        assert_eq!(tree.get_leftmost_derivation(), vec!["E", "E+E", "a+E", "a+a"]);
+        assert_eq!(tree.to_string(), "E\n├─ E\n│  └─ a\n├─ +\n└─ E\n   └─ a\n");
+        assert!(tree.to_dot().contains("    n0 -> n1;\n"));
+    }
+
+    #[test]
+    fn test_empty_productions() {
+        // Generates balanced parentheses: S → (S)S | ε.
+        let mut cfg = Cfg::new("S");
+        cfg.add_production("S", "(S)S");
+        cfg.add_production("S", "");
+        let trees = cfg.get_parse_trees("()", 10);
+        assert_eq!(trees.len(), 1);
+        assert!(trees[0].to_string().contains("│  └─ ε\n"));
+        assert!(trees[0].to_dot().contains("[label=\"ε\", shape=plaintext]"));
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// How the number of accepted words of length n grows with n.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrowthRate {
+    /// There are no accepted words beyond some length.
+    Finite,
+    /// The number of accepted words of length n is bounded by a polynomial of the degree, e.g. 0 for a*b and 1 for a*b*.
+    Polynomial(usize),
+    /// The number of accepted words of length n grows exponentially, like for (a|b)*.
+    Exponential,
+}
+
+impl fmt::Display for GrowthRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrowthRate::Finite => write!(f, "finite"),
+            GrowthRate::Polynomial(degree) => write!(f, "polynomial of degree {}", degree),
+            GrowthRate::Exponential => write!(f, "exponential"),
+        }
+    }
+}
+
+/// The "identity card" of a regular language, as computed by `Dfa::classify`. All properties refer to the language over
+/// the alphabet of the DFA, e.g. whether it is co-finite depends on the symbols it could miss.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageClassification {
+    pub is_finite: bool,
+    /// Whether only finitely many words over the alphabet are rejected.
+    pub is_cofinite: bool,
+    /// Whether the language can be described by a regex using complement instead of the star, which by Schützenberger's theorem
+    /// is the case if the syntactic monoid is aperiodic.
+    pub is_star_free: bool,
+    /// Whether membership only depends on the subwords (scattered subsequences) up to some length, which by Simon's theorem
+    /// is the case if the syntactic monoid is J-trivial.
+    pub is_piecewise_testable: bool,
+    /// Whether the minimal DFA is reversible (see `Dfa::is_reversible`), i.e. no state is entered by two transitions reading the same symbol.
+    pub is_reversible: bool,
+    /// Whether the minimal DFA is bideterministic (see `Dfa::is_bideterministic`), i.e. it is reversible and has a single accept state,
+    /// so that reversing its transitions yields a DFA for the reversed language.
+    pub is_bideterministic: bool,
+    /// Whether all prefixes of accepted words are accepted, too.
+    pub is_prefix_closed: bool,
+    /// The number of states of the minimal DFA in which missing transitions reject, i.e. without a dead state.
+    pub minimal_dfa_size: usize,
+    /// The number of Myhill–Nerode equivalence classes, i.e. the number of states of the minimal complete DFA.
+    pub nerode_index: usize,
+    /// The number of elements of the syntactic monoid, i.e. the transition monoid of the minimal complete DFA.
+    pub syntactic_monoid_size: usize,
+    pub growth_rate: GrowthRate,
+}
+
+impl fmt::Display for LanguageClassification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_or_no = |value: bool| if value { "yes" } else { "no" };
+        writeln!(f, "finite: {}", yes_or_no(self.is_finite))?;
+        writeln!(f, "co-finite: {}", yes_or_no(self.is_cofinite))?;
+        writeln!(f, "star-free: {}", yes_or_no(self.is_star_free))?;
+        writeln!(f, "piecewise testable: {}", yes_or_no(self.is_piecewise_testable))?;
+        writeln!(f, "reversible: {}", yes_or_no(self.is_reversible))?;
+        writeln!(f, "bideterministic: {}", yes_or_no(self.is_bideterministic))?;
+        writeln!(f, "prefix-closed: {}", yes_or_no(self.is_prefix_closed))?;
+        writeln!(f, "minimal DFA size: {}", self.minimal_dfa_size)?;
+        writeln!(f, "Nerode index: {}", self.nerode_index)?;
+        writeln!(f, "syntactic monoid size: {}", self.syntactic_monoid_size)?;
+        writeln!(f, "growth rate: {}", self.growth_rate)
+    }
+}
+
+impl Dfa {
+    /// Classifies the language of the DFA. NFAs and regexes can be classified after converting them with `to_dfa`.
+    /// All properties are decided on the minimal complete DFA, which is obtained by adding a trap state for missing transitions
+    /// and minimizing. Star-freeness and piecewise testability are decided on the transition monoid, whose size can be exponential
+    /// in the number of states, so this is meant for the automata of textbooks rather than large ones. Accept tags are ignored.
+    pub fn classify(&self) -> LanguageClassification {
+        let mut dfa = self.complete();
+        dfa.accept_tags.clear();
+        let alphabet = dfa.get_alphabet().into_iter().sorted().collect_vec();
+        dfa.minimize();
+        let states = dfa.get_reachable_states().into_iter().sorted().collect_vec();
+        let indices: HashMap<&String, usize> = states.iter().enumerate().map(|(index, state)| (state, index)).collect();
+        // The minimal DFA as a table: for each symbol, the index of the next state of each state.
+        let table = alphabet.iter()
+            .map(|input| states.iter().map(|state| indices[&dfa.get_transition(state, input).unwrap().next_state]).collect_vec())
+            .collect_vec();
+        let is_accepting = states.iter().map(|state| dfa.accept_states.contains(state)).collect_vec();
+        let live_states = get_states_reaching(&table, &is_accepting);
+        let rejecting_live_states = get_states_reaching(&table, &is_accepting.iter().map(|is_accepting| !is_accepting).collect_vec());
+        let monoid = get_transition_monoid(&table, states.len());
+        let has_dead_state = live_states.len() < states.len();
+        LanguageClassification {
+            is_finite: !has_cycle(&table, &live_states),
+            is_cofinite: !has_cycle(&table, &rejecting_live_states),
+            is_star_free: monoid.iter().all(|element| is_aperiodic(element)),
+            is_piecewise_testable: is_j_trivial(&monoid),
+            is_reversible: dfa.is_reversible(),
+            is_bideterministic: dfa.is_bideterministic(),
+            is_prefix_closed: live_states.iter().all(|state| is_accepting[*state]),
+            minimal_dfa_size: states.len() - if has_dead_state { 1 } else { 0 },
+            nerode_index: states.len(),
+            syntactic_monoid_size: monoid.len(),
+            growth_rate: get_growth_rate(&table, &live_states, indices[&dfa.start_state]),
+        }
+    }
+}
+
+/// Returns the states from which a state with the property can be reached, using the breath first algorithm on the reversed transitions.
+fn get_states_reaching(table: &[Vec<usize>], has_property: &[bool]) -> BTreeSet<usize> {
+    let mut visited_states: BTreeSet<usize> = (0..has_property.len()).filter(|state| has_property[*state]).collect();
+    let mut states_to_visit: VecDeque<usize> = visited_states.iter().cloned().collect();
+    while let Some(state) = states_to_visit.pop_front() {
+        for row in table {
+            for (previous_state, _) in row.iter().enumerate().filter(|(_, next_state)| **next_state == state) {
+                if visited_states.insert(previous_state) {
+                    states_to_visit.push_back(previous_state);
+                }
+            }
+        }
+    }
+    visited_states
+}
+
+/// Returns the transitions between the states as pairs, with one pair per symbol.
+fn get_edges(table: &[Vec<usize>], states: &BTreeSet<usize>) -> Vec<(usize, usize)> {
+    table.iter()
+        .flat_map(|row| states.iter().map(move |state| (*state, row[*state])))
+        .filter(|(_, next_state)| states.contains(next_state))
+        .collect()
+}
+
+/// Checks whether the transitions between the states form a cycle, by repeatedly removing states without outgoing transitions.
+fn has_cycle(table: &[Vec<usize>], states: &BTreeSet<usize>) -> bool {
+    let edges = get_edges(table, states);
+    let mut remaining_states = states.clone();
+    loop {
+        let sinks = remaining_states.iter()
+            .filter(|state| !edges.iter().any(|(edge_state, next_state)| edge_state == *state && remaining_states.contains(next_state)))
+            .cloned()
+            .collect_vec();
+        if sinks.is_empty() {
+            return !remaining_states.is_empty();
+        }
+        for sink in sinks {
+            remaining_states.remove(&sink);
+        }
+    }
+}
+
+/// Returns all functions on the states that are induced by words, starting with the empty word.
+fn get_transition_monoid(table: &[Vec<usize>], state_count: usize) -> Vec<Vec<usize>> {
+    let identity = (0..state_count).collect_vec();
+    let mut elements: HashSet<Vec<usize>> = vec![identity.clone()].into_iter().collect();
+    let mut monoid = vec![identity.clone()];
+    let mut elements_to_visit = VecDeque::new();
+    elements_to_visit.push_back(identity);
+    while let Some(element) = elements_to_visit.pop_front() {
+        for row in table {
+            let next_element = element.iter().map(|state| row[*state]).collect_vec();
+            if elements.insert(next_element.clone()) {
+                monoid.push(next_element.clone());
+                elements_to_visit.push_back(next_element);
+            }
+        }
+    }
+    monoid
+}
+
+fn compose(first: &[usize], second: &[usize]) -> Vec<usize> {
+    first.iter().map(|state| second[*state]).collect()
+}
+
+/// Checks whether the powers of the element eventually stop changing, i.e. mⁿ = mⁿ⁺¹ for some n.
+fn is_aperiodic(element: &[usize]) -> bool {
+    let mut powers = vec![element.to_vec()];
+    loop {
+        let next_power = compose(powers.last().unwrap(), element);
+        if let Some(index) = powers.iter().position(|power| *power == next_power) {
+            return index == powers.len() - 1;
+        }
+        powers.push(next_power);
+    }
+}
+
+/// Checks whether distinct elements generate distinct two-sided ideals.
+fn is_j_trivial(monoid: &[Vec<usize>]) -> bool {
+    let ideals = monoid.iter()
+        .map(|element| {
+            monoid.iter()
+                .cartesian_product(monoid.iter())
+                .map(|(left, right)| compose(&compose(left, element), right))
+                .collect::<BTreeSet<_>>()
+        })
+        .collect_vec();
+    ideals.iter().unique().count() == ideals.len()
+}
+
+/// Determines the growth rate from the strongly connected components of the live states: the number of words of length n grows
+/// exponentially if a component contains two different cycles, and otherwise polynomially, with the degree being one less than
+/// the largest number of components with a cycle on a path from the start state.
+fn get_growth_rate(table: &[Vec<usize>], live_states: &BTreeSet<usize>, start_state: usize) -> GrowthRate {
+    if !live_states.contains(&start_state) {
+        return GrowthRate::Finite;
+    }
+    let edges = get_edges(table, live_states);
+    let reachable_states: HashMap<usize, BTreeSet<usize>> = live_states.iter()
+        .map(|state| {
+            let mut visited_states: BTreeSet<usize> = vec![*state].into_iter().collect();
+            let mut states_to_visit: VecDeque<usize> = vec![*state].into_iter().collect();
+            while let Some(current_state) = states_to_visit.pop_front() {
+                for (_, next_state) in edges.iter().filter(|(edge_state, _)| *edge_state == current_state) {
+                    if visited_states.insert(*next_state) {
+                        states_to_visit.push_back(*next_state);
+                    }
+                }
+            }
+            (*state, visited_states)
+        })
+        .collect();
+    let get_component = |state: usize| -> BTreeSet<usize> {
+        reachable_states[&state].iter().filter(|other_state| reachable_states[other_state].contains(&state)).cloned().collect()
+    };
+    let components = live_states.iter().map(|state| get_component(*state)).unique().collect_vec();
+    let mut max_cyclic_counts: HashMap<BTreeSet<usize>, usize> = HashMap::new();
+    // Components reaching fewer states come later in a topological order, so they are processed first.
+    for component in components.iter().sorted_by_key(|component| reachable_states[component.iter().next().unwrap()].len()) {
+        let inner_edge_count = edges.iter().filter(|(state, next_state)| component.contains(state) && component.contains(next_state)).count();
+        if inner_edge_count > component.len() {
+            return GrowthRate::Exponential;
+        }
+        let successor_count = edges.iter()
+            .filter(|(state, next_state)| component.contains(state) && !component.contains(next_state))
+            .map(|(_, next_state)| max_cyclic_counts[&get_component(*next_state)])
+            .max()
+            .unwrap_or(0);
+        max_cyclic_counts.insert(component.clone(), successor_count + if inner_edge_count > 0 { 1 } else { 0 });
+    }
+    match max_cyclic_counts[&get_component(start_state)] {
+        0 => GrowthRate::Finite,
+        cyclic_count => GrowthRate::Polynomial(cyclic_count - 1),
+    }
+}
+
+#[cfg(test)]
+mod classification_tests {
+    use crate::{Dfa, GrowthRate, Regex};
+
+    #[test]
+    fn test_classify() {
+        // a*b* is star-free, piecewise testable and prefix-closed.
+        let classification = Regex::parse("a*b*").unwrap().to_dfa().classify();
+        assert!(classification.is_star_free && classification.is_piecewise_testable && classification.is_prefix_closed);
+        assert!(!classification.is_finite && !classification.is_cofinite && !classification.is_reversible);
+        assert_eq!((classification.minimal_dfa_size, classification.nerode_index), (2, 3));
+        assert_eq!(classification.growth_rate, GrowthRate::Polynomial(1));
+        // (aa)* is not star-free, as counting modulo 2 needs a group, but its minimal DFA is reversible.
+        let classification = Regex::parse("(aa)*").unwrap().to_dfa().classify();
+        assert!(!classification.is_star_free && !classification.is_piecewise_testable && classification.is_reversible && classification.is_bideterministic);
+        assert_eq!(classification.growth_rate, GrowthRate::Polynomial(0));
+        assert_eq!(classification.syntactic_monoid_size, 2);
+        // (a|b)*a is star-free but not piecewise testable, and its number of words grows exponentially.
+        let classification = Regex::parse("(a|b)*a").unwrap().to_dfa().classify();
+        assert!(classification.is_star_free && !classification.is_piecewise_testable);
+        assert_eq!(classification.growth_rate, GrowthRate::Exponential);
+        assert!(classification.to_string().contains("growth rate: exponential\n"));
+    }
+
+    #[test]
+    fn test_classify_finite_and_cofinite() {
+        // Accepts the words "a" and "ab" only.
+        let finite = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), None], &[None, Some(2)], &[None, None]], 0, &[1, 2]).unwrap();
+        let classification = finite.classify();
+        assert!(classification.is_finite && !classification.is_cofinite && !classification.is_prefix_closed, "the empty word is missing");
+        assert!(classification.is_reversible && !classification.is_bideterministic, "there are two accept states");
+        assert_eq!(classification.growth_rate, GrowthRate::Finite);
+        // Accepts all words over 'a' and 'b' except the empty word.
+        let cofinite = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(1), Some(1)], &[Some(1), Some(1)]], 0, &[1]).unwrap();
+        let classification = cofinite.classify();
+        assert!(!classification.is_finite && classification.is_cofinite && !classification.is_prefix_closed);
+        assert_eq!(classification.nerode_index, 2);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Cfg, GrammarSymbol, ParseTree};
+
+/// For the part of the word with the given start index and length, maps every nonterminal deriving it to the index of the production
+/// and the length of the first part of the split that derive it first.
+type CykTable<'a> = HashMap<(usize, usize), HashMap<&'a str, (usize, usize)>>;
+
+impl Cfg {
+    /// Checks whether the grammar is in [Chomsky normal form](https://en.wikipedia.org/wiki/Chomsky_normal_form): every production
+    /// is of the form A → BC for nonterminals B and C other than the start symbol, A → a for a terminal a, or S → ε for the start symbol S.
+    pub fn is_in_cnf(&self) -> bool {
+        self.productions.iter().all(|(head, body)| match body.as_slice() {
+            [] => *head == self.start_symbol,
+            [GrammarSymbol::Terminal(_)] => true,
+            [GrammarSymbol::Nonterminal(first), GrammarSymbol::Nonterminal(second)] => *first != self.start_symbol && *second != self.start_symbol,
+            _ => false,
+        })
+    }
+
+    /// Converts the grammar into an equivalent grammar in Chomsky normal form (see `is_in_cnf`). Grammars that are already in that form are returned unchanged.
+    /// Otherwise, the steps are applied in the usual order: a new start symbol is introduced, terminals in longer bodies are replaced by
+    /// nonterminals like "T_a", long bodies are split into chains of nonterminals like "A_1", empty productions are eliminated
+    /// by adding copies of the productions without nullable nonterminals, and productions A → B are replaced by the productions of B.
+    pub fn to_cnf(&self) -> Cfg {
+        if self.is_in_cnf() {
+            return self.clone();
+        }
+        let mut nonterminals: HashSet<String> = self.productions.iter()
+            .flat_map(|(head, body)| body.iter().filter_map(|symbol| match symbol {
+                GrammarSymbol::Nonterminal(nonterminal) => Some(nonterminal.clone()),
+                GrammarSymbol::Terminal(_) => None,
+            }).chain(vec![head.clone()]))
+            .collect();
+        nonterminals.insert(self.start_symbol.clone());
+        let mut fresh_nonterminal = |name: String| {
+            let nonterminal = fresh_state_name(&name, &nonterminals);
+            nonterminals.insert(nonterminal.clone());
+            nonterminal
+        };
+        // The new start symbol does not appear in any body.
+        let start_symbol = fresh_nonterminal(format!("{}0", self.start_symbol));
+        let mut productions = vec![(start_symbol.clone(), vec![GrammarSymbol::Nonterminal(self.start_symbol.clone())])];
+        productions.extend(self.productions.iter().cloned());
+        // Terminals in bodies of two or more symbols are replaced by nonterminals deriving them.
+        let mut terminal_nonterminals: HashMap<char, String> = HashMap::new();
+        for (_, body) in productions.iter_mut().filter(|(_, body)| body.len() >= 2) {
+            for symbol in body.iter_mut() {
+                if let GrammarSymbol::Terminal(terminal) = symbol {
+                    let nonterminal = terminal_nonterminals.entry(*terminal).or_insert_with(|| fresh_nonterminal(format!("T_{}", terminal)));
+                    *symbol = GrammarSymbol::Nonterminal(nonterminal.clone());
+                }
+            }
+        }
+        for (terminal, nonterminal) in terminal_nonterminals.into_iter().sorted() {
+            productions.push((nonterminal, vec![GrammarSymbol::Terminal(terminal)]));
+        }
+        // Bodies of more than two symbols are split into a chain of productions with two symbols each.
+        let mut binary_productions = Vec::new();
+        for (head, body) in productions {
+            let mut current_head = head.clone();
+            let mut remaining_symbols = body.as_slice();
+            while remaining_symbols.len() > 2 {
+                let next_head = fresh_nonterminal(format!("{}_1", head));
+                binary_productions.push((current_head, vec![remaining_symbols[0].clone(), GrammarSymbol::Nonterminal(next_head.clone())]));
+                current_head = next_head;
+                remaining_symbols = &remaining_symbols[1..];
+            }
+            binary_productions.push((current_head, remaining_symbols.to_vec()));
+        }
+        // Empty productions are eliminated, except for the new start symbol.
+        let nullable_nonterminals = get_nullable_nonterminals(&binary_productions);
+        let mut productions_without_empty_bodies = Vec::new();
+        for (head, body) in &binary_productions {
+            let nullable_positions = (0..body.len())
+                .filter(|index| matches!(&body[*index], GrammarSymbol::Nonterminal(nonterminal) if nullable_nonterminals.contains(nonterminal)))
+                .collect_vec();
+            // Bodies have at most two symbols now, so there are at most four subsets of nullable positions to omit.
+            for omitted_subset in 0..1 << nullable_positions.len() {
+                let omitted_positions = nullable_positions.iter().enumerate().filter(|(bit, _)| omitted_subset & 1 << bit != 0).map(|(_, index)| *index).collect_vec();
+                let shortened_body = body.iter().enumerate().filter(|(index, _)| !omitted_positions.contains(index)).map(|(_, symbol)| symbol.clone()).collect_vec();
+                if !shortened_body.is_empty() {
+                    productions_without_empty_bodies.push((head.clone(), shortened_body));
+                }
+            }
+        }
+        // Productions A → B are replaced by the other productions of all nonterminals reachable through such productions.
+        let is_unit_production = |body: &[GrammarSymbol]| matches!(body, [GrammarSymbol::Nonterminal(_)]);
+        let mut cnf = Cfg::new(&start_symbol);
+        if nullable_nonterminals.contains(&self.start_symbol) {
+            cnf.productions.push((start_symbol.clone(), Vec::new()));
+        }
+        let heads = productions_without_empty_bodies.iter().map(|(head, _)| head.clone()).unique().collect_vec();
+        for head in heads {
+            let mut reachable_nonterminals = vec![head.clone()];
+            let mut nonterminals_to_visit: VecDeque<String> = vec![head.clone()].into_iter().collect();
+            while let Some(nonterminal) = nonterminals_to_visit.pop_front() {
+                for (_, body) in productions_without_empty_bodies.iter().filter(|(other_head, body)| *other_head == nonterminal && is_unit_production(body)) {
+                    if let GrammarSymbol::Nonterminal(next_nonterminal) = &body[0] {
+                        if !reachable_nonterminals.contains(next_nonterminal) {
+                            reachable_nonterminals.push(next_nonterminal.clone());
+                            nonterminals_to_visit.push_back(next_nonterminal.clone());
+                        }
+                    }
+                }
+            }
+            for nonterminal in &reachable_nonterminals {
+                for (_, body) in productions_without_empty_bodies.iter().filter(|(other_head, body)| other_head == nonterminal && !is_unit_production(body)) {
+                    cnf.productions.push((head.clone(), body.clone()));
+                }
+            }
+        }
+        cnf.productions = cnf.productions.into_iter().unique().collect();
+        cnf
+    }
+
+    /// Checks whether the grammar generates the word with the [CYK algorithm](https://en.wikipedia.org/wiki/CYK_algorithm) and returns
+    /// a parse tree if it does. The grammar is converted into Chomsky normal form first (see `to_cnf`), so the tree belongs to
+    /// the converted grammar. Use `get_parse_trees` for trees of the grammar itself, which may take exponential time, though.
+    /// For every part of the word, the algorithm determines the nonterminals deriving it from those deriving its shorter parts,
+    /// which takes cubic time in the length of the word.
+    pub fn check(&self, input: &str) -> Option<ParseTree> {
+        let cnf = self.to_cnf();
+        let word = input.chars().collect_vec();
+        if word.is_empty() {
+            return cnf.productions.iter()
+                .find(|(head, body)| *head == cnf.start_symbol && body.is_empty())
+                .map(|_| ParseTree { symbol: GrammarSymbol::Nonterminal(cnf.start_symbol.clone()), children: Vec::new() });
+        }
+        let mut table = CykTable::new();
+        for (start, symbol) in word.iter().enumerate() {
+            let cell = table.entry((start, 1)).or_default();
+            for (index, (head, body)) in cnf.productions.iter().enumerate() {
+                if body.as_slice() == [GrammarSymbol::Terminal(*symbol)] {
+                    cell.entry(head).or_insert((index, 0));
+                }
+            }
+        }
+        for length in 2..=word.len() {
+            for start in 0..=word.len() - length {
+                let mut cell = HashMap::new();
+                for first_length in 1..length {
+                    for (index, (head, body)) in cnf.productions.iter().enumerate() {
+                        if let [GrammarSymbol::Nonterminal(first), GrammarSymbol::Nonterminal(second)] = body.as_slice() {
+                            if table[&(start, first_length)].contains_key(first.as_str()) && table[&(start + first_length, length - first_length)].contains_key(second.as_str()) {
+                                cell.entry(head.as_str()).or_insert((index, first_length));
+                            }
+                        }
+                    }
+                }
+                table.insert((start, length), cell);
+            }
+        }
+        if !table[&(0, word.len())].contains_key(cnf.start_symbol.as_str()) {
+            return None;
+        }
+        Some(build_parse_tree(&cnf, &table, &cnf.start_symbol, 0, word.len()))
+    }
+}
+
+/// Returns the nonterminals that derive the empty word, by repeatedly adding nonterminals with a body consisting of nullable nonterminals only.
+fn get_nullable_nonterminals(productions: &[(String, Vec<GrammarSymbol>)]) -> HashSet<String> {
+    let mut nullable_nonterminals = HashSet::new();
+    loop {
+        let new_nullable_nonterminals = productions.iter()
+            .filter(|(head, body)| !nullable_nonterminals.contains(head) && body.iter().all(|symbol| {
+                matches!(symbol, GrammarSymbol::Nonterminal(nonterminal) if nullable_nonterminals.contains(nonterminal))
+            }))
+            .map(|(head, _)| head.clone())
+            .collect_vec();
+        if new_nullable_nonterminals.is_empty() {
+            return nullable_nonterminals;
+        }
+        nullable_nonterminals.extend(new_nullable_nonterminals);
+    }
+}
+
+/// Builds the parse tree of the part of the word from the productions and splits recorded in the CYK table.
+fn build_parse_tree(cnf: &Cfg, table: &CykTable, nonterminal: &str, start: usize, length: usize) -> ParseTree {
+    let (index, first_length) = table[&(start, length)][nonterminal];
+    let children = match cnf.productions[index].1.as_slice() {
+        [GrammarSymbol::Nonterminal(first), GrammarSymbol::Nonterminal(second)] => vec![
+            build_parse_tree(cnf, table, first, start, first_length),
+            build_parse_tree(cnf, table, second, start + first_length, length - first_length),
+        ],
+        body => body.iter().map(|symbol| ParseTree { symbol: symbol.clone(), children: Vec::new() }).collect(),
+    };
+    ParseTree { symbol: GrammarSymbol::Nonterminal(nonterminal.to_string()), children }
+}
+
+#[cfg(test)]
+mod cnf_tests {
+    use crate::Cfg;
+
+    #[test]
+    fn test_check() {
+        // Generates balanced parentheses: S → (S)S | ε.
+        let mut cfg = Cfg::new("S");
+        cfg.add_production("S", "(S)S");
+        cfg.add_production("S", "");
+        let cnf = cfg.to_cnf();
+        assert!(cnf.is_in_cnf() && !cfg.is_in_cnf());
+        assert_eq!(cnf.to_cnf(), cnf);
+        for word in &["", "()", "(())()", "((()))"] {
+            let tree = cfg.check(word).unwrap();
+            assert_eq!(tree.get_yield(), *word);
+        }
+        for word in &["(", ")(", "(()"] {
+            assert!(cfg.check(word).is_none(), "{}", word);
+        }
+        let tree = cfg.check("()").unwrap();
+        assert_eq!(tree.to_string(), "S0\n├─ T_(\n│  └─ (\n└─ S_1\n   └─ )\n");
+    }
+}
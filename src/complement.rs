@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Dfa, Transition};
+
+impl Dfa {
+    /// Adds a rejecting trap state and routes every missing transition over the alphabet into it, so that every state
+    /// has a transition for every symbol. Missing transitions that are self-loops by policy are materialized instead.
+    /// If no transition is missing, the DFA is returned unchanged and no trap state is added.
+    pub fn complete(&self) -> Dfa {
+        let mut complete_dfa = self.clone();
+        complete_dfa.materialize_missing_transitions();
+        let alphabet = complete_dfa.get_alphabet().into_iter().sorted().collect_vec();
+        let mut states = complete_dfa.get_all_mentioned_states();
+        states.insert(complete_dfa.start_state.clone());
+        let trap_state = fresh_state_name("trap", &states);
+        let missing_transitions = states.iter().sorted()
+            .cartesian_product(alphabet.iter())
+            .filter(|(state, input)| complete_dfa.get_transition(state, input).is_none())
+            .map(|(state, input)| Transition { state: state.clone(), input: *input, next_state: trap_state.clone() })
+            .collect_vec();
+        if !missing_transitions.is_empty() {
+            complete_dfa.transitions.extend(missing_transitions);
+            complete_dfa.transitions.extend(alphabet.iter().map(|input| Transition { state: trap_state.clone(), input: *input, next_state: trap_state.clone() }));
+        }
+        complete_dfa.alphabet = Some(alphabet.into_iter().collect());
+        complete_dfa
+    }
+
+    /// Returns a DFA accepting exactly the words over the alphabet that this one rejects. The DFA is completed first (see `complete`),
+    /// as flipping the accept states of a DFA with missing transitions would still reject the words that run into them.
+    /// Accept tags are dropped, as the former accept states do not accept any more.
+    pub fn complement(&self) -> Dfa {
+        let mut complement = self.complete();
+        let mut states = complement.get_all_mentioned_states();
+        states.insert(complement.start_state.clone());
+        complement.name = format!("complement of {}", self.name);
+        complement.accept_states = states.difference(&complement.accept_states).cloned().collect();
+        complement.accept_tags = HashMap::new();
+        complement
+    }
+}
+
+#[cfg(test)]
+mod complement_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_complement() {
+        // Accepts "a" followed by any number of 'b's, with missing transitions.
+        let a_b_star = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(1), None], &[None, Some(1)]], 0, &[1]).unwrap();
+        let complete_dfa = a_b_star.complete();
+        assert!(complete_dfa.get_all_states().contains("trap"));
+        assert_eq!(complete_dfa.transitions.len(), 6);
+        let complement = a_b_star.complement();
+        for word in &["", "b", "aa", "aba", "abb", "a"] {
+            assert_eq!(complement.check(word).0, !a_b_star.check(word).0, "{}", word);
+        }
+        // A complete DFA is not changed, so complementing twice only flips the accept states back.
+        assert_eq!(complement.complement().get_all_states(), complete_dfa.get_all_states());
+    }
+}
@@ -0,0 +1,65 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Sets the cost of taking the transition of the state for the input, e.g. the cost of an action in a plan.
+    /// Costs do not influence the language, only `cheapest_accepted_word`.
+    pub fn set_transition_cost(&mut self, state: &str, input: char, cost: u64) {
+        self.transition_costs.insert((state.to_string(), input), cost);
+    }
+
+    /// Returns the cost of taking the transition of the state for the input, which is 1 unless set otherwise,
+    /// so that without costs the cheapest words are the shortest ones.
+    pub fn get_transition_cost(&self, state: &str, input: char) -> u64 {
+        self.transition_costs.get(&(state.to_string(), input)).cloned().unwrap_or(1)
+    }
+
+    /// Returns an accepted word with the lowest total cost of its transitions together with that cost, or None if no word is accepted.
+    /// Uses [Dijkstra's algorithm](https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm). Among the cheapest words,
+    /// the shortest and then the alphabetically smallest one is returned.
+    pub fn cheapest_accepted_word(&self) -> Option<(String, u64)> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let mut visited_states: HashSet<&str> = HashSet::new();
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((0, 0, String::new(), dfa.start_state.as_str())));
+        while let Some(Reverse((cost, length, word, state))) = queue.pop() {
+            if !visited_states.insert(state) {
+                continue;
+            }
+            if dfa.accept_states.contains(state) {
+                return Some((word, cost));
+            }
+            for transition in dfa.transitions.iter().filter(|transition| transition.state == state).sorted() {
+                if !visited_states.contains(transition.next_state.as_str()) {
+                    let next_cost = cost + dfa.get_transition_cost(state, transition.input);
+                    queue.push(Reverse((next_cost, length + 1, format!("{}{}", word, transition.input), transition.next_state.as_str())));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod costs_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_cheapest_accepted_word() {
+        // Reaches the goal either directly with an expensive 'x' or via two cheap steps 'a' and 'b'.
+        let mut dfa = Dfa::from_table(&["start", "middle", "goal"], &['a', 'b', 'x'], &[&[Some(1), None, Some(2)], &[None, Some(2), None], &[None, None, None]], 0, &[2]).unwrap();
+        assert_eq!(dfa.cheapest_accepted_word(), Some(("x".to_string(), 1)), "without costs, the shortest word is the cheapest");
+        dfa.set_transition_cost("start", 'x', 10);
+        dfa.set_transition_cost("start", 'a', 2);
+        dfa.set_transition_cost("middle", 'b', 3);
+        assert_eq!(dfa.get_transition_cost("start", 'x'), 10);
+        assert_eq!(dfa.cheapest_accepted_word(), Some(("ab".to_string(), 5)));
+        dfa.accept_states.clear();
+        assert_eq!(dfa.cheapest_accepted_word(), None);
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Counts the accepted words of exactly the given length, using dynamic programming over the lengths: the number of words of length
+    /// k + 1 accepted from a state is the sum of the numbers of words of length k accepted from its next states.
+    /// This takes time proportional to the length times the number of transitions. Counts that do not fit into an u128 saturate at `u128::MAX`.
+    pub fn count_words_of_length(&self, length: usize) -> u128 {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let states = dfa.get_all_mentioned_states();
+        let mut counts: HashMap<&str, u128> = states.iter()
+            .map(|state| (state.as_str(), if dfa.accept_states.contains(state) { 1 } else { 0 }))
+            .collect();
+        for _ in 0..length {
+            let mut next_counts: HashMap<&str, u128> = states.iter().map(|state| (state.as_str(), 0)).collect();
+            for transition in &dfa.transitions {
+                let count = next_counts.get_mut(transition.state.as_str()).unwrap();
+                *count = count.saturating_add(counts[transition.next_state.as_str()]);
+            }
+            counts = next_counts;
+        }
+        counts[dfa.start_state.as_str()]
+    }
+}
+
+#[cfg(test)]
+mod counting_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_count_words_of_length() {
+        // Accepts words over 'a' and 'b' ending with 'b', so half of all words of every positive length.
+        let ends_with_b = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        assert_eq!(ends_with_b.count_words_of_length(0), 0);
+        assert_eq!(ends_with_b.count_words_of_length(3), 4);
+        assert_eq!(ends_with_b.count_words_of_length(200), u128::MAX);
+        // Accepts "a", "ab" and "b", with missing transitions.
+        let finite = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), Some(2)], &[None, Some(2)], &[None, None]], 0, &[1, 2]).unwrap();
+        assert_eq!(finite.count_words_of_length(1), 2);
+        assert_eq!(finite.count_words_of_length(5), 0);
+    }
+}
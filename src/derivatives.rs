@@ -0,0 +1,165 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Regex;
+
+impl Regex {
+    /// Checks whether the regex matches the whole input by taking the derivative with respect to each symbol on the fly
+    /// (see `distinguishing_word`) and checking whether the final derivative matches the empty word. Unlike converting the regex
+    /// into an automaton first, this only pays for the symbols actually read, so it suits one-off checks.
+    pub fn matches(&self, input: &str) -> bool {
+        let mut derivative = self.normalize();
+        for symbol in input.chars() {
+            if derivative == Regex::Empty {
+                return false;
+            }
+            derivative = derivative.get_derivative(symbol);
+        }
+        derivative.is_nullable()
+    }
+
+    /// Checks whether both regexes match the same words (see `distinguishing_word`).
+    pub fn equivalent(&self, other: &Regex) -> bool {
+        self.distinguishing_word(other).is_none()
+    }
+
+    /// Returns a shortest word matched by exactly one of the two regexes, or None if they are equivalent.
+    /// Among the shortest words, the alphabetically smallest one is returned.
+    ///
+    /// Instead of constructing automata, pairs of [Brzozowski derivatives](https://en.wikipedia.org/wiki/Brzozowski_derivative)
+    /// are explored with the breath first algorithm: the derivatives with respect to a word match what remains to be matched after reading the word,
+    /// so the regexes differ if some pair has exactly one derivative matching the empty word. Derivatives are kept in a normal form
+    /// that treats unions as sets, which guarantees that only finitely many pairs exist.
+    pub fn distinguishing_word(&self, other: &Regex) -> Option<String> {
+        let alphabet = self.get_symbols().union(&other.get_symbols()).cloned().collect_vec();
+        let start = (self.normalize(), other.normalize());
+        let mut words: HashMap<(Regex, Regex), String> = HashMap::new();
+        let mut pairs_to_visit = VecDeque::new();
+        words.insert(start.clone(), String::new());
+        pairs_to_visit.push_back(start);
+        while let Some((first, second)) = pairs_to_visit.pop_front() {
+            let word = words[&(first.clone(), second.clone())].clone();
+            if first.is_nullable() != second.is_nullable() {
+                return Some(word);
+            }
+            for symbol in &alphabet {
+                let next_pair = (first.get_derivative(*symbol), second.get_derivative(*symbol));
+                if !words.contains_key(&next_pair) {
+                    words.insert(next_pair.clone(), format!("{}{}", word, symbol));
+                    pairs_to_visit.push_back(next_pair);
+                }
+            }
+        }
+        None
+    }
+
+    /// Checks whether the regex matches the empty word.
+    pub(crate) fn is_nullable(&self) -> bool {
+        match self {
+            Regex::Empty | Regex::Symbol(_) => false,
+            Regex::Epsilon | Regex::Star(_) => true,
+            Regex::Concatenation(left, right) => left.is_nullable() && right.is_nullable(),
+            Regex::Union(left, right) => left.is_nullable() || right.is_nullable(),
+        }
+    }
+
+    /// Returns the normalized regex matching the rest of every matched word starting with the symbol.
+    pub(crate) fn get_derivative(&self, symbol: char) -> Regex {
+        match self {
+            Regex::Empty | Regex::Epsilon => Regex::Empty,
+            Regex::Symbol(other_symbol) if *other_symbol == symbol => Regex::Epsilon,
+            Regex::Symbol(_) => Regex::Empty,
+            Regex::Concatenation(left, right) => {
+                let derivative = concatenate(left.get_derivative(symbol), right.as_ref().clone());
+                match left.is_nullable() {
+                    true => unite(derivative, right.get_derivative(symbol)),
+                    false => derivative,
+                }
+            }
+            Regex::Union(left, right) => unite(left.get_derivative(symbol), right.get_derivative(symbol)),
+            Regex::Star(inner) => concatenate(inner.get_derivative(symbol), self.clone()),
+        }
+    }
+
+    /// Rebuilds the regex with the normalizing constructors used for derivatives.
+    fn normalize(&self) -> Regex {
+        match self {
+            Regex::Empty | Regex::Epsilon | Regex::Symbol(_) => self.clone(),
+            Regex::Concatenation(left, right) => concatenate(left.normalize(), right.normalize()),
+            Regex::Union(left, right) => unite(left.normalize(), right.normalize()),
+            Regex::Star(inner) => match inner.normalize() {
+                Regex::Empty | Regex::Epsilon => Regex::Epsilon,
+                Regex::Star(inner) => Regex::Star(inner),
+                inner => Regex::star(inner),
+            },
+        }
+    }
+
+    fn get_symbols(&self) -> BTreeSet<char> {
+        match self {
+            Regex::Empty | Regex::Epsilon => BTreeSet::new(),
+            Regex::Symbol(symbol) => vec![*symbol].into_iter().collect(),
+            Regex::Concatenation(left, right) | Regex::Union(left, right) => left.get_symbols().union(&right.get_symbols()).cloned().collect(),
+            Regex::Star(inner) => inner.get_symbols(),
+        }
+    }
+}
+
+/// Concatenates two regexes, leaving out epsilon and collapsing concatenations with ∅.
+fn concatenate(left: Regex, right: Regex) -> Regex {
+    match (left, right) {
+        (Regex::Empty, _) | (_, Regex::Empty) => Regex::Empty,
+        (Regex::Epsilon, regex) | (regex, Regex::Epsilon) => regex,
+        // Concatenations are associated to the right, so that equal regexes get the same tree.
+        (Regex::Concatenation(first, second), right) => Regex::concatenation(*first, concatenate(*second, right)),
+        (left, right) => Regex::concatenation(left, right),
+    }
+}
+
+/// Unites two regexes, treating the union as a set: nested alternatives are flattened, sorted and deduplicated, and ∅ is left out.
+fn unite(left: Regex, right: Regex) -> Regex {
+    let alternatives: BTreeSet<Regex> = get_alternatives(left).into_iter()
+        .chain(get_alternatives(right))
+        .filter(|alternative| *alternative != Regex::Empty)
+        .collect();
+    alternatives.into_iter().rev().fold1(|regex, alternative| Regex::union(alternative, regex)).unwrap_or(Regex::Empty)
+}
+
+fn get_alternatives(regex: Regex) -> Vec<Regex> {
+    match regex {
+        Regex::Union(left, right) => get_alternatives(*left).into_iter().chain(get_alternatives(*right)).collect(),
+        regex => vec![regex],
+    }
+}
+
+#[cfg(test)]
+mod derivatives_tests {
+    use crate::Regex;
+
+    #[test]
+    fn test_equivalent() {
+        let a = || Regex::Symbol('a');
+        let b = || Regex::Symbol('b');
+        // (a|b)* and (a*b*)* both match every word over 'a' and 'b'.
+        let any = Regex::star(Regex::union(a(), b()));
+        assert!(any.equivalent(&Regex::star(Regex::concatenation(Regex::star(a()), Regex::star(b())))));
+        // (ab)*a and a(ba)* are equivalent, but differ from (ab)*.
+        let ab_star_a = Regex::concatenation(Regex::star(Regex::word("ab")), a());
+        assert!(ab_star_a.equivalent(&Regex::concatenation(a(), Regex::star(Regex::word("ba")))));
+        assert_eq!(ab_star_a.distinguishing_word(&Regex::star(Regex::word("ab"))), Some(String::new()));
+        assert_eq!(any.distinguishing_word(&Regex::star(a())), Some("b".to_string()));
+    }
+
+    #[test]
+    fn test_matches() {
+        // Matches 'a' followed by any number of 'b's or "cd"s.
+        let regex = Regex::concatenation(Regex::Symbol('a'), Regex::star(Regex::union(Regex::Symbol('b'), Regex::word("cd"))));
+        assert!(regex.matches("a"));
+        assert!(regex.matches("abcdb"));
+        assert!(!regex.matches("abc"));
+        assert!(!regex.matches("ba"));
+        assert!(!Regex::Empty.matches(""));
+        assert!(Regex::Epsilon.matches(""));
+    }
+}
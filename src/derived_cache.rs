@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{Dfa, ImmutableDfa, Matcher};
+
+/// The artifacts derived from one language so far.
+#[derive(Debug, Default)]
+struct DerivedArtifacts {
+    minimized_dfa: Option<Dfa>,
+    /// The complements, keyed by the sorted alphabet they were taken over, as the complement depends on the alphabet, unlike the language.
+    complements: HashMap<String, Dfa>,
+    matcher: Option<Matcher>,
+}
+
+/// An opt-in registry for automata derived from DFAs, so that pipelines working on the same languages over and over again,
+/// like build tools, do not repeat expensive constructions. Artifacts are keyed by the canonical form of the language of the DFA they were
+/// derived from (see `language_hash`), so DFAs that accept the same language share the artifacts, even if their states are named differently.
+/// The canonical form itself is stored and compared instead of its hash, so that colliding hashes cannot mix up the artifacts of different languages.
+/// Consequently, the state names of a cached artifact are those of the DFA it was first derived from.
+///
+/// Computing the key requires minimizing the DFA, so the cache pays off for constructions that are more expensive than that
+/// or for artifacts that are used many times, like matchers.
+#[derive(Debug, Default)]
+pub struct DerivedCache {
+    artifacts: HashMap<String, DerivedArtifacts>,
+    hit_count: usize,
+    miss_count: usize,
+}
+
+impl DerivedCache {
+    pub fn new() -> DerivedCache {
+        DerivedCache::default()
+    }
+
+    /// Returns the minimal DFA accepting the same language (see `Dfa::minimize`).
+    pub fn get_minimized_dfa(&mut self, dfa: &Dfa) -> Dfa {
+        let artifacts = self.get_artifacts(dfa, |artifacts| artifacts.minimized_dfa.is_some());
+        artifacts.minimized_dfa.get_or_insert_with(|| {
+            let mut minimized_dfa = dfa.clone();
+            minimized_dfa.minimize();
+            minimized_dfa
+        }).clone()
+    }
+
+    /// Returns a DFA accepting exactly the words over the alphabet that the DFA rejects (see `ImmutableDfa::complement`).
+    pub fn get_complement(&mut self, dfa: &Dfa) -> Dfa {
+        let alphabet: String = dfa.get_alphabet().into_iter().sorted().collect();
+        let artifacts = self.get_artifacts(dfa, |artifacts| artifacts.complements.contains_key(&alphabet));
+        artifacts.complements.entry(alphabet).or_insert_with(|| ImmutableDfa::from(dfa.clone()).complement().to_dfa()).clone()
+    }
+
+    /// Returns a handle to the compiled DFA (see `Dfa::compile`).
+    pub fn get_matcher(&mut self, dfa: &Dfa) -> Matcher {
+        let artifacts = self.get_artifacts(dfa, |artifacts| artifacts.matcher.is_some());
+        artifacts.matcher.get_or_insert_with(|| dfa.compile()).clone_handle()
+    }
+
+    /// Returns how many requested artifacts were found in the cache.
+    pub fn get_hit_count(&self) -> usize {
+        self.hit_count
+    }
+
+    /// Returns how many requested artifacts had to be constructed.
+    pub fn get_miss_count(&self) -> usize {
+        self.miss_count
+    }
+
+    /// Returns the number of distinct languages with cached artifacts.
+    pub fn len(&self) -> usize {
+        self.artifacts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.artifacts.clear();
+    }
+
+    /// Returns the artifacts of the DFA's language and counts whether the requested one is already there.
+    fn get_artifacts<F: Fn(&DerivedArtifacts) -> bool>(&mut self, dfa: &Dfa, is_cached: F) -> &mut DerivedArtifacts {
+        let artifacts = self.artifacts.entry(dfa.get_canonical_encoding()).or_default();
+        if is_cached(artifacts) {
+            self.hit_count += 1;
+        } else {
+            self.miss_count += 1;
+        }
+        artifacts
+    }
+}
+
+#[cfg(test)]
+mod derived_cache_tests {
+    use crate::{DerivedCache, Dfa};
+
+    #[test]
+    fn test_derived_cache() {
+        let mut cache = DerivedCache::new();
+        // Both accept the words over 'a' of even length, but the second one has redundant states.
+        let dfa = Dfa::from_table(&["even", "odd"], &['a'], &[&[Some(1)], &[Some(0)]], 0, &[0]).unwrap();
+        let redundant_dfa = Dfa::from_table(&["p0", "p1", "p2", "p3"], &['a'], &[&[Some(1)], &[Some(2)], &[Some(3)], &[Some(0)]], 0, &[0, 2]).unwrap();
+        let complement = cache.get_complement(&dfa);
+        assert!(complement.check("a").0 && !complement.check("aa").0);
+        assert_eq!(cache.get_complement(&redundant_dfa).get_all_states(), complement.get_all_states(), "the language is the same");
+        assert!(cache.get_matcher(&redundant_dfa).is_match("aaaa"));
+        assert_eq!(cache.get_minimized_dfa(&redundant_dfa).get_all_states().len(), 2);
+        assert_eq!((cache.get_hit_count(), cache.get_miss_count()), (1, 3));
+        assert_eq!(cache.len(), 1);
+        // Accepts the same language, but over the alphabet {a, b}, so "b" belongs to the complement.
+        let dfa_over_a_b = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), None], &[Some(0), None]], 0, &[0]).unwrap();
+        assert!(cache.get_complement(&dfa_over_a_b).check("b").0);
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Dfa, Nfa, StateMetadata, Transition};
+
+impl Nfa {
+    /// Converts the NFA into a DFA accepting the same language with the subset construction (see `determinize`).
+    /// Each state of the DFA is named after the NFA states it stands for, e.g. "{q0,q1}". If NFA states contain commas or braces,
+    /// two subsets might get the same name, so apostrophes are appended to later ones until the names are unique.
+    pub fn to_dfa(&self) -> Dfa {
+        let (mut dfa, origins) = self.determinize();
+        let mut new_names: HashMap<String, String> = HashMap::new();
+        let mut used_names = HashSet::new();
+        // The generated names are numbered in the order the subsets were discovered, which keeps the renaming deterministic.
+        for state in origins.keys().sorted_by_key(|state| state[1..].parse::<usize>().unwrap()) {
+            let new_name = fresh_state_name(&format!("{{{}}}", origins[state].iter().join(",")), &used_names);
+            used_names.insert(new_name.clone());
+            new_names.insert(state.clone(), new_name);
+        }
+        dfa.start_state = new_names[&dfa.start_state].clone();
+        dfa.accept_states = dfa.accept_states.iter().map(|state| new_names[state].clone()).collect();
+        for transition in &mut dfa.transitions {
+            transition.state = new_names[&transition.state].clone();
+            transition.next_state = new_names[&transition.next_state].clone();
+        }
+        dfa.state_metadata = dfa.state_metadata.into_iter().map(|(state, metadata)| (new_names[&state].clone(), metadata)).collect();
+        dfa
+    }
+
+    /// Converts the NFA into a DFA accepting the same language with the
+    /// [subset construction](https://en.wikipedia.org/wiki/Powerset_construction). Each state of the DFA stands for the set of NFA states
+    /// the NFA could be in. Only subsets that can be reached are constructed, layer by layer with the breath first algorithm,
+    /// and the empty subset is left out, so missing transitions reject.
+    ///
+    /// The states of the DFA are called "q0", "q1" and so on in the order they were discovered. The NFA states each of them
+    /// corresponds to are returned as a mapping and are also stored as the origin in the metadata of the state,
+    /// so that exports show them as labels.
+    pub fn determinize(&self) -> (Dfa, HashMap<String, BTreeSet<String>>) {
+        let alphabet: HashSet<char> = self.transitions.iter().map(|transition| transition.input).collect();
+        let start_subset: BTreeSet<&str> = vec![self.start_state.as_str()].into_iter().collect();
+        let mut state_names: HashMap<BTreeSet<&str>, String> = HashMap::new();
+        let mut subsets_to_visit = VecDeque::new();
+        let mut accept_states = HashSet::new();
+        let mut transitions = Vec::new();
+        state_names.insert(start_subset.clone(), String::from("q0"));
+        subsets_to_visit.push_back(start_subset);
+        while let Some(subset) = subsets_to_visit.pop_front() {
+            let state = state_names[&subset].clone();
+            if subset.iter().any(|nfa_state| self.accept_states.contains(*nfa_state)) {
+                accept_states.insert(state.clone());
+            }
+            for input in alphabet.iter().sorted() {
+                let next_subset: BTreeSet<&str> = self.transitions.iter()
+                    .filter(|transition| transition.input == *input && subset.contains(transition.state.as_str()))
+                    .map(|transition| transition.next_state.as_str())
+                    .collect();
+                if next_subset.is_empty() {
+                    continue;
+                }
+                if !state_names.contains_key(&next_subset) {
+                    state_names.insert(next_subset.clone(), format!("q{}", state_names.len()));
+                    subsets_to_visit.push_back(next_subset.clone());
+                }
+                transitions.push(Transition { state: state.clone(), input: *input, next_state: state_names[&next_subset].clone() });
+            }
+        }
+        let origins: HashMap<String, BTreeSet<String>> = state_names.into_iter()
+            .map(|(subset, state)| (state, subset.into_iter().map(String::from).collect()))
+            .collect();
+        let state_metadata = origins.iter()
+            .map(|(state, subset)| (state.clone(), StateMetadata { origin: subset.iter().cloned().collect(), ..StateMetadata::default() }))
+            .collect();
+        let dfa = Dfa {
+            alphabet: Some(alphabet),
+            state_metadata,
+            ..Dfa::with_parts(self.name.clone(), String::from("q0"), accept_states, transitions)
+        };
+        (dfa, origins)
+    }
+}
+
+#[cfg(test)]
+mod determinization_tests {
+    use crate::Nfa;
+
+    #[test]
+    fn test_determinize() {
+        // Accepts all words over 'a' and 'b' whose second to last symbol is 'a'.
+        let mut nfa = Nfa::new("second to last is a", "p");
+        nfa.add_transition("p", 'a', "p");
+        nfa.add_transition("p", 'b', "p");
+        nfa.add_transition("p", 'a', "r");
+        nfa.add_transition("r", 'a', "s");
+        nfa.add_transition("r", 'b', "s");
+        nfa.add_accept_state("s");
+        let (dfa, origins) = nfa.determinize();
+        assert_eq!(dfa.get_all_states().len(), 4);
+        for word in &["bbab", "aa", "abba", "a", ""] {
+            assert_eq!(dfa.check(word).0, nfa.check(word), "{}", word);
+        }
+        assert_eq!(origins["q1"], vec!["p".to_string(), "r".to_string()].into_iter().collect());
+        assert!(dfa.to_dot().contains("\"q1\" [shape=circle, xlabel=\"{p, r}\"];"));
+    }
+
+    #[test]
+    fn test_to_dfa() {
+        // Accepts all words over 'a' and 'b' ending with 'a'.
+        let mut nfa = Nfa::new("ends with a", "p");
+        nfa.add_transition("p", 'a', "p");
+        nfa.add_transition("p", 'b', "p");
+        nfa.add_transition("p", 'a', "q");
+        nfa.add_accept_state("q");
+        let mut dfa = nfa.to_dfa();
+        assert_eq!(dfa.get_all_states(), vec!["{p}".to_string(), "{p,q}".to_string()].into_iter().collect());
+        assert!(dfa.check("aba").0 && !dfa.check("ab").0);
+        // The subsets {"p,q"} and {"p", "q"} would both be named "{p,q}".
+        let mut colliding_nfa = Nfa::new("collision", "p,q");
+        colliding_nfa.add_transition("p,q", 'a', "p");
+        colliding_nfa.add_transition("p,q", 'a', "q");
+        assert_eq!(colliding_nfa.to_dfa().get_all_states(), vec!["{p,q}".to_string(), "{p,q}'".to_string()].into_iter().collect());
+        dfa.minimize();
+        assert_eq!(dfa.get_all_states().len(), 2);
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// What determines the language of a state in an acyclic DFA: its acceptance and the next states of its transitions.
+type StateSignature = (bool, Vec<(char, String)>);
+
+/// Maintains a minimal acyclic DFA, like a dictionary, while words are added and removed, using Daciuk's algorithm for unsorted data.
+/// Besides the transitions by state, it keeps the number of incoming transitions of every state and a register which maps
+/// the signature of every state that is not being changed to that state. As all registered states are pairwise inequivalent,
+/// a changed state is merged with the registered state of the same signature, if there is one, with a single lookup.
+/// Unlike rebuilding and minimizing, the cost of adding or removing a word only depends on its length.
+#[derive(Debug, Clone)]
+pub struct DictionaryBuilder {
+    /// Holds everything but the transitions.
+    dfa: Dfa,
+    outgoing_transitions: HashMap<String, BTreeMap<char, String>>,
+    incoming_transition_counts: HashMap<String, usize>,
+    register: HashMap<StateSignature, String>,
+    next_state_index: usize,
+}
+
+impl From<Dfa> for DictionaryBuilder {
+    /// The DFA must be minimal, acyclic and must not have states from which no accept state can be reached.
+    fn from(mut dfa: Dfa) -> DictionaryBuilder {
+        let mut outgoing_transitions: HashMap<String, BTreeMap<char, String>> = dfa.get_all_mentioned_states().into_iter()
+            .map(|state| (state, BTreeMap::new()))
+            .collect();
+        let mut incoming_transition_counts = HashMap::new();
+        for transition in mem::take(&mut dfa.transitions) {
+            *incoming_transition_counts.entry(transition.next_state.clone()).or_insert(0) += 1;
+            outgoing_transitions.entry(transition.state).or_default().insert(transition.input, transition.next_state);
+        }
+        let mut builder = DictionaryBuilder { dfa, outgoing_transitions, incoming_transition_counts, register: HashMap::new(), next_state_index: 0 };
+        // The start state is never merged, as no other state of a minimal acyclic DFA accepts the same words.
+        let states = builder.outgoing_transitions.keys().filter(|state| **state != builder.dfa.start_state).cloned().collect_vec();
+        for state in states {
+            builder.register.insert(builder.get_state_signature(&state), state);
+        }
+        builder
+    }
+}
+
+impl DictionaryBuilder {
+    /// Adds the word to the language, so that the DFA stays minimal. Returns false if the word was already accepted.
+    /// The states on the path of the word's longest accepted prefix are cloned from the first state that is shared with other words on,
+    /// the rest of the word gets new states, and finally the states on the path are merged with equivalent states, from the end to the start.
+    pub fn add_word(&mut self, word: &str) -> bool {
+        let chars = word.chars().collect_vec();
+        let mut path = self.get_path(&chars);
+        if path.len() == chars.len() + 1 && self.dfa.accept_states.contains(path.last().unwrap()) {
+            return false;
+        }
+        self.prepare_path(&mut path, &chars);
+        for char in &chars[path.len() - 1..] {
+            let new_state = self.create_state();
+            self.add_transition(&path.last().unwrap().clone(), *char, &new_state);
+            path.push(new_state);
+        }
+        if let Some(alphabet) = &mut self.dfa.alphabet {
+            alphabet.extend(chars.iter().cloned());
+        }
+        self.dfa.accept_states.insert(path.last().unwrap().clone());
+        self.register_path(&path, &chars);
+        true
+    }
+
+    /// Removes the word from the language, so that the DFA stays minimal. Returns false if the word was not accepted.
+    /// States on the path of the word that no longer lead into an accept state are removed.
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        let chars = word.chars().collect_vec();
+        let mut path = self.get_path(&chars);
+        if path.len() < chars.len() + 1 || !self.dfa.accept_states.contains(path.last().unwrap()) {
+            return false;
+        }
+        self.prepare_path(&mut path, &chars);
+        let last_state = path.last().unwrap().clone();
+        self.dfa.accept_states.remove(&last_state);
+        self.dfa.accept_tags.remove(&last_state);
+        // Remove the states that became useless, starting at the end of the path.
+        while path.len() > 1 {
+            let state = path.last().unwrap();
+            if self.dfa.accept_states.contains(state) || !self.outgoing_transitions[state].is_empty() {
+                break;
+            }
+            let state = path.pop().unwrap();
+            self.outgoing_transitions.get_mut(path.last().unwrap()).unwrap().remove(&chars[path.len() - 1]);
+            self.remove_state(&state);
+        }
+        self.register_path(&path, &chars);
+        true
+    }
+
+    pub fn into_dfa(mut self) -> Dfa {
+        self.dfa.transitions = self.outgoing_transitions.into_iter()
+            .flat_map(|(state, transitions)| {
+                transitions.into_iter().map(move |(input, next_state)| Transition { state: state.clone(), input, next_state })
+            })
+            .sorted()
+            .collect();
+        self.dfa
+    }
+
+    /// Returns the states visited while reading the longest prefix of the word that has a path, starting with the start state.
+    fn get_path(&self, chars: &[char]) -> Vec<String> {
+        let mut path = vec![self.dfa.start_state.clone()];
+        while let Some(next_state) = chars.get(path.len() - 1).and_then(|char| self.outgoing_transitions[path.last().unwrap()].get(char)) {
+            path.push(next_state.clone());
+        }
+        path
+    }
+
+    /// Prepares the states of the path for being changed. States only reached by the path are removed from the register,
+    /// as their signature is about to change. The other states are replaced by unregistered copies, so that changing them only affects
+    /// the words sharing the prefix of the path. Once a state is copied, all following states are shared by the copy, too.
+    fn prepare_path(&mut self, path: &mut [String], chars: &[char]) {
+        for index in 1..path.len() {
+            let state = path[index].clone();
+            if self.incoming_transition_counts[&state] <= 1 {
+                self.unregister(&state);
+                continue;
+            }
+            let copy = self.create_state();
+            for (input, next_state) in self.outgoing_transitions[&state].clone() {
+                self.add_transition(&copy, input, &next_state);
+            }
+            if self.dfa.accept_states.contains(&state) {
+                self.dfa.accept_states.insert(copy.clone());
+            }
+            self.redirect_transition(&path[index - 1], chars[index - 1], &copy);
+            path[index] = copy;
+        }
+    }
+
+    /// Merges each state of the path with the registered state of the same signature, if there is one, and registers it otherwise,
+    /// starting at the end of the path. As all registered states are pairwise inequivalent, this restores minimality.
+    fn register_path(&mut self, path: &[String], chars: &[char]) {
+        for index in (1..path.len()).rev() {
+            let state = &path[index];
+            let signature = self.get_state_signature(state);
+            match self.register.get(&signature).cloned() {
+                Some(equivalent_state) => {
+                    self.redirect_transition(&path[index - 1], chars[index - 1], &equivalent_state);
+                    self.remove_state(state);
+                }
+                None => {
+                    self.register.insert(signature, state.clone());
+                }
+            }
+        }
+    }
+
+    fn get_state_signature(&self, state: &str) -> StateSignature {
+        let transitions = self.outgoing_transitions[state].iter().map(|(input, next_state)| (*input, next_state.clone())).collect();
+        (self.dfa.accept_states.contains(state), transitions)
+    }
+
+    fn unregister(&mut self, state: &str) {
+        let signature = self.get_state_signature(state);
+        if self.register.get(&signature).map_or(false, |registered_state| registered_state == state) {
+            self.register.remove(&signature);
+        }
+    }
+
+    /// Returns a new state without transitions, named like "q0", "q1" and so on.
+    fn create_state(&mut self) -> String {
+        loop {
+            let state = format!("q{}", self.next_state_index);
+            self.next_state_index += 1;
+            if !self.outgoing_transitions.contains_key(&state) {
+                self.outgoing_transitions.insert(state.clone(), BTreeMap::new());
+                self.incoming_transition_counts.insert(state.clone(), 0);
+                return state;
+            }
+        }
+    }
+
+    fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.outgoing_transitions.get_mut(state).unwrap().insert(input, next_state.to_string());
+        *self.incoming_transition_counts.entry(next_state.to_string()).or_insert(0) += 1;
+    }
+
+    fn redirect_transition(&mut self, state: &str, input: char, next_state: &str) {
+        let previous_next_state = self.outgoing_transitions.get_mut(state).unwrap().insert(input, next_state.to_string());
+        if let Some(previous_next_state) = previous_next_state {
+            *self.incoming_transition_counts.get_mut(&previous_next_state).unwrap() -= 1;
+        }
+        *self.incoming_transition_counts.entry(next_state.to_string()).or_insert(0) += 1;
+    }
+
+    /// Removes an unregistered state that is no longer reached by any transition.
+    fn remove_state(&mut self, state: &str) {
+        for next_state in self.outgoing_transitions.remove(state).unwrap().values() {
+            *self.incoming_transition_counts.get_mut(next_state).unwrap() -= 1;
+        }
+        self.incoming_transition_counts.remove(state);
+        self.dfa.accept_states.remove(state);
+        self.dfa.accept_tags.remove(state);
+    }
+}
+
+impl Dfa {
+    /// Adds the word to the language of a minimal acyclic DFA, like a dictionary, so that the DFA stays minimal (see `DictionaryBuilder`).
+    /// Returns false if the word was already accepted. As the DFA is converted from and to a `DictionaryBuilder` on every call,
+    /// the builder should be used directly for adding or removing many words.
+    /// The DFA must be minimal, acyclic and must not have states from which no accept state can be reached.
+    pub fn add_word(&mut self, word: &str) -> bool {
+        self.edit_dictionary(|builder| builder.add_word(word))
+    }
+
+    /// Removes the word from the language of a minimal acyclic DFA, so that the DFA stays minimal (see `add_word`).
+    /// Returns false if the word was not accepted.
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        self.edit_dictionary(|builder| builder.remove_word(word))
+    }
+
+    fn edit_dictionary<F: FnOnce(&mut DictionaryBuilder) -> bool>(&mut self, edit: F) -> bool {
+        let mut builder = DictionaryBuilder::from(self.clone());
+        let is_changed = edit(&mut builder);
+        if is_changed {
+            *self = builder.into_dfa();
+        }
+        is_changed
+    }
+}
+
+#[cfg(test)]
+mod dictionary_tests {
+    use itertools::Itertools;
+
+    use crate::{DictionaryBuilder, Dfa};
+
+    #[test]
+    fn test_add_word_and_remove_word() {
+        let mut dfa = Dfa::from_table(&["q0"], &[], &[&[]], 0, &[]).unwrap();
+        for word in &["tap", "taps", "top", "tops"] {
+            assert!(dfa.add_word(word));
+        }
+        assert!(!dfa.add_word("top"), "the word is already accepted");
+        // The minimal DFA shares both the prefix "t" and the suffix "p" with an optional "s".
+        assert_eq!(dfa.get_all_states().len(), 5);
+        assert!(dfa.check("taps").0);
+        assert!(!dfa.check("ta").0);
+        assert!(dfa.remove_word("taps"));
+        assert!(!dfa.remove_word("taps"), "the word is no longer accepted");
+        assert!(!dfa.check("taps").0);
+        assert!(dfa.check("tops").0 && dfa.check("tap").0 && dfa.check("top").0);
+        assert_eq!(dfa.get_all_states().len(), 6, "\"ta\" and \"to\" no longer lead into the same state");
+        let mut minimized_dfa = dfa.clone();
+        minimized_dfa.minimize();
+        assert_eq!(minimized_dfa.get_all_states().len(), dfa.get_all_states().len());
+    }
+    #[test]
+    fn test_dictionary_builder() {
+        let mut builder = DictionaryBuilder::from(Dfa::from_table(&["q0"], &[], &[&[]], 0, &[]).unwrap());
+        // All words of length 1 to 3 over 'a' and 'b' without "bb", in an unsorted order.
+        let words = (1..=3)
+            .flat_map(|length| (0..length).map(|_| vec!['a', 'b']).multi_cartesian_product())
+            .map(|chars| chars.into_iter().collect::<String>())
+            .filter(|word| !word.contains("bb"))
+            .sorted_by_key(|word| word.chars().rev().collect::<String>())
+            .collect_vec();
+        for word in &words {
+            assert!(builder.add_word(word));
+        }
+        assert!(builder.remove_word("aba"));
+        assert!(!builder.remove_word("bb"));
+        let dfa = builder.into_dfa();
+        for word in &words {
+            assert_eq!(dfa.check(word).0, *word != "aba", "{}", word);
+        }
+        let mut minimized_dfa = dfa.clone();
+        minimized_dfa.minimize();
+        assert_eq!(minimized_dfa.get_all_states().len(), dfa.get_all_states().len());
+        // Words can be added to an existing dictionary.
+        let mut builder = DictionaryBuilder::from(dfa);
+        assert!(builder.add_word("aba"));
+        assert!(!builder.add_word("ab"));
+        let dfa = builder.into_dfa();
+        assert!(words.iter().all(|word| dfa.check(word).0));
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::table::escape_markdown;
+use crate::Dfa;
+
+/// The discrimination tree of the [Kearns–Vazirani algorithm](https://en.wikipedia.org/wiki/Induction_of_regular_languages#Learning_from_queries),
+/// which TTT refines, as computed by `Dfa::discrimination_tree`. Its leaves are labelled by access words, which stand for the states
+/// of the hypothesis, and its inner nodes by discriminators, which are suffixes telling these states apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscriminationTree {
+    root: Option<DiscriminationNode>,
+}
+
+/// A node of a `DiscriminationTree`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscriminationNode {
+    /// A leaf standing for the state that is reached by the access word.
+    Leaf(String),
+    /// An inner node whose subtrees contain the access words that lead to rejection or acceptance when followed by the discriminator.
+    Inner { discriminator: String, rejecting: Box<DiscriminationNode>, accepting: Box<DiscriminationNode> },
+}
+
+impl DiscriminationTree {
+    /// Returns the root of the tree, or None if the tree has no access words.
+    pub fn get_root(&self) -> Option<&DiscriminationNode> {
+        self.root.as_ref()
+    }
+
+    /// Returns the access words of the leaves from left to right, where rejecting subtrees come before accepting ones.
+    pub fn get_access_words(&self) -> Vec<&str> {
+        let mut access_words = Vec::new();
+        let mut pending_nodes: Vec<&DiscriminationNode> = self.root.iter().collect();
+        while let Some(node) = pending_nodes.pop() {
+            match node {
+                DiscriminationNode::Leaf(access_word) => access_words.push(access_word.as_str()),
+                DiscriminationNode::Inner { rejecting, accepting, .. } => {
+                    pending_nodes.push(accepting);
+                    pending_nodes.push(rejecting);
+                }
+            }
+        }
+        access_words
+    }
+
+    /// Sifts the word down the tree and returns the access word of the leaf it ends up in, that is, the state of the hypothesis
+    /// the word leads to. The membership of the word followed by each discriminator on the way is decided by `is_accepted`.
+    /// Returns None if the tree has no access words.
+    pub fn sift(&self, word: &str, is_accepted: impl Fn(&str) -> bool) -> Option<&str> {
+        let mut node = self.root.as_ref()?;
+        loop {
+            match node {
+                DiscriminationNode::Leaf(access_word) => return Some(access_word),
+                DiscriminationNode::Inner { discriminator, rejecting, accepting } => {
+                    node = match is_accepted(&format!("{}{}", word, discriminator)) {
+                        true => accepting,
+                        false => rejecting,
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Renders the tree as a nested Markdown list. The children of an inner node are marked with 0 for the rejecting
+/// and 1 for the accepting subtree, and ε stands for the empty word.
+impl fmt::Display for DiscriminationTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Contains the nodes that still have to be written, together with their depth and the mark of their subtree.
+        let mut pending_nodes = self.root.iter().map(|node| (node, 0, "")).collect_vec();
+        while let Some((node, depth, mark)) = pending_nodes.pop() {
+            write!(f, "{}- {}", "  ".repeat(depth), mark)?;
+            match node {
+                DiscriminationNode::Leaf(access_word) => writeln!(f, "access word {}", label(access_word))?,
+                DiscriminationNode::Inner { discriminator, rejecting, accepting } => {
+                    writeln!(f, "discriminator {}", label(discriminator))?;
+                    pending_nodes.push((accepting, depth + 1, "1: "));
+                    pending_nodes.push((rejecting, depth + 1, "0: "));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn label(word: &str) -> String {
+    match word.is_empty() {
+        true => "ε".to_string(),
+        false => escape_markdown(word),
+    }
+}
+
+impl Dfa {
+    /// Builds the discrimination tree for the access words, using this DFA to answer the membership queries of Kearns–Vazirani.
+    /// The access words are sifted into the tree one after another. When a word ends up in the leaf of another one,
+    /// the leaf is split by the first discriminator that tells both apart. Words that none of the discriminators
+    /// can tell apart from an access word already in the tree are left out, as they lead to the same state of the hypothesis.
+    pub fn discrimination_tree(&self, access_words: &[&str], discriminators: &[&str]) -> DiscriminationTree {
+        let is_accepted = |word: &str| self.check(word).0;
+        let mut root: Option<DiscriminationNode> = None;
+        for access_word in access_words {
+            let mut node = match root.as_mut() {
+                Some(node) => node,
+                None => {
+                    root = Some(DiscriminationNode::Leaf(access_word.to_string()));
+                    continue;
+                }
+            };
+            while let DiscriminationNode::Inner { discriminator, rejecting, accepting } = node {
+                node = match is_accepted(&format!("{}{}", access_word, discriminator)) {
+                    true => accepting,
+                    false => rejecting,
+                };
+            }
+            let leaf_word = match node {
+                DiscriminationNode::Leaf(leaf_word) => leaf_word.clone(),
+                DiscriminationNode::Inner { .. } => continue,
+            };
+            let distinguishing_discriminator = discriminators.iter().find(|discriminator| {
+                is_accepted(&format!("{}{}", leaf_word, discriminator)) != is_accepted(&format!("{}{}", access_word, discriminator))
+            });
+            if let Some(discriminator) = distinguishing_discriminator {
+                let leaf = Box::new(DiscriminationNode::Leaf(leaf_word.clone()));
+                let new_leaf = Box::new(DiscriminationNode::Leaf(access_word.to_string()));
+                let (rejecting, accepting) = match is_accepted(&format!("{}{}", access_word, discriminator)) {
+                    true => (leaf, new_leaf),
+                    false => (new_leaf, leaf),
+                };
+                *node = DiscriminationNode::Inner { discriminator: discriminator.to_string(), rejecting, accepting };
+            }
+        }
+        DiscriminationTree { root }
+    }
+}
+
+#[cfg(test)]
+mod discrimination_tree_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_discrimination_tree() {
+        // Accepts words over 'a' and 'b' ending with "ab".
+        let dfa = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(1), Some(2)], &[Some(1), Some(0)]], 0, &[2]).unwrap();
+        let tree = dfa.discrimination_tree(&["", "a", "ab", "aa"], &["", "b"]);
+        assert_eq!(tree.get_access_words(), ["", "ab", "a"], "\"aa\" leads to the same state as \"a\"");
+        let is_accepted = |word: &str| dfa.check(word).0;
+        assert_eq!(tree.sift("b", is_accepted), Some(""));
+        assert_eq!(tree.sift("aab", is_accepted), Some("ab"));
+        assert_eq!(tree.sift("ba", is_accepted), Some("a"));
+        assert_eq!(
+            tree.to_string(),
+            "- discriminator b\n  - 0: discriminator ε\n    - 0: access word ε\n    - 1: access word ab\n  - 1: access word a\n",
+        );
+        assert_eq!(dfa.discrimination_tree(&[], &[""]).get_root(), None);
+    }
+}
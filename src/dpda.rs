@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bounded, Fuel, PdaConfiguration, PdaTransition};
+
+/// The simulation of deterministic pushdown automata gives up after this many steps by default, because transitions reading nothing
+/// might push symbols forever. It is lower than for nondeterministic machines, as every step of the run is recorded along with its stack.
+const MAX_STEPS: usize = 10_000;
+
+/// Reports that a transition cannot be added to a deterministic pushdown automaton, because the state already has
+/// a transition that applies in the same situation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DpdaConflict {
+    pub state: String,
+    /// The input of the existing transition, or None if it reads nothing.
+    pub input: Option<char>,
+    /// The popped symbol of the existing transition, or None if it pops nothing.
+    pub pop: Option<char>,
+}
+
+impl fmt::Display for DpdaConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_epsilon = |symbol: Option<char>| symbol.map(String::from).unwrap_or_else(|| String::from("ε"));
+        write!(f, "the state {} already has a transition for input {} and stack symbol {}", self.state, or_epsilon(self.input), or_epsilon(self.pop))
+    }
+}
+
+impl std::error::Error for DpdaConflict {}
+
+/// # [Deterministic pushdown automaton](https://en.wikipedia.org/wiki/Deterministic_pushdown_automaton)
+/// A pushdown automaton (see `Pda`) in which at most one transition applies in every configuration, so there is exactly one run
+/// per input that can be followed step by step. It recognizes the deterministic context-free languages, which include the languages
+/// of most programming languages. An input is accepted if it is read completely and the automaton ends up in an accept state,
+/// possibly after some more transitions that read nothing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dpda {
+    name: String,
+    start_state: String,
+    start_stack_symbol: char,
+    accept_states: HashSet<String>,
+    transitions: Vec<PdaTransition>,
+}
+
+impl Dpda {
+    /// Creates a deterministic pushdown automaton without transitions and accept states.
+    pub fn new(name: &str, start_state: &str, start_stack_symbol: char) -> Dpda {
+        Dpda {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            start_stack_symbol,
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    /// Adds a transition like `Pda::add_transition`. Fails if the state already has a transition that could apply in the same configuration,
+    /// which is the case if both read the same symbol or one of them reads nothing, and both pop the same symbol or one of them pops nothing.
+    pub fn add_transition(&mut self, state: &str, input: Option<char>, pop: Option<char>, push: &str, next_state: &str) -> Result<(), DpdaConflict> {
+        let overlaps = |first: Option<char>, second: Option<char>| first.is_none() || second.is_none() || first == second;
+        if let Some(transition) = self.transitions.iter()
+            .find(|transition| transition.state == state && overlaps(transition.input, input) && overlaps(transition.pop, pop)) {
+            return Err(DpdaConflict { state: state.to_string(), input: transition.input, pop: transition.pop });
+        }
+        self.transitions.push(PdaTransition {
+            state: state.to_string(),
+            input,
+            pop,
+            push: push.to_string(),
+            next_state: next_state.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Checks whether a certain input is accepted by the DPDA.
+    /// Like `Dfa::check`, additionally returns the configurations that have been traversed while processing the input,
+    /// starting with the initial one. The run stops when no transition applies. As transitions reading nothing might push symbols forever,
+    /// the run is rejected after `MAX_STEPS` steps.
+    pub fn check(&self, input: &str) -> (bool, Vec<PdaConfiguration>) {
+        let (is_accepted, traversed_configurations) = self.run(input, &mut Fuel::new(MAX_STEPS));
+        (is_accepted == Some(true), traversed_configurations)
+    }
+
+    /// Checks the input like `check`, but takes at most as many steps as there is fuel,
+    /// and tells apart rejected inputs from inputs for which the fuel ran out.
+    pub fn check_with_fuel(&self, input: &str, fuel: &mut Fuel) -> Bounded<(bool, Vec<PdaConfiguration>)> {
+        match self.run(input, fuel) {
+            (Some(is_accepted), traversed_configurations) => Bounded::Done((is_accepted, traversed_configurations)),
+            (None, _) => Bounded::Exhausted,
+        }
+    }
+
+    /// Follows the only run on the input. Returns whether it accepts, or None if the fuel ran out, together with the traversed configurations.
+    fn run(&self, input: &str, fuel: &mut Fuel) -> (Option<bool>, Vec<PdaConfiguration>) {
+        let input: Vec<char> = input.chars().collect();
+        let mut state = self.start_state.as_str();
+        let mut position = 0;
+        let mut stack = vec![self.start_stack_symbol];
+        let mut traversed_configurations = vec![PdaConfiguration::new(state, position, &stack, &input)];
+        loop {
+            if position == input.len() && self.accept_states.contains(state) {
+                return (Some(true), traversed_configurations);
+            }
+            let next_configuration = self.transitions.iter()
+                .filter(|transition| transition.state == state)
+                .find_map(|transition| transition.take(position, &stack, &input).map(|(position, stack)| (transition.next_state.as_str(), position, stack)));
+            match next_configuration {
+                Some(_) if !fuel.consume() => return (None, traversed_configurations),
+                Some((next_state, next_position, next_stack)) => {
+                    state = next_state;
+                    position = next_position;
+                    stack = next_stack;
+                    traversed_configurations.push(PdaConfiguration::new(state, position, &stack, &input));
+                }
+                // The automaton is stuck, either before reading the whole input or in a state that does not accept.
+                None => return (Some(false), traversed_configurations),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod dpda_tests {
+    use crate::{Bounded, Dpda, DpdaConflict, Fuel};
+
+    #[test]
+    fn test_check() {
+        // Accepts the words a^n b^n for n ≥ 1.
+        let mut dpda = Dpda::new("anbn", "q0", 'Z');
+        dpda.add_transition("q0", Some('a'), None, "A", "q0").unwrap();
+        dpda.add_transition("q0", Some('b'), Some('A'), "", "q1").unwrap();
+        dpda.add_transition("q1", Some('b'), Some('A'), "", "q1").unwrap();
+        dpda.add_transition("q1", None, Some('Z'), "Z", "accept").unwrap();
+        dpda.add_accept_state("accept");
+        let (is_accepted, configurations) = dpda.check("aabb");
+        assert!(is_accepted);
+        let configurations = configurations.iter().map(|configuration| configuration.to_string()).collect::<Vec<_>>();
+        assert_eq!(configurations, vec!["(q0, aabb, Z)", "(q0, abb, AZ)", "(q0, bb, AAZ)", "(q1, b, AZ)", "(q1, ε, Z)", "(accept, ε, Z)"]);
+        let (is_accepted, configurations) = dpda.check("aab");
+        assert!(!is_accepted);
+        assert_eq!(configurations.last().unwrap().to_string(), "(q1, ε, AZ)");
+        assert_eq!(dpda.add_transition("q1", None, Some('A'), "", "q0"), Err(DpdaConflict { state: "q1".to_string(), input: Some('b'), pop: Some('A') }));
+        // Pushes 'A' forever without reading anything.
+        let mut endless_dpda = Dpda::new("endless", "q0", 'Z');
+        endless_dpda.add_transition("q0", None, None, "A", "q0").unwrap();
+        assert_eq!(endless_dpda.check_with_fuel("", &mut Fuel::new(10)), Bounded::Exhausted);
+    }
+}
@@ -0,0 +1,216 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::Dfa;
+
+/// The costs of the operations that turn one word into another. Copying a symbol unchanged is free. Without explicit costs,
+/// every substitution, insertion and deletion costs 1, which yields the [Levenshtein distance](https://en.wikipedia.org/wiki/Levenshtein_distance).
+/// Non-uniform costs model e.g. keys that are typed by mistake because they are next to each other, or substitution matrices for sequence alignment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditCosts {
+    substitutions: HashMap<(char, char), u64>,
+    insertions: HashMap<char, u64>,
+    deletions: HashMap<char, u64>,
+    default_cost: u64,
+}
+
+impl Default for EditCosts {
+    fn default() -> EditCosts {
+        EditCosts::new(1)
+    }
+}
+
+impl EditCosts {
+    /// Creates costs where every operation without an explicit cost has the default cost.
+    pub fn new(default_cost: u64) -> EditCosts {
+        EditCosts { substitutions: HashMap::new(), insertions: HashMap::new(), deletions: HashMap::new(), default_cost }
+    }
+
+    /// Sets the cost of replacing the symbol `from` by the symbol `to`.
+    pub fn set_substitution_cost(&mut self, from: char, to: char, cost: u64) {
+        self.substitutions.insert((from, to), cost);
+    }
+
+    /// Sets the cost of inserting the symbol.
+    pub fn set_insertion_cost(&mut self, symbol: char, cost: u64) {
+        self.insertions.insert(symbol, cost);
+    }
+
+    /// Sets the cost of deleting the symbol.
+    pub fn set_deletion_cost(&mut self, symbol: char, cost: u64) {
+        self.deletions.insert(symbol, cost);
+    }
+
+    /// Returns the cost of replacing the symbol `from` by the symbol `to`, which is 0 if both are equal.
+    pub fn get_substitution_cost(&self, from: char, to: char) -> u64 {
+        match from == to {
+            true => 0,
+            false => self.substitutions.get(&(from, to)).cloned().unwrap_or(self.default_cost),
+        }
+    }
+
+    pub fn get_insertion_cost(&self, symbol: char) -> u64 {
+        self.insertions.get(&symbol).cloned().unwrap_or(self.default_cost)
+    }
+
+    pub fn get_deletion_cost(&self, symbol: char) -> u64 {
+        self.deletions.get(&symbol).cloned().unwrap_or(self.default_cost)
+    }
+
+    /// Returns the lowest total cost of operations turning the word `from` into the word `to`, computed with the
+    /// [Wagner–Fischer algorithm](https://en.wikipedia.org/wiki/Wagner%E2%80%93Fischer_algorithm).
+    pub fn distance(&self, from: &str, to: &str) -> u64 {
+        let from = from.chars().collect_vec();
+        let to = to.chars().collect_vec();
+        // The distances from the prefix of `from` processed so far to all prefixes of `to`.
+        let mut distances = vec![0];
+        for symbol in &to {
+            distances.push(distances.last().unwrap() + self.get_insertion_cost(*symbol));
+        }
+        for from_symbol in &from {
+            let mut next_distances = vec![distances[0] + self.get_deletion_cost(*from_symbol)];
+            for (index, to_symbol) in to.iter().enumerate() {
+                let distance = (distances[index] + self.get_substitution_cost(*from_symbol, *to_symbol))
+                    .min(distances[index + 1] + self.get_deletion_cost(*from_symbol))
+                    .min(next_distances[index] + self.get_insertion_cost(*to_symbol));
+                next_distances.push(distance);
+            }
+            distances = next_distances;
+        }
+        *distances.last().unwrap()
+    }
+
+    /// Builds a weighted transducer with a single state that rewrites words over the alphabet with these costs.
+    /// It copies every symbol for free and has a transition for every substitution, insertion and deletion.
+    pub fn to_transducer(&self, alphabet: &HashSet<char>) -> WeightedTransducer {
+        let mut transducer = WeightedTransducer::new("edit distance", "q0");
+        transducer.add_accept_state("q0");
+        for from in alphabet.iter().sorted() {
+            for to in alphabet.iter().sorted() {
+                transducer.add_transition("q0", Some(*from), Some(*to), self.get_substitution_cost(*from, *to), "q0");
+            }
+            transducer.add_transition("q0", Some(*from), None, self.get_deletion_cost(*from), "q0");
+            transducer.add_transition("q0", None, Some(*from), self.get_insertion_cost(*from), "q0");
+        }
+        transducer
+    }
+}
+
+/// Describes to which next state a weighted transducer may switch while reading an input symbol (or nothing, if `input` is None)
+/// and writing an output symbol (or nothing, if `output` is None), and what it costs to do so.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct WeightedTransition {
+    state: String,
+    input: Option<char>,
+    output: Option<char>,
+    cost: u64,
+    next_state: String,
+}
+
+/// # Weighted finite state transducer
+/// A nondeterministic transducer whose transitions have costs, like the one built by `EditCosts::to_transducer`.
+/// The cost of a translation is the lowest total cost of the runs that read the input completely, write the output and end in an accept state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedTransducer {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<WeightedTransition>,
+}
+
+impl WeightedTransducer {
+    /// Creates a weighted transducer without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> WeightedTransducer {
+        WeightedTransducer {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: Option<char>, output: Option<char>, cost: u64, next_state: &str) {
+        self.transitions.push(WeightedTransition { state: state.to_string(), input, output, cost, next_state: next_state.to_string() });
+    }
+
+    /// Finds the word accepted by the dictionary that the input translates into with the lowest cost, together with that cost,
+    /// or None if the input cannot be translated into any word of the dictionary. With the transducer of `EditCosts::to_transducer`,
+    /// this is the best correction of a misspelled word. The input, the transducer and the dictionary are composed on the fly,
+    /// and [Dijkstra's algorithm](https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm) searches the composition, so only the
+    /// cheap parts are explored. Among the cheapest words, the shortest and then the alphabetically smallest one is returned.
+    pub fn best_correction(&self, input: &str, dictionary: &Dfa) -> Option<(String, u64)> {
+        let mut dictionary = dictionary.clone();
+        dictionary.materialize_missing_transitions();
+        let input = input.chars().collect_vec();
+        let mut visited_configurations: HashSet<(usize, &str, &str)> = HashSet::new();
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((0, 0, String::new(), 0, self.start_state.as_str(), dictionary.start_state.as_str())));
+        while let Some(Reverse((cost, length, output, position, state, dictionary_state))) = queue.pop() {
+            if !visited_configurations.insert((position, state, dictionary_state)) {
+                continue;
+            }
+            if position == input.len() && self.accept_states.contains(state) && dictionary.accept_states.contains(dictionary_state) {
+                return Some((output, cost));
+            }
+            for transition in self.transitions.iter().filter(|transition| transition.state == state) {
+                let next_position = match transition.input {
+                    None => position,
+                    Some(symbol) if input.get(position) == Some(&symbol) => position + 1,
+                    Some(_) => continue,
+                };
+                let (next_dictionary_state, next_output, next_length) = match transition.output {
+                    None => (dictionary_state, output.clone(), length),
+                    Some(symbol) => match dictionary.get_transition(dictionary_state, &symbol) {
+                        Some(dictionary_transition) => (dictionary_transition.next_state.as_str(), format!("{}{}", output, symbol), length + 1),
+                        None => continue,
+                    },
+                };
+                if !visited_configurations.contains(&(next_position, transition.next_state.as_str(), next_dictionary_state)) {
+                    queue.push(Reverse((cost + transition.cost, next_length, next_output, next_position, transition.next_state.as_str(), next_dictionary_state)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod edit_distance_tests {
+    use crate::{Dfa, EditCosts};
+
+    #[test]
+    fn test_distance() {
+        let levenshtein = EditCosts::default();
+        assert_eq!(levenshtein.distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein.distance("", "abc"), 3);
+        let mut costs = EditCosts::new(4);
+        costs.set_substitution_cost('A', 'G', 1);
+        costs.set_deletion_cost('T', 2);
+        assert_eq!(costs.distance("ACT", "GC"), 3);
+        assert_eq!(costs.distance("GC", "ACT"), 8);
+    }
+
+    #[test]
+    fn test_best_correction() {
+        let mut dictionary = Dfa::from_table(&["q0"], &[], &[&[]], 0, &[]).unwrap();
+        for word in &["cat", "cut", "cart"] {
+            dictionary.add_word(word);
+        }
+        let mut costs = EditCosts::default();
+        let alphabet = "acrtuxy".chars().collect();
+        assert_eq!(costs.to_transducer(&alphabet).best_correction("cat", &dictionary), Some(("cat".to_string(), 0)));
+        assert_eq!(costs.to_transducer(&alphabet).best_correction("cxt", &dictionary), Some(("cat".to_string(), 1)));
+        // Typing 'y' instead of 'u' is a common mistake, as both keys are next to each other.
+        costs.set_substitution_cost('y', 'u', 0);
+        let transducer = costs.to_transducer(&alphabet);
+        assert_eq!(transducer.best_correction("cyt", &dictionary), Some(("cut".to_string(), 0)));
+        assert_eq!(transducer.best_correction("cyrt", &dictionary), Some(("cut".to_string(), 1)), "cart costs 1 as well, but is longer");
+    }
+}
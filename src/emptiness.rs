@@ -0,0 +1,68 @@
+use std::collections::{HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Returns the shortest accepted word, alphabetically smallest first, or None if no word is accepted.
+    /// The states are visited using the breath first algorithm, reading the symbols in alphabetical order,
+    /// so the first accept state that is reached is reached by the wanted word.
+    pub fn shortest_accepted_word(&self) -> Option<String> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let mut visited_states: HashSet<&str> = HashSet::new();
+        let mut states_to_visit = VecDeque::new();
+        visited_states.insert(&dfa.start_state);
+        states_to_visit.push_back((String::new(), dfa.start_state.as_str()));
+        while let Some((word, state)) = states_to_visit.pop_front() {
+            if dfa.accept_states.contains(state) {
+                return Some(word);
+            }
+            for transition in dfa.transitions.iter().filter(|transition| transition.state == state).sorted() {
+                if visited_states.insert(&transition.next_state) {
+                    states_to_visit.push_back((format!("{}{}", word, transition.input), &transition.next_state));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the shortest word over the alphabet that is rejected, alphabetically smallest first, or None if every word is accepted.
+    /// The alphabet is inferred from the transitions unless it is set.
+    pub fn shortest_rejected_word(&self) -> Option<String> {
+        self.complement().shortest_accepted_word()
+    }
+
+    /// Checks whether the DFA accepts no word at all. Otherwise, `shortest_accepted_word` returns a witness.
+    pub fn is_empty(&self) -> bool {
+        self.shortest_accepted_word().is_none()
+    }
+
+    /// Checks whether the DFA accepts every word over its alphabet, which is inferred from the transitions unless it is set.
+    /// Otherwise, `shortest_rejected_word` returns a witness.
+    pub fn is_universal(&self) -> bool {
+        self.shortest_rejected_word().is_none()
+    }
+}
+
+#[cfg(test)]
+mod emptiness_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_is_empty_and_is_universal() {
+        // Accepts words over 'a' and 'b' containing "ab".
+        let contains_ab = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(1), Some(2)], &[Some(2), Some(2)]], 0, &[2]).unwrap();
+        assert_eq!(contains_ab.shortest_accepted_word(), Some("ab".to_string()));
+        assert_eq!(contains_ab.shortest_rejected_word(), Some("".to_string()));
+        assert!(!contains_ab.is_empty() && !contains_ab.is_universal());
+        assert_eq!(contains_ab.complement().shortest_rejected_word(), Some("ab".to_string()));
+        // The accept state cannot be reached.
+        let unreachable = Dfa::from_table(&["q0", "q1"], &['a'], &[&[Some(0)], &[Some(1)]], 0, &[1]).unwrap();
+        assert_eq!(unreachable.shortest_accepted_word(), None);
+        assert!(unreachable.is_empty());
+        assert!(unreachable.complement().is_universal());
+        assert_eq!(unreachable.complement().shortest_rejected_word(), None);
+    }
+}
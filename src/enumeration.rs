@@ -0,0 +1,286 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// Iterates over all minimal complete DFAs with up to a maximum number of states over an alphabet (see `Dfa::enumerate_minimal_dfas`).
+#[derive(Debug, Clone)]
+pub struct MinimalDfas {
+    alphabet: Vec<char>,
+    max_states: usize,
+    state_count: usize,
+    /// The next state for every state and symbol, row by row.
+    table: Vec<usize>,
+    /// The i-th bit is set if the i-th state is accepting.
+    accept_mask: usize,
+}
+
+/// Iterates over the words accepted by a DFA, shortest first and then alphabetically (see `Dfa::enumerate_words`).
+#[derive(Debug, Clone)]
+pub struct AcceptedWords {
+    dfa: Dfa,
+    alphabet: Vec<char>,
+    productive_states: HashSet<String>,
+    /// The length of the words that are currently enumerated.
+    length: usize,
+    /// The productive states that are reached by words of the current length.
+    frontier: HashSet<String>,
+    /// For every length up to the current one, the states from which a word of exactly that length is accepted.
+    accepting_within: Vec<HashSet<String>>,
+    /// Prefixes of words of the current length that still need to be extended, with the state they lead into, alphabetically smallest on top.
+    prefixes_to_extend: Vec<(String, String)>,
+}
+
+impl Dfa {
+    /// Lazily enumerates the accepted words, shortest first and then alphabetically. The words of each length are found by
+    /// a depth first search that only extends prefixes which can still be completed to an accepted word of that length,
+    /// so only one word after the other is held in memory. The iterator ends once no longer word can be accepted, which only happens for finite languages.
+    pub fn enumerate_words(&self) -> AcceptedWords {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let alphabet = dfa.get_alphabet().into_iter().sorted().collect_vec();
+        let productive_states = dfa.get_productive_states();
+        let mut frontier = HashSet::new();
+        let mut prefixes_to_extend = Vec::new();
+        if productive_states.contains(&dfa.start_state) {
+            frontier.insert(dfa.start_state.clone());
+        }
+        if dfa.accept_states.contains(&dfa.start_state) {
+            prefixes_to_extend.push((String::new(), dfa.start_state.clone()));
+        }
+        let accepting_within = vec![dfa.accept_states.clone()];
+        AcceptedWords { dfa, alphabet, productive_states, length: 0, frontier, accepting_within, prefixes_to_extend }
+    }
+
+    /// Returns the k smallest accepted words in shortlex order, i.e. shortest first and then alphabetically, or fewer if the language is smaller.
+    /// Every word corresponds to one path through the DFA, so this is a k-shortest-paths search: the paths are extended from a priority queue,
+    /// smallest prefix first, and every state is extended at most k times. Later prefixes reaching a state cannot lead to one of the k smallest words,
+    /// as the k earlier prefixes can be completed the same way to smaller words. Only states from which an accept state can be reached are extended,
+    /// so the running time depends on k and the size of the DFA, but not on the size of the language.
+    pub fn k_shortest_words(&self, k: usize) -> Vec<String> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let productive_states = dfa.get_productive_states();
+        let mut extensions: HashMap<&str, usize> = HashMap::new();
+        let mut words = Vec::new();
+        let mut queue = BinaryHeap::new();
+        if productive_states.contains(&dfa.start_state) {
+            queue.push(Reverse((0, String::new(), dfa.start_state.as_str())));
+        }
+        while let Some(Reverse((length, word, state))) = queue.pop() {
+            if words.len() == k {
+                break;
+            }
+            let extension_count = extensions.entry(state).or_insert(0);
+            if *extension_count == k {
+                continue;
+            }
+            *extension_count += 1;
+            if dfa.accept_states.contains(state) {
+                words.push(word.clone());
+            }
+            for transition in dfa.transitions.iter().filter(|transition| transition.state == state && productive_states.contains(&transition.next_state)) {
+                queue.push(Reverse((length + 1, format!("{}{}", word, transition.input), transition.next_state.as_str())));
+            }
+        }
+        words
+    }
+
+    /// Enumerates all minimal complete DFAs with at most `max_states` states over the alphabet, ordered by their number of states.
+    /// No two of them are isomorphic, which for minimal DFAs means that no two of them accept the same language.
+    /// States are named "q0", "q1" and so on in the order in which the breath first algorithm discovers them, reading symbols
+    /// in alphabetical order. Every DFA whose states are all reachable has exactly one such numbering, so each language shows up once.
+    /// The number of DFAs grows very fast, so this is only feasible for a few states and symbols.
+    pub fn enumerate_minimal_dfas(max_states: usize, alphabet: &[char]) -> MinimalDfas {
+        let alphabet = alphabet.iter().cloned().sorted().dedup().collect_vec();
+        MinimalDfas { table: vec![0; alphabet.len()], alphabet, max_states, state_count: 1, accept_mask: 0 }
+    }
+}
+
+impl MinimalDfas {
+    /// Checks whether the states are numbered in breath first order, which also means that all states are reachable.
+    fn is_canonical(&self) -> bool {
+        let mut discovered_states = 1;
+        for (index, next_state) in self.table.iter().enumerate() {
+            if index / self.alphabet.len() >= discovered_states || *next_state > discovered_states {
+                return false;
+            }
+            if *next_state == discovered_states {
+                discovered_states += 1;
+            }
+        }
+        discovered_states == self.state_count
+    }
+
+    /// Checks whether all states are distinguishable, by refining the partition into accepting and rejecting states
+    /// until states of the same class lead into the same classes for every symbol.
+    fn is_minimal(&self) -> bool {
+        let mut classes = (0..self.state_count).map(|state| self.accept_mask >> state & 1).collect_vec();
+        loop {
+            let signatures = (0..self.state_count)
+                .map(|state| {
+                    let row = &self.table[state * self.alphabet.len()..(state + 1) * self.alphabet.len()];
+                    (classes[state], row.iter().map(|next_state| classes[*next_state]).collect_vec())
+                })
+                .collect_vec();
+            let class_count = classes.iter().unique().count();
+            let unique_signatures = signatures.iter().unique().collect_vec();
+            if unique_signatures.len() == self.state_count {
+                return true;
+            }
+            if unique_signatures.len() == class_count {
+                return false;
+            }
+            classes = signatures.iter().map(|signature| unique_signatures.iter().position(|other| *other == signature).unwrap()).collect();
+        }
+    }
+
+    fn create_dfa(&self) -> Dfa {
+        let state_name = |state: usize| format!("q{}", state);
+        let transitions = self.table.iter().enumerate()
+            .map(|(index, next_state)| Transition {
+                state: state_name(index / self.alphabet.len()),
+                input: self.alphabet[index % self.alphabet.len()],
+                next_state: state_name(*next_state),
+            })
+            .collect();
+        Dfa {
+            alphabet: Some(self.alphabet.iter().cloned().collect::<HashSet<char>>()),
+            ..Dfa::with_parts(
+                String::new(),
+                state_name(0),
+                (0..self.state_count).filter(|state| self.accept_mask >> state & 1 == 1).map(state_name).collect(),
+                transitions,
+            )
+        }
+    }
+
+    /// Moves on to the next combination of accept states, transition table and number of states.
+    fn advance(&mut self) {
+        self.accept_mask += 1;
+        if self.accept_mask < 1 << self.state_count {
+            return;
+        }
+        self.accept_mask = 0;
+        for next_state in self.table.iter_mut() {
+            *next_state += 1;
+            if *next_state < self.state_count {
+                return;
+            }
+            *next_state = 0;
+        }
+        self.state_count += 1;
+        self.table = vec![0; self.state_count * self.alphabet.len()];
+    }
+}
+
+impl Iterator for MinimalDfas {
+    type Item = Dfa;
+
+    fn next(&mut self) -> Option<Dfa> {
+        while self.state_count <= self.max_states {
+            let dfa = match self.is_canonical() && self.is_minimal() {
+                true => Some(self.create_dfa()),
+                false => None,
+            };
+            self.advance();
+            if dfa.is_some() {
+                return dfa;
+            }
+        }
+        None
+    }
+}
+
+impl AcceptedWords {
+    /// Moves on to the words of the next length. Returns false if there are none and no longer ones either.
+    fn advance(&mut self) -> bool {
+        let dfa = &self.dfa;
+        self.frontier = self.frontier.iter()
+            .cartesian_product(self.alphabet.iter())
+            .filter_map(|(state, input)| dfa.get_transition(state, input))
+            .map(|transition| transition.next_state.clone())
+            .filter(|state| self.productive_states.contains(state))
+            .collect();
+        if self.frontier.is_empty() {
+            return false;
+        }
+        let accepting_within_previous_length = &self.accepting_within[self.length];
+        let accepting_within_length = dfa.get_all_mentioned_states().into_iter()
+            .filter(|state| self.alphabet.iter().any(|input| {
+                dfa.get_transition(state, input).map_or(false, |transition| accepting_within_previous_length.contains(&transition.next_state))
+            }))
+            .collect();
+        self.accepting_within.push(accepting_within_length);
+        self.length += 1;
+        if self.accepting_within[self.length].contains(&dfa.start_state) {
+            self.prefixes_to_extend.push((String::new(), dfa.start_state.clone()));
+        }
+        true
+    }
+}
+
+impl Iterator for AcceptedWords {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let (prefix, state) = match self.prefixes_to_extend.pop() {
+                Some(prefix) => prefix,
+                None if self.advance() => continue,
+                None => return None,
+            };
+            let remaining_length = self.length - prefix.chars().count();
+            if remaining_length == 0 {
+                return Some(prefix);
+            }
+            for input in self.alphabet.iter().rev() {
+                if let Some(transition) = self.dfa.get_transition(&state, input) {
+                    if self.accepting_within[remaining_length - 1].contains(&transition.next_state) {
+                        self.prefixes_to_extend.push((format!("{}{}", prefix, input), transition.next_state.clone()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod enumeration_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_enumerate_minimal_dfas() {
+        // Over a unary alphabet, the minimal DFAs with one state accept everything or nothing. With two states, the languages
+        // are the words of even or odd length, the empty word only, and all words except the empty word.
+        assert_eq!(Dfa::enumerate_minimal_dfas(1, &['a']).count(), 2);
+        let dfas = Dfa::enumerate_minimal_dfas(2, &['a']).collect::<Vec<_>>();
+        assert_eq!(dfas.len(), 6);
+        assert!(dfas.iter().all(|dfa| dfa.get_all_states().len() <= 2));
+        // The known number of minimal DFAs with two states over two symbols is 24.
+        assert_eq!(Dfa::enumerate_minimal_dfas(2, &['a', 'b']).count(), 2 + 24);
+    }
+
+    #[test]
+    fn test_enumerate_words() {
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        let ends_with_b = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        assert_eq!(ends_with_b.enumerate_words().take(6).collect::<Vec<_>>(), vec!["b", "ab", "bb", "aab", "abb", "bab"]);
+        // Accepts "a", "ab" and "b", with missing transitions.
+        let finite = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), Some(2)], &[None, Some(2)], &[None, None]], 0, &[1, 2]).unwrap();
+        assert_eq!(finite.enumerate_words().collect::<Vec<_>>(), vec!["a", "b", "ab"]);
+    }
+
+    #[test]
+    fn test_k_shortest_words() {
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        let ends_with_b = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        assert_eq!(ends_with_b.k_shortest_words(6), ends_with_b.enumerate_words().take(6).collect::<Vec<_>>());
+        // Accepts "a", "ab" and "b", with missing transitions.
+        let finite = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), Some(2)], &[None, Some(2)], &[None, None]], 0, &[1, 2]).unwrap();
+        assert_eq!(finite.k_shortest_words(10), vec!["a", "b", "ab"]);
+        assert!(finite.k_shortest_words(0).is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{Dfa, Nfa};
+
+/// Describes to which next state an ε-NFA may switch when it reads the input, or without reading anything if there is no input.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct EpsilonTransition {
+    state: String,
+    input: Option<char>,
+    next_state: String,
+}
+
+/// # [Nondeterministic finite acceptor with ε-moves](https://en.wikipedia.org/wiki/Nondeterministic_finite_automaton#NFA_with_%CE%B5-moves)
+/// Like an NFA, but transitions without input (ε-transitions) let it switch states without reading a symbol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpsilonNfa {
+    pub(crate) name: String,
+    pub(crate) start_state: String,
+    pub(crate) accept_states: HashSet<String>,
+    pub(crate) transitions: Vec<EpsilonTransition>,
+}
+
+impl EpsilonNfa {
+    /// Creates an ε-NFA without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> EpsilonNfa {
+        EpsilonNfa {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.transitions.push(EpsilonTransition { state: state.to_string(), input: Some(input), next_state: next_state.to_string() });
+    }
+
+    pub fn add_epsilon_transition(&mut self, state: &str, next_state: &str) {
+        self.transitions.push(EpsilonTransition { state: state.to_string(), input: None, next_state: next_state.to_string() });
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        self.transitions.iter().flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()]).collect()
+    }
+
+    /// Returns all states that can be reached from the state by following ε-transitions only, including the state itself.
+    pub fn epsilon_closure(&self, state: &str) -> BTreeSet<String> {
+        self.get_epsilon_closure(vec![state]).into_iter().map(String::from).collect()
+    }
+
+    fn get_epsilon_closure<'a>(&'a self, states: Vec<&'a str>) -> BTreeSet<&'a str> {
+        let mut closure: BTreeSet<&str> = states.iter().cloned().collect();
+        let mut states_to_visit: VecDeque<&str> = states.into_iter().collect();
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in self.transitions.iter().filter(|transition| transition.input.is_none() && transition.state == state) {
+                if closure.insert(&transition.next_state) {
+                    states_to_visit.push_back(&transition.next_state);
+                }
+            }
+        }
+        closure
+    }
+
+    /// Checks whether a certain input is accepted. Like for NFAs, the set of all states the automaton could be in is tracked,
+    /// and it is extended by its ε-closure before and after reading each symbol.
+    pub fn check(&self, input: &str) -> bool {
+        let mut current_states = self.get_epsilon_closure(vec![&self.start_state]);
+        for char in input.chars() {
+            let next_states = self.transitions.iter()
+                .filter(|transition| transition.input == Some(char) && current_states.contains(transition.state.as_str()))
+                .map(|transition| transition.next_state.as_str())
+                .collect();
+            current_states = self.get_epsilon_closure(next_states);
+        }
+        current_states.iter().any(|state| self.accept_states.contains(*state))
+    }
+
+    /// Converts the automaton into an NFA with the same states by eliminating the ε-transitions: a state gets a transition
+    /// to r reading 'a' if some state of its ε-closure has one, and it becomes an accept state if its ε-closure contains one.
+    pub fn to_nfa(&self) -> Nfa {
+        let mut nfa = Nfa::new(&self.name, &self.start_state);
+        let mut states = self.get_all_states();
+        states.insert(self.start_state.clone());
+        for state in states.iter().sorted() {
+            let closure = self.get_epsilon_closure(vec![state]);
+            if closure.iter().any(|closure_state| self.accept_states.contains(*closure_state)) {
+                nfa.add_accept_state(state);
+            }
+            let transitions = self.transitions.iter()
+                .filter(|transition| closure.contains(transition.state.as_str()))
+                .filter_map(|transition| transition.input.map(|input| (input, &transition.next_state)))
+                .sorted()
+                .dedup();
+            for (input, next_state) in transitions {
+                nfa.add_transition(state, input, next_state);
+            }
+        }
+        nfa
+    }
+
+    /// Converts the automaton into a DFA accepting the same language by eliminating the ε-transitions (see `to_nfa`)
+    /// and applying the subset construction (see `Nfa::to_dfa`).
+    pub fn to_dfa(&self) -> Dfa {
+        self.to_nfa().to_dfa()
+    }
+}
+
+#[cfg(test)]
+mod epsilon_nfa_tests {
+    use crate::EpsilonNfa;
+
+    /// Creates an ε-NFA accepting any number of 'a's followed by any number of 'b's, with an ε-transition between both loops.
+    fn create_example_epsilon_nfa() -> EpsilonNfa {
+        let mut epsilon_nfa = EpsilonNfa::new("a*b*", "q0");
+        epsilon_nfa.add_transition("q0", 'a', "q0");
+        epsilon_nfa.add_epsilon_transition("q0", "q1");
+        epsilon_nfa.add_transition("q1", 'b', "q1");
+        epsilon_nfa.add_epsilon_transition("q1", "q2");
+        epsilon_nfa.add_accept_state("q2");
+        epsilon_nfa
+    }
+
+    #[test]
+    fn test_epsilon_closure_and_check() {
+        let epsilon_nfa = create_example_epsilon_nfa();
+        assert_eq!(epsilon_nfa.epsilon_closure("q0"), vec!["q0".to_string(), "q1".to_string(), "q2".to_string()].into_iter().collect());
+        assert_eq!(epsilon_nfa.epsilon_closure("q2"), vec!["q2".to_string()].into_iter().collect());
+        assert!(epsilon_nfa.check("") && epsilon_nfa.check("aabbb") && epsilon_nfa.check("b"));
+        assert!(!epsilon_nfa.check("ba"));
+    }
+
+    #[test]
+    fn test_to_nfa_and_to_dfa() {
+        let epsilon_nfa = create_example_epsilon_nfa();
+        let nfa = epsilon_nfa.to_nfa();
+        let dfa = epsilon_nfa.to_dfa();
+        for word in &["", "a", "ab", "abb", "aab", "ba", "bab"] {
+            assert_eq!(nfa.check(word), epsilon_nfa.check(word), "{}", word);
+            assert_eq!(dfa.check(word).0, epsilon_nfa.check(word), "{}", word);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Checks whether both DFAs accept the same words (see `equivalence_counterexample`).
+    pub fn is_equivalent(&self, other: &Dfa) -> bool {
+        self.equivalence_counterexample(other).is_none()
+    }
+
+    /// Returns a word accepted by exactly one of the DFAs, or None if they are equivalent. Uses the algorithm of Hopcroft and Karp:
+    /// starting with the pair of start states, pairs of states that must be equivalent are merged with a union-find structure,
+    /// and their successors are merged in turn, until two states of different acceptance would be merged. As pairs of already merged states
+    /// are skipped, this is almost linear, but the word is not necessarily the shortest one.
+    pub fn equivalence_counterexample(&self, other: &Dfa) -> Option<String> {
+        let mut first = self.clone();
+        first.materialize_missing_transitions();
+        let mut second = other.clone();
+        second.materialize_missing_transitions();
+        let alphabet = first.get_alphabet().union(&second.get_alphabet()).cloned().sorted().collect_vec();
+        // States of both DFAs are numbered consecutively, followed by the implicit error state of each DFA.
+        let first_states = first.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let second_states = second.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let first_error_state = first_states.len() + second_states.len();
+        let second_error_state = first_error_state + 1;
+        let mut indices: HashMap<(bool, &str), usize> = HashMap::new();
+        for (index, state) in first_states.iter().enumerate() {
+            indices.insert((false, state), index);
+        }
+        for (index, state) in second_states.iter().enumerate() {
+            indices.insert((true, state), first_states.len() + index);
+        }
+        let get_index = |is_second: bool, state: Option<&str>| match (is_second, state) {
+            (_, Some(state)) => indices[&(is_second, state)],
+            (false, None) => first_error_state,
+            (true, None) => second_error_state,
+        };
+        let mut parents = (0..=second_error_state).collect_vec();
+        let mut pairs_to_visit = VecDeque::new();
+        pairs_to_visit.push_back((String::new(), Some(first.start_state.as_str()), Some(second.start_state.as_str())));
+        while let Some((word, first_state, second_state)) = pairs_to_visit.pop_front() {
+            let first_root = find_root(&mut parents, get_index(false, first_state));
+            let second_root = find_root(&mut parents, get_index(true, second_state));
+            if first_root == second_root {
+                continue;
+            }
+            let is_first_accepting = first_state.map_or(false, |state| first.accept_states.contains(state));
+            let is_second_accepting = second_state.map_or(false, |state| second.accept_states.contains(state));
+            if is_first_accepting != is_second_accepting {
+                return Some(word);
+            }
+            parents[first_root] = second_root;
+            for input in &alphabet {
+                pairs_to_visit.push_back((
+                    format!("{}{}", word, input),
+                    first_state.and_then(|state| first.get_transition(state, input)).map(|transition| transition.next_state.as_str()),
+                    second_state.and_then(|state| second.get_transition(state, input)).map(|transition| transition.next_state.as_str()),
+                ));
+            }
+        }
+        None
+    }
+
+    /// Checks whether every word accepted by this DFA is also accepted by the other one. Otherwise, the shortest word accepted by this DFA
+    /// but rejected by the other one is returned, alphabetically smallest first.
+    pub fn is_subset_of(&self, other: &Dfa) -> Result<(), String> {
+        match self.difference(other).shortest_accepted_word() {
+            Some(word) => Err(word),
+            None => Ok(()),
+        }
+    }
+
+    /// Compares both DFAs by brute force on all words up to the length, over the symbols of both alphabets, and returns
+    /// the words accepted by exactly one of them, shortest first and then alphabetically. The number of words grows exponentially
+    /// with the length, so this is meant for cross-checking the exact algorithms and for small bounds only.
+    pub fn equivalent_up_to(&self, other: &Dfa, max_length: usize) -> Vec<String> {
+        let mut first = self.clone();
+        first.materialize_missing_transitions();
+        let mut second = other.clone();
+        second.materialize_missing_transitions();
+        let alphabet = first.get_alphabet().union(&second.get_alphabet()).cloned().sorted().collect_vec();
+        // Words of the current length with the states both DFAs are in after reading them. None stands for the implicit error state.
+        let mut layer = vec![(String::new(), Some(first.start_state.as_str()), Some(second.start_state.as_str()))];
+        let mut disagreements = Vec::new();
+        for length in 0..=max_length {
+            for (word, first_state, second_state) in &layer {
+                let is_first_accepting = first_state.map_or(false, |state| first.accept_states.contains(state));
+                let is_second_accepting = second_state.map_or(false, |state| second.accept_states.contains(state));
+                if is_first_accepting != is_second_accepting {
+                    disagreements.push(word.clone());
+                }
+            }
+            if length == max_length {
+                break;
+            }
+            layer = layer.iter()
+                .cartesian_product(alphabet.iter())
+                .map(|((word, first_state, second_state), input)| (
+                    format!("{}{}", word, input),
+                    first_state.and_then(|state| first.get_transition(state, input)).map(|transition| transition.next_state.as_str()),
+                    second_state.and_then(|state| second.get_transition(state, input)).map(|transition| transition.next_state.as_str()),
+                ))
+                .collect();
+        }
+        disagreements
+    }
+}
+
+/// Returns the representative of the set containing the element and compresses the path to it.
+fn find_root(parents: &mut [usize], element: usize) -> usize {
+    let mut root = element;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = element;
+    while parents[current] != root {
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+    root
+}
+
+#[cfg(test)]
+mod equivalence_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_equivalent_up_to() {
+        // Accepts words over 'a' and 'b' with an even number of 'a's.
+        let even_a = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap();
+        // Accepts words over 'a' and 'b' of even length.
+        let even_length = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(1)], &[Some(0), Some(0)]], 0, &[0]).unwrap();
+        assert_eq!(even_a.equivalent_up_to(&even_length, 2), vec!["b", "ab", "ba"]);
+        assert!(even_a.equivalent_up_to(&even_a.clone(), 4).is_empty());
+    }
+
+    #[test]
+    fn test_is_equivalent() {
+        // Accepts words over 'a' and 'b' ending with "ab". q3 is a redundant copy of q0.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3"],
+            &['a', 'b'],
+            &[&[Some(1), Some(3)], &[Some(1), Some(2)], &[Some(1), Some(0)], &[Some(1), Some(0)]],
+            0,
+            &[2],
+        ).unwrap();
+        let mut minimized_dfa = dfa.clone();
+        minimized_dfa.minimize();
+        assert!(dfa.is_equivalent(&minimized_dfa));
+        // Accepts words over 'a' and 'b' ending with 'b', without transitions out of the accept state.
+        let ends_with_b = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[None, None]], 0, &[1]).unwrap();
+        let counterexample = dfa.equivalence_counterexample(&ends_with_b).unwrap();
+        assert_ne!(dfa.check(&counterexample).0, ends_with_b.check(&counterexample).0);
+        assert_eq!(counterexample, "b");
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        // Accepts "ab" followed by any number of 'b's.
+        let ab_b_star = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), None], &[None, Some(2)], &[None, Some(2)]], 0, &[2]).unwrap();
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        let ends_with_b = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        assert_eq!(ab_b_star.is_subset_of(&ends_with_b), Ok(()));
+        assert_eq!(ends_with_b.is_subset_of(&ab_b_star), Err("b".to_string()));
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::{Dfa, MissingTransitionPolicy, UsageProfile};
+
+/// Radius of the circles representing states in SVG exports.
+const STATE_RADIUS: f64 = 25.0;
+/// Space around the drawn automaton in SVG exports.
+const SVG_MARGIN: f64 = 60.0;
+/// Number of layout units (see `compute_layout`) per centimeter in TikZ exports.
+const TIKZ_UNITS_PER_CM: f64 = 50.0;
+/// Color of states and edges that were never used in heatmap exports.
+const UNUSED_COLOR: &str = "#cccccc";
+
+/// Splits a set of symbols into maximal runs of consecutive characters, given by their first and last character.
+pub(crate) fn symbol_runs(symbols: &[char]) -> Vec<(char, char)> {
+    let mut runs: Vec<(char, char)> = Vec::new();
+    for symbol in symbols.iter().sorted().dedup() {
+        match runs.last_mut() {
+            Some(run) if run.1 as u32 + 1 == *symbol as u32 => run.1 = *symbol,
+            _ => runs.push((*symbol, *symbol)),
+        }
+    }
+    runs
+}
+
+/// Renders a set of symbols as a compact label like "a, b, 0-9". Runs of at least three consecutive characters are
+/// compressed into ranges, shorter runs are listed one by one.
+pub(crate) fn compress_symbols(symbols: &[char]) -> String {
+    symbol_runs(symbols).into_iter()
+        .flat_map(|(from, to)| match to as u32 - from as u32 {
+            0 => vec![from.to_string()],
+            1 => vec![from.to_string(), to.to_string()],
+            _ => vec![format!("{}-{}", from, to)],
+        })
+        .join(", ")
+}
+
+impl Dfa {
+    /// Groups the transitions by the pair of states they connect, so that parallel transitions can be drawn as one edge.
+    /// Implicit self-loops of the `MissingTransitionPolicy::SelfLoop` policy are included.
+    fn get_merged_edges(&self) -> BTreeMap<(String, String), Vec<char>> {
+        let mut dfa = self.clone();
+        if dfa.missing_transitions == MissingTransitionPolicy::SelfLoop {
+            dfa.materialize_missing_transitions();
+        }
+        let mut edges: BTreeMap<(String, String), Vec<char>> = BTreeMap::new();
+        for transition in &dfa.transitions {
+            edges.entry((transition.state.clone(), transition.next_state.clone())).or_default().push(transition.input);
+        }
+        edges
+    }
+
+    /// Renders the origin of a state from its metadata as a label like "{q0, q1}", if it has one.
+    fn get_origin_label(&self, state: &str) -> Option<String> {
+        self.state_metadata.get(state)
+            .filter(|metadata| !metadata.origin.is_empty())
+            .map(|metadata| format!("{{{}}}", metadata.origin.join(", ")))
+    }
+
+    /// Renders the DFA in the [DOT language](https://graphviz.org/doc/info/lang.html) of Graphviz.
+    /// Parallel transitions are merged into one edge labeled with all their symbols. If the states have positions in their
+    /// metadata (see `compute_layout`), they are pinned to those positions. Their origins are shown as external labels.
+    pub fn to_dot(&self) -> String {
+        self.render_dot(None)
+    }
+
+    /// Renders the DFA like `to_dot`, but colors states and edges by how often they were used according to the profile
+    /// (see `Dfa::profile`), from yellow for rarely used to red for the most used ones. Unused states and edges are gray.
+    /// Edges are additionally labeled with their number of uses, and their width grows with it.
+    pub fn to_dot_heatmap(&self, profile: &UsageProfile) -> String {
+        self.render_dot(Some(profile))
+    }
+
+    fn render_dot(&self, heatmap: Option<&UsageProfile>) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n    rankdir=LR;\n    __start [shape=point];\n", escape_dot(&self.name));
+        for state in self.get_all_mentioned_states().into_iter().sorted() {
+            let shape = if self.accept_states.contains(&state) { "doublecircle" } else { "circle" };
+            let position = self.state_metadata.get(&state)
+                .and_then(|metadata| metadata.position)
+                .map(|(x, y)| format!(", pos=\"{},{}!\"", x, -y))
+                .unwrap_or_default();
+            let fill = heatmap
+                .map(|profile| format!(", style=filled, fillcolor=\"{}\"", get_heat_color(profile.get_state_visits(&state), get_max_state_visits(profile))))
+                .unwrap_or_default();
+            let origin = self.get_origin_label(&state).map(|origin| format!(", xlabel=\"{}\"", escape_dot(&origin))).unwrap_or_default();
+            dot += &format!("    \"{}\" [shape={}{}{}{}];\n", escape_dot(&state), shape, position, fill, origin);
+        }
+        dot += &format!("    __start -> \"{}\";\n", escape_dot(&self.start_state));
+        let edges = self.get_merged_edges();
+        let max_uses = heatmap.map(|profile| get_max_edge_uses(profile, &edges)).unwrap_or(0);
+        for ((state, next_state), symbols) in &edges {
+            let label = compress_symbols(symbols);
+            let attributes = match heatmap {
+                Some(profile) => {
+                    let uses = get_edge_uses(profile, state, symbols);
+                    format!(
+                        "label=\"{} ({})\", color=\"{}\", penwidth={}",
+                        escape_dot(&label), uses, get_heat_color(uses, max_uses), 1.0 + 3.0 * get_heat(uses, max_uses),
+                    )
+                }
+                None => format!("label=\"{}\"", escape_dot(&label)),
+            };
+            dot += &format!("    \"{}\" -> \"{}\" [{}];\n", escape_dot(state), escape_dot(next_state), attributes);
+        }
+        dot + "}\n"
+    }
+
+    /// Renders the DFA as a [Mermaid](https://mermaid.js.org/syntax/stateDiagram.html) state diagram.
+    /// As Mermaid restricts the characters of state identifiers, states get generated identifiers and their names as descriptions.
+    /// Parallel transitions are merged into one edge labeled with all their symbols.
+    pub fn to_mermaid(&self) -> String {
+        let states = self.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let identifier = |state: &str| format!("s{}", states.iter().position(|other| other == state).unwrap());
+        let mut mermaid = String::from("stateDiagram-v2\n");
+        for state in &states {
+            mermaid += &format!("    state \"{}\" as {}\n", state.replace('"', "#quot;"), identifier(state));
+        }
+        mermaid += &format!("    [*] --> {}\n", identifier(&self.start_state));
+        for ((state, next_state), symbols) in self.get_merged_edges() {
+            mermaid += &format!("    {} --> {} : {}\n", identifier(&state), identifier(&next_state), compress_symbols(&symbols));
+        }
+        for accept_state in self.accept_states.iter().sorted() {
+            mermaid += &format!("    {} --> [*]\n", identifier(accept_state));
+        }
+        mermaid
+    }
+
+    /// Renders the DFA as a standalone SVG image. The states are drawn at the positions stored in their metadata,
+    /// with their origins written below them.
+    /// If some state has no position yet, the layout is computed first (see `compute_layout`).
+    /// Parallel transitions are merged into one edge labeled with all their symbols.
+    pub fn to_svg(&self) -> String {
+        self.render_svg(None)
+    }
+
+    /// Renders the DFA like `to_svg`, but colors states and edges by how often they were used according to the profile,
+    /// just like `to_dot_heatmap` does.
+    pub fn to_svg_heatmap(&self, profile: &UsageProfile) -> String {
+        self.render_svg(Some(profile))
+    }
+
+    /// Renders the DFA as a TikZ picture for LaTeX documents, which needs the `automata` TikZ library.
+    /// The states are placed at the positions stored in their metadata, like in `to_svg`, and get generated node names.
+    /// Parallel transitions are merged into one edge labeled with all their symbols, and edges in both directions are bent apart.
+    pub fn to_tikz(&self) -> String {
+        let dfa = self.with_layout();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let identifier = |state: &str| format!("s{}", states.iter().position(|other| other == state).unwrap());
+        let mut tikz = String::from("\\begin{tikzpicture}[->, >=stealth, auto]\n");
+        for state in &states {
+            let (x, y) = dfa.state_metadata[state].position.unwrap();
+            let mut options = vec!["state"];
+            if *state == dfa.start_state {
+                options.push("initial");
+            }
+            if dfa.accept_states.contains(state) {
+                options.push("accepting");
+            }
+            // TikZ's y axis points upwards, unlike the one of the layout. Subtracting from zero avoids printing "-0".
+            tikz += &format!(
+                "    \\node[{}] ({}) at ({}, {}) {{{}}};\n",
+                options.join(", "), identifier(state), x / TIKZ_UNITS_PER_CM, (0.0 - y) / TIKZ_UNITS_PER_CM, escape_latex(state),
+            );
+        }
+        let edges = dfa.get_merged_edges();
+        for ((state, next_state), symbols) in &edges {
+            let shape = if state == next_state {
+                " [loop above]"
+            } else if edges.contains_key(&(next_state.clone(), state.clone())) {
+                " [bend left]"
+            } else {
+                ""
+            };
+            tikz += &format!(
+                "    \\path ({}) edge{} node {{{}}} ({});\n",
+                identifier(state), shape, escape_latex(&compress_symbols(symbols)), identifier(next_state),
+            );
+        }
+        tikz + "\\end{tikzpicture}\n"
+    }
+
+    /// Returns a copy of the DFA in which every state has a position, computing the layout if some state has none.
+    fn with_layout(&self) -> Dfa {
+        let mut dfa = self.clone();
+        if dfa.get_all_mentioned_states().iter().any(|state| dfa.state_metadata.get(state).and_then(|metadata| metadata.position).is_none()) {
+            dfa.compute_layout();
+        }
+        dfa
+    }
+
+    fn render_svg(&self, heatmap: Option<&UsageProfile>) -> String {
+        let dfa = self.with_layout();
+        let all_states = dfa.get_all_mentioned_states();
+        let position = |state: &str| dfa.state_metadata[state].position.unwrap();
+        let min_x = all_states.iter().map(|state| position(state).0).fold(f64::INFINITY, f64::min) - SVG_MARGIN;
+        let min_y = all_states.iter().map(|state| position(state).1).fold(f64::INFINITY, f64::min) - SVG_MARGIN;
+        let max_x = all_states.iter().map(|state| position(state).0).fold(f64::NEG_INFINITY, f64::max) + SVG_MARGIN;
+        let max_y = all_states.iter().map(|state| position(state).1).fold(f64::NEG_INFINITY, f64::max) + SVG_MARGIN;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            min_x, min_y, max_x - min_x, max_y - min_y,
+        );
+        svg += "  <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+                <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>\n";
+        let (start_x, start_y) = position(&dfa.start_state);
+        svg += &format!(
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" marker-end=\"url(#arrow)\"/>\n",
+            start_x - 2.0 * STATE_RADIUS, start_y, start_x - STATE_RADIUS, start_y,
+        );
+        let edges = dfa.get_merged_edges();
+        let max_uses = heatmap.map(|profile| get_max_edge_uses(profile, &edges)).unwrap_or(0);
+        for ((state, next_state), symbols) in &edges {
+            let label = escape_xml(&compress_symbols(symbols));
+            let stroke = heatmap
+                .map(|profile| get_heat_color(get_edge_uses(profile, state, symbols), max_uses))
+                .unwrap_or_else(|| "black".to_string());
+            let (x1, y1) = position(state);
+            let (x2, y2) = position(next_state);
+            if state == next_state {
+                // Self-loops are drawn as an arc above the state.
+                svg += &format!(
+                    "  <path d=\"M {} {} C {} {} {} {} {} {}\" fill=\"none\" stroke=\"{}\" marker-end=\"url(#arrow)\"/>\n",
+                    x1 - 10.0, y1 - STATE_RADIUS + 2.0, x1 - 30.0, y1 - 70.0, x1 + 30.0, y1 - 70.0, x1 + 10.0, y1 - STATE_RADIUS + 2.0, stroke,
+                );
+                svg += &format!("  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n", x1, y1 - 60.0, label);
+                continue;
+            }
+            // Edges start and end at the border of the circles instead of their centers.
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            let (dx, dy) = ((x2 - x1) / length * STATE_RADIUS, (y2 - y1) / length * STATE_RADIUS);
+            svg += &format!(
+                "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" marker-end=\"url(#arrow)\"/>\n",
+                x1 + dx, y1 + dy, x2 - dx, y2 - dy, stroke,
+            );
+            svg += &format!("  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n", (x1 + x2) / 2.0, (y1 + y2) / 2.0 - 5.0, label);
+        }
+        for state in all_states.iter().sorted() {
+            let (x, y) = position(state);
+            let fill = heatmap
+                .map(|profile| get_heat_color(profile.get_state_visits(state), get_max_state_visits(profile)))
+                .unwrap_or_else(|| "white".to_string());
+            svg += &format!("  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\"/>\n", x, y, STATE_RADIUS, fill);
+            if dfa.accept_states.contains(state) {
+                svg += &format!("  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"black\"/>\n", x, y, STATE_RADIUS - 4.0);
+            }
+            svg += &format!("  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>\n", x, y, escape_xml(state));
+            if let Some(origin) = dfa.get_origin_label(state) {
+                svg += &format!("  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"small\">{}</text>\n", x, y + STATE_RADIUS + 15.0, escape_xml(&origin));
+            }
+        }
+        svg + "</svg>\n"
+    }
+}
+
+/// Sums up the uses of the parallel transitions merged into one edge.
+fn get_edge_uses(profile: &UsageProfile, state: &str, symbols: &[char]) -> usize {
+    symbols.iter().map(|symbol| profile.get_transition_uses(state, *symbol)).sum()
+}
+
+fn get_max_state_visits(profile: &UsageProfile) -> usize {
+    profile.state_visits.values().cloned().max().unwrap_or(0)
+}
+
+/// The heat of an edge is relative to the most used edge, so that edges with several symbols are compared with their totals.
+fn get_max_edge_uses(profile: &UsageProfile, edges: &BTreeMap<(String, String), Vec<char>>) -> usize {
+    edges.iter().map(|((state, _), symbols)| get_edge_uses(profile, state, symbols)).max().unwrap_or(0)
+}
+
+/// Returns the usage relative to the maximum usage, between 0 and 1.
+fn get_heat(uses: usize, max_uses: usize) -> f64 {
+    match max_uses {
+        0 => 0.0,
+        _ => uses as f64 / max_uses as f64,
+    }
+}
+
+/// Interpolates between yellow for rarely used and red for the most used states and edges. Unused ones are gray.
+fn get_heat_color(uses: usize, max_uses: usize) -> String {
+    if uses == 0 {
+        return UNUSED_COLOR.to_string();
+    }
+    format!("#ff{:02x}00", (220.0 * (1.0 - get_heat(uses, max_uses))).round() as u8)
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes the characters that have a special meaning in LaTeX.
+fn escape_latex(text: &str) -> String {
+    text.chars()
+        .map(|character| match character {
+            '\\' => "\\textbackslash{}".to_string(),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", character),
+            _ => character.to_string(),
+        })
+        .collect()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod export_tests {
+    use crate::export::compress_symbols;
+    use crate::Dfa;
+
+    /// Creates a DFA accepting identifiers over 'a'-'f' and digits, starting with a letter.
+    fn create_example_dfa() -> Dfa {
+        let alphabet = ('a'..='f').chain('0'..='9').collect::<Vec<char>>();
+        let letters_only: Vec<Option<usize>> = alphabet.iter().map(|symbol| if symbol.is_alphabetic() { Some(1) } else { None }).collect();
+        let everything: Vec<Option<usize>> = alphabet.iter().map(|_| Some(1)).collect();
+        Dfa::from_table(&["start", "identifier"], &alphabet, &[&letters_only, &everything], 0, &[1]).unwrap()
+    }
+
+    #[test]
+    fn test_compress_symbols() {
+        assert_eq!(compress_symbols(&['b', 'a', '3', '1', '2', 'x', '9', '8', '7', '0']), "0-3, 7-9, a, b, x");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = create_example_dfa().to_dot();
+        assert!(dot.contains("\"identifier\" [shape=doublecircle];"));
+        assert!(dot.contains("__start -> \"start\";"));
+        assert!(dot.contains("\"start\" -> \"identifier\" [label=\"a-f\"];"));
+        assert!(dot.contains("\"identifier\" -> \"identifier\" [label=\"0-9, a-f\"];"));
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mermaid = create_example_dfa().to_mermaid();
+        assert_eq!(mermaid, "stateDiagram-v2\n    state \"identifier\" as s0\n    state \"start\" as s1\n    [*] --> s1\n    \
+            s0 --> s0 : 0-9, a-f\n    s1 --> s0 : a-f\n    s0 --> [*]\n");
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let svg = create_example_dfa().to_svg();
+        assert_eq!(svg.matches("<circle").count(), 3, "should draw one circle per state and an inner circle for the accept state");
+        assert!(svg.contains(">0-9, a-f</text>"));
+    }
+
+    #[test]
+    fn test_to_tikz() {
+        let mut dfa = Dfa::from_table(&["q_0", "q_1"], &['a', 'b'], &[&[Some(1), None], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        dfa.compute_layout();
+        assert_eq!(
+            dfa.to_tikz(),
+            "\\begin{tikzpicture}[->, >=stealth, auto]\n    \\node[state, initial] (s0) at (0, 0) {q\\_0};\n    \
+            \\node[state, accepting] (s1) at (3, 0) {q\\_1};\n    \\path (s0) edge [bend left] node {a} (s1);\n    \
+            \\path (s1) edge [bend left] node {a} (s0);\n    \\path (s1) edge [loop above] node {b} (s1);\n\\end{tikzpicture}\n",
+        );
+        assert!(create_example_dfa().to_tikz().contains("{0-9, a-f}"), "the layout is computed if it is missing");
+    }
+
+    #[test]
+    fn test_to_dot_heatmap() {
+        let dfa = create_example_dfa();
+        let profile = dfa.profile(vec!["ab", "abc", "a"]);
+        let dot = dfa.to_dot_heatmap(&profile);
+        assert!(dot.contains("\"start\" [shape=circle, style=filled, fillcolor=\"#ff6e00\"];"), "start is visited half as often as identifier");
+        assert!(dot.contains("\"start\" -> \"identifier\" [label=\"a-f (3)\", color=\"#ff0000\", penwidth=4];"));
+        assert!(dot.contains("\"identifier\" -> \"identifier\" [label=\"0-9, a-f (3)\""));
+        let unused_profile = dfa.profile(vec!["1"]);
+        assert!(dfa.to_svg_heatmap(&unused_profile).contains("stroke=\"#cccccc\""), "unused edges are gray");
+    }
+
+    #[test]
+    fn test_to_dot_heatmap_merged_edge() {
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2"], &['a', 'b', 'c'], &[&[Some(1), Some(1), None], &[None, None, Some(2)], &[None, None, None]], 0, &[1, 2],
+        ).unwrap();
+        let profile = dfa.profile(vec!["a", "b", "a", "bc"]);
+        let dot = dfa.to_dot_heatmap(&profile);
+        // No single transition is used four times, but the edge merging 'a' and 'b' is, so it is the hottest one.
+        assert!(dot.contains("\"q0\" -> \"q1\" [label=\"a, b (4)\", color=\"#ff0000\", penwidth=4];"));
+        assert!(dot.contains("\"q1\" -> \"q2\" [label=\"c (1)\", color=\"#ffa500\", penwidth=1.75];"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Checks whether the DFA accepts only finitely many words, which is the case if no cycle lies on a path from the start state to an accept state.
+    pub fn is_finite(&self) -> bool {
+        self.get_longest_accepting_paths().is_some()
+    }
+
+    /// Returns the length of the longest accepted word, or None if no word is accepted or infinitely many words are accepted.
+    pub fn max_word_length(&self) -> Option<usize> {
+        self.get_longest_accepting_paths()?.into_iter().filter(|(state, _)| self.accept_states.contains(state)).map(|(_, length)| length).max()
+    }
+
+    /// Returns the length of the longest path from the start state to every useful state, i.e. to every state that lies on a path
+    /// from the start state to an accept state, or None if there is a cycle of useful states. The useful states are visited in topological order.
+    fn get_longest_accepting_paths(&self) -> Option<HashMap<String, usize>> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let useful_states: HashSet<String> = dfa.get_reachable_states().intersection(&dfa.get_productive_states()).cloned().collect();
+        let useful_transitions = dfa.transitions.iter()
+            .filter(|transition| useful_states.contains(&transition.state) && useful_states.contains(&transition.next_state))
+            .collect_vec();
+        let mut incoming_transitions: HashMap<&str, usize> = useful_states.iter().map(|state| (state.as_str(), 0)).collect();
+        for transition in &useful_transitions {
+            *incoming_transitions.get_mut(transition.next_state.as_str()).unwrap() += 1;
+        }
+        let mut longest_paths = HashMap::new();
+        let mut states_to_visit: VecDeque<&str> = incoming_transitions.iter().filter(|(_, count)| **count == 0).map(|(state, _)| *state).collect();
+        while let Some(state) = states_to_visit.pop_front() {
+            let length = longest_paths.get(state).cloned().unwrap_or(0);
+            longest_paths.insert(state.to_string(), length);
+            for transition in useful_transitions.iter().filter(|transition| transition.state == state) {
+                let next_length = longest_paths.entry(transition.next_state.clone()).or_insert(0);
+                *next_length = (*next_length).max(length + 1);
+                let count = incoming_transitions.get_mut(transition.next_state.as_str()).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    states_to_visit.push_back(&transition.next_state);
+                }
+            }
+        }
+        // States on a cycle never lose all their incoming transitions, so they are never visited.
+        if incoming_transitions.values().any(|count| *count > 0) {
+            return None;
+        }
+        Some(longest_paths)
+    }
+}
+
+#[cfg(test)]
+mod finiteness_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_is_finite() {
+        // Accepts "a", "b", "ab", "bb" and "abb". The loop of q4 cannot reach an accept state, so it does not make the language infinite.
+        let finite = Dfa::from_table(
+            &["q0", "q1", "q2", "q3", "q4"],
+            &['a', 'b'],
+            &[&[Some(1), Some(2)], &[Some(4), Some(2)], &[Some(4), Some(3)], &[None, None], &[Some(4), Some(4)]],
+            0,
+            &[1, 2, 3],
+        ).unwrap();
+        assert!(finite.is_finite());
+        assert_eq!(finite.max_word_length(), Some(3));
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        let ends_with_b = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        assert!(!ends_with_b.is_finite());
+        assert_eq!(ends_with_b.max_word_length(), None);
+        let empty = Dfa::from_table(&["q0"], &['a'], &[&[Some(0)]], 0, &[]).unwrap();
+        assert!(empty.is_finite());
+        assert_eq!(empty.max_word_length(), None);
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::fresh_state_name;
+
+/// Describes to which next state a finite state transducer switches when it reads a certain input while being in
+/// a certain state, and what it writes to the output while doing so.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct FstTransition {
+    state: String,
+    input: char,
+    output: String,
+    next_state: String,
+}
+
+/// Describes why a transducer cannot be inverted into a deterministic transducer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FstInversionError {
+    /// The transition writes nothing, so its inverse would have to read nothing.
+    EmptyOutput { state: String, input: char },
+    /// Two transitions of the state write outputs where one is a prefix of the other, like `output`,
+    /// so after reading that output, the inverse could not tell which input to write.
+    AmbiguousOutput { state: String, output: String },
+}
+
+impl fmt::Display for FstInversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FstInversionError::EmptyOutput { state, input } => write!(f, "the transition of the state {} for input {} writes nothing", state, input),
+            FstInversionError::AmbiguousOutput { state, output } => {
+                write!(f, "the state {} has several transitions whose outputs start with {}", state, output)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FstInversionError {}
+
+/// # [Finite state transducer](https://en.wikipedia.org/wiki/Finite-state_transducer)
+/// A deterministic automaton that writes an output string while it reads its input. Every transition reads one symbol
+/// and may write any string, including the empty one. Like for a DFA, a state has at most one transition per input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fst {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<FstTransition>,
+}
+
+impl Fst {
+    /// Creates a transducer without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> Fst {
+        Fst {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, output: &str, next_state: &str) {
+        self.transitions.push(FstTransition { state: state.to_string(), input, output: output.to_string(), next_state: next_state.to_string() });
+    }
+
+    pub fn get_transition(&self, state: &str, input: &char) -> Option<&FstTransition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    /// Translates the input. Returns None if the input is not accepted.
+    pub fn apply(&self, input: &str) -> Option<String> {
+        let mut current_state = &self.start_state;
+        let mut output = String::new();
+        for char in input.chars() {
+            let transition = self.get_transition(current_state, &char)?;
+            output += &transition.output;
+            current_state = &transition.next_state;
+        }
+        if !self.accept_states.contains(current_state) {
+            return None;
+        }
+        Some(output)
+    }
+
+    /// Composes both transducers, so that the output of this transducer is translated by the other one. The composition accepts
+    /// an input if this transducer accepts it and the other one accepts its translation. States are named "(p, q)" and found using
+    /// the breath first algorithm. A transition is left out if the other transducer cannot read its output.
+    pub fn compose(&self, other: &Fst) -> Fst {
+        let state_name = |pair: &(&str, &str)| format!("({}, {})", pair.0, pair.1);
+        let start = (self.start_state.as_str(), other.start_state.as_str());
+        let mut composition = Fst::new(&format!("composition of {} and {}", self.name, other.name), &state_name(&start));
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        visited_pairs.insert(start);
+        pairs_to_visit.push_back(start);
+        while let Some(pair) = pairs_to_visit.pop_front() {
+            if self.accept_states.contains(pair.0) && other.accept_states.contains(pair.1) {
+                composition.add_accept_state(&state_name(&pair));
+            }
+            for transition in self.transitions.iter().filter(|transition| transition.state == pair.0).sorted() {
+                // The other transducer reads the whole output of the transition in one step of the composition.
+                let mut other_state = pair.1;
+                let mut output = String::new();
+                let mut can_read_output = true;
+                for char in transition.output.chars() {
+                    match other.get_transition(other_state, &char) {
+                        Some(other_transition) => {
+                            output += &other_transition.output;
+                            other_state = &other_transition.next_state;
+                        }
+                        None => {
+                            can_read_output = false;
+                            break;
+                        }
+                    }
+                }
+                if !can_read_output {
+                    continue;
+                }
+                let next_pair = (transition.next_state.as_str(), other_state);
+                composition.add_transition(&state_name(&pair), transition.input, &output, &state_name(&next_pair));
+                if visited_pairs.insert(next_pair) {
+                    pairs_to_visit.push_back(next_pair);
+                }
+            }
+        }
+        composition
+    }
+
+    /// Swaps inputs and outputs, so that the result translates the outputs of this transducer back into their inputs.
+    /// As transitions read single symbols, a transition writing several symbols becomes a chain of transitions through intermediate states,
+    /// named like "q0/ab" after the state and the output read so far, and the input is written at the end of the chain.
+    /// Chains of the same state share their intermediate states. Fails if a transition writes nothing or if the outputs of a state
+    /// are ambiguous, as the result would not be deterministic.
+    pub fn invert(&self) -> Result<Fst, FstInversionError> {
+        let mut inverse = Fst::new(&format!("inverse of {}", self.name), &self.start_state);
+        inverse.accept_states = self.accept_states.clone();
+        let mut state_names: HashSet<String> = self.transitions.iter()
+            .flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()])
+            .chain(self.accept_states.iter().cloned())
+            .chain(vec![self.start_state.clone()])
+            .collect();
+        // Maps a state and an output read so far to the intermediate state of the inverse that stands for them.
+        let mut intermediate_states: HashMap<(String, String), String> = HashMap::new();
+        for transition in self.transitions.iter().sorted_by_key(|transition| (&transition.state, &transition.output)) {
+            let output = transition.output.chars().collect_vec();
+            if output.is_empty() {
+                return Err(FstInversionError::EmptyOutput { state: transition.state.clone(), input: transition.input });
+            }
+            let mut current_state = transition.state.clone();
+            for length in 1..output.len() {
+                let prefix: String = output[..length].iter().collect();
+                let key = (transition.state.clone(), prefix.clone());
+                let next_state = match intermediate_states.get(&key) {
+                    Some(next_state) => next_state.clone(),
+                    None => {
+                        // A shorter output of the state ends where this output continues.
+                        if inverse.get_transition(&current_state, &output[length - 1]).is_some() {
+                            return Err(FstInversionError::AmbiguousOutput { state: transition.state.clone(), output: prefix });
+                        }
+                        let next_state = fresh_state_name(&format!("{}/{}", transition.state, prefix), &state_names);
+                        state_names.insert(next_state.clone());
+                        intermediate_states.insert(key, next_state.clone());
+                        inverse.add_transition(&current_state, output[length - 1], "", &next_state);
+                        next_state
+                    }
+                };
+                current_state = next_state;
+            }
+            if inverse.get_transition(&current_state, output.last().unwrap()).is_some() {
+                return Err(FstInversionError::AmbiguousOutput { state: transition.state.clone(), output: transition.output.clone() });
+            }
+            inverse.add_transition(&current_state, *output.last().unwrap(), &transition.input.to_string(), &transition.next_state);
+        }
+        Ok(inverse)
+    }
+
+    /// Rewrites the text by replacing every accepted substring by its translation, scanning the text from left to right.
+    /// At every position, the longest accepted substring is replaced and scanning continues after it. Characters that are not
+    /// part of any non-empty accepted substring are copied unchanged, so empty matches never lead to replacements.
+    pub fn replace_all(&self, text: &str) -> String {
+        let mut result = String::new();
+        let mut position = 0;
+        while position < text.len() {
+            match self.find_longest_translation(&text[position..]) {
+                Some((length, translation)) => {
+                    result += &translation;
+                    position += length;
+                }
+                None => {
+                    let char = text[position..].chars().next().unwrap();
+                    result.push(char);
+                    position += char.len_utf8();
+                }
+            }
+        }
+        result
+    }
+
+    /// Runs the transducer on the text and returns the length of the longest non-empty accepted prefix together with its translation.
+    fn find_longest_translation(&self, text: &str) -> Option<(usize, String)> {
+        let mut current_state = &self.start_state;
+        let mut output = String::new();
+        let mut longest_translation = None;
+        for (offset, char) in text.char_indices() {
+            let transition = match self.get_transition(current_state, &char) {
+                Some(transition) => transition,
+                None => break,
+            };
+            output += &transition.output;
+            current_state = &transition.next_state;
+            if self.accept_states.contains(current_state) {
+                longest_translation = Some((offset + char.len_utf8(), output.clone()));
+            }
+        }
+        longest_translation
+    }
+}
+
+#[cfg(test)]
+mod fst_tests {
+    use crate::{Fst, FstInversionError};
+
+    /// Creates a transducer that turns the transliterations "ae", "oe", "ue" and "ss" into the German letters "ä", "ö", "ü" and "ß".
+    fn create_example_fst() -> Fst {
+        let mut fst = Fst::new("german letters", "q0");
+        for (first, second, letter) in &[('a', 'e', "ä"), ('o', 'e', "ö"), ('u', 'e', "ü"), ('s', 's', "ß")] {
+            fst.add_transition("q0", *first, "", &first.to_string());
+            fst.add_transition(&first.to_string(), *second, letter, "done");
+        }
+        fst.add_accept_state("done");
+        fst
+    }
+
+    #[test]
+    fn test_apply() {
+        let fst = create_example_fst();
+        assert_eq!(fst.apply("ae"), Some("ä".to_string()));
+        assert_eq!(fst.apply("a"), None);
+    }
+
+    #[test]
+    fn test_compose_and_invert() {
+        // Transliterates Greek letters into Latin letters, and abbreviates "th" as "þ", respectively.
+        let mut transliteration = Fst::new("transliteration", "q");
+        for (letter, latin) in &[('α', "a"), ('θ', "th"), ('ε', "e"), ('τ', "t")] {
+            transliteration.add_transition("q", *letter, latin, "q");
+        }
+        transliteration.add_accept_state("q");
+        let mut abbreviation = Fst::new("abbreviation", "r");
+        for letter in &['a', 'e'] {
+            abbreviation.add_transition("r", *letter, &letter.to_string(), "r");
+            abbreviation.add_transition("t", *letter, &format!("t{}", letter), "r");
+        }
+        abbreviation.add_transition("r", 'h', "h", "r");
+        abbreviation.add_transition("r", 't', "", "t");
+        abbreviation.add_transition("t", 'h', "þ", "r");
+        abbreviation.add_transition("t", 't', "t", "t");
+        abbreviation.add_accept_state("r");
+        let composition = transliteration.compose(&abbreviation);
+        assert_eq!(composition.apply("θετα"), Some("þeta".to_string()));
+        assert_eq!(composition.apply("ατ"), None, "the abbreviation does not accept a trailing 't'");
+        // After reading 't', the inverse could not tell whether it belongs to 'τ' or to 'θ'.
+        assert_eq!(transliteration.invert().unwrap_err(), FstInversionError::AmbiguousOutput { state: "q".to_string(), output: "t".to_string() });
+        let mut transliteration = Fst::new("transliteration", "q");
+        for (letter, latin) in &[('α', "a"), ('φ', "ph"), ('ψ', "ps"), ('ι', "i")] {
+            transliteration.add_transition("q", *letter, latin, "q");
+        }
+        transliteration.add_accept_state("q");
+        let inverse = transliteration.invert().unwrap();
+        assert_eq!(inverse.apply("psaphi"), Some("ψαφι".to_string()));
+        assert_eq!(inverse.apply("pa"), None);
+    }
+
+    #[test]
+    fn test_replace_all() {
+        assert_eq!(create_example_fst().replace_all("Gruesse aus Koeln"), "Grüße aus Köln");
+    }
+}
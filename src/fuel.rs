@@ -0,0 +1,92 @@
+/// A budget of steps for simulations that might not terminate or take very long, like the exploration of the configurations
+/// of nondeterministic pushdown machines or exhaustive searches like `Dfa::repair`. Every step consumes one unit of fuel,
+/// and the simulation stops once the fuel runs out.
+/// The same fuel can be passed to several simulations to bound them together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fuel {
+    remaining: usize,
+}
+
+impl Fuel {
+    pub fn new(steps: usize) -> Fuel {
+        Fuel { remaining: steps }
+    }
+
+    /// Fuel that practically never runs out.
+    pub fn unlimited() -> Fuel {
+        Fuel { remaining: usize::MAX }
+    }
+
+    /// Consumes one unit of fuel for the next step. Returns false without consuming anything if the fuel has run out.
+    pub fn consume(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+
+    pub fn get_remaining(&self) -> usize {
+        self.remaining
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// The result of a simulation bounded by `Fuel`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Bounded<T> {
+    /// The simulation finished with the given result.
+    Done(T),
+    /// The fuel ran out before the simulation finished, so the result is unknown.
+    Exhausted,
+}
+
+impl<T> Bounded<T> {
+    pub fn is_exhausted(&self) -> bool {
+        matches!(self, Bounded::Exhausted)
+    }
+
+    /// Returns the result, or None if the fuel ran out.
+    pub fn done(self) -> Option<T> {
+        match self {
+            Bounded::Done(result) => Some(result),
+            Bounded::Exhausted => None,
+        }
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Bounded<U> {
+        match self {
+            Bounded::Done(result) => Bounded::Done(f(result)),
+            Bounded::Exhausted => Bounded::Exhausted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod fuel_tests {
+    use crate::{Bounded, Fuel};
+
+    #[test]
+    fn test_consume() {
+        let mut fuel = Fuel::new(2);
+        assert!(fuel.consume() && fuel.consume());
+        assert!(!fuel.consume(), "the fuel should have run out");
+        assert!(fuel.is_exhausted());
+        assert_eq!(fuel.get_remaining(), 0);
+        let mut fuel = Fuel::unlimited();
+        assert!((0..1000).all(|_| fuel.consume()));
+        assert!(!fuel.is_exhausted());
+    }
+
+    #[test]
+    fn test_bounded() {
+        assert_eq!(Bounded::Done(2).map(|result| result * 3), Bounded::Done(6));
+        assert_eq!(Bounded::Exhausted.map(|result: i32| result * 3), Bounded::Exhausted);
+        assert_eq!(Bounded::Done("result").done(), Some("result"));
+        assert_eq!(Bounded::<&str>::Exhausted.done(), None);
+        assert!(Bounded::<()>::Exhausted.is_exhausted());
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::iter::FromIterator;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{fresh_state_name, Dfa, MissingTransitionPolicy, Regex};
+
+/// Describes to which next state a GNFA may switch when it reads a word matching the label.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct GnfaTransition {
+    state: String,
+    label: Regex,
+    next_state: String,
+}
+
+/// # [Generalized nondeterministic finite automaton](https://en.wikipedia.org/wiki/Generalized_nondeterministic_finite_automaton)
+/// Transitions are labeled by regular expressions instead of single characters. There is at most one transition
+/// between two states, parallel transitions are merged into a union of their labels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gnfa {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<GnfaTransition>,
+}
+
+/// Describes why a state cannot be eliminated from a GNFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GnfaError {
+    UnknownState(String),
+    /// The start state and the accept states cannot be eliminated as the language would change.
+    CannotEliminate(String),
+}
+
+impl fmt::Display for GnfaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GnfaError::UnknownState(state) => write!(f, "the state {} does not exist", state),
+            GnfaError::CannotEliminate(state) => write!(f, "the state {} is the start state or an accept state and cannot be eliminated", state),
+        }
+    }
+}
+
+impl std::error::Error for GnfaError {}
+
+impl Gnfa {
+    /// Creates a GNFA without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> Gnfa {
+        Gnfa {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    /// Adds a transition labeled by the given regex. If there already is a transition between the two states,
+    /// its label is extended by a union with the new label instead.
+    pub fn add_transition(&mut self, state: &str, label: Regex, next_state: &str) {
+        let existing_transition = self.transitions.iter_mut()
+            .find(|transition| transition.state == state && transition.next_state == next_state);
+        match existing_transition {
+            Some(transition) => {
+                let existing_label = std::mem::replace(&mut transition.label, Regex::Empty);
+                transition.label = Regex::union(existing_label, label);
+            }
+            None => self.transitions.push(GnfaTransition { state: state.to_string(), label, next_state: next_state.to_string() }),
+        }
+    }
+
+    /// Returns the label of the transition between the two states, if there is one.
+    pub fn get_label(&self, state: &str, next_state: &str) -> Option<&Regex> {
+        self.transitions.iter()
+            .find(|transition| transition.state == state && transition.next_state == next_state)
+            .map(|transition| &transition.label)
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        let mut all_states = HashSet::from_iter(self.transitions.iter().flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()]));
+        all_states.insert(self.start_state.clone());
+        all_states.extend(self.accept_states.iter().cloned());
+        all_states
+    }
+
+    /// Checks whether a certain input is accepted by the GNFA. Uses the breath first algorithm on pairs of
+    /// states and input positions, because a label can consume an arbitrary number of characters.
+    pub fn check(&self, input: &str) -> bool {
+        let input: Vec<char> = input.chars().collect();
+        let mut visited_configurations: HashSet<(&str, usize)> = HashSet::new();
+        let mut configurations_to_visit: VecDeque<(&str, usize)> = VecDeque::new();
+        configurations_to_visit.push_back((&self.start_state, 0));
+        while let Some((state, position)) = configurations_to_visit.pop_front() {
+            if !visited_configurations.insert((state, position)) {
+                continue;
+            }
+            if position == input.len() && self.accept_states.contains(state) {
+                return true;
+            }
+            for transition in self.transitions.iter().filter(|transition| transition.state == state) {
+                for end in transition.label.match_ends(&input, position) {
+                    configurations_to_visit.push_back((&transition.next_state, end));
+                }
+            }
+        }
+        false
+    }
+
+    /// Removes a state while keeping the language of the GNFA. Every path p -> state -> q is replaced by a direct
+    /// transition from p to q labeled r1 (r2)* r3, where r1 and r3 are the labels of the incoming and outgoing
+    /// transition and r2 is the label of the self-loop of the eliminated state.
+    pub fn eliminate_state(&mut self, state: &str) -> Result<(), GnfaError> {
+        if !self.get_all_states().contains(state) {
+            return Err(GnfaError::UnknownState(state.to_string()));
+        }
+        if self.start_state == state || self.accept_states.contains(state) {
+            return Err(GnfaError::CannotEliminate(state.to_string()));
+        }
+        let self_loop = self.get_label(state, state).cloned();
+        let (transitions_touching_state, remaining_transitions): (Vec<GnfaTransition>, Vec<GnfaTransition>) = self.transitions.drain(..)
+            .partition(|transition| transition.state == state || transition.next_state == state);
+        self.transitions = remaining_transitions;
+        let incoming_transitions = transitions_touching_state.iter().filter(|transition| transition.next_state == state && transition.state != state);
+        for incoming_transition in incoming_transitions {
+            let outgoing_transitions = transitions_touching_state.iter().filter(|transition| transition.state == state && transition.next_state != state);
+            for outgoing_transition in outgoing_transitions {
+                let mut label = incoming_transition.label.clone();
+                if let Some(self_loop) = &self_loop {
+                    label = concatenate_labels(label, Regex::star(self_loop.clone()));
+                }
+                label = concatenate_labels(label, outgoing_transition.label.clone());
+                self.add_transition(&incoming_transition.state, label, &outgoing_transition.next_state);
+            }
+        }
+        Ok(())
+    }
+
+    /// Simplifies the labels of all transitions (see `Regex::simplify`), e.g. after eliminating states.
+    pub fn simplify_labels(&mut self) {
+        for transition in &mut self.transitions {
+            transition.label = transition.label.simplify();
+        }
+    }
+
+    /// Eliminates all states that merely sit on a chain, i.e. states with exactly one incoming and one outgoing transition
+    /// and no self-loop. This compresses diagrams, e.g. three states reading 'a', 'b' and 'c' become one transition labeled "abc".
+    /// Returns the eliminated states.
+    pub fn compress(&mut self) -> Vec<String> {
+        let mut eliminated_states = Vec::new();
+        loop {
+            let chain_state = self.get_all_states().into_iter().sorted().find(|state| {
+                let incoming_count = self.transitions.iter().filter(|transition| &transition.next_state == state).count();
+                let outgoing_count = self.transitions.iter().filter(|transition| &transition.state == state).count();
+                incoming_count == 1 && outgoing_count == 1 && self.get_label(state, state).is_none()
+                    && *state != self.start_state && !self.accept_states.contains(state)
+            });
+            match chain_state {
+                Some(state) => {
+                    self.eliminate_state(&state).unwrap();
+                    eliminated_states.push(state);
+                }
+                None => break,
+            }
+        }
+        eliminated_states
+    }
+
+    /// Brings the GNFA into the form required by the state-elimination algorithm: The start state has no incoming
+    /// transitions and there is exactly one accept state without outgoing transitions. Fresh states are added
+    /// and connected with epsilon transitions when necessary.
+    pub fn normalize(&mut self) {
+        let all_states = self.get_all_states();
+        let new_start_state = fresh_state_name("start", &all_states);
+        let new_accept_state = fresh_state_name("accept", &all_states);
+        let old_start_state = std::mem::replace(&mut self.start_state, new_start_state.clone());
+        self.add_transition(&new_start_state, Regex::Epsilon, &old_start_state);
+        for old_accept_state in self.accept_states.drain().sorted().collect_vec() {
+            self.add_transition(&old_accept_state, Regex::Epsilon, &new_accept_state);
+        }
+        self.accept_states.insert(new_accept_state);
+    }
+
+    /// Converts the GNFA into a regex describing its language with the state-elimination method: after normalizing, all states except the new start
+    /// and accept state are eliminated in alphabetical order, so that the label of the only remaining transition describes the language.
+    /// Labels are simplified after each step to keep them from growing needlessly (see `Regex::simplify`).
+    pub fn to_regex(&self) -> Regex {
+        let mut gnfa = self.clone();
+        gnfa.normalize();
+        let accept_state = gnfa.accept_states.iter().next().unwrap().clone();
+        for state in gnfa.get_all_states().into_iter().sorted() {
+            if state != gnfa.start_state && state != accept_state {
+                gnfa.eliminate_state(&state).unwrap();
+                gnfa.simplify_labels();
+            }
+        }
+        gnfa.get_label(&gnfa.start_state, &accept_state).cloned().unwrap_or(Regex::Empty).simplify()
+    }
+}
+
+/// Concatenates two labels but leaves out epsilon, so that the labels created by state elimination stay readable.
+fn concatenate_labels(left: Regex, right: Regex) -> Regex {
+    match (left, right) {
+        (Regex::Epsilon, right) => right,
+        (left, Regex::Epsilon) => left,
+        (left, right) => Regex::concatenation(left, right),
+    }
+}
+
+impl Dfa {
+    /// Converts the DFA into an equivalent GNFA whose transitions are labeled by single symbols.
+    /// Transitions between the same pair of states are merged into one transition labeled by a union.
+    pub fn to_gnfa(&self) -> Gnfa {
+        if self.missing_transitions == MissingTransitionPolicy::SelfLoop {
+            let mut materialized_dfa = self.clone();
+            materialized_dfa.materialize_missing_transitions();
+            return materialized_dfa.to_gnfa();
+        }
+        let mut gnfa = Gnfa::new(&self.name, &self.start_state);
+        for accept_state in &self.accept_states {
+            gnfa.add_accept_state(accept_state);
+        }
+        for transition in self.transitions.iter().sorted() {
+            gnfa.add_transition(&transition.state, Regex::Symbol(transition.input), &transition.next_state);
+        }
+        gnfa
+    }
+
+    /// Converts the DFA into a regex describing its language by eliminating the states of its GNFA (see `Gnfa::to_regex`).
+    /// Accept tags are ignored.
+    pub fn to_regex(&self) -> Regex {
+        self.to_gnfa().to_regex()
+    }
+}
+
+#[cfg(test)]
+mod gnfa_tests {
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+
+    use crate::{Dfa, Gnfa, GnfaError, Regex, Transition};
+
+    /// Creates a GNFA accepting "abc" followed by any number of 'd' characters, using a chain of states for "abc".
+    fn create_example_gnfa() -> Gnfa {
+        let mut gnfa = Gnfa::new("abcd*", "q0");
+        gnfa.add_transition("q0", Regex::Symbol('a'), "q1");
+        gnfa.add_transition("q1", Regex::Symbol('b'), "q2");
+        gnfa.add_transition("q2", Regex::Symbol('c'), "q3");
+        gnfa.add_transition("q3", Regex::Symbol('d'), "q3");
+        gnfa.add_accept_state("q3");
+        gnfa
+    }
+
+    #[test]
+    fn test_check() {
+        let gnfa = create_example_gnfa();
+        assert!(gnfa.check("abcdd"));
+        assert!(!gnfa.check("abdd"));
+        let mut gnfa = Gnfa::new("", "q0");
+        gnfa.add_transition("q0", Regex::star(Regex::word("ab")), "q1");
+        gnfa.add_accept_state("q1");
+        assert!(gnfa.check(""));
+        assert!(gnfa.check("abab"));
+        assert!(!gnfa.check("aba"));
+    }
+
+    #[test]
+    fn test_compress() {
+        let mut gnfa = create_example_gnfa();
+        assert_eq!(gnfa.compress(), vec!["q1", "q2"]);
+        assert_eq!(gnfa.get_label("q0", "q3"), Some(&Regex::word("abc")));
+        assert!(gnfa.check("abcd"));
+        assert_eq!(gnfa.eliminate_state("q3"), Err(GnfaError::CannotEliminate("q3".to_string())));
+    }
+
+    #[test]
+    fn test_to_gnfa_and_state_elimination() {
+        let dfa = Dfa::with_parts(
+            String::from("abcd*"),
+            "q0".to_string(),
+            HashSet::from_iter(vec!["q3".to_string()]),
+            vec![
+                Transition { state: "q0".to_string(), input: 'a', next_state: "q1".to_string() },
+                Transition { state: "q1".to_string(), input: 'b', next_state: "q2".to_string() },
+                Transition { state: "q2".to_string(), input: 'c', next_state: "q3".to_string() },
+                Transition { state: "q3".to_string(), input: 'd', next_state: "q3".to_string() },
+            ],
+        );
+        let mut gnfa = dfa.to_gnfa();
+        gnfa.normalize();
+        for state in &["q0", "q1", "q2", "q3"] {
+            gnfa.eliminate_state(state).unwrap();
+        }
+        assert_eq!(gnfa.get_label("start", "accept").unwrap().to_string(), "abcd*");
+    }
+
+    #[test]
+    fn test_to_regex() {
+        // Accepts words over 'a' and 'b' with an even number of 'a's.
+        let dfa = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap();
+        let regex = dfa.to_regex();
+        for word in &["", "b", "aa", "abab", "baab", "a", "bab", "aaa"] {
+            assert_eq!(regex.matches(word), dfa.check(word).0, "{}", word);
+        }
+        assert_eq!(create_example_gnfa().to_regex().to_string(), "abcd*");
+        assert_eq!(Gnfa::new("nothing", "q0").to_regex(), Regex::Empty);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+
+use crate::Dfa;
+
+/// The verdict on a candidate automaton, e.g. a student submission, compared to a reference DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GradingVerdict {
+    /// The shortest word accepted by the reference but rejected by the candidate, alphabetically smallest first.
+    pub missing_word: Option<String>,
+    /// The shortest word accepted by the candidate but rejected by the reference, alphabetically smallest first.
+    pub extra_word: Option<String>,
+    /// Whether the candidate has no more states than necessary for its own language, regardless of whether that language is correct.
+    pub is_minimal: bool,
+}
+
+impl GradingVerdict {
+    /// Checks whether the candidate accepts exactly the language of the reference.
+    pub fn is_equivalent(&self) -> bool {
+        self.missing_word.is_none() && self.extra_word.is_none()
+    }
+}
+
+impl fmt::Display for GradingVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_equivalent() {
+            write!(f, "equivalent")?;
+        } else {
+            write!(f, "not equivalent")?;
+        }
+        if let Some(word) = &self.missing_word {
+            write!(f, ", rejects \"{}\"", word)?;
+        }
+        if let Some(word) = &self.extra_word {
+            write!(f, ", accepts \"{}\"", word)?;
+        }
+        if self.is_minimal {
+            write!(f, ", minimal")
+        } else {
+            write!(f, ", not minimal")
+        }
+    }
+}
+
+impl Dfa {
+    /// Grades every candidate against this DFA as the reference, in the order of the candidates.
+    /// The counterexamples are the shortest accepted words of the differences of both languages (see `difference`).
+    pub fn grade_candidates(&self, candidates: &[Dfa]) -> Vec<GradingVerdict> {
+        candidates.iter()
+            .map(|candidate| {
+                let mut minimized_candidate = candidate.clone();
+                minimized_candidate.minimize();
+                GradingVerdict {
+                    missing_word: self.difference(candidate).shortest_accepted_word(),
+                    extra_word: candidate.difference(self).shortest_accepted_word(),
+                    is_minimal: minimized_candidate.get_all_mentioned_states().len() == candidate.get_all_mentioned_states().len(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod grading_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_grade_candidates() {
+        // Accepts words over 'a' and 'b' with an even number of 'a's.
+        let reference = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap();
+        // Correct, but with a redundant copy of the start state.
+        let redundant = Dfa::from_table(&["e0", "o", "e1"], &['a', 'b'], &[&[Some(1), Some(2)], &[Some(2), Some(1)], &[Some(1), Some(0)]], 0, &[0, 2]).unwrap();
+        // Accepts words of even length instead.
+        let even_length = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(1)], &[Some(0), Some(0)]], 0, &[0]).unwrap();
+        let verdicts = reference.grade_candidates(&[reference.clone(), redundant, even_length]);
+        assert_eq!(verdicts[0].to_string(), "equivalent, minimal");
+        assert!(verdicts[1].is_equivalent());
+        assert!(!verdicts[1].is_minimal);
+        assert_eq!(verdicts[2].missing_word, Some("b".to_string()));
+        assert_eq!(verdicts[2].extra_word, Some("ab".to_string()));
+        assert_eq!(verdicts[2].to_string(), "not equivalent, rejects \"b\", accepts \"ab\", minimal");
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Dfa, Nfa};
+
+/// A production of a linear grammar: the head is replaced by a word of terminals with at most one nonterminal,
+/// which is either at the right end (A → wB) or at the left end (A → Bw).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct LinearProduction {
+    head: String,
+    terminals: String,
+    nonterminal: Option<String>,
+    is_nonterminal_left: bool,
+}
+
+impl LinearProduction {
+    fn is_right_linear(&self) -> bool {
+        self.nonterminal.is_none() || !self.is_nonterminal_left
+    }
+
+    fn is_left_linear(&self) -> bool {
+        self.nonterminal.is_none() || self.is_nonterminal_left
+    }
+}
+
+/// # [Regular grammar](https://en.wikipedia.org/wiki/Regular_grammar)
+/// A grammar whose productions have at most one nonterminal, always at the right end (right-linear) or always at the left end
+/// (left-linear). Both forms generate exactly the regular languages. Productions whose body consists of terminals only belong to both forms,
+/// while grammars mixing both kinds of productions are linear grammars that might generate non-regular languages.
+/// Such grammars can be built and inspected, but not converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegularGrammar {
+    start_symbol: String,
+    productions: Vec<LinearProduction>,
+}
+
+impl RegularGrammar {
+    /// Creates a grammar without productions, which generates nothing.
+    pub fn new(start_symbol: &str) -> RegularGrammar {
+        RegularGrammar { start_symbol: start_symbol.to_string(), productions: Vec::new() }
+    }
+
+    /// Adds the production head → terminals nonterminal, or head → terminals if there is no nonterminal.
+    pub fn add_right_linear_production(&mut self, head: &str, terminals: &str, nonterminal: Option<&str>) {
+        self.add_production(head, terminals, nonterminal, false);
+    }
+
+    /// Adds the production head → nonterminal terminals, or head → terminals if there is no nonterminal.
+    pub fn add_left_linear_production(&mut self, head: &str, nonterminal: Option<&str>, terminals: &str) {
+        self.add_production(head, terminals, nonterminal, true);
+    }
+
+    fn add_production(&mut self, head: &str, terminals: &str, nonterminal: Option<&str>, is_nonterminal_left: bool) {
+        self.productions.push(LinearProduction {
+            head: head.to_string(),
+            terminals: terminals.to_string(),
+            nonterminal: nonterminal.map(String::from),
+            is_nonterminal_left,
+        });
+    }
+
+    pub fn is_right_linear(&self) -> bool {
+        self.productions.iter().all(LinearProduction::is_right_linear)
+    }
+
+    pub fn is_left_linear(&self) -> bool {
+        self.productions.iter().all(LinearProduction::is_left_linear)
+    }
+
+    /// Returns a grammar generating the reversed words: the terminals of each production are reversed and the nonterminal
+    /// switches sides, so right-linear grammars become left-linear and vice versa.
+    pub fn reverse(&self) -> RegularGrammar {
+        let productions = self.productions.iter()
+            .map(|production| LinearProduction {
+                terminals: production.terminals.chars().rev().collect(),
+                is_nonterminal_left: !production.is_nonterminal_left,
+                ..production.clone()
+            })
+            .collect();
+        RegularGrammar { start_symbol: self.start_symbol.clone(), productions }
+    }
+
+    /// Converts the grammar into an equivalent right-linear grammar, or returns None if it mixes left- and right-linear productions.
+    /// A left-linear grammar is converted via reversal: its reversal is right-linear and generates the reversed language,
+    /// so reversing the automaton of the reversal yields an automaton for the original language, whose transitions become productions.
+    /// The nonterminals of converted grammars are named after the states of that automaton.
+    pub fn to_right_linear(&self) -> Option<RegularGrammar> {
+        match self.is_right_linear() {
+            true => Some(self.clone()),
+            false => self.to_nfa().map(|nfa| RegularGrammar::from_nfa(&nfa)),
+        }
+    }
+
+    /// Converts the grammar into an equivalent left-linear grammar, or returns None if it mixes left- and right-linear productions.
+    /// A right-linear grammar is converted via reversal, just like `to_right_linear` does it the other way around.
+    pub fn to_left_linear(&self) -> Option<RegularGrammar> {
+        match self.is_left_linear() {
+            true => Some(self.clone()),
+            false => self.to_nfa().map(|nfa| RegularGrammar::from_nfa(&reverse_nfa(&nfa)).reverse()),
+        }
+    }
+
+    /// Converts the grammar into an NFA generating the same words, or returns None if it mixes left- and right-linear productions.
+    /// For right-linear grammars, nonterminals become states and a production A → aB becomes a transition from A to B reading 'a'.
+    /// Longer words of terminals are split using fresh states, and productions A → B are eliminated by copying the productions of B to A.
+    /// Left-linear grammars are reversed first, and so is the resulting NFA.
+    pub fn to_nfa(&self) -> Option<Nfa> {
+        if self.is_right_linear() {
+            return Some(self.to_nfa_from_right_linear());
+        }
+        if self.is_left_linear() {
+            return Some(reverse_nfa(&self.reverse().to_nfa_from_right_linear()));
+        }
+        None
+    }
+
+    fn to_nfa_from_right_linear(&self) -> Nfa {
+        let mut states: HashSet<String> = self.get_nonterminals();
+        let mut transitions: Vec<(String, char, String)> = Vec::new();
+        let mut unit_productions: Vec<(String, String)> = Vec::new();
+        let mut accept_states: HashSet<String> = HashSet::new();
+        for production in &self.productions {
+            let mut state = production.head.clone();
+            let terminals = production.terminals.chars().collect_vec();
+            for (index, terminal) in terminals.iter().enumerate() {
+                let next_state = match (&production.nonterminal, index + 1 == terminals.len()) {
+                    (Some(nonterminal), true) => nonterminal.clone(),
+                    _ => {
+                        let next_state = fresh_state_name(&format!("{}_{}", production.head, states.len()), &states);
+                        states.insert(next_state.clone());
+                        next_state
+                    }
+                };
+                transitions.push((state, *terminal, next_state.clone()));
+                state = next_state;
+            }
+            match (&production.nonterminal, terminals.is_empty()) {
+                (Some(nonterminal), true) => unit_productions.push((state, nonterminal.clone())),
+                (Some(_), false) => {}
+                (None, _) => {
+                    accept_states.insert(state);
+                }
+            }
+        }
+        let mut nfa = Nfa::new(&format!("grammar of {}", self.start_symbol), &self.start_symbol);
+        for state in states.iter().sorted() {
+            // All nonterminals reachable through productions A → B contribute their transitions and acceptance to A.
+            let mut reachable_states: HashSet<&str> = vec![state.as_str()].into_iter().collect();
+            let mut states_to_visit: VecDeque<&str> = vec![state.as_str()].into_iter().collect();
+            while let Some(current_state) = states_to_visit.pop_front() {
+                for (_, next_state) in unit_productions.iter().filter(|(head, _)| head == current_state) {
+                    if reachable_states.insert(next_state) {
+                        states_to_visit.push_back(next_state);
+                    }
+                }
+            }
+            for reachable_state in reachable_states.iter().sorted() {
+                if accept_states.contains(*reachable_state) {
+                    nfa.add_accept_state(state);
+                }
+                for (_, input, next_state) in transitions.iter().filter(|(transition_state, _, _)| transition_state == reachable_state) {
+                    nfa.add_transition(state, *input, next_state);
+                }
+            }
+        }
+        nfa.transitions = nfa.transitions.into_iter().unique().collect();
+        nfa
+    }
+
+    /// Creates a right-linear grammar from an NFA: states become nonterminals, a transition from p to q reading 'a' becomes
+    /// the production p → aq and every accept state p gets the production p → ε.
+    fn from_nfa(nfa: &Nfa) -> RegularGrammar {
+        let mut grammar = RegularGrammar::new(&nfa.start_state);
+        for transition in nfa.transitions.iter().sorted() {
+            grammar.add_right_linear_production(&transition.state, &transition.input.to_string(), Some(&transition.next_state));
+        }
+        for accept_state in nfa.accept_states.iter().sorted() {
+            grammar.add_right_linear_production(accept_state, "", None);
+        }
+        grammar
+    }
+
+    fn get_nonterminals(&self) -> HashSet<String> {
+        let mut nonterminals: HashSet<String> = self.productions.iter()
+            .flat_map(|production| production.nonterminal.iter().chain(vec![&production.head]).cloned())
+            .collect();
+        nonterminals.insert(self.start_symbol.clone());
+        nonterminals
+    }
+}
+
+impl Dfa {
+    /// Converts the DFA into a right-linear grammar generating the accepted words, just like `RegularGrammar::to_nfa` works the other way around:
+    /// states become nonterminals, a transition from p to q reading 'a' becomes the production p → aq and every accept state p gets the production p → ε.
+    pub fn to_grammar(&self) -> RegularGrammar {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        RegularGrammar::from_nfa(&Nfa::from_parts(dfa.name, dfa.start_state, dfa.accept_states, dfa.transitions))
+    }
+}
+
+/// Returns an NFA accepting the reversed words. The transitions are reversed, the old start state becomes the only accept state
+/// and a fresh start state takes over the transitions of all old accept states, as an NFA has a single start state.
+fn reverse_nfa(nfa: &Nfa) -> Nfa {
+    let mut states = nfa.get_all_states();
+    states.insert(nfa.start_state.clone());
+    let start_state = fresh_state_name(&nfa.start_state, &states);
+    let mut reversed_nfa = Nfa::new(&format!("reversal of {}", nfa.name), &start_state);
+    reversed_nfa.add_accept_state(&nfa.start_state);
+    if nfa.accept_states.contains(&nfa.start_state) {
+        reversed_nfa.add_accept_state(&start_state);
+    }
+    for transition in nfa.transitions.iter().sorted() {
+        reversed_nfa.add_transition(&transition.next_state, transition.input, &transition.state);
+        if nfa.accept_states.contains(&transition.next_state) {
+            reversed_nfa.add_transition(&start_state, transition.input, &transition.state);
+        }
+    }
+    reversed_nfa.transitions = reversed_nfa.transitions.into_iter().unique().collect();
+    reversed_nfa
+}
+
+/// Lists the productions of each nonterminal in one line like "S → aS | b", in the order the nonterminals first appear as heads.
+impl fmt::Display for RegularGrammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for head in self.productions.iter().map(|production| &production.head).unique() {
+            let bodies = self.productions.iter()
+                .filter(|production| production.head == *head)
+                .map(|production| {
+                    let nonterminal = production.nonterminal.clone().unwrap_or_default();
+                    match (production.is_nonterminal_left, production.terminals.is_empty() && nonterminal.is_empty()) {
+                        (_, true) => String::from("ε"),
+                        (true, false) => format!("{}{}", nonterminal, production.terminals),
+                        (false, false) => format!("{}{}", production.terminals, nonterminal),
+                    }
+                })
+                .join(" | ");
+            writeln!(f, "{} → {}", head, bodies)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod grammar_tests {
+    use crate::{Dfa, RegularGrammar};
+
+    #[test]
+    fn test_dfa_to_grammar() {
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        let dfa = Dfa::from_table(&["S", "B"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap();
+        let grammar = dfa.to_grammar();
+        assert_eq!(grammar.to_string(), "B → aS | bB | ε\nS → aS | bB\n");
+        let nfa = grammar.to_nfa().unwrap();
+        assert!(nfa.check("b") && nfa.check("abab"));
+        assert!(!nfa.check("") && !nfa.check("ba"));
+    }
+
+    #[test]
+    fn test_left_and_right_linear_conversion() {
+        // Generates "ab" followed by any number of 'c's: S → Sc | ab.
+        let mut left_linear_grammar = RegularGrammar::new("S");
+        left_linear_grammar.add_left_linear_production("S", Some("S"), "c");
+        left_linear_grammar.add_left_linear_production("S", None, "ab");
+        assert!(left_linear_grammar.is_left_linear() && !left_linear_grammar.is_right_linear());
+        assert_eq!(left_linear_grammar.to_string(), "S → Sc | ab\n");
+        let right_linear_grammar = left_linear_grammar.to_right_linear().unwrap();
+        assert!(right_linear_grammar.is_right_linear());
+        let back_to_left_linear_grammar = right_linear_grammar.to_left_linear().unwrap();
+        assert!(back_to_left_linear_grammar.is_left_linear());
+        for grammar in &[&left_linear_grammar, &right_linear_grammar, &back_to_left_linear_grammar] {
+            let nfa = grammar.to_nfa().unwrap();
+            assert!(nfa.check("ab") && nfa.check("abccc"));
+            assert!(!nfa.check("a") && !nfa.check("abcb") && !nfa.check("cab"));
+        }
+        let mut mixed_grammar = RegularGrammar::new("S");
+        mixed_grammar.add_right_linear_production("S", "a", Some("T"));
+        mixed_grammar.add_left_linear_production("T", Some("S"), "b");
+        mixed_grammar.add_left_linear_production("T", None, "");
+        assert!(mixed_grammar.to_right_linear().is_none() && mixed_grammar.to_nfa().is_none());
+    }
+}
@@ -0,0 +1,45 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::TokenDfa;
+
+/// Splits the text into user-perceived characters, the extended grapheme clusters of
+/// [Unicode Standard Annex #29](https://www.unicode.org/reports/tr29/), as computed by the `unicode-segmentation` crate.
+pub fn graphemes(text: &str) -> Vec<&str> {
+    UnicodeSegmentation::graphemes(text, true).collect()
+}
+
+impl TokenDfa<String> {
+    /// Checks the input with grapheme clusters as symbols (see `graphemes`), so that emoji and characters with combining
+    /// accents are read as one symbol, just like a user perceives them, instead of being split into several chars.
+    pub fn check_graphemes(&self, input: &str) -> (bool, Vec<String>) {
+        let symbols: Vec<String> = graphemes(input).into_iter().map(|grapheme| grapheme.to_string()).collect();
+        self.check(&symbols)
+    }
+}
+
+#[cfg(test)]
+mod grapheme_tests {
+    use crate::{graphemes, TokenDfa};
+
+    #[test]
+    fn test_graphemes() {
+        assert_eq!(graphemes("e\u{301}a\r\n"), vec!["e\u{301}", "a", "\r\n"]);
+        // A family emoji consists of several emoji joined by zero width joiners.
+        assert_eq!(graphemes("👨\u{200D}👩\u{200D}👧!"), vec!["👨\u{200D}👩\u{200D}👧", "!"]);
+        assert_eq!(graphemes("👍🏽"), vec!["👍🏽"], "skin tone modifiers should extend the emoji");
+        assert_eq!(graphemes("🇩🇪🇫🇷🇮"), vec!["🇩🇪", "🇫🇷", "🇮"], "regional indicators should be paired up into flags");
+        // A Hangul syllable written with conjoining jamo, and a Devanagari syllable with a spacing mark.
+        assert_eq!(graphemes("\u{1100}\u{1161}\u{11A8}한"), vec!["\u{1100}\u{1161}\u{11A8}", "한"]);
+        assert_eq!(graphemes("कि"), vec!["कि"]);
+    }
+
+    #[test]
+    fn test_check_graphemes() {
+        // Accepts exactly one user-perceived character "é", written with a combining accent.
+        let mut dfa = TokenDfa::new("accented e", "q0");
+        dfa.add_transition("q0", "e\u{301}".to_string(), "q1");
+        dfa.add_accept_state("q1");
+        assert!(dfa.check_graphemes("e\u{301}").0);
+        assert!(!dfa.check_graphemes("e").0);
+    }
+}
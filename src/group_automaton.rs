@@ -0,0 +1,80 @@
+use std::collections::{HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Checks whether the DFA is a permutation automaton, also called group automaton,
+    /// i.e. whether every symbol of the alphabet acts as a permutation of the states. This requires a transition for every state
+    /// and symbol, and no symbol may lead from two different states into the same state. The transformation monoid is then a group.
+    pub fn is_group_automaton(&self) -> bool {
+        self.get_symbol_permutations().is_some()
+    }
+
+    /// Returns the order of the permutation group generated by the symbols (see `is_group_automaton`), or None if some symbol
+    /// does not act as a permutation. The group is computed by composing the permutations using the breath first algorithm
+    /// until no new permutation shows up, so its order is the number of different state transformations caused by words.
+    pub fn get_permutation_group_order(&self) -> Option<usize> {
+        let generators = self.get_symbol_permutations()?;
+        let identity = (0..self.get_all_mentioned_states().len()).collect_vec();
+        let mut group: HashSet<Vec<usize>> = HashSet::new();
+        let mut permutations_to_visit = VecDeque::new();
+        group.insert(identity.clone());
+        permutations_to_visit.push_back(identity);
+        while let Some(permutation) = permutations_to_visit.pop_front() {
+            for generator in &generators {
+                // First apply the permutation, then the generator, just like reading a word followed by a symbol.
+                let composition = permutation.iter().map(|state| generator[*state]).collect_vec();
+                if group.insert(composition.clone()) {
+                    permutations_to_visit.push_back(composition);
+                }
+            }
+        }
+        Some(group.len())
+    }
+
+    /// Returns the permutation of every symbol of the alphabet, mapping the index of each state (in alphabetical order) to the index
+    /// of the next state. Returns None if some symbol does not act as a permutation.
+    fn get_symbol_permutations(&self) -> Option<Vec<Vec<usize>>> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let mut permutations = Vec::new();
+        for input in dfa.get_alphabet().into_iter().sorted() {
+            let mut permutation = Vec::new();
+            for state in &states {
+                let next_state = &dfa.get_transition(state, &input)?.next_state;
+                permutation.push(states.iter().position(|other_state| other_state == next_state).unwrap());
+            }
+            if permutation.iter().unique().count() != states.len() {
+                return None;
+            }
+            permutations.push(permutation);
+        }
+        Some(permutations)
+    }
+}
+
+#[cfg(test)]
+mod group_automaton_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_get_permutation_group_order() {
+        // 'a' rotates three states and 'b' swaps the first two, which generates the symmetric group of order 6.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2"],
+            &['a', 'b'],
+            &[&[Some(1), Some(1)], &[Some(2), Some(0)], &[Some(0), Some(2)]],
+            0,
+            &[0],
+        ).unwrap();
+        assert!(dfa.is_group_automaton());
+        assert_eq!(dfa.get_permutation_group_order(), Some(6));
+        // 'a' leads from every state into the same state.
+        let reset = Dfa::from_table(&["q0", "q1"], &['a'], &[&[Some(0)], &[Some(0)]], 0, &[1]).unwrap();
+        assert!(!reset.is_group_automaton());
+        assert_eq!(reset.get_permutation_group_order(), None);
+    }
+}
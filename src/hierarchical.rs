@@ -0,0 +1,238 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// # Hierarchical automaton
+/// A deterministic automaton whose states may contain nested sub-automata, as in statecharts. Entering a composite state
+/// enters the start state of its sub-automaton, recursively. A transition of a composite state leaves it from any of its nested states,
+/// unless a nested state has a transition for the same input itself, which takes priority. A configuration accepts
+/// if every state along the path from the outermost to the innermost state is an accept state of its automaton.
+/// States may have entry and exit actions, which are executed when a transition enters or leaves them, including the nested states.
+/// A transition first executes the exit actions from the innermost state outwards, then the entry actions from the outermost state inwards.
+#[derive(Debug, Clone)]
+pub struct HierarchicalAutomaton {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<Transition>,
+    sub_automata: HashMap<String, HierarchicalAutomaton>,
+    entry_actions: HashMap<String, String>,
+    exit_actions: HashMap<String, String>,
+}
+
+impl HierarchicalAutomaton {
+    /// Creates an automaton without transitions, accept states and sub-automata.
+    pub fn new(name: &str, start_state: &str) -> HierarchicalAutomaton {
+        HierarchicalAutomaton {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+            sub_automata: HashMap::new(),
+            entry_actions: HashMap::new(),
+            exit_actions: HashMap::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    /// Adds a transition between two states of this automaton. Transitions into or out of nested states belong to the sub-automata.
+    pub fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.transitions.push(Transition { state: state.to_string(), input, next_state: next_state.to_string() });
+    }
+
+    /// Turns the state into a composite state containing the sub-automaton, replacing any previous one.
+    pub fn set_sub_automaton(&mut self, state: &str, sub_automaton: HierarchicalAutomaton) {
+        self.sub_automata.insert(state.to_string(), sub_automaton);
+    }
+
+    /// Sets the action that is executed whenever the state of this automaton is entered, replacing any previous one.
+    pub fn set_entry_action(&mut self, state: &str, action: &str) {
+        self.entry_actions.insert(state.to_string(), action.to_string());
+    }
+
+    /// Sets the action that is executed whenever the state of this automaton is left, replacing any previous one.
+    pub fn set_exit_action(&mut self, state: &str, action: &str) {
+        self.exit_actions.insert(state.to_string(), action.to_string());
+    }
+
+    /// Runs the automaton on the input and returns the executed actions, starting with the entry actions of the initial states,
+    /// or None if a symbol has no transition.
+    pub fn get_actions(&self, input: &str) -> Option<Vec<String>> {
+        let mut path = self.enter(&self.start_state);
+        let mut actions = self.get_entry_actions(&path, 0);
+        for char in input.chars() {
+            let (depth, next_path) = self.get_next_path(&path, char)?;
+            actions.extend(self.get_transition_actions(&path, &next_path, depth));
+            path = next_path;
+        }
+        Some(actions)
+    }
+
+    /// Returns the path of states that is entered when entering the state, i.e. the state followed by the start states of the nested sub-automata.
+    fn enter<'a>(&'a self, state: &'a str) -> Vec<&'a str> {
+        let mut path = vec![state];
+        let mut automaton = self;
+        while let Some(sub_automaton) = automaton.sub_automata.get(path[path.len() - 1]) {
+            path.push(&sub_automaton.start_state);
+            automaton = sub_automaton;
+        }
+        path
+    }
+
+    /// Returns the automata along the path, starting with this one, so that the state of the path at each index belongs to the automaton at that index.
+    fn get_automata_along<'a>(&'a self, path: &[&str]) -> Vec<&'a HierarchicalAutomaton> {
+        let mut automata = vec![self];
+        for state in &path[..path.len() - 1] {
+            automata.push(&automata[automata.len() - 1].sub_automata[*state]);
+        }
+        automata
+    }
+
+    /// Returns the path of states after reading the input, by searching for a transition from the innermost state outwards,
+    /// together with the depth of the transition's automaton. The states up to that depth are neither left nor entered.
+    fn get_next_path<'a>(&'a self, path: &[&'a str], input: char) -> Option<(usize, Vec<&'a str>)> {
+        let automata = self.get_automata_along(path);
+        (0..path.len()).rev().find_map(|depth| {
+            let transition = automata[depth].transitions.iter().find(|transition| transition.state == path[depth] && transition.input == input)?;
+            let mut next_path = path[..depth].to_vec();
+            next_path.extend(automata[depth].enter(&transition.next_state));
+            Some((depth, next_path))
+        })
+    }
+
+    /// Returns the entry actions of the states of the path from the depth on, from the outermost state inwards.
+    fn get_entry_actions(&self, path: &[&str], depth: usize) -> Vec<String> {
+        let automata = self.get_automata_along(path);
+        (depth..path.len()).filter_map(|index| automata[index].entry_actions.get(path[index]).cloned()).collect()
+    }
+
+    /// Returns the actions of a transition of the given depth: the exit actions of the left states, followed by the entry actions of the entered ones.
+    fn get_transition_actions(&self, path: &[&str], next_path: &[&str], depth: usize) -> Vec<String> {
+        let automata = self.get_automata_along(path);
+        let exit_actions = (depth..path.len()).rev().filter_map(|index| automata[index].exit_actions.get(path[index]).cloned());
+        exit_actions.chain(self.get_entry_actions(next_path, depth)).collect()
+    }
+
+    fn get_input_symbols(&self) -> BTreeSet<char> {
+        let mut input_symbols: BTreeSet<char> = self.transitions.iter().map(|transition| transition.input).collect();
+        for sub_automaton in self.sub_automata.values() {
+            input_symbols.extend(sub_automaton.get_input_symbols());
+        }
+        input_symbols
+    }
+
+    /// Builds a plain DFA whose states are the reachable paths of nested states, named by joining the path with "/", e.g. "on/playing".
+    /// Slashes and backslashes within the names of states are escaped with a backslash, so that different paths get different names.
+    /// The states are found using the breath first algorithm.
+    pub fn flatten(&self) -> Dfa {
+        self.flatten_with_actions().0
+    }
+
+    /// Flattens the automaton like `flatten` and additionally returns the actions executed by each transition of the DFA,
+    /// given by its state and input. Transitions without actions are left out.
+    pub fn flatten_with_actions(&self) -> (Dfa, HashMap<(String, char), Vec<String>>) {
+        let input_symbols = self.get_input_symbols();
+        let start_path = self.enter(&self.start_state);
+        let mut visited_paths = HashSet::new();
+        let mut paths_to_visit = VecDeque::new();
+        let mut accept_states = HashSet::new();
+        let mut transitions = Vec::new();
+        let mut transition_actions = HashMap::new();
+        visited_paths.insert(start_path.clone());
+        paths_to_visit.push_back(start_path.clone());
+        while let Some(path) = paths_to_visit.pop_front() {
+            let is_accepting = self.get_automata_along(&path).iter().zip(path.iter()).all(|(automaton, state)| automaton.accept_states.contains(*state));
+            if is_accepting {
+                accept_states.insert(get_flat_state_name(&path));
+            }
+            for input in &input_symbols {
+                if let Some((depth, next_path)) = self.get_next_path(&path, *input) {
+                    let actions = self.get_transition_actions(&path, &next_path, depth);
+                    if !actions.is_empty() {
+                        transition_actions.insert((get_flat_state_name(&path), *input), actions);
+                    }
+                    transitions.push(Transition { state: get_flat_state_name(&path), input: *input, next_state: get_flat_state_name(&next_path) });
+                    if visited_paths.insert(next_path.clone()) {
+                        paths_to_visit.push_back(next_path);
+                    }
+                }
+            }
+        }
+        let dfa = Dfa::with_parts(self.name.clone(), get_flat_state_name(&start_path), accept_states, transitions.into_iter().sorted().collect());
+        (dfa, transition_actions)
+    }
+}
+
+fn get_flat_state_name(path: &[&str]) -> String {
+    path.iter().map(|state| state.replace('\\', "\\\\").replace('/', "\\/")).join("/")
+}
+
+#[cfg(test)]
+mod hierarchical_tests {
+    use itertools::Itertools;
+
+    use crate::HierarchicalAutomaton;
+
+    #[test]
+    fn test_flatten() {
+        // A player that is switched on with 'o' and off with 'x'. While on, 'p' toggles between paused and playing.
+        let mut on = HierarchicalAutomaton::new("on", "paused");
+        on.add_transition("paused", 'p', "playing");
+        on.add_transition("playing", 'p', "paused");
+        // While playing, 'x' only pauses, overriding the transition of the composite state.
+        on.add_transition("playing", 'x', "paused");
+        on.add_accept_state("paused");
+        on.add_accept_state("playing");
+        let mut player = HierarchicalAutomaton::new("player", "off");
+        player.add_transition("off", 'o', "on");
+        player.add_transition("on", 'x', "off");
+        player.add_accept_state("off");
+        player.set_sub_automaton("on", on);
+        let dfa = player.flatten();
+        assert_eq!(dfa.get_all_states().len(), 3);
+        assert!(dfa.get_all_states().contains("on/playing"));
+        assert!(dfa.check("ox").0);
+        assert!(dfa.check("opxx").0);
+        assert!(!dfa.check("opx").0, "the nested transition should take priority");
+        assert!(!dfa.check("op").0, "the composite state is not an accept state");
+    }
+    #[test]
+    fn test_flatten_with_actions() {
+        let mut door = HierarchicalAutomaton::new("door", "closed");
+        door.add_transition("closed", 'o', "open");
+        door.add_transition("open", 'c', "closed");
+        door.set_entry_action("open", "turn on light");
+        door.set_exit_action("open", "turn off light");
+        let mut room = HierarchicalAutomaton::new("room", "door");
+        room.add_transition("door", 'a', "alarm");
+        room.set_sub_automaton("door", door);
+        room.set_entry_action("door", "unlock");
+        room.set_exit_action("door", "lock");
+        assert_eq!(room.get_actions("oa").unwrap(), vec!["unlock", "turn on light", "turn off light", "lock"]);
+        let (dfa, actions) = room.flatten_with_actions();
+        assert_eq!(actions[&("door/closed".to_string(), 'o')], vec!["turn on light"]);
+        assert_eq!(actions[&("door/open".to_string(), 'a')], vec!["turn off light", "lock"]);
+        assert_eq!(actions[&("door/closed".to_string(), 'a')], vec!["lock"], "closed has no exit action");
+        assert_eq!(actions.len(), 4);
+        assert_eq!(dfa.get_all_states().len(), 3);
+    }
+
+    #[test]
+    fn test_flatten_escapes_slashes() {
+        // Without escaping, the state "a/b" and the nested state "b" of "a" would both be called "a/b".
+        let mut a = HierarchicalAutomaton::new("a", "b");
+        a.add_accept_state("b");
+        let mut automaton = HierarchicalAutomaton::new("slashes", "a/b");
+        automaton.add_transition("a/b", 'x', "a");
+        automaton.set_sub_automaton("a", a);
+        automaton.add_accept_state("a");
+        let dfa = automaton.flatten();
+        assert_eq!(dfa.get_all_states().into_iter().sorted().collect_vec(), vec!["a/b", "a\\/b"]);
+        assert!(dfa.check("x").0 && !dfa.check("").0);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Dfa, Transition};
+
+/// A DFA that cannot be changed after its creation. Operations return new automata instead of mutating in place,
+/// and parts that an operation leaves unchanged are shared through reference counting instead of being copied.
+/// Cloning is therefore cheap, and the automaton can be shared between threads.
+///
+/// Missing transitions always lead into an implicit error state. State metadata and accept tags are not kept.
+#[derive(Debug, Clone)]
+pub struct ImmutableDfa {
+    name: Arc<String>,
+    start_state: Arc<String>,
+    accept_states: Arc<HashSet<String>>,
+    transitions: Arc<Vec<Transition>>,
+    alphabet: Arc<HashSet<char>>,
+}
+
+impl From<Dfa> for ImmutableDfa {
+    fn from(mut dfa: Dfa) -> ImmutableDfa {
+        dfa.materialize_missing_transitions();
+        let alphabet = dfa.get_alphabet();
+        ImmutableDfa {
+            name: Arc::new(dfa.name),
+            start_state: Arc::new(dfa.start_state),
+            accept_states: Arc::new(dfa.accept_states),
+            transitions: Arc::new(dfa.transitions),
+            alphabet: Arc::new(alphabet),
+        }
+    }
+}
+
+impl ImmutableDfa {
+    /// Creates a mutable copy of the automaton.
+    pub fn to_dfa(&self) -> Dfa {
+        Dfa {
+            alphabet: Some(self.alphabet.as_ref().clone()),
+            ..Dfa::with_parts(
+                self.name.to_string(),
+                self.start_state.to_string(),
+                self.accept_states.as_ref().clone(),
+                self.transitions.as_ref().clone(),
+            )
+        }
+    }
+
+    pub fn check(&self, input: &str) -> bool {
+        let mut state: &str = &self.start_state;
+        for char in input.chars() {
+            state = match self.get_transition(state, &char) {
+                Some(transition) => &transition.next_state,
+                None => return false,
+            };
+        }
+        self.accept_states.contains(state)
+    }
+
+    pub fn get_transition(&self, state: &str, input: &char) -> Option<&Transition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    /// Checks whether both automata share the same transitions in memory, which is the case if one was derived from the other
+    /// by an operation that left the transitions unchanged.
+    pub fn shares_transitions_with(&self, other: &ImmutableDfa) -> bool {
+        Arc::ptr_eq(&self.transitions, &other.transitions)
+    }
+
+    /// Returns the minimal automaton accepting the same language (see `Dfa::minimize`). If the automaton is already minimal,
+    /// the result shares all of its parts.
+    pub fn minimize(&self) -> ImmutableDfa {
+        let mut dfa = self.to_dfa();
+        let renamings = dfa.minimize();
+        if renamings.iter().all(|(state, new_state)| state == new_state) && dfa.transitions.len() == self.transitions.len() {
+            return self.clone();
+        }
+        ImmutableDfa { name: Arc::clone(&self.name), alphabet: Arc::clone(&self.alphabet), ..ImmutableDfa::from(dfa) }
+    }
+
+    /// Returns an automaton accepting exactly the words over the alphabet that this one rejects. If every state has a transition
+    /// for every symbol, only the accept states change and the transitions are shared. Otherwise, missing transitions lead into a new sink state.
+    pub fn complement(&self) -> ImmutableDfa {
+        let mut states = self.get_all_states();
+        let missing_transitions = states.iter().sorted()
+            .cartesian_product(self.alphabet.iter().sorted())
+            .filter(|(state, input)| self.get_transition(state, input).is_none())
+            .map(|(state, input)| (state.clone(), *input))
+            .collect_vec();
+        let transitions = match missing_transitions.is_empty() {
+            true => Arc::clone(&self.transitions),
+            false => {
+                let sink_state = fresh_state_name("sink", &states);
+                states.insert(sink_state.clone());
+                let mut transitions = self.transitions.as_ref().clone();
+                transitions.extend(missing_transitions.into_iter().map(|(state, input)| Transition { state, input, next_state: sink_state.clone() }));
+                transitions.extend(self.alphabet.iter().sorted().map(|input| Transition { state: sink_state.clone(), input: *input, next_state: sink_state.clone() }));
+                Arc::new(transitions)
+            }
+        };
+        ImmutableDfa {
+            name: Arc::new(format!("complement of {}", self.name)),
+            start_state: Arc::clone(&self.start_state),
+            accept_states: Arc::new(states.difference(&self.accept_states).cloned().collect()),
+            transitions,
+            alphabet: Arc::clone(&self.alphabet),
+        }
+    }
+
+    /// Runs both automata in parallel, see `Dfa::product`. A word is accepted if `combine` returns true for the acceptance of both automata,
+    /// e.g. `|first, second| first && second` for the intersection.
+    pub fn product<F: Fn(bool, bool) -> bool>(&self, other: &ImmutableDfa, combine: F) -> ImmutableDfa {
+        ImmutableDfa::from(self.to_dfa().product(&other.to_dfa(), combine))
+    }
+
+    fn get_all_states(&self) -> HashSet<String> {
+        let mut states: HashSet<String> = self.transitions.iter()
+            .flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()])
+            .collect();
+        states.insert(self.start_state.to_string());
+        states.extend(self.accept_states.iter().cloned());
+        states
+    }
+}
+
+#[cfg(test)]
+mod immutable_dfa_tests {
+    use crate::{Dfa, ImmutableDfa};
+
+    #[test]
+    fn test_operations() {
+        // Accepts words over 'a' and 'b' with an even number of 'a's.
+        let even_a = ImmutableDfa::from(Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap());
+        let odd_a = even_a.complement();
+        assert!(odd_a.shares_transitions_with(&even_a), "the DFA is complete, so the transitions are shared");
+        assert!(odd_a.check("ab"));
+        assert!(!odd_a.check("aa"));
+        assert!(even_a.minimize().shares_transitions_with(&even_a), "the DFA is already minimal");
+        let nothing = even_a.product(&odd_a, |first, second| first && second).minimize();
+        assert!(!nothing.check("") && !nothing.check("a"));
+        let everything = even_a.product(&odd_a, |first, second| first || second);
+        assert!(everything.check("") && everything.check("aba"));
+        assert!(even_a.check("abba"), "operations do not change the original automaton");
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+pub(crate) const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Dfa {
+    /// Computes a fingerprint of the accepted language, so that DFAs accepting the same words get the same hash, no matter how their
+    /// states are named or how many states they have. Unlike `std::hash::Hash`, the value is stable across processes and Rust versions,
+    /// so it can be stored in caches or compared between builds. Different languages may collide, though that is very unlikely.
+    ///
+    /// The hash is computed from the canonical form of the language: the minimal DFA without dead states, whose states are numbered
+    /// in the order the breath first algorithm discovers them when reading symbols in alphabetical order.
+    /// The canonical form is encoded as text and hashed with [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function).
+    pub fn language_hash(&self) -> u64 {
+        self.get_canonical_encoding().bytes().fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME))
+    }
+
+    /// Encodes the canonical form of the language (see `language_hash`). Each state is encoded by its acceptance
+    /// followed by its transitions, for example "1 a0 b1;" for an accept state leading into state 0 with 'a' and into state 1 with 'b'.
+    pub(crate) fn get_canonical_encoding(&self) -> String {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        // Tags would keep equivalent accept states apart during minimization, but do not belong to the language.
+        dfa.accept_tags.clear();
+        // Dead states only reject, so removing them does not change the language but makes partial and complete DFAs look the same.
+        let productive_states = dfa.get_productive_states();
+        if !productive_states.contains(&dfa.start_state) {
+            return String::from("empty");
+        }
+        dfa.transitions.retain(|transition| productive_states.contains(&transition.state) && productive_states.contains(&transition.next_state));
+        dfa.minimize();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut states_to_visit = VecDeque::new();
+        let mut encoding = String::new();
+        indices.insert(dfa.start_state.clone(), 0);
+        states_to_visit.push_back(dfa.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            encoding.push(if dfa.accept_states.contains(&state) { '1' } else { '0' });
+            for transition in dfa.transitions.iter().filter(|transition| transition.state == state).sorted_by_key(|transition| transition.input) {
+                if !indices.contains_key(&transition.next_state) {
+                    indices.insert(transition.next_state.clone(), indices.len());
+                    states_to_visit.push_back(transition.next_state.clone());
+                }
+                encoding.push_str(&format!(" {}{}", transition.input, indices[&transition.next_state]));
+            }
+            encoding.push(';');
+        }
+        encoding
+    }
+}
+
+#[cfg(test)]
+mod language_hash_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_language_hash() {
+        // Both accept the words over 'a' of even length, but the second one has redundant states, other names and a dead state for 'b'.
+        let dfa = Dfa::from_table(&["even", "odd"], &['a'], &[&[Some(1)], &[Some(0)]], 0, &[0]).unwrap();
+        let redundant_dfa = Dfa::from_table(
+            &["p0", "p1", "p2", "p3", "dead"],
+            &['a', 'b'],
+            &[&[Some(1), Some(4)], &[Some(2), Some(4)], &[Some(3), Some(4)], &[Some(0), Some(4)], &[Some(4), Some(4)]],
+            0,
+            &[0, 2],
+        ).unwrap();
+        assert_eq!(dfa.language_hash(), redundant_dfa.language_hash());
+        let odd = Dfa::from_table(&["even", "odd"], &['a'], &[&[Some(1)], &[Some(0)]], 0, &[1]).unwrap();
+        assert_ne!(dfa.language_hash(), odd.language_hash());
+        assert_eq!(dfa.get_canonical_encoding(), "1 a1;0 a0;");
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// Horizontal distance between two layers of states.
+const LAYER_SPACING: f64 = 150.0;
+/// Vertical distance between two states of the same layer.
+const STATE_SPACING: f64 = 100.0;
+/// Number of sweeps of the barycenter heuristic used to reduce edge crossings.
+const ORDERING_SWEEPS: usize = 4;
+
+impl Dfa {
+    /// Computes a layered layout (a simplified [Sugiyama layout](https://en.wikipedia.org/wiki/Layered_graph_drawing))
+    /// and stores the position of every state in its metadata. States are put into layers from left to right by their
+    /// distance from the start state. Within each layer, states are ordered by the barycenter heuristic, so that states
+    /// are placed close to their neighbors in the adjacent layers and edges cross less often.
+    /// Unreachable states are put into an additional layer at the very right.
+    pub fn compute_layout(&mut self) {
+        let mut orders = self.compute_layers();
+        // The position of every state within its layer, used for computing barycenters.
+        let index_within_layer = |orders: &Vec<Vec<String>>| -> HashMap<String, usize> {
+            orders.iter().flat_map(|layer| layer.iter().enumerate().map(|(index, state)| (state.clone(), index))).collect()
+        };
+        for sweep in 0..ORDERING_SWEEPS {
+            // Alternate between sweeping from left to right (looking at predecessors) and right to left (looking at successors).
+            let is_forward_sweep = sweep % 2 == 0;
+            let layer_indices = (1..orders.len()).map(|layer| if is_forward_sweep { layer } else { orders.len() - 1 - layer }).collect_vec();
+            for layer in layer_indices {
+                let indices = index_within_layer(&orders);
+                let fixed_layer = if is_forward_sweep { layer - 1 } else { layer + 1 };
+                let fixed_states = &orders[fixed_layer];
+                let barycenter = |state: &String| -> f64 {
+                    let neighbor_indices = self.transitions.iter()
+                        .filter_map(|transition| {
+                            if transition.state == *state && fixed_states.contains(&transition.next_state) {
+                                Some(indices[&transition.next_state])
+                            } else if transition.next_state == *state && fixed_states.contains(&transition.state) {
+                                Some(indices[&transition.state])
+                            } else {
+                                None
+                            }
+                        })
+                        .collect_vec();
+                    // States without neighbors in the fixed layer keep their current position.
+                    if neighbor_indices.is_empty() {
+                        return indices[state] as f64;
+                    }
+                    neighbor_indices.iter().sum::<usize>() as f64 / neighbor_indices.len() as f64
+                };
+                orders[layer] = orders[layer].iter()
+                    .map(|state| (barycenter(state), state.clone()))
+                    .sorted_by(|(first_barycenter, first_state), (second_barycenter, second_state)| {
+                        first_barycenter.partial_cmp(second_barycenter).unwrap().then_with(|| first_state.cmp(second_state))
+                    })
+                    .map(|(_, state)| state)
+                    .collect();
+            }
+        }
+        for (layer_index, layer) in orders.iter().enumerate() {
+            for (index, state) in layer.iter().enumerate() {
+                // Center every layer vertically around zero.
+                let y = (index as f64 - (layer.len() - 1) as f64 / 2.0) * STATE_SPACING;
+                self.state_metadata.entry(state.clone()).or_default().position = Some((layer_index as f64 * LAYER_SPACING, y));
+            }
+        }
+    }
+
+    /// Groups the states by their distance from the start state, using the breath first algorithm.
+    fn compute_layers(&self) -> Vec<Vec<String>> {
+        let mut distances: HashMap<String, usize> = HashMap::new();
+        let mut states_to_visit = VecDeque::new();
+        distances.insert(self.start_state.clone(), 0);
+        states_to_visit.push_back(self.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            let distance = distances[&state];
+            for transition in self.transitions.iter().filter(|transition| transition.state == state).sorted() {
+                if !distances.contains_key(&transition.next_state) {
+                    distances.insert(transition.next_state.clone(), distance + 1);
+                    states_to_visit.push_back(transition.next_state.clone());
+                }
+            }
+        }
+        let layer_count = distances.values().max().unwrap() + 1;
+        let mut layers = vec![Vec::new(); layer_count];
+        let mut unreachable_states = Vec::new();
+        for state in self.get_all_mentioned_states().into_iter().sorted() {
+            match distances.get(&state) {
+                Some(distance) => layers[*distance].push(state),
+                None => unreachable_states.push(state),
+            }
+        }
+        if !unreachable_states.is_empty() {
+            layers.push(unreachable_states);
+        }
+        layers
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_compute_layout() {
+        // q0 branches into q1 and q2, which both lead to q3. q4 is unreachable.
+        let mut dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3", "q4"],
+            &['a', 'b'],
+            &[&[Some(2), Some(1)], &[Some(3), None], &[Some(3), None], &[None, None], &[Some(0), None]],
+            0,
+            &[3],
+        ).unwrap();
+        dfa.compute_layout();
+        let position = |state: &str| dfa.get_state_metadata(state).unwrap().position.unwrap();
+        assert_eq!(position("q0"), (0.0, 0.0));
+        assert_eq!(position("q1"), (150.0, -50.0));
+        assert_eq!(position("q2"), (150.0, 50.0));
+        assert_eq!(position("q3"), (300.0, 0.0));
+        assert_eq!(position("q4").0, 450.0, "unreachable states should be placed in the last layer");
+    }
+}
@@ -1,13 +1,154 @@
-use std::borrow::Borrow;
-use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs::copy;
+use std::fmt;
 use std::iter::FromIterator;
-use std::ops::Deref;
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::RandomState;
+
+pub use accept_tag::AcceptTag;
+pub use buchi::{BuchiAutomaton, Lasso, OmegaLanguageClass};
+pub use captures::Captures;
+pub use cfg::{Cfg, GrammarSymbol, ParseTree};
+pub use classification::{GrowthRate, LanguageClassification};
+pub use derived_cache::DerivedCache;
+pub use dictionary::DictionaryBuilder;
+pub use discrimination_tree::{DiscriminationNode, DiscriminationTree};
+pub use dpda::{Dpda, DpdaConflict};
+pub use edit_distance::{EditCosts, WeightedTransducer, WeightedTransition};
+pub use enumeration::{AcceptedWords, MinimalDfas};
+pub use epsilon_nfa::{EpsilonNfa, EpsilonTransition};
+pub use fst::{Fst, FstInversionError, FstTransition};
+pub use fuel::{Bounded, Fuel};
+pub use gnfa::{Gnfa, GnfaError, GnfaTransition};
+pub use grading::GradingVerdict;
+pub use grammar::RegularGrammar;
+pub use grapheme::graphemes;
+pub use hierarchical::HierarchicalAutomaton;
+pub use immutable_dfa::ImmutableDfa;
+pub use location::Location;
+pub use lr::{Lr0Automaton, LrAction, LrConflict, LrItem, LrTable};
+pub use ltlf::Ltlf;
+pub use matcher::Matcher;
+pub use mealy::{CausalityConflict, MealyMachine, MealyTransition};
+pub use minimization_report::MinimizationReport;
+pub use moore::MooreMachine;
+pub use mutation::{Mutation, MutationReport, SurvivingMutant};
+pub use nerode_table::{NerodeMark, NerodeTable};
+pub use nfa::Nfa;
+pub use normalization::{nfc, LabelNormalizationError};
+pub use observation_table::ObservationTable;
+pub use parity_automaton::ParityAutomaton;
+pub use parity_game::{ParityGame, ParityGameError, Player};
+pub use pda::{Pda, PdaConfiguration, PdaTransition};
+pub use profiling::UsageProfile;
+pub use pumping_game::{PumpingGameError, PumpingLemmaGame, PumpingSplit};
+pub use pushdown_transducer::{PushdownTransducer, PushdownTransducerTransition};
+pub use quotient::{QuotientConflict, QuotientError};
+pub use regex::Regex;
+pub use regex_parser::{RegexDialect, RegexParseError};
+pub use register_automaton::{RegisterAutomaton, RegisterTest, RegisterTransition};
+pub use relabel::RelabelScheme;
+pub use renaming::{MinimizedDfa, RenamingError, StateRenaming};
+pub use serialization::{LoadedAutomaton, SymbolClassError};
+pub use state_equivalence::StateEquivalence;
+pub use state_merging::{LearningError, LearningStep, MergeDecision, MergeStrategy};
+pub use stream::{process_records, RecordFormat, RecordReader, RecordWriter, StreamError};
+pub use symbol_grouping::{GroupingSemantics, SymbolGroupingError};
+pub use symbolic_automaton::{BooleanAlgebra, CharRangeAlgebra, CharRanges, SymbolicAutomaton, SymbolicTransition};
+pub use table::TableError;
+pub use tagged_dfa::{TaggedDfa, TaggedDfaError, TaggedTransition};
+pub use test_vectors::{RejectionReason, TestVector};
+pub use timed_automaton::{ClockConstraint, Comparison, TimedAutomaton, TimedTransition};
+pub use token_dfa::{TokenDfa, TokenTransition};
+pub use tokens::{ErrorRecovery, LexError, Token, Tokens};
+pub use trace_validation::TraceError;
+pub use turing_machine::{TapeMove, TapeSnapshot, TuringMachine, TuringOutcome, TuringTransition};
+pub use union_set::UnionSet;
+
+mod accept_tag;
+mod antichain;
+mod automorphism;
+mod buchi;
+mod captures;
+mod cfg;
+mod classification;
+mod cnf;
+mod complement;
+mod costs;
+mod counting;
+mod derivatives;
+mod derived_cache;
+mod determinization;
+mod dictionary;
+mod discrimination_tree;
+mod dpda;
+mod edit_distance;
+mod emptiness;
+mod enumeration;
+mod epsilon_nfa;
+mod equivalence;
+mod export;
+mod finiteness;
+mod fst;
+mod fuel;
+mod gnfa;
+mod grading;
+mod grammar;
+mod grapheme;
+mod group_automaton;
+mod hierarchical;
+mod immutable_dfa;
+mod language_hash;
+mod layout;
+mod location;
+mod lr;
+mod ltlf;
+mod matcher;
+mod mealy;
+mod minimization_report;
+mod model_checker_export;
+mod moore;
+mod mutation;
+mod nerode_table;
+mod nfa;
+mod normalization;
+mod observation_table;
+mod parity_automaton;
+mod parity_game;
+mod pda;
+mod product;
+mod profiling;
+mod pumping_game;
+mod pushdown_transducer;
+mod quotient;
+mod regex;
+mod regex_parser;
+mod register_automaton;
+mod relabel;
+mod renaming;
+mod repair;
+mod replace;
+mod reversibility;
+mod serialization;
+mod simplification;
+mod simulation;
+mod state_equivalence;
+mod state_merging;
+mod stream;
+mod symbol_grouping;
+mod symbol_usage;
+mod symbolic_automaton;
+mod synthesis;
+mod table;
+mod tagged_dfa;
+mod test_vectors;
+mod thompson;
+mod timed_automaton;
+mod token_dfa;
+mod tokens;
+mod trace_validation;
+mod turing_machine;
+mod union_set;
 
 /// Describes to which next state a DFA switches when it reads a certain input while being in
 /// a certain state.
@@ -20,15 +161,106 @@ pub struct Transition {
 
 /// # [Deterministic finite acceptor](https://en.wikipedia.org/wiki/Deterministic_finite_automaton)
 /// The DFA is modelled slightly different than in its mathematical model.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// When serialized, transitions may use symbol classes instead of single symbols (see `SymbolClassError`).
+#[derive(Debug, Clone)]
 pub struct Dfa {
     name: String,
     start_state: String,
     accept_states: HashSet<String>,
     transitions: Vec<Transition>,
+    /// The intended input alphabet. When it is not declared, the alphabet is inferred from the transitions.
+    alphabet: Option<HashSet<char>>,
+    /// Determines what happens when the DFA reads a symbol for which the current state has no transition.
+    missing_transitions: MissingTransitionPolicy,
+    /// Additional information about the states that does not influence the language, like positions for drawing.
+    state_metadata: HashMap<String, StateMetadata>,
+    /// Tags of accept states, like the kind of token they recognize. Every tagged state is an accept state.
+    accept_tags: HashMap<String, AcceptTag>,
+    /// Costs of taking transitions, keyed by the state and the input. Transitions without a cost cost 1 (see `set_transition_cost`).
+    transition_costs: HashMap<(String, char), u64>,
+}
+
+/// Additional information about a state of an automaton that does not influence its language.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateMetadata {
+    /// Position of the state when the automaton is drawn, as computed by `Dfa::compute_layout`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<(f64, f64)>,
+    /// The states of the automata the state was constructed from, like the NFA states of a subset (see `Nfa::determinize`).
+    /// Exports show them next to the state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub origin: Vec<String>,
+}
+
+/// Different modelling styles interpret missing transitions differently. Partial specifications usually
+/// mean that the input is rejected, while some complete machines leave out transitions that do not change the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MissingTransitionPolicy {
+    /// The DFA goes into an implicit error state and rejects the input.
+    Reject,
+    /// The DFA stays in its current state. This only applies to symbols of the alphabet, other symbols are still rejected.
+    SelfLoop,
+    /// Missing transitions are considered a modelling error which is reported by `try_check`. `check` rejects the input.
+    Error,
+}
+
+impl Default for MissingTransitionPolicy {
+    fn default() -> MissingTransitionPolicy {
+        MissingTransitionPolicy::Reject
+    }
+}
+
+/// Reports that a DFA with the `MissingTransitionPolicy::Error` policy had no transition for the given state and input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingTransitionError {
+    pub state: String,
+    pub input: char,
+    /// Location of the input symbol for which no transition exists.
+    pub location: Location,
 }
 
+impl fmt::Display for MissingTransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: the state {} has no transition for input {}", self.location, self.state, self.input)
+    }
+}
+
+impl std::error::Error for MissingTransitionError {}
+
+/// Describes why the declared alphabet of a DFA does not fit its transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlphabetError {
+    /// A transition reads a symbol that is not part of the declared alphabet.
+    UndeclaredSymbol { state: String, input: char },
+}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlphabetError::UndeclaredSymbol { state, input } => write!(f, "the transition from {} reads the undeclared symbol {}", state, input),
+        }
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
 impl Dfa {
+    /// Creates a DFA from its states and transitions. The alphabet is inferred from the transitions, missing transitions are rejected,
+    /// and the DFA has no state metadata, accept tags or transition costs.
+    pub(crate) fn with_parts(name: String, start_state: String, accept_states: HashSet<String>, transitions: Vec<Transition>) -> Dfa {
+        Dfa {
+            name,
+            start_state,
+            accept_states,
+            transitions,
+            alphabet: None,
+            missing_transitions: MissingTransitionPolicy::Reject,
+            state_metadata: HashMap::new(),
+            accept_tags: HashMap::new(),
+            transition_costs: HashMap::new(),
+        }
+    }
+
     /// Checks whether a certain input is accepted by the DFA.
     /// Additionally returns a list of the states that have been traversed while processing the input.
     /// The start_state is included in that list of traversed states.
@@ -39,12 +271,16 @@ impl Dfa {
         // Go over each character and find suitable transitions for the current state.
         for char in input.chars() {
             let current_state = traversed_states.last().unwrap();
-            let next_transition_option = self.get_transition(&current_state, &char);
+            let next_transition_option = self.get_transition(current_state, &char);
             match next_transition_option {
                 Some(next_transition) => {
                     // Transition to the next state.
                     traversed_states.push(next_transition.next_state.to_string());
                 }
+                None if self.missing_transitions == MissingTransitionPolicy::SelfLoop && self.get_alphabet().contains(&char) => {
+                    // The missing transition is interpreted as staying in the current state.
+                    traversed_states.push(current_state.clone());
+                }
                 None => {
                     // The next state cannot be determined, which means that we are in an error state.
                     return (false, traversed_states);
@@ -55,23 +291,114 @@ impl Dfa {
         (self.accept_states.contains(&traversed_states.last().unwrap().clone()), traversed_states)
     }
 
+    /// Works like `check` but reports missing transitions as an error if the DFA uses the `MissingTransitionPolicy::Error` policy.
+    pub fn try_check(&self, input: &str) -> Result<(bool, Vec<String>), MissingTransitionError> {
+        let (is_accepted, traversed_states) = self.check(input);
+        if self.missing_transitions == MissingTransitionPolicy::Error {
+            // The check stopped early if fewer states than expected were traversed. The next character is the one
+            // for which no transition could be found.
+            if let Some((offset, symbol)) = input.char_indices().nth(traversed_states.len() - 1) {
+                let location = Location::in_text(input, offset);
+                return Err(MissingTransitionError { state: traversed_states.last().unwrap().clone(), input: symbol, location });
+            }
+        }
+        Ok((is_accepted, traversed_states))
+    }
+
+    pub fn get_state_metadata(&self, state: &str) -> Option<&StateMetadata> {
+        self.state_metadata.get(state)
+    }
+
+    pub fn get_missing_transition_policy(&self) -> MissingTransitionPolicy {
+        self.missing_transitions
+    }
+
+    pub fn set_missing_transition_policy(&mut self, policy: MissingTransitionPolicy) {
+        self.missing_transitions = policy;
+    }
+
+    /// Replaces the implicit self-loops of the `MissingTransitionPolicy::SelfLoop` policy by explicit transitions
+    /// and switches to the `MissingTransitionPolicy::Reject` policy afterwards. The language stays the same.
+    /// Algorithms that only look at explicit transitions, like minimization, rely on this.
+    pub fn materialize_missing_transitions(&mut self) {
+        if self.missing_transitions != MissingTransitionPolicy::SelfLoop {
+            return;
+        }
+        for state in self.get_all_mentioned_states().into_iter().sorted() {
+            for input in self.get_alphabet().into_iter().sorted() {
+                if self.get_transition(&state, &input).is_none() {
+                    self.transitions.push(Transition { state: state.clone(), input, next_state: state.clone() });
+                }
+            }
+        }
+        self.missing_transitions = MissingTransitionPolicy::Reject;
+    }
+
     /// Tries to find a transition that fits for the current situation. Calling this function is like
     /// saying: What happens when the DFA is in state q0 and reads an A?
     /// If no transition can be found, the DFA is in an error state.
     pub fn get_transition(&self, state: &str, input: &char) -> Option<&Transition> {
         self.transitions.iter()
-            .find(|transition| transition.state.eq(&String::from(state)) && transition.input.eq(&input))
+            .find(|transition| transition.state.eq(&String::from(state)) && transition.input.eq(input))
     }
 
+    /// Checks that no state has more than one transition for the same input. Otherwise, the DFA is actually nondeterministic
+    /// and `check` would silently follow the first matching transition.
+    pub fn is_deterministic(&self) -> bool {
+        find_nondeterministic_transition(&self.transitions).is_none()
+    }
+
+    /// Returns the symbols that are actually read by some transition. Use `get_alphabet` if you are interested
+    /// in the intended alphabet, which might contain symbols that are not used by any transition.
     pub fn get_all_input_symbols(&self) -> HashSet<char> {
         HashSet::from_iter(self.transitions.iter().map(|transition| transition.input))
     }
 
+    /// Returns the declared alphabet or, if there is none, the symbols used by the transitions.
+    pub fn get_alphabet(&self) -> HashSet<char> {
+        match &self.alphabet {
+            Some(alphabet) => alphabet.clone(),
+            None => self.get_all_input_symbols(),
+        }
+    }
+
+    /// Declares the intended alphabet of the DFA. The alphabet must contain every symbol that is used by a transition.
+    /// Passing None removes the declaration so that the alphabet is inferred from the transitions again.
+    pub fn set_alphabet(&mut self, alphabet: Option<HashSet<char>>) -> Result<(), AlphabetError> {
+        let previous_alphabet = std::mem::replace(&mut self.alphabet, alphabet);
+        let validation = self.validate_alphabet();
+        if validation.is_err() {
+            self.alphabet = previous_alphabet;
+        }
+        validation
+    }
+
+    /// Checks that every transition reads a symbol of the declared alphabet. This is especially useful after
+    /// deserializing a DFA, as the serialized alphabet might not fit the serialized transitions.
+    pub fn validate_alphabet(&self) -> Result<(), AlphabetError> {
+        let alphabet = match &self.alphabet {
+            Some(alphabet) => alphabet,
+            None => return Ok(()),
+        };
+        match self.transitions.iter().find(|transition| !alphabet.contains(&transition.input)) {
+            Some(transition) => Err(AlphabetError::UndeclaredSymbol { state: transition.state.clone(), input: transition.input }),
+            None => Ok(()),
+        }
+    }
+
     pub fn get_all_states(&self) -> HashSet<String> {
         HashSet::from_iter(self.transitions.iter().flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()]))
     }
 
-    /// Minimizes the DFA with the algorithm found on [here.](https://www.geeksforgeeks.org/minimization-of-dfa/)
+    /// Like `get_all_states` but also includes the start state and accept states, even if no transition mentions them.
+    pub(crate) fn get_all_mentioned_states(&self) -> HashSet<String> {
+        let mut all_states = self.get_all_states();
+        all_states.insert(self.start_state.clone());
+        all_states.extend(self.accept_states.iter().cloned());
+        all_states
+    }
+
+    /// Minimizes the DFA by merging its equivalent states, which are found by the same refinement as `get_state_equivalence`.
     /// Usually, when the states "q0" and "q1" are equivalent, you would expect this algorithm to merge them into
     /// a state called something like "q0,q1". This, however, could lead to name collisions as there might already exist
     /// another state called "q0,q1". Therefore, the new name for the merged state would just be "qo". This method concentrates
@@ -80,54 +407,10 @@ impl Dfa {
     /// Returns a hash map with all renaming operations. For example, if q0 and q1 are merged into a new
     /// state called q0, the returned object will map the old names qo and q1 to the new name q0.
     pub fn minimize(&mut self) -> HashMap<String, String> {
+        self.materialize_missing_transitions();
         self.remove_inaccessible_states();
-        let all_input_symbols = self.get_all_input_symbols();
-        let rejecting_states = HashSet::from_iter(self.get_all_states().difference(&self.accept_states).map(|x| x.clone()));
-        // Initially, states are only split into accepting and rejecting states. Those are obviously distinguishable states that must
-        // belong into different equivalence classes.
-        let mut equivalence_classes = vec![self.accept_states.clone(), rejecting_states];
-        // We further split distinguishable states into separate equivalence classes until we do not find any
-        // distinguishable states within one equivalence class any more. Then we know for sure,
-        // that every one of our equivalence classes only contains indistinguishable states.
-        loop {
-            // The following list keeps track of state pairs that are indistinguishable for the current equivalence classes.
-            let mut indistinguishable_states_list: Vec<(&String, &String)> = Vec::new();
-            for equivalence_class in &equivalence_classes {
-                for state_1 in equivalence_class {
-                    for state_2 in equivalence_class {
-                        let are_indistinguishable = self.are_states_indistinguishable(&state_1, &state_2, &all_input_symbols, &equivalence_classes);
-                        if are_indistinguishable {
-                            indistinguishable_states_list.push((state_1, state_2))
-                        }
-                    }
-                }
-            }
-            let mut new_equivalence_classes: Vec<HashSet<String>> = Vec::new();
-            for indistinguishable_states in indistinguishable_states_list {
-                // Within the new_equivalence_classes, find an equivalence class into which the current indistinguishable_states can be put.
-                // If the current indistinguishable_states are (q0, q1) and we know that q1 is indistinguishable from q3 and there is already
-                // an equivalence class with q3, we'll put q0 and q1 into that equivalence class. q0, q1 and q3 are indistinguishable and
-                // belong in the same equivalence class.
-                let equivalence_class_to_put_indistinguishable_states_into = new_equivalence_classes.iter_mut()
-                    .find(|class| class.contains(indistinguishable_states.0) || class.contains(indistinguishable_states.1));
-                // There are two options: Either a suitable equivalence class already exists or we have to create a new one.
-                // We build new equivalence classes because we build new_equivalence_classes from scratch within each iteration.
-                match equivalence_class_to_put_indistinguishable_states_into {
-                    Some(equivalence_class) => {
-                        equivalence_class.insert(indistinguishable_states.0.clone());
-                        equivalence_class.insert(indistinguishable_states.1.clone());
-                    }
-                    None => {
-                        new_equivalence_classes.push(HashSet::from_iter(vec![indistinguishable_states.0.clone(), indistinguishable_states.1.clone()]));
-                    }
-                }
-            }
-            let has_split_occurred = equivalence_classes.len() < new_equivalence_classes.len();
-            if !has_split_occurred {
-                break;
-            }
-            equivalence_classes = new_equivalence_classes;
-        }
+        // Equivalent states are merged. Accept states with different tags are distinguishable, as merging them would lose a tag.
+        let equivalence_classes = self.compute_state_equivalence(true).get_classes();
         // We build a hash map that maps the old names to the new names.
         // If q0 and q1 are indistinguishable and thus in the same equivalence class,
         // q0 will be mapped to q0 and q1 will also be mapped to q0. Thus,
@@ -139,7 +422,7 @@ impl Dfa {
             if equivalence_class.len() <= 1 {
                 continue;
             }
-            let new_name = equivalence_class.iter().sorted().next().unwrap().clone();
+            let new_name = equivalence_class[0].clone();
             for old_state_name in equivalence_class {
                 renaming_operations.insert(old_state_name, new_name.clone());
             }
@@ -153,31 +436,22 @@ impl Dfa {
                 next_state: renaming_operations.get(transition.next_state.as_str()).unwrap_or(&transition.next_state).clone(),
             }
         }).sorted().dedup());
-        renaming_operations
-    }
-
-    /// Two states are considered indistinguishable if they transition to states of the same equivalence class __for every input__.
-    /// Put simply: Given any input symbol, it does not matter whether you are in state_1 or state_2, you will transition to the same
-    /// equivalence class.
-    fn are_states_indistinguishable(&self, state_1: &str, state_2: &str, all_input_symbols: &HashSet<char>, equivalence_classes: &Vec<HashSet<String>>) -> bool {
-        if state_1 == state_2 {
-            return true;
+        let rename = |state: &String| renaming_operations.get(state).unwrap_or(state).clone();
+        self.start_state = rename(&self.start_state);
+        self.accept_states = self.accept_states.iter().map(rename).collect();
+        self.accept_tags = self.accept_tags.iter().map(|(state, tag)| (rename(state), tag.clone())).collect();
+        // Merged states keep the cheapest cost of each of their transitions.
+        let mut transition_costs: HashMap<(String, char), u64> = HashMap::new();
+        for ((state, input), cost) in &self.transition_costs {
+            let merged_cost = transition_costs.entry((rename(state), *input)).or_insert(*cost);
+            *merged_cost = (*merged_cost).min(*cost);
         }
-        for input in all_input_symbols {
-            // Determine for state 1 and 2 to which equivalence class the DFA would transition for a given input.
-            // When the DFA transitions to a different equivalence classes depending on whether the DFA is in state 1 or 2,
-            // state 1 and 2 are distinguishable.
-            // The next equivalence class is determined by first determining the next state (via transition) and then looking up
-            // to which equivalence class this next state belongs.
-            let next_equivalence_class_for_state_1 = self.get_transition(&state_1, input)
-                .and_then(|transition| equivalence_classes.iter().find(|equivalence_class| equivalence_class.contains(&transition.next_state[..])));
-            let next_equivalence_class_for_state_2 = self.get_transition(&state_2, input)
-                .and_then(|transition| equivalence_classes.iter().find(|equivalence_class| equivalence_class.contains(&transition.next_state[..])));
-            if next_equivalence_class_for_state_1 != next_equivalence_class_for_state_2 {
-                return false;
-            }
-        }
-        true
+        self.transition_costs = transition_costs;
+        // Metadata of states that do not exist any more would be misleading.
+        let remaining_states = self.get_all_mentioned_states();
+        self.state_metadata.retain(|state, _| remaining_states.contains(state));
+        self.transition_costs.retain(|(state, _), _| remaining_states.contains(state));
+        renaming_operations
     }
 
     /// Removes all states that cannot be reached by removing all transitions that have this state
@@ -192,10 +466,10 @@ impl Dfa {
         // We'll start visiting the start state, of course.
         states_to_visit.push_back(&self.start_state);
         // Traverse the graph until there are no states to visit any more.
-        while states_to_visit.len() > 0 {
+        while !states_to_visit.is_empty() {
             // First come - first serve. Just as the breath first algorithm is described.
             let currently_visited_state = states_to_visit.pop_front().unwrap();
-            visited_states.insert(&currently_visited_state[..]);
+            visited_states.insert(currently_visited_state);
             // Find all neighbors of currently visited state (via transitions).
             let transitions_for_currently_visited_state = self.transitions.iter().filter(|transition| transition.state[..] == *currently_visited_state);
             // Loop over each neighbor (via transition) but only add it to our states_to_visit if it has not been visited before.
@@ -214,20 +488,38 @@ impl Dfa {
 }
 
 
+/// Finds a transition for a state and input that already has another transition with a different next state.
+pub(crate) fn find_nondeterministic_transition(transitions: &[Transition]) -> Option<&Transition> {
+    let mut next_states: HashMap<(&str, char), &str> = HashMap::new();
+    transitions.iter().find(|transition| {
+        let next_state = next_states.entry((&transition.state, transition.input)).or_insert(&transition.next_state);
+        *next_state != transition.next_state
+    })
+}
+
+/// Appends apostrophes to the given name until it does not collide with any of the existing state names.
+pub(crate) fn fresh_state_name(name: &str, existing_states: &HashSet<String>) -> String {
+    let mut fresh_name = name.to_string();
+    while existing_states.contains(&fresh_name) {
+        fresh_name.push('\'');
+    }
+    fresh_name
+}
+
 #[cfg(test)]
 mod dfa_tests {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::HashSet;
     use std::iter::FromIterator;
 
-    use crate::{Dfa, Transition};
+    use crate::{AlphabetError, Dfa, Location, MissingTransitionError, MissingTransitionPolicy, Transition};
 
     /// Creates DFA that accepts input if all '1' characters are placed at the end and there is at least one '1' character.
     fn create_example_dfa() -> Dfa {
-        Dfa {
-            name: String::from("Accept if all '1' characters are placed at the end and there is at least one '1' character."),
-            start_state: "q0".to_string(),
-            accept_states: HashSet::from_iter(vec!["q1".to_string()]),
-            transitions: vec![
+        Dfa::with_parts(
+            String::from("Accept if all '1' characters are placed at the end and there is at least one '1' character."),
+            "q0".to_string(),
+            HashSet::from_iter(vec!["q1".to_string()]),
+            vec![
                 Transition {
                     state: "q0".to_string(),
                     input: '0',
@@ -244,15 +536,15 @@ mod dfa_tests {
                     next_state: "q1".to_string(),
                 },
             ],
-        }
+        )
     }
 
     fn create_example_dfa_that_can_be_minimized() -> Dfa {
-        Dfa {
-            name: String::from(""),
-            start_state: "q1".to_string(),
-            accept_states: HashSet::from_iter(vec!["q8".to_string()]),
-            transitions: vec![
+        Dfa::with_parts(
+            String::from(""),
+            "q1".to_string(),
+            HashSet::from_iter(vec!["q8".to_string()]),
+            vec![
                 Transition {
                     state: "q1".to_string(),
                     input: 'a',
@@ -339,7 +631,7 @@ mod dfa_tests {
                     next_state: "q8".to_string(),
                 }
             ],
-        }
+        )
     }
 
     #[test]
@@ -357,6 +649,40 @@ mod dfa_tests {
         assert_eq!(dfa.get_all_input_symbols(), HashSet::from_iter(vec!['0', '1']));
     }
 
+    #[test]
+    fn test_alphabet() {
+        let mut dfa = create_example_dfa();
+        assert_eq!(dfa.get_alphabet(), HashSet::from_iter(vec!['0', '1']), "should infer the alphabet from the transitions");
+        assert!(dfa.set_alphabet(Some(HashSet::from_iter(vec!['0', '1', '2']))).is_ok());
+        assert_eq!(dfa.get_alphabet(), HashSet::from_iter(vec!['0', '1', '2']), "should contain symbols that are not used by transitions");
+        assert_eq!(dfa.set_alphabet(Some(HashSet::from_iter(vec!['0']))), Err(AlphabetError::UndeclaredSymbol { state: "q0".to_string(), input: '1' }));
+        assert_eq!(dfa.get_alphabet().len(), 3, "should keep the previous alphabet when the new one is invalid");
+    }
+
+    #[test]
+    fn test_missing_transition_policies() {
+        let mut dfa = create_example_dfa();
+        assert!(!dfa.check("10").0, "should reject by default if a transition is missing");
+        dfa.set_missing_transition_policy(MissingTransitionPolicy::SelfLoop);
+        assert!(dfa.check("10").0, "should stay in q1 when reading '0'");
+        assert_eq!(dfa.check("10").1, vec!["q0", "q1", "q1"]);
+        assert!(!dfa.check("12").0, "should reject symbols outside of the alphabet");
+        dfa.set_missing_transition_policy(MissingTransitionPolicy::Error);
+        assert!(!dfa.check("10").0);
+        assert_eq!(dfa.try_check("10"), Err(MissingTransitionError { state: "q1".to_string(), input: '0', location: Location { offset: 1, line: 1, column: 2 } }));
+        assert!(dfa.try_check("011").unwrap().0);
+    }
+
+    #[test]
+    fn test_materialize_missing_transitions() {
+        let mut dfa = create_example_dfa();
+        dfa.set_missing_transition_policy(MissingTransitionPolicy::SelfLoop);
+        dfa.materialize_missing_transitions();
+        assert_eq!(dfa.get_missing_transition_policy(), MissingTransitionPolicy::Reject);
+        assert_eq!(dfa.get_transition("q1", &'0').unwrap().next_state, "q1");
+        assert!(dfa.check("0110").0);
+    }
+
     #[test]
     fn test_minimize() {
         let mut dfa = create_example_dfa_that_can_be_minimized();
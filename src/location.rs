@@ -0,0 +1,56 @@
+use std::fmt;
+
+/// A position within a text, given as byte offset as well as line and column. Lines and columns are counted from 1,
+/// and columns count characters instead of bytes, just like editors do it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// The location at the very beginning of a text.
+    pub fn start() -> Location {
+        Location { offset: 0, line: 1, column: 1 }
+    }
+
+    /// Determines the location of a byte offset within the text.
+    pub fn in_text(text: &str, offset: usize) -> Location {
+        Location::start().advance(&text[..offset])
+    }
+
+    /// Returns the location that is reached after reading the text, starting at this location.
+    pub fn advance(self, text: &str) -> Location {
+        let mut location = self;
+        for char in text.chars() {
+            location.offset += char.len_utf8();
+            if char == '\n' {
+                location.line += 1;
+                location.column = 1;
+            } else {
+                location.column += 1;
+            }
+        }
+        location
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod location_tests {
+    use crate::Location;
+
+    #[test]
+    fn test_in_text() {
+        let text = "ab\nüx\n";
+        assert_eq!(Location::in_text(text, 0), Location { offset: 0, line: 1, column: 1 });
+        assert_eq!(Location::in_text(text, 5), Location { offset: 5, line: 2, column: 2 }, "columns should count characters, not bytes");
+        assert_eq!(Location::in_text(text, 7).to_string(), "3:1");
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Cfg, GrammarSymbol, TokenDfa};
+
+/// An LR(0) item: a production with a dot marking how much of its body has been recognized, like E → E·+T.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct LrItem {
+    /// The number of the production. 0 is the production S' → S added for the new start symbol,
+    /// the productions of the grammar are numbered from 1 in the order they were added.
+    pub production: usize,
+    pub head: String,
+    pub body: Vec<GrammarSymbol>,
+    pub dot: usize,
+}
+
+impl LrItem {
+    fn get_symbol_after_dot(&self) -> Option<&GrammarSymbol> {
+        self.body.get(self.dot)
+    }
+}
+
+impl fmt::Display for LrItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}·{}", self.head, self.body[..self.dot].iter().join(""), self.body[self.dot..].iter().join(""))
+    }
+}
+
+/// The canonical collection of LR(0) item sets of a grammar, as computed by `Cfg::lr0_automaton`.
+#[derive(Debug, Clone)]
+pub struct Lr0Automaton {
+    item_sets: Vec<BTreeSet<LrItem>>,
+    dfa: TokenDfa<GrammarSymbol>,
+    /// The transitions of the DFA by the indices of the item sets.
+    transitions: BTreeMap<(usize, GrammarSymbol), usize>,
+}
+
+impl Lr0Automaton {
+    /// Returns the item sets, each closed under adding the items of productions of nonterminals right after the dot.
+    /// The index of an item set is the number of its state.
+    pub fn get_item_sets(&self) -> &[BTreeSet<LrItem>] {
+        &self.item_sets
+    }
+
+    /// Returns the automaton switching between the item sets, whose states are called "I0", "I1" and so on.
+    /// All states accept, so that it accepts exactly the viable prefixes of the grammar.
+    pub fn get_dfa(&self) -> &TokenDfa<GrammarSymbol> {
+        &self.dfa
+    }
+}
+
+/// Lists the items of every item set, one per line.
+impl fmt::Display for Lr0Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, item_set) in self.item_sets.iter().enumerate() {
+            writeln!(f, "I{}:", index)?;
+            for item in item_set {
+                writeln!(f, "    {}", item)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entry of the action table of an LR parser.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum LrAction {
+    /// Reads the terminal and pushes the state.
+    Shift(usize),
+    /// Replaces the body of the production with the number by its head.
+    Reduce(usize),
+    Accept,
+}
+
+/// Renders the action like in textbook tables: "s4", "r2" or "acc".
+impl fmt::Display for LrAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LrAction::Shift(state) => write!(f, "s{}", state),
+            LrAction::Reduce(production) => write!(f, "r{}", production),
+            LrAction::Accept => write!(f, "acc"),
+        }
+    }
+}
+
+/// Several actions for the same state and lookahead, which means that the grammar cannot be parsed with the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LrConflict {
+    pub state: usize,
+    /// The terminal the actions are taken on, or None for the end of the input.
+    pub lookahead: Option<char>,
+    pub actions: Vec<LrAction>,
+}
+
+impl LrConflict {
+    /// Checks whether the conflict is between shifting and reducing, rather than between two reductions.
+    pub fn is_shift_reduce(&self) -> bool {
+        self.actions.iter().any(|action| matches!(action, LrAction::Shift(_)))
+    }
+}
+
+impl fmt::Display for LrConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.is_shift_reduce() { "shift/reduce" } else { "reduce/reduce" };
+        let lookahead = self.lookahead.map_or(String::from("$"), |lookahead| lookahead.to_string());
+        write!(f, "{} conflict in state {} on {}: {}", kind, self.state, lookahead, self.actions.iter().join(", "))
+    }
+}
+
+/// The action and goto table of an LR parser, as computed by `Cfg::lr0_table` and `Cfg::slr_table`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LrTable {
+    actions: BTreeMap<(usize, Option<char>), Vec<LrAction>>,
+    gotos: BTreeMap<(usize, String), usize>,
+}
+
+impl LrTable {
+    /// Returns the actions for the state and the terminal, or the end of the input if the lookahead is None.
+    /// More than one action means a conflict, and no action means a syntax error.
+    pub fn get_actions(&self, state: usize, lookahead: Option<char>) -> &[LrAction] {
+        self.actions.get(&(state, lookahead)).map_or(&[], |actions| actions.as_slice())
+    }
+
+    /// Returns the state to switch to after reducing to the nonterminal in the state.
+    pub fn get_goto(&self, state: usize, nonterminal: &str) -> Option<usize> {
+        self.gotos.get(&(state, nonterminal.to_string())).cloned()
+    }
+
+    /// Returns all conflicts, sorted by state and lookahead.
+    pub fn get_conflicts(&self) -> Vec<LrConflict> {
+        self.actions.iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|((state, lookahead), actions)| LrConflict { state: *state, lookahead: *lookahead, actions: actions.clone() })
+            .collect()
+    }
+
+    pub fn is_conflict_free(&self) -> bool {
+        self.actions.values().all(|actions| actions.len() <= 1)
+    }
+}
+
+impl Cfg {
+    /// Constructs the canonical collection of [LR(0)](https://en.wikipedia.org/wiki/LR_parser) item sets, layer by layer
+    /// with the breath first algorithm. The grammar is augmented by a fresh start symbol S' and the production S' → S,
+    /// where S is the old start symbol. Item sets are numbered in the order they were discovered.
+    pub fn lr0_automaton(&self) -> Lr0Automaton {
+        let productions = self.get_augmented_productions();
+        let start_item_set = self.get_closure(&productions, vec![LrItem { production: 0, head: productions[0].0.clone(), body: productions[0].1.clone(), dot: 0 }]);
+        let mut item_sets = vec![start_item_set.clone()];
+        let mut indices: HashMap<BTreeSet<LrItem>, usize> = vec![(start_item_set, 0)].into_iter().collect();
+        let mut indices_to_visit: VecDeque<usize> = vec![0].into_iter().collect();
+        let mut dfa = TokenDfa::new(&format!("LR(0) automaton of {}", self.start_symbol), "I0");
+        let mut transitions = BTreeMap::new();
+        while let Some(index) = indices_to_visit.pop_front() {
+            dfa.add_accept_state(&format!("I{}", index));
+            let symbols = item_sets[index].iter().filter_map(LrItem::get_symbol_after_dot).cloned().sorted().dedup().collect_vec();
+            for symbol in symbols {
+                let next_item_set = self.get_goto(&productions, &item_sets[index], &symbol);
+                let next_index = match indices.get(&next_item_set) {
+                    Some(next_index) => *next_index,
+                    None => {
+                        item_sets.push(next_item_set.clone());
+                        indices.insert(next_item_set, item_sets.len() - 1);
+                        indices_to_visit.push_back(item_sets.len() - 1);
+                        item_sets.len() - 1
+                    }
+                };
+                dfa.add_transition(&format!("I{}", index), symbol.clone(), &format!("I{}", next_index));
+                transitions.insert((index, symbol), next_index);
+            }
+        }
+        Lr0Automaton { item_sets, dfa, transitions }
+    }
+
+    /// Computes the LR(0) parsing table, which reduces completed items regardless of the next terminal.
+    /// Conflicts show that the grammar is not LR(0) (see `LrTable::get_conflicts`).
+    pub fn lr0_table(&self) -> LrTable {
+        let terminals = self.get_terminals().into_iter().map(Some).chain(vec![None]).collect();
+        self.get_table(|_| &terminals)
+    }
+
+    /// Computes the [SLR](https://en.wikipedia.org/wiki/Simple_LR_parser) parsing table, which reduces a completed item
+    /// only if the next terminal can follow its head, so that fewer conflicts arise than in the LR(0) table.
+    pub fn slr_table(&self) -> LrTable {
+        let follow_sets = self.get_follow_sets();
+        let no_terminals = HashSet::new();
+        self.get_table(|head| follow_sets.get(head).unwrap_or(&no_terminals))
+    }
+
+    fn get_table<'a>(&self, get_lookaheads: impl Fn(&str) -> &'a HashSet<Option<char>>) -> LrTable {
+        let automaton = self.lr0_automaton();
+        let mut actions: BTreeMap<(usize, Option<char>), Vec<LrAction>> = BTreeMap::new();
+        let mut gotos = BTreeMap::new();
+        let mut add_action = |state: usize, lookahead: Option<char>, action: LrAction| {
+            let actions = actions.entry((state, lookahead)).or_default();
+            if !actions.contains(&action) {
+                actions.push(action);
+                actions.sort();
+            }
+        };
+        for (index, item_set) in automaton.item_sets.iter().enumerate() {
+            for item in item_set {
+                match item.get_symbol_after_dot() {
+                    Some(symbol) => {
+                        let next_index = automaton.transitions[&(index, symbol.clone())];
+                        match symbol {
+                            GrammarSymbol::Terminal(terminal) => add_action(index, Some(*terminal), LrAction::Shift(next_index)),
+                            GrammarSymbol::Nonterminal(nonterminal) => {
+                                gotos.insert((index, nonterminal.clone()), next_index);
+                            }
+                        }
+                    }
+                    None if item.production == 0 => add_action(index, None, LrAction::Accept),
+                    None => {
+                        for lookahead in get_lookaheads(&item.head) {
+                            add_action(index, *lookahead, LrAction::Reduce(item.production));
+                        }
+                    }
+                }
+            }
+        }
+        LrTable { actions, gotos }
+    }
+
+    /// Returns the productions with the production S' → S for a fresh start symbol S' at index 0.
+    fn get_augmented_productions(&self) -> Vec<(String, Vec<GrammarSymbol>)> {
+        let nonterminals = self.productions.iter().map(|(head, _)| head.clone()).collect();
+        let start_symbol = fresh_state_name(&self.start_symbol, &nonterminals);
+        vec![(start_symbol, vec![GrammarSymbol::Nonterminal(self.start_symbol.clone())])].into_iter()
+            .chain(self.productions.iter().cloned())
+            .collect()
+    }
+
+    fn get_closure(&self, productions: &[(String, Vec<GrammarSymbol>)], items: Vec<LrItem>) -> BTreeSet<LrItem> {
+        let mut closure: BTreeSet<LrItem> = items.iter().cloned().collect();
+        let mut items_to_visit: VecDeque<LrItem> = items.into_iter().collect();
+        while let Some(item) = items_to_visit.pop_front() {
+            if let Some(GrammarSymbol::Nonterminal(nonterminal)) = item.get_symbol_after_dot() {
+                for (production, (head, body)) in productions.iter().enumerate().filter(|(_, (head, _))| head == nonterminal) {
+                    let new_item = LrItem { production, head: head.clone(), body: body.clone(), dot: 0 };
+                    if closure.insert(new_item.clone()) {
+                        items_to_visit.push_back(new_item);
+                    }
+                }
+            }
+        }
+        closure
+    }
+
+    fn get_goto(&self, productions: &[(String, Vec<GrammarSymbol>)], item_set: &BTreeSet<LrItem>, symbol: &GrammarSymbol) -> BTreeSet<LrItem> {
+        let moved_items = item_set.iter()
+            .filter(|item| item.get_symbol_after_dot() == Some(symbol))
+            .map(|item| LrItem { dot: item.dot + 1, ..item.clone() })
+            .collect();
+        self.get_closure(productions, moved_items)
+    }
+
+    fn get_terminals(&self) -> HashSet<char> {
+        self.productions.iter()
+            .flat_map(|(_, body)| body)
+            .filter_map(|symbol| match symbol {
+                GrammarSymbol::Terminal(terminal) => Some(*terminal),
+                GrammarSymbol::Nonterminal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Computes the FOLLOW sets of all nonterminals, i.e. the terminals that can come right after them in a sentential form.
+    /// None stands for the end of the input, which follows the start symbol.
+    fn get_follow_sets(&self) -> HashMap<String, HashSet<Option<char>>> {
+        let (nullable_nonterminals, first_sets) = self.get_first_sets();
+        let mut follow_sets: HashMap<String, HashSet<Option<char>>> = HashMap::new();
+        follow_sets.entry(self.start_symbol.clone()).or_default().insert(None);
+        let mut has_changed = true;
+        while has_changed {
+            has_changed = false;
+            for (head, body) in &self.productions {
+                for (index, symbol) in body.iter().enumerate() {
+                    let nonterminal = match symbol {
+                        GrammarSymbol::Nonterminal(nonterminal) => nonterminal,
+                        GrammarSymbol::Terminal(_) => continue,
+                    };
+                    let mut follow_set: HashSet<Option<char>> = HashSet::new();
+                    let mut is_rest_nullable = true;
+                    for next_symbol in &body[index + 1..] {
+                        match next_symbol {
+                            GrammarSymbol::Terminal(terminal) => {
+                                follow_set.insert(Some(*terminal));
+                            }
+                            GrammarSymbol::Nonterminal(next_nonterminal) => {
+                                follow_set.extend(first_sets.get(next_nonterminal).into_iter().flatten().map(|terminal| Some(*terminal)));
+                            }
+                        }
+                        if !matches!(next_symbol, GrammarSymbol::Nonterminal(next_nonterminal) if nullable_nonterminals.contains(next_nonterminal)) {
+                            is_rest_nullable = false;
+                            break;
+                        }
+                    }
+                    if is_rest_nullable {
+                        follow_set.extend(follow_sets.get(head).cloned().unwrap_or_default());
+                    }
+                    let existing_follow_set = follow_sets.entry(nonterminal.clone()).or_default();
+                    let previous_len = existing_follow_set.len();
+                    existing_follow_set.extend(follow_set);
+                    has_changed |= existing_follow_set.len() > previous_len;
+                }
+            }
+        }
+        follow_sets
+    }
+
+    /// Computes the nonterminals deriving the empty word and the FIRST sets of all nonterminals, i.e. the terminals
+    /// the words derived from them can start with.
+    fn get_first_sets(&self) -> (HashSet<String>, HashMap<String, HashSet<char>>) {
+        let mut nullable_nonterminals: HashSet<String> = HashSet::new();
+        let mut first_sets: HashMap<String, HashSet<char>> = HashMap::new();
+        let mut has_changed = true;
+        while has_changed {
+            has_changed = false;
+            for (head, body) in &self.productions {
+                let mut first_set = HashSet::new();
+                let mut is_nullable = true;
+                for symbol in body {
+                    match symbol {
+                        GrammarSymbol::Terminal(terminal) => {
+                            first_set.insert(*terminal);
+                        }
+                        GrammarSymbol::Nonterminal(nonterminal) => first_set.extend(first_sets.get(nonterminal).cloned().unwrap_or_default()),
+                    }
+                    if !matches!(symbol, GrammarSymbol::Nonterminal(nonterminal) if nullable_nonterminals.contains(nonterminal)) {
+                        is_nullable = false;
+                        break;
+                    }
+                }
+                if is_nullable {
+                    has_changed |= nullable_nonterminals.insert(head.clone());
+                }
+                let existing_first_set = first_sets.entry(head.clone()).or_default();
+                let previous_len = existing_first_set.len();
+                existing_first_set.extend(first_set);
+                has_changed |= existing_first_set.len() > previous_len;
+            }
+        }
+        (nullable_nonterminals, first_sets)
+    }
+}
+
+#[cfg(test)]
+mod lr_tests {
+    use crate::{Cfg, GrammarSymbol, LrAction};
+
+    /// Creates the classic grammar of arithmetic expressions: E → E+T | T, T → T*F | F, F → (E) | a.
+    fn create_expression_grammar() -> Cfg {
+        let mut cfg = Cfg::new("E");
+        cfg.add_production("E", "E+T");
+        cfg.add_production("E", "T");
+        cfg.add_production("T", "T*F");
+        cfg.add_production("T", "F");
+        cfg.add_production("F", "(E)");
+        cfg.add_production("F", "a");
+        cfg
+    }
+
+    #[test]
+    fn test_lr0_automaton() {
+        let automaton = create_expression_grammar().lr0_automaton();
+        assert_eq!(automaton.get_item_sets().len(), 12);
+        assert!(automaton.to_string().starts_with("I0:\n    E' → ·E\n"));
+        let viable_prefix = vec![GrammarSymbol::Nonterminal("E".to_string()), GrammarSymbol::Terminal('+')];
+        assert!(automaton.get_dfa().check(&viable_prefix).0);
+        assert!(!automaton.get_dfa().check(&[GrammarSymbol::Terminal('+')]).0);
+    }
+
+    #[test]
+    fn test_slr_table() {
+        let cfg = create_expression_grammar();
+        assert!(!cfg.lr0_table().is_conflict_free(), "E → T· and T → T·*F conflict without lookahead");
+        let table = cfg.slr_table();
+        assert!(table.is_conflict_free());
+        assert_eq!(table.get_actions(0, Some('a')), &[LrAction::Shift(2)]);
+        assert_eq!(table.get_actions(3, None), &[LrAction::Accept]);
+        assert_eq!(table.get_goto(0, "T"), Some(5));
+        // The ambiguous grammar E → E+E | a cannot decide whether to reduce a+a before reading another +.
+        let mut ambiguous_cfg = Cfg::new("E");
+        ambiguous_cfg.add_production("E", "E+E");
+        ambiguous_cfg.add_production("E", "a");
+        let conflicts = ambiguous_cfg.slr_table().get_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].to_string(), "shift/reduce conflict in state 4 on +: s3, r1");
+    }
+}
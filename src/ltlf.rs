@@ -0,0 +1,300 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// # [Linear temporal logic on finite traces](https://en.wikipedia.org/wiki/Linear_temporal_logic)
+/// A formula of LTLf, which is LTL interpreted over finite traces. A trace is a non-empty word in which every symbol
+/// is one step, and the atom `a` holds in a step if its symbol is `a`. The empty trace satisfies no formula.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
+pub enum Ltlf {
+    True,
+    False,
+    Atom(char),
+    Not(Box<Ltlf>),
+    And(Box<Ltlf>, Box<Ltlf>),
+    Or(Box<Ltlf>, Box<Ltlf>),
+    /// Holds if there is a next step and the formula holds in it.
+    Next(Box<Ltlf>),
+    /// Holds if there is no next step or the formula holds in it.
+    WeakNext(Box<Ltlf>),
+    /// Holds if the right formula holds eventually and the left one holds in every step before.
+    Until(Box<Ltlf>, Box<Ltlf>),
+    /// Holds if the right formula holds in every step up to and including the first step in which the left one holds.
+    Release(Box<Ltlf>, Box<Ltlf>),
+    Eventually(Box<Ltlf>),
+    Always(Box<Ltlf>),
+}
+
+/// Something a trace has to fulfill from the next step on. A strong obligation requires a next step,
+/// while a weak one is also fulfilled if the trace ends.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
+enum Obligation {
+    Strong(Ltlf),
+    Weak(Ltlf),
+}
+
+/// A disjunction of conjunctions of obligations. This normal form keeps the number of states of the translation finite.
+type ObligationSet = BTreeSet<BTreeSet<Obligation>>;
+
+impl Ltlf {
+    pub fn negation(inner: Ltlf) -> Ltlf {
+        Ltlf::Not(Box::new(inner))
+    }
+
+    pub fn and(left: Ltlf, right: Ltlf) -> Ltlf {
+        Ltlf::And(Box::new(left), Box::new(right))
+    }
+
+    pub fn or(left: Ltlf, right: Ltlf) -> Ltlf {
+        Ltlf::Or(Box::new(left), Box::new(right))
+    }
+
+    pub fn implies(left: Ltlf, right: Ltlf) -> Ltlf {
+        Ltlf::or(Ltlf::negation(left), right)
+    }
+
+    pub fn next(inner: Ltlf) -> Ltlf {
+        Ltlf::Next(Box::new(inner))
+    }
+
+    pub fn weak_next(inner: Ltlf) -> Ltlf {
+        Ltlf::WeakNext(Box::new(inner))
+    }
+
+    pub fn until(left: Ltlf, right: Ltlf) -> Ltlf {
+        Ltlf::Until(Box::new(left), Box::new(right))
+    }
+
+    pub fn release(left: Ltlf, right: Ltlf) -> Ltlf {
+        Ltlf::Release(Box::new(left), Box::new(right))
+    }
+
+    pub fn eventually(inner: Ltlf) -> Ltlf {
+        Ltlf::Eventually(Box::new(inner))
+    }
+
+    pub fn always(inner: Ltlf) -> Ltlf {
+        Ltlf::Always(Box::new(inner))
+    }
+
+    /// Checks whether the trace satisfies the formula, by progressing the formula through the trace step by step.
+    pub fn check_trace(&self, trace: &str) -> bool {
+        let state = trace.chars().fold(self.get_initial_obligations(), |state, symbol| Ltlf::progress_obligations(&state, symbol));
+        Ltlf::is_fulfilled_at_end(&state)
+    }
+
+    /// Translates the formula into a complete DFA over the alphabet that accepts exactly the traces satisfying the formula.
+    /// Every state stands for what the rest of the trace still has to fulfill. The states are found using the breath
+    /// first algorithm and the result is minimized.
+    pub fn to_dfa(&self, alphabet: &[char]) -> Dfa {
+        let alphabet = alphabet.iter().cloned().sorted().dedup().collect_vec();
+        let initial_state = self.get_initial_obligations();
+        let mut state_names: HashMap<ObligationSet, String> = HashMap::new();
+        let mut states_to_visit = VecDeque::new();
+        let mut transitions = Vec::new();
+        state_names.insert(initial_state.clone(), "q0".to_string());
+        states_to_visit.push_back(initial_state);
+        while let Some(state) = states_to_visit.pop_front() {
+            for symbol in &alphabet {
+                let next_state = Ltlf::progress_obligations(&state, *symbol);
+                if !state_names.contains_key(&next_state) {
+                    state_names.insert(next_state.clone(), format!("q{}", state_names.len()));
+                    states_to_visit.push_back(next_state.clone());
+                }
+                transitions.push(Transition { state: state_names[&state].clone(), input: *symbol, next_state: state_names[&next_state].clone() });
+            }
+        }
+        let mut dfa = Dfa {
+            alphabet: Some(alphabet.into_iter().collect()),
+            ..Dfa::with_parts(
+                self.to_string(),
+                "q0".to_string(),
+                state_names.iter().filter(|(state, _)| Ltlf::is_fulfilled_at_end(state)).map(|(_, name)| name.clone()).collect(),
+                transitions,
+            )
+        };
+        dfa.minimize();
+        dfa
+    }
+
+    /// The formula has to hold from the first step on, which must exist.
+    fn get_initial_obligations(&self) -> ObligationSet {
+        let clause = vec![Obligation::Strong(self.to_negation_normal_form(false))].into_iter().collect();
+        vec![clause].into_iter().collect()
+    }
+
+    /// Pushes all negations down to the atoms, so that progression only has to deal with negated atoms.
+    fn to_negation_normal_form(&self, is_negated: bool) -> Ltlf {
+        let convert = |inner: &Ltlf| inner.to_negation_normal_form(is_negated);
+        match (self, is_negated) {
+            (Ltlf::True, false) | (Ltlf::False, true) => Ltlf::True,
+            (Ltlf::True, true) | (Ltlf::False, false) => Ltlf::False,
+            (Ltlf::Atom(_), false) => self.clone(),
+            (Ltlf::Atom(_), true) => Ltlf::negation(self.clone()),
+            (Ltlf::Not(inner), _) => inner.to_negation_normal_form(!is_negated),
+            (Ltlf::And(left, right), false) | (Ltlf::Or(left, right), true) => Ltlf::and(convert(left), convert(right)),
+            (Ltlf::Or(left, right), false) | (Ltlf::And(left, right), true) => Ltlf::or(convert(left), convert(right)),
+            (Ltlf::Next(inner), false) | (Ltlf::WeakNext(inner), true) => Ltlf::next(convert(inner)),
+            (Ltlf::WeakNext(inner), false) | (Ltlf::Next(inner), true) => Ltlf::weak_next(convert(inner)),
+            (Ltlf::Until(left, right), false) | (Ltlf::Release(left, right), true) => Ltlf::until(convert(left), convert(right)),
+            (Ltlf::Release(left, right), false) | (Ltlf::Until(left, right), true) => Ltlf::release(convert(left), convert(right)),
+            (Ltlf::Eventually(inner), false) | (Ltlf::Always(inner), true) => Ltlf::eventually(convert(inner)),
+            (Ltlf::Always(inner), false) | (Ltlf::Eventually(inner), true) => Ltlf::always(convert(inner)),
+        }
+    }
+
+    /// Computes what the rest of the trace has to fulfill, when the formula (in negation normal form) has to hold in a step with the symbol.
+    fn progress(&self, symbol: char) -> ObligationSet {
+        let fulfilled = || vec![BTreeSet::new()].into_iter().collect();
+        let obligation = |obligation: Obligation| vec![vec![obligation].into_iter().collect()].into_iter().collect();
+        match self {
+            Ltlf::True => fulfilled(),
+            Ltlf::False => ObligationSet::new(),
+            Ltlf::Atom(atom) if *atom == symbol => fulfilled(),
+            Ltlf::Atom(_) => ObligationSet::new(),
+            Ltlf::Not(inner) => match inner.progress(symbol).is_empty() {
+                true => fulfilled(),
+                false => ObligationSet::new(),
+            },
+            Ltlf::And(left, right) => Ltlf::conjoin(&left.progress(symbol), &right.progress(symbol)),
+            Ltlf::Or(left, right) => Ltlf::disjoin(left.progress(symbol), right.progress(symbol)),
+            Ltlf::Next(inner) => obligation(Obligation::Strong(*inner.clone())),
+            Ltlf::WeakNext(inner) => obligation(Obligation::Weak(*inner.clone())),
+            Ltlf::Until(left, right) => Ltlf::disjoin(
+                right.progress(symbol),
+                Ltlf::conjoin(&left.progress(symbol), &obligation(Obligation::Strong(self.clone()))),
+            ),
+            Ltlf::Release(left, right) => Ltlf::conjoin(
+                &right.progress(symbol),
+                &Ltlf::disjoin(left.progress(symbol), obligation(Obligation::Weak(self.clone()))),
+            ),
+            Ltlf::Eventually(inner) => Ltlf::disjoin(inner.progress(symbol), obligation(Obligation::Strong(self.clone()))),
+            Ltlf::Always(inner) => Ltlf::conjoin(&inner.progress(symbol), &obligation(Obligation::Weak(self.clone()))),
+        }
+    }
+
+    /// Progresses all obligations through a step with the symbol.
+    fn progress_obligations(state: &ObligationSet, symbol: char) -> ObligationSet {
+        state.iter()
+            .map(|clause| clause.iter().fold(vec![BTreeSet::new()].into_iter().collect(), |result, obligation| {
+                let formula = match obligation {
+                    Obligation::Strong(formula) | Obligation::Weak(formula) => formula,
+                };
+                Ltlf::conjoin(&result, &formula.progress(symbol))
+            }))
+            .fold(ObligationSet::new(), Ltlf::disjoin)
+    }
+
+    /// A trace may end if all obligations of some clause are weak.
+    fn is_fulfilled_at_end(state: &ObligationSet) -> bool {
+        state.iter().any(|clause| clause.iter().all(|obligation| matches!(obligation, Obligation::Weak(_))))
+    }
+
+    fn conjoin(left: &ObligationSet, right: &ObligationSet) -> ObligationSet {
+        let clauses = left.iter()
+            .cartesian_product(right.iter())
+            .map(|(left_clause, right_clause)| left_clause.union(right_clause).cloned().collect())
+            .collect();
+        Ltlf::remove_subsumed_clauses(clauses)
+    }
+
+    fn disjoin(left: ObligationSet, right: ObligationSet) -> ObligationSet {
+        Ltlf::remove_subsumed_clauses(left.into_iter().chain(right).collect())
+    }
+
+    /// A clause that contains all obligations of another clause is redundant in a disjunction.
+    fn remove_subsumed_clauses(clauses: ObligationSet) -> ObligationSet {
+        clauses.iter()
+            .filter(|clause| !clauses.iter().any(|other| other != *clause && other.is_subset(clause)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl fmt::Display for Ltlf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ltlf::True => write!(f, "true"),
+            Ltlf::False => write!(f, "false"),
+            Ltlf::Atom(atom) => write!(f, "{}", atom),
+            Ltlf::Not(inner) => write!(f, "!{}", inner),
+            Ltlf::And(left, right) => write!(f, "({} & {})", left, right),
+            Ltlf::Or(left, right) => write!(f, "({} | {})", left, right),
+            Ltlf::Next(inner) => write!(f, "X {}", inner),
+            Ltlf::WeakNext(inner) => write!(f, "WX {}", inner),
+            Ltlf::Until(left, right) => write!(f, "({} U {})", left, right),
+            Ltlf::Release(left, right) => write!(f, "({} R {})", left, right),
+            Ltlf::Eventually(inner) => write!(f, "F {}", inner),
+            Ltlf::Always(inner) => write!(f, "G {}", inner),
+        }
+    }
+}
+
+impl Dfa {
+    /// Verifies that every non-empty word accepted by the DFA, seen as a finite trace of a system, satisfies the property.
+    /// Returns a shortest violating trace as counterexample, or None if the property holds. The check uses the breath first
+    /// algorithm on the product of the DFA with the translation of the property (see `Ltlf::to_dfa`).
+    pub fn find_counterexample(&self, property: &Ltlf) -> Option<String> {
+        let mut system = self.clone();
+        system.materialize_missing_transitions();
+        let alphabet = system.get_alphabet().into_iter().sorted().collect_vec();
+        let monitor = property.to_dfa(&alphabet);
+        let start = (system.start_state.clone(), monitor.start_state.clone());
+        let mut words: HashMap<(String, String), String> = HashMap::new();
+        let mut pairs_to_visit = VecDeque::new();
+        words.insert(start.clone(), String::new());
+        pairs_to_visit.push_back(start);
+        while let Some((system_state, monitor_state)) = pairs_to_visit.pop_front() {
+            let word = words[&(system_state.clone(), monitor_state.clone())].clone();
+            if !word.is_empty() && system.accept_states.contains(&system_state) && !monitor.accept_states.contains(&monitor_state) {
+                return Some(word);
+            }
+            for transition in system.transitions.iter().filter(|transition| transition.state == system_state).sorted() {
+                let next_monitor_state = monitor.get_transition(&monitor_state, &transition.input).unwrap().next_state.clone();
+                let next_pair = (transition.next_state.clone(), next_monitor_state);
+                if !words.contains_key(&next_pair) {
+                    words.insert(next_pair.clone(), format!("{}{}", word, transition.input));
+                    pairs_to_visit.push_back(next_pair);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod ltlf_tests {
+    use crate::{Dfa, Ltlf};
+
+    /// Every request ('r') is eventually followed by a grant ('g').
+    fn create_example_property() -> Ltlf {
+        Ltlf::always(Ltlf::implies(Ltlf::Atom('r'), Ltlf::eventually(Ltlf::Atom('g'))))
+    }
+
+    #[test]
+    fn test_check_trace() {
+        let property = create_example_property();
+        assert!(property.check_trace("rig"));
+        assert!(property.check_trace("iii"));
+        assert!(!property.check_trace("rgr"));
+        assert!(Ltlf::weak_next(Ltlf::Atom('a')).check_trace("b"));
+        assert!(!Ltlf::next(Ltlf::Atom('a')).check_trace("b"));
+        let dfa = property.to_dfa(&['r', 'g', 'i']);
+        assert_eq!(dfa.get_all_states().len(), 3);
+        for trace in &["", "r", "rg", "rgi", "rri", "grrg", "iig"] {
+            assert_eq!(dfa.check(trace).0, property.check_trace(trace), "trace {}", trace);
+        }
+    }
+
+    #[test]
+    fn test_find_counterexample() {
+        // The system idles ('i') and alternates between requests and grants. It may only stop after a grant.
+        let mut system = Dfa::from_table(&["idle", "waiting"], &['r', 'g', 'i'], &[&[Some(1), None, Some(0)], &[None, Some(0), Some(1)]], 0, &[0]).unwrap();
+        assert_eq!(system.find_counterexample(&create_example_property()), None);
+        system.accept_states.insert("waiting".to_string());
+        assert_eq!(system.find_counterexample(&create_example_property()), Some("r".to_string()));
+    }
+}
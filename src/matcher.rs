@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// The transition table of a DFA in which states are replaced by indices, so that matching needs no string comparisons.
+#[derive(Debug)]
+pub(crate) struct CompiledDfa {
+    pub(crate) start_state: usize,
+    pub(crate) is_accepting: Vec<bool>,
+    pub(crate) transitions: Vec<HashMap<char, usize>>,
+}
+
+/// A compiled DFA for fast matching, as created by `Dfa::compile`.
+///
+/// Matchers are immutable, `Send` and `Sync`, so one matcher can be used by many threads at the same time without locks.
+/// `clone_handle` creates another handle to the same compiled automaton, which only increments a reference count,
+/// so each worker thread can own a handle. The compiled automaton is freed when the last handle is dropped.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    compiled_dfa: Arc<CompiledDfa>,
+}
+
+impl Dfa {
+    /// Compiles the DFA into a matcher. Later changes of the DFA do not affect the matcher.
+    /// Missing transitions are handled according to the `MissingTransitionPolicy`, where errors are treated as rejection.
+    pub fn compile(&self) -> Matcher {
+        Matcher { compiled_dfa: Arc::new(self.compile_table()) }
+    }
+
+    /// Replaces the states by indices, in alphabetical order of their names.
+    pub(crate) fn compile_table(&self) -> CompiledDfa {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let indices: HashMap<&String, usize> = states.iter().enumerate().map(|(index, state)| (state, index)).collect();
+        let mut transitions = vec![HashMap::new(); states.len()];
+        for transition in &dfa.transitions {
+            transitions[indices[&transition.state]].entry(transition.input).or_insert(indices[&transition.next_state]);
+        }
+        CompiledDfa {
+            start_state: indices[&dfa.start_state],
+            is_accepting: states.iter().map(|state| dfa.accept_states.contains(state)).collect(),
+            transitions,
+        }
+    }
+}
+
+impl Matcher {
+    /// Checks whether the input is accepted by the compiled DFA.
+    pub fn is_match(&self, input: &str) -> bool {
+        let compiled_dfa = &self.compiled_dfa;
+        let mut state = compiled_dfa.start_state;
+        for char in input.chars() {
+            state = match compiled_dfa.transitions[state].get(&char) {
+                Some(next_state) => *next_state,
+                None => return false,
+            };
+        }
+        compiled_dfa.is_accepting[state]
+    }
+
+    /// Creates another handle to the same compiled DFA without copying it.
+    pub fn clone_handle(&self) -> Matcher {
+        Matcher { compiled_dfa: Arc::clone(&self.compiled_dfa) }
+    }
+
+    /// Returns the number of handles to the compiled DFA, including this one.
+    pub fn get_handle_count(&self) -> usize {
+        Arc::strong_count(&self.compiled_dfa)
+    }
+}
+
+#[cfg(test)]
+mod matcher_tests {
+    use std::thread;
+
+    use crate::{Dfa, ImmutableDfa, Matcher, TokenDfa};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_concurrency_contract() {
+        assert_send_sync::<Dfa>();
+        assert_send_sync::<Matcher>();
+        assert_send_sync::<ImmutableDfa>();
+        assert_send_sync::<TokenDfa<String>>();
+    }
+
+    #[test]
+    fn test_clone_handle() {
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        let matcher = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap().compile();
+        let workers = (0..4)
+            .map(|worker| {
+                let handle = matcher.clone_handle();
+                thread::spawn(move || handle.is_match(&format!("{}b", "a".repeat(worker))) && !handle.is_match("ba"))
+            })
+            .collect::<Vec<_>>();
+        assert!(workers.into_iter().all(|worker| worker.join().unwrap()));
+        assert_eq!(matcher.get_handle_count(), 1, "the handles of the workers are dropped");
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{AcceptTag, Dfa, Fst, Transition};
+
+/// Describes to which next state a Mealy machine switches when it reads a certain input while being in a certain state,
+/// and which output symbol it emits while doing so.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct MealyTransition {
+    pub(crate) state: String,
+    pub(crate) input: char,
+    pub(crate) output: char,
+    pub(crate) next_state: String,
+}
+
+/// # [Mealy machine](https://en.wikipedia.org/wiki/Mealy_machine)
+/// A deterministic automaton that emits exactly one output symbol for every input symbol it reads. It does not accept or reject,
+/// but it cannot react to inputs for which the current state has no transition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MealyMachine {
+    pub(crate) name: String,
+    pub(crate) start_state: String,
+    pub(crate) transitions: Vec<MealyTransition>,
+}
+
+/// A reaction of a synchronous composition that is not well-defined, because the feedback from the second machine to the first one
+/// is not resolved by exactly one consistent output of the second machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CausalityConflict {
+    /// No output of the second machine, fed back into the first one, leads to the second machine emitting that very output.
+    NoReaction { state: String, input: char },
+    /// Several outputs of the second machine are consistent with the feedback, so the reaction is nondeterministic.
+    AmbiguousReaction { state: String, input: char, outputs: Vec<char> },
+}
+
+impl MealyMachine {
+    /// Creates a Mealy machine without transitions.
+    pub fn new(name: &str, start_state: &str) -> MealyMachine {
+        MealyMachine {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, output: char, next_state: &str) {
+        self.transitions.push(MealyTransition { state: state.to_string(), input, output, next_state: next_state.to_string() });
+    }
+
+    pub fn get_transition(&self, state: &str, input: &char) -> Option<&MealyTransition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        self.transitions.iter().flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()]).collect()
+    }
+
+    pub fn get_output_symbols(&self) -> BTreeSet<char> {
+        self.transitions.iter().map(|transition| transition.output).collect()
+    }
+
+    /// Returns the output for the input, or None if some symbol cannot be read.
+    pub fn run(&self, input: &str) -> Option<String> {
+        let mut current_state = &self.start_state;
+        let mut output = String::new();
+        for char in input.chars() {
+            let transition = self.get_transition(current_state, &char)?;
+            output.push(transition.output);
+            current_state = &transition.next_state;
+        }
+        Some(output)
+    }
+
+    /// Checks whether both machines produce the same output for every input (see `equivalence_counterexample`).
+    pub fn is_equivalent(&self, other: &MealyMachine) -> bool {
+        self.equivalence_counterexample(other).is_none()
+    }
+
+    /// Returns a shortest input for which the machines produce different outputs, or None if they are equivalent.
+    /// An input that only one of the machines can read counts as a difference. Pairs of states are visited using the breath first algorithm,
+    /// reading symbols in alphabetical order, and every pair is visited once.
+    pub fn equivalence_counterexample(&self, other: &MealyMachine) -> Option<String> {
+        let input_symbols = self.transitions.iter().chain(&other.transitions).map(|transition| transition.input).collect::<BTreeSet<char>>();
+        let start = (self.start_state.as_str(), other.start_state.as_str());
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        visited_pairs.insert(start);
+        pairs_to_visit.push_back((String::new(), start));
+        while let Some((word, (first_state, second_state))) = pairs_to_visit.pop_front() {
+            for input in &input_symbols {
+                let next_pair = match (self.get_transition(first_state, input), other.get_transition(second_state, input)) {
+                    (None, None) => continue,
+                    (Some(first_transition), Some(second_transition)) if first_transition.output == second_transition.output => {
+                        (first_transition.next_state.as_str(), second_transition.next_state.as_str())
+                    }
+                    _ => return Some(format!("{}{}", word, input)),
+                };
+                if visited_pairs.insert(next_pair) {
+                    pairs_to_visit.push_back((format!("{}{}", word, input), next_pair));
+                }
+            }
+        }
+        None
+    }
+
+    /// Minimizes the machine by merging states that produce the same outputs for every input, and removes unreachable states.
+    /// It reuses the partition refinement of `Dfa::minimize`: the machine is viewed as a DFA in which every state accepts with a tag
+    /// listing the outputs of its transitions, so that initially only states that react the same way to every single symbol share a class.
+    /// Merged states are named after the smallest name within their class. Returns a hash map with all renaming operations, like `Dfa::minimize`.
+    pub fn minimize(&mut self) -> HashMap<String, String> {
+        let mut states = self.get_all_states();
+        states.insert(self.start_state.clone());
+        let accept_tags = states.iter()
+            .map(|state| {
+                let reactions = self.transitions.iter()
+                    .filter(|transition| transition.state == *state)
+                    .map(|transition| format!("{}/{}", transition.input, transition.output))
+                    .sorted()
+                    .join(",");
+                (state.clone(), AcceptTag::new(&reactions, 0))
+            })
+            .collect();
+        let mut dfa = Dfa {
+            accept_tags,
+            ..Dfa::with_parts(
+                self.name.clone(),
+                self.start_state.clone(),
+                states,
+                self.transitions.iter()
+                    .map(|transition| Transition { state: transition.state.clone(), input: transition.input, next_state: transition.next_state.clone() })
+                    .collect(),
+            )
+        };
+        let reachable_states = dfa.get_reachable_states();
+        let mut renaming_operations = dfa.minimize();
+        // Unreachable states are still accept states of the DFA and might have been merged, although they are removed.
+        renaming_operations.retain(|state, _| reachable_states.contains(state));
+        let rename = |state: &String| renaming_operations.get(state).unwrap_or(state).clone();
+        self.start_state = rename(&self.start_state);
+        self.transitions = self.transitions.iter()
+            .filter(|transition| reachable_states.contains(&transition.state))
+            .map(|transition| MealyTransition {
+                state: rename(&transition.state),
+                input: transition.input,
+                output: transition.output,
+                next_state: rename(&transition.next_state),
+            })
+            .sorted()
+            .dedup()
+            .collect();
+        renaming_operations
+    }
+
+    /// Converts the machine into a transducer in which every state accepts, so that it can be applied and analyzed like one.
+    pub fn to_fst(&self) -> Fst {
+        let mut fst = Fst::new(&self.name, &self.start_state);
+        fst.add_accept_state(&self.start_state);
+        for transition in &self.transitions {
+            fst.add_accept_state(&transition.next_state);
+            fst.add_transition(&transition.state, transition.input, &transition.output.to_string(), &transition.next_state);
+        }
+        fst
+    }
+
+    /// Composes both machines synchronously, so that they react to every input in the same step. The output of this machine is the input
+    /// of the other one, whose output is the output of the composition. The other machine's output is also fed back into this machine:
+    /// it reads `wire(input, feedback)` for the external input and the feedback. For a plain cascade without feedback, `wire` ignores the feedback.
+    ///
+    /// As the feedback is needed for the reaction that produces it, every output of the other machine is tried as the feedback,
+    /// and a reaction is only well-defined if exactly one of them is consistent. Otherwise, the reaction is left out and reported as a
+    /// causality conflict. Reactions for which the machines have no transitions at all are left out without a conflict.
+    /// States are named "(p, q)" and found using the breath first algorithm.
+    pub fn compose(&self, other: &MealyMachine, wire: impl Fn(char, char) -> char) -> (MealyMachine, Vec<CausalityConflict>) {
+        let input_symbols = self.transitions.iter().map(|transition| transition.input).collect::<BTreeSet<char>>();
+        // Without any transition of the other machine, there is no feedback, but this machine may still react.
+        let feedback_symbols = match other.get_output_symbols() {
+            symbols if symbols.is_empty() => vec![None],
+            symbols => symbols.into_iter().map(Some).collect_vec(),
+        };
+        let state_name = |pair: &(&str, &str)| format!("({}, {})", pair.0, pair.1);
+        let start = (self.start_state.as_str(), other.start_state.as_str());
+        let mut composition = MealyMachine::new(&format!("composition of {} and {}", self.name, other.name), &state_name(&start));
+        let mut conflicts = Vec::new();
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        visited_pairs.insert(start);
+        pairs_to_visit.push_back(start);
+        while let Some(pair) = pairs_to_visit.pop_front() {
+            for input in &input_symbols {
+                let mut is_defined = false;
+                let mut consistent_reactions = Vec::new();
+                for feedback in &feedback_symbols {
+                    let first_input = feedback.map_or(*input, |feedback| wire(*input, feedback));
+                    let first_transition = match self.get_transition(pair.0, &first_input) {
+                        Some(transition) => transition,
+                        None => continue,
+                    };
+                    let second_transition = match other.get_transition(pair.1, &first_transition.output) {
+                        Some(transition) => transition,
+                        None => continue,
+                    };
+                    is_defined = true;
+                    if feedback.is_none() || *feedback == Some(second_transition.output) {
+                        consistent_reactions.push((first_transition, second_transition));
+                    }
+                }
+                let state = state_name(&pair);
+                match consistent_reactions.as_slice() {
+                    [] if is_defined => conflicts.push(CausalityConflict::NoReaction { state, input: *input }),
+                    [] => {}
+                    [(first_transition, second_transition)] => {
+                        let next_pair = (first_transition.next_state.as_str(), second_transition.next_state.as_str());
+                        composition.add_transition(&state, *input, second_transition.output, &state_name(&next_pair));
+                        if visited_pairs.insert(next_pair) {
+                            pairs_to_visit.push_back(next_pair);
+                        }
+                    }
+                    reactions => conflicts.push(CausalityConflict::AmbiguousReaction {
+                        state,
+                        input: *input,
+                        outputs: reactions.iter().map(|(_, second_transition)| second_transition.output).collect(),
+                    }),
+                }
+            }
+        }
+        (composition, conflicts)
+    }
+}
+
+#[cfg(test)]
+mod mealy_tests {
+    use crate::{CausalityConflict, MealyMachine};
+
+    #[test]
+    fn test_compose() {
+        // Emits '1' on every second 't', like a clock divider.
+        let mut divider = MealyMachine::new("divider", "low");
+        divider.add_transition("low", 't', '0', "high");
+        divider.add_transition("high", 't', '1', "low");
+        // Counts the '1's modulo 2 and emits the parity.
+        let mut parity = MealyMachine::new("parity", "even");
+        parity.add_transition("even", '0', 'e', "even");
+        parity.add_transition("even", '1', 'o', "odd");
+        parity.add_transition("odd", '0', 'o', "odd");
+        parity.add_transition("odd", '1', 'e', "even");
+        let (cascade, conflicts) = divider.compose(&parity, |input, _| input);
+        assert!(conflicts.is_empty());
+        assert_eq!(cascade.run("tttt"), Some("eooe".to_string()));
+        assert_eq!(cascade.get_all_states().len(), 4);
+        assert_eq!(cascade.to_fst().replace_all("tt"), "eo");
+    }
+
+    #[test]
+    fn test_minimize_and_is_equivalent() {
+        // Emits '1' on every second 't' like the divider, but counts up to four.
+        let mut counter = MealyMachine::new("counter", "q0");
+        for (state, output, next_state) in &[("q0", '0', "q1"), ("q1", '1', "q2"), ("q2", '0', "q3"), ("q3", '1', "q0")] {
+            counter.add_transition(state, 't', *output, next_state);
+        }
+        counter.add_transition("unreachable", 't', '0', "q0");
+        let mut divider = MealyMachine::new("divider", "low");
+        divider.add_transition("low", 't', '0', "high");
+        divider.add_transition("high", 't', '1', "low");
+        assert!(counter.is_equivalent(&divider));
+        let mut minimized_counter = counter.clone();
+        let renaming_operations = minimized_counter.minimize();
+        assert_eq!(renaming_operations["q2"], "q0");
+        assert!(!renaming_operations.contains_key("unreachable"));
+        assert_eq!(minimized_counter.get_all_states().len(), 2);
+        assert!(minimized_counter.is_equivalent(&counter));
+        divider.add_transition("high", 'r', '0', "low");
+        assert_eq!(counter.equivalence_counterexample(&divider), Some("tr".to_string()));
+    }
+
+    #[test]
+    fn test_compose_detects_causality_conflicts() {
+        // Inverts its input, and copies its input, respectively, so feeding back the copy contradicts itself.
+        let mut inverter = MealyMachine::new("inverter", "q");
+        inverter.add_transition("q", '0', '1', "q");
+        inverter.add_transition("q", '1', '0', "q");
+        let mut copy = MealyMachine::new("copy", "q");
+        copy.add_transition("q", '0', '0', "q");
+        copy.add_transition("q", '1', '1', "q");
+        let (_, conflicts) = inverter.compose(&copy, |_, feedback| feedback);
+        assert_eq!(conflicts, vec![
+            CausalityConflict::NoReaction { state: "(q, q)".to_string(), input: '0' },
+            CausalityConflict::NoReaction { state: "(q, q)".to_string(), input: '1' },
+        ]);
+        // Copying the feedback instead is consistent with either output.
+        let (_, conflicts) = copy.compose(&copy, |_, feedback| feedback);
+        assert!(matches!(&conflicts[0], CausalityConflict::AmbiguousReaction { outputs, .. } if *outputs == vec!['0', '1']));
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// Explains what `Dfa::minimize_with_report` did, for auditing the minimization or for teaching the algorithm.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinimizationReport {
+    pub state_count_before: usize,
+    pub state_count_after: usize,
+    /// States that could not be reached from the start state, sorted by name.
+    pub removed_unreachable_states: Vec<String>,
+    /// The classes of equivalent states that were merged into one state, each sorted by name. The first state of a class is the one
+    /// the others were merged into. Classes of a single state are left out.
+    pub merged_classes: Vec<Vec<String>>,
+    /// The renaming operations, as returned by `minimize`.
+    pub renamings: HashMap<String, String>,
+    /// For every pair of states of the minimized DFA, the shortest word that is accepted from exactly one of them (see `Dfa::distinguishing_word`),
+    /// which proves that the states must be kept apart. Pairs of accept states that are only kept apart by their accept tags are left out.
+    pub distinguishing_words: BTreeMap<(String, String), String>,
+}
+
+impl fmt::Display for MinimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} states before, {} states after", self.state_count_before, self.state_count_after)?;
+        writeln!(f, "removed unreachable states: {}", self.removed_unreachable_states.join(", "))?;
+        for class in &self.merged_classes {
+            writeln!(f, "merged {} into {}", class.join(", "), class[0])?;
+        }
+        for ((state_1, state_2), word) in &self.distinguishing_words {
+            writeln!(f, "{} and {} are distinguished by \"{}\"", state_1, state_2, word)?;
+        }
+        Ok(())
+    }
+}
+
+impl Dfa {
+    /// Minimizes the DFA like `minimize` and reports the unreachable states that were removed, the equivalence classes
+    /// that were merged and the words that distinguish the remaining states.
+    pub fn minimize_with_report(&mut self) -> MinimizationReport {
+        let states_before = self.get_all_mentioned_states();
+        let mut reachable_dfa = self.clone();
+        reachable_dfa.materialize_missing_transitions();
+        reachable_dfa.remove_inaccessible_states();
+        let reachable_states = reachable_dfa.get_all_mentioned_states();
+        let renamings = self.minimize();
+        let states_after = self.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let merged_classes = renamings.iter()
+            .map(|(state, new_state)| (new_state.clone(), state.clone()))
+            .into_group_map()
+            .into_iter().map(|(_, class)| class)
+            .map(|class| class.into_iter().sorted().collect_vec())
+            .sorted()
+            .collect();
+        let distinguishing_words = states_after.iter()
+            .tuple_combinations()
+            .filter_map(|(state_1, state_2)| {
+                self.distinguishing_word(state_1, state_2).map(|word| ((state_1.clone(), state_2.clone()), word))
+            })
+            .collect();
+        MinimizationReport {
+            state_count_before: states_before.len(),
+            state_count_after: states_after.len(),
+            removed_unreachable_states: states_before.difference(&reachable_states).cloned().sorted().collect(),
+            merged_classes,
+            renamings,
+            distinguishing_words,
+        }
+    }
+}
+
+#[cfg(test)]
+mod minimization_report_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_minimize_with_report() {
+        // Accepts words over 'a' and 'b' ending with "ab". q3 is a redundant copy of q0 and q4 is unreachable.
+        let mut dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3", "q4"],
+            &['a', 'b'],
+            &[&[Some(1), Some(3)], &[Some(1), Some(2)], &[Some(1), Some(3)], &[Some(1), Some(0)], &[Some(0), Some(0)]],
+            0,
+            &[2],
+        ).unwrap();
+        let report = dfa.minimize_with_report();
+        assert_eq!((report.state_count_before, report.state_count_after), (5, 3));
+        assert_eq!(report.removed_unreachable_states, vec!["q4"]);
+        assert_eq!(report.merged_classes, vec![vec!["q0", "q3"]]);
+        assert_eq!(report.distinguishing_words[&("q0".to_string(), "q1".to_string())], "b");
+        assert!(report.to_string().contains("merged q0, q3 into q0\n"));
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// Renders a symbol as an SMV symbolic constant, like "c_a" for 'a' or "c_u2d" for '-'.
+fn smv_symbol(symbol: char) -> String {
+    match symbol.is_ascii_alphanumeric() {
+        true => format!("c_{}", symbol),
+        false => format!("c_u{:x}", symbol as u32),
+    }
+}
+
+/// Renders a symbol as a Promela expression, like 'a' for 'a' or 45 for '-'.
+fn promela_symbol(symbol: char) -> String {
+    match symbol.is_ascii_alphanumeric() {
+        true => format!("'{}'", symbol),
+        false => (symbol as u32).to_string(),
+    }
+}
+
+/// Returns the states sorted by name, starting with the start state, so that they can be referred to by their index.
+fn get_numbered_states(start_state: &str, accept_states: &HashSet<String>, transitions: &[Transition]) -> Vec<String> {
+    let mut states: HashSet<&str> = transitions.iter().flat_map(|transition| vec![transition.state.as_str(), transition.next_state.as_str()]).collect();
+    states.extend(accept_states.iter().map(|state| state.as_str()));
+    states.remove(start_state);
+    std::iter::once(start_state).chain(states.into_iter().sorted()).map(String::from).collect()
+}
+
+/// Renders the automaton as a NuSMV or nuXmv model reading one symbol per step from the input variable `input`.
+/// As state names need not be identifiers, states are renamed to "s0", "s1" and so on, starting with the start state, and their names
+/// are given in comments. Missing transitions lead into the state "error". The accept states are defined as `accepting`,
+/// which is additionally used as a fairness constraint if the automaton is a Büchi automaton.
+pub(crate) fn render_smv(name: &str, start_state: &str, accept_states: &HashSet<String>, transitions: &[Transition], is_buchi: bool) -> String {
+    let states = get_numbered_states(start_state, accept_states, transitions);
+    let identifier = |state: &str| format!("s{}", states.iter().position(|other| other == state).unwrap());
+    let alphabet = transitions.iter().map(|transition| transition.input).sorted().dedup().collect_vec();
+    let is_complete = states.iter().cartesian_product(alphabet.iter())
+        .all(|(state, input)| transitions.iter().any(|transition| transition.state == *state && transition.input == *input));
+    let mut state_identifiers = states.iter().map(|state| identifier(state)).collect_vec();
+    if !is_complete {
+        state_identifiers.push("error".to_string());
+    }
+    let mut smv = format!("-- {}\nMODULE main\n", name.replace('\n', " "));
+    smv += &format!("IVAR\n    input : {{{}}};\n", alphabet.iter().map(|symbol| smv_symbol(*symbol)).join(", "));
+    smv += &format!("VAR\n    state : {{{}}};\n", state_identifiers.join(", "));
+    for state in &states {
+        smv += &format!("    -- {}: {}\n", identifier(state), state.replace('\n', " "));
+    }
+    smv += &format!("ASSIGN\n    init(state) := {};\n    next(state) := case\n", identifier(start_state));
+    for transition in transitions.iter().unique_by(|transition| (&transition.state, transition.input)).sorted() {
+        smv += &format!(
+            "        state = {} & input = {} : {};\n",
+            identifier(&transition.state), smv_symbol(transition.input), identifier(&transition.next_state),
+        );
+    }
+    if !is_complete {
+        smv += "        TRUE : error;\n";
+    }
+    smv += "    esac;\n";
+    let accepting = match accept_states.is_empty() {
+        true => "FALSE".to_string(),
+        false => format!("state in {{{}}}", accept_states.iter().sorted().map(|state| identifier(state)).join(", ")),
+    };
+    smv += &format!("DEFINE\n    accepting := {};\n", accepting);
+    if is_buchi {
+        smv += "JUSTICE\n    accepting;\n";
+    }
+    smv
+}
+
+/// Renders the automaton as a Promela never claim for [Spin](https://spinroot.com/spin/Man/never.html) observing the global variable `input`.
+/// States are renamed like in `render_smv`. For a Büchi automaton, accept states get labels starting with "accept", so Spin reports runs
+/// visiting them infinitely often. Otherwise, reaching an accept state ends the claim, which Spin reports as a violation, too.
+pub(crate) fn render_never_claim(name: &str, start_state: &str, accept_states: &HashSet<String>, transitions: &[Transition], is_buchi: bool) -> String {
+    let states = get_numbered_states(start_state, accept_states, transitions);
+    let label = |state: &str| {
+        let index = states.iter().position(|other| other == state).unwrap();
+        match is_buchi && accept_states.contains(state) {
+            true => format!("accept_s{}", index),
+            false => format!("T_s{}", index),
+        }
+    };
+    let mut never_claim = format!("/* {} */\nnever {{\n", name.replace("*/", "* /"));
+    for state in &states {
+        never_claim += &format!("{}: /* {} */\n", label(state), state.replace("*/", "* /"));
+        if !is_buchi && accept_states.contains(state) {
+            never_claim += "    goto violation;\n";
+            continue;
+        }
+        let outgoing_transitions = transitions.iter().filter(|transition| transition.state == *state).unique_by(|transition| transition.input).sorted().collect_vec();
+        if outgoing_transitions.is_empty() {
+            never_claim += "    false;\n";
+            continue;
+        }
+        never_claim += "    if\n";
+        for transition in outgoing_transitions {
+            never_claim += &format!("    :: (input == {}) -> goto {}\n", promela_symbol(transition.input), label(&transition.next_state));
+        }
+        never_claim += "    fi;\n";
+    }
+    if !is_buchi {
+        never_claim += "violation:\n    skip\n";
+    }
+    never_claim + "}\n"
+}
+
+impl Dfa {
+    /// Renders the DFA as a [NuSMV](https://nusmv.fbk.eu/) or nuXmv model, so that properties designed with this crate can be checked
+    /// by established model checkers. The model reads one symbol per step from the input variable `input`, and `accepting` holds
+    /// whenever the symbols read so far are accepted. States are renamed to "s0", "s1" and so on, with their names given in comments.
+    pub fn to_smv(&self) -> String {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        render_smv(&dfa.name, &dfa.start_state, &dfa.accept_states, &dfa.transitions, false)
+    }
+
+    /// Renders the DFA as a Promela never claim for the model checker Spin, observing the global variable `input`.
+    /// The DFA is read as a monitor of bad prefixes: as soon as an accept state is reached, the claim ends, which Spin reports as a violation.
+    pub fn to_promela_never_claim(&self) -> String {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        render_never_claim(&dfa.name, &dfa.start_state, &dfa.accept_states, &dfa.transitions, false)
+    }
+}
+
+#[cfg(test)]
+mod model_checker_export_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_to_smv_and_to_promela_never_claim() {
+        // Accepts the bad prefixes of "no 'b' after an 'a'", without transitions out of the accept state.
+        let mut dfa = Dfa::from_table(&["q0", "q1", "bad"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(1), Some(2)], &[None, None]], 0, &[2]).unwrap();
+        dfa.name = "b after a".to_string();
+        let smv = dfa.to_smv();
+        assert!(smv.contains("IVAR\n    input : {c_a, c_b};\n"));
+        assert!(smv.contains("VAR\n    state : {s0, s1, s2, error};\n    -- s0: q0\n    -- s1: bad\n    -- s2: q1\n"));
+        assert!(smv.contains("        state = s2 & input = c_b : s1;\n"));
+        assert!(smv.contains("        TRUE : error;\n    esac;\nDEFINE\n    accepting := state in {s1};\n"));
+        let never_claim = dfa.to_promela_never_claim();
+        assert!(never_claim.starts_with("/* b after a */\nnever {\nT_s0: /* q0 */\n    if\n    :: (input == 'a') -> goto T_s2\n"));
+        assert!(never_claim.contains("T_s1: /* bad */\n    goto violation;\n"));
+        assert!(never_claim.ends_with("violation:\n    skip\n}\n"));
+    }
+}
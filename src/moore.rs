@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{MealyMachine, Transition};
+
+/// # [Moore machine](https://en.wikipedia.org/wiki/Moore_machine)
+/// A deterministic automaton whose states carry output symbols. While reading the input, it emits the output of every state it enters,
+/// starting with the start state, so the output is one symbol longer than the input. Like a Mealy machine, it does not accept or reject,
+/// but it cannot react to inputs for which the current state has no transition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MooreMachine {
+    name: String,
+    start_state: String,
+    outputs: HashMap<String, char>,
+    transitions: Vec<Transition>,
+}
+
+impl MooreMachine {
+    /// Creates a Moore machine without transitions whose start state emits the output.
+    pub fn new(name: &str, start_state: &str, start_output: char) -> MooreMachine {
+        let mut outputs = HashMap::new();
+        outputs.insert(start_state.to_string(), start_output);
+        MooreMachine {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            outputs,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Adds the state with its output, or changes the output of an existing state.
+    pub fn add_state(&mut self, state: &str, output: char) {
+        self.outputs.insert(state.to_string(), output);
+    }
+
+    /// Adds a transition between states that have been added with their outputs before.
+    pub fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.transitions.push(Transition { state: state.to_string(), input, next_state: next_state.to_string() });
+    }
+
+    pub fn get_transition(&self, state: &str, input: &char) -> Option<&Transition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    pub fn get_output(&self, state: &str) -> Option<char> {
+        self.outputs.get(state).cloned()
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        self.outputs.keys().cloned().collect()
+    }
+
+    /// Returns the outputs of the start state and of every state entered while reading the input,
+    /// or None if some symbol cannot be read or a state has no output.
+    pub fn run(&self, input: &str) -> Option<Vec<char>> {
+        let mut current_state = &self.start_state;
+        let mut output = vec![self.get_output(current_state)?];
+        for char in input.chars() {
+            current_state = &self.get_transition(current_state, &char)?.next_state;
+            output.push(self.get_output(current_state)?);
+        }
+        Some(output)
+    }
+
+    /// Converts the machine into a Mealy machine with the same states, whose transitions emit the output of the state they lead to.
+    /// It produces the same outputs except for the one of the start state, which a Mealy machine cannot emit before reading anything.
+    pub fn to_mealy(&self) -> MealyMachine {
+        let mut mealy = MealyMachine::new(&self.name, &self.start_state);
+        for transition in &self.transitions {
+            if let Some(output) = self.get_output(&transition.next_state) {
+                mealy.add_transition(&transition.state, transition.input, output, &transition.next_state);
+            }
+        }
+        mealy
+    }
+
+    /// Converts the Mealy machine into a Moore machine producing the same outputs after the output of its start state, which is `start_output`.
+    /// As a state of the Mealy machine may be entered with different outputs, every state of the Moore machine stands for
+    /// a state of the Mealy machine together with the output of the transition entering it, like "(q1, x)". The start state is named
+    /// after the start state of the Mealy machine and `start_output`. Only states that can be reached are constructed, using the breath first algorithm.
+    pub fn from_mealy(mealy: &MealyMachine, start_output: char) -> MooreMachine {
+        let state_name = |pair: &(&str, char)| format!("({}, {})", pair.0, pair.1);
+        let start = (mealy.start_state.as_str(), start_output);
+        let mut moore = MooreMachine::new(&mealy.name, &state_name(&start), start_output);
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        visited_pairs.insert(start);
+        pairs_to_visit.push_back(start);
+        while let Some(pair) = pairs_to_visit.pop_front() {
+            for transition in mealy.transitions.iter().filter(|transition| transition.state == pair.0) {
+                let next_pair = (transition.next_state.as_str(), transition.output);
+                if visited_pairs.insert(next_pair) {
+                    moore.add_state(&state_name(&next_pair), transition.output);
+                    pairs_to_visit.push_back(next_pair);
+                }
+                moore.add_transition(&state_name(&pair), transition.input, &state_name(&next_pair));
+            }
+        }
+        moore
+    }
+}
+
+#[cfg(test)]
+mod moore_tests {
+    use crate::{MealyMachine, MooreMachine};
+
+    #[test]
+    fn test_run_and_conversions() {
+        // Controls a traffic light that switches from red ('r') to green ('g') to yellow ('y') on every tick ('t') and stays on a hold ('h').
+        let mut moore = MooreMachine::new("traffic light", "red", 'r');
+        moore.add_state("green", 'g');
+        moore.add_state("yellow", 'y');
+        for (state, next_state) in &[("red", "green"), ("green", "yellow"), ("yellow", "red")] {
+            moore.add_transition(state, 't', next_state);
+            moore.add_transition(state, 'h', state);
+        }
+        assert_eq!(moore.run("tht"), Some(vec!['r', 'g', 'g', 'y']));
+        assert_eq!(moore.run("x"), None);
+        let mealy = moore.to_mealy();
+        assert_eq!(mealy.run("tht"), Some("ggy".to_string()));
+        // Emits '1' whenever the input symbol equals the previous one.
+        let mut mealy = MealyMachine::new("repetition", "start");
+        for (state, symbol) in &[("start", '0'), ("start", '1'), ("seen 0", '0'), ("seen 0", '1'), ("seen 1", '0'), ("seen 1", '1')] {
+            let output = if state.ends_with(*symbol) { '1' } else { '0' };
+            mealy.add_transition(state, *symbol, output, &format!("seen {}", symbol));
+        }
+        let moore = MooreMachine::from_mealy(&mealy, '0');
+        assert_eq!(moore.run("0010111"), Some("00100011".chars().collect()));
+        assert_eq!(moore.get_all_states().len(), 5);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// A small structural change of a DFA, as used for mutation testing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Mutation {
+    /// Makes an accept state rejecting or the other way round.
+    FlipAcceptance { state: String },
+    /// Lets the transition of the state for the input lead into a different next state. Adds the transition if it is missing.
+    RedirectTransition { state: String, input: char, next_state: String },
+    /// Removes the transition of the state for the input.
+    RemoveTransition { state: String, input: char },
+}
+
+/// A mutant that is not detected by a test suite, together with a word on which it disagrees with the original DFA.
+/// The word is None if the mutant is equivalent to the original DFA, so no test suite can detect it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurvivingMutant {
+    pub mutation: Mutation,
+    pub distinguishing_word: Option<String>,
+}
+
+/// The result of running a test suite against all mutants of a DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MutationReport {
+    /// Mutations detected by the test suite, because some word of the suite is judged differently by the mutant.
+    pub killed: Vec<Mutation>,
+    pub surviving: Vec<SurvivingMutant>,
+}
+
+impl MutationReport {
+    /// The share of non-equivalent mutants that are killed by the test suite, which measures the quality of the suite.
+    pub fn get_mutation_score(&self) -> f64 {
+        let equivalent_mutants = self.surviving.iter().filter(|mutant| mutant.distinguishing_word.is_none()).count();
+        let relevant_mutants = self.killed.len() + self.surviving.len() - equivalent_mutants;
+        if relevant_mutants == 0 {
+            return 1.0;
+        }
+        self.killed.len() as f64 / relevant_mutants as f64
+    }
+}
+
+impl Dfa {
+    /// Generates all mutants of the DFA that differ by exactly one mutation: every state's acceptance is flipped,
+    /// every transition is removed and every transition is redirected to every other state.
+    pub fn generate_mutants(&self) -> Vec<(Mutation, Dfa)> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let mut mutations = Vec::new();
+        for state in &states {
+            mutations.push(Mutation::FlipAcceptance { state: state.clone() });
+        }
+        for transition in dfa.transitions.iter().sorted() {
+            mutations.push(Mutation::RemoveTransition { state: transition.state.clone(), input: transition.input });
+            for next_state in states.iter().filter(|next_state| **next_state != transition.next_state) {
+                mutations.push(Mutation::RedirectTransition { state: transition.state.clone(), input: transition.input, next_state: next_state.clone() });
+            }
+        }
+        mutations.into_iter()
+            .map(|mutation| {
+                let mut mutant = dfa.clone();
+                mutant.apply_mutation(&mutation);
+                (mutation, mutant)
+            })
+            .collect()
+    }
+
+    /// Changes the DFA according to the mutation. Accept tags of states that become rejecting are removed.
+    pub fn apply_mutation(&mut self, mutation: &Mutation) {
+        match mutation {
+            Mutation::FlipAcceptance { state } => {
+                if !self.accept_states.remove(state) {
+                    self.accept_states.insert(state.clone());
+                }
+                self.accept_tags.remove(state);
+            }
+            Mutation::RedirectTransition { state, input, next_state } => {
+                self.transitions.retain(|transition| transition.state != *state || transition.input != *input);
+                self.transitions.push(Transition { state: state.clone(), input: *input, next_state: next_state.clone() });
+            }
+            Mutation::RemoveTransition { state, input } => {
+                self.transitions.retain(|transition| transition.state != *state || transition.input != *input);
+            }
+        }
+    }
+
+    /// Runs the test suite against all mutants (see `generate_mutants`). A mutant is killed if it judges some word of the
+    /// test suite differently than the DFA. For surviving mutants, a shortest word distinguishing them from the DFA is reported.
+    pub fn evaluate_test_suite(&self, test_suite: &[&str]) -> MutationReport {
+        let mut killed = Vec::new();
+        let mut surviving = Vec::new();
+        for (mutation, mutant) in self.generate_mutants() {
+            if test_suite.iter().any(|word| mutant.check(word).0 != self.check(word).0) {
+                killed.push(mutation);
+            } else {
+                surviving.push(SurvivingMutant { mutation, distinguishing_word: self.find_distinguishing_word(&mutant) });
+            }
+        }
+        MutationReport { killed, surviving }
+    }
+
+    /// Finds a shortest word that is accepted by exactly one of the two DFAs, preferring alphabetically smaller words.
+    /// Uses the breath first algorithm on pairs of states, where None stands for the implicit error state.
+    pub(crate) fn find_distinguishing_word(&self, other: &Dfa) -> Option<String> {
+        let mut first = self.clone();
+        first.materialize_missing_transitions();
+        let mut second = other.clone();
+        second.materialize_missing_transitions();
+        let alphabet = first.get_alphabet().union(&second.get_alphabet()).cloned().sorted().collect_vec();
+        let start = (Some(first.start_state.clone()), Some(second.start_state.clone()));
+        let mut words: HashMap<(Option<String>, Option<String>), String> = HashMap::new();
+        let mut pairs_to_visit = VecDeque::new();
+        words.insert(start.clone(), String::new());
+        pairs_to_visit.push_back(start);
+        while let Some((first_state, second_state)) = pairs_to_visit.pop_front() {
+            let word = words[&(first_state.clone(), second_state.clone())].clone();
+            let is_first_accepting = first_state.as_ref().map_or(false, |state| first.accept_states.contains(state));
+            let is_second_accepting = second_state.as_ref().map_or(false, |state| second.accept_states.contains(state));
+            if is_first_accepting != is_second_accepting {
+                return Some(word);
+            }
+            for input in &alphabet {
+                let next_pair = (
+                    first_state.as_ref().and_then(|state| first.get_transition(state, input)).map(|transition| transition.next_state.clone()),
+                    second_state.as_ref().and_then(|state| second.get_transition(state, input)).map(|transition| transition.next_state.clone()),
+                );
+                if !words.contains_key(&next_pair) {
+                    words.insert(next_pair.clone(), format!("{}{}", word, input));
+                    pairs_to_visit.push_back(next_pair);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod mutation_tests {
+    use crate::{Dfa, Mutation};
+
+    #[test]
+    fn test_evaluate_test_suite() {
+        // Accepts words over 'a' of even length.
+        let dfa = Dfa::from_table(&["even", "odd"], &['a'], &[&[Some(1)], &[Some(0)]], 0, &[0]).unwrap();
+        assert_eq!(dfa.generate_mutants().len(), 6);
+        let weak_report = dfa.evaluate_test_suite(&[""]);
+        let surviving_mutant = weak_report.surviving.iter()
+            .find(|mutant| mutant.mutation == Mutation::FlipAcceptance { state: "odd".to_string() })
+            .unwrap();
+        assert_eq!(surviving_mutant.distinguishing_word, Some("a".to_string()));
+        let strong_report = dfa.evaluate_test_suite(&["", "a", "aa"]);
+        assert!(strong_report.surviving.is_empty());
+        assert_eq!(strong_report.get_mutation_score(), 1.0);
+        assert!(weak_report.get_mutation_score() < 1.0);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// Why a pair of states of a `NerodeTable` is distinguishable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NerodeMark {
+    /// The round of the table-filling algorithm in which the pair was marked, starting at 0 for pairs of an accept and a non-accept state.
+    pub round: usize,
+    /// A shortest suffix accepted when starting in exactly one of the states. Its length equals the round.
+    pub witness: String,
+}
+
+/// The table of the [table-filling algorithm](https://en.wikipedia.org/wiki/DFA_minimization) based on the Myhill–Nerode theorem,
+/// as computed by `Dfa::nerode_table`. Pairs of states that remain unmarked are equivalent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NerodeTable {
+    /// The states of the DFA, sorted by name.
+    states: Vec<String>,
+    /// The marked pairs, keyed by the two states in the order of `states`.
+    marks: BTreeMap<(String, String), NerodeMark>,
+}
+
+impl NerodeTable {
+    /// Returns the states in the order of the rows and columns of the table.
+    pub fn get_states(&self) -> &[String] {
+        &self.states
+    }
+
+    /// Returns why the two states are distinguishable, or None if they are equivalent. The order of the states does not matter.
+    pub fn get_mark(&self, state_a: &str, state_b: &str) -> Option<&NerodeMark> {
+        let (state_a, state_b) = match state_a <= state_b {
+            true => (state_a, state_b),
+            false => (state_b, state_a),
+        };
+        self.marks.get(&(state_a.to_string(), state_b.to_string()))
+    }
+
+    pub fn is_distinguishable(&self, state_a: &str, state_b: &str) -> bool {
+        self.get_mark(state_a, state_b).is_some()
+    }
+
+    /// Returns all marked pairs with their marks, sorted by state names.
+    pub fn get_marks(&self) -> &BTreeMap<(String, String), NerodeMark> {
+        &self.marks
+    }
+}
+
+/// Renders the lower triangle of the table in Markdown, the way it is drawn by hand: each marked cell shows the witnessing suffix,
+/// with ε for the empty word, and cells of equivalent states stay empty.
+impl fmt::Display for NerodeTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = &self.states[..self.states.len().saturating_sub(1)];
+        writeln!(f, "| |{}", columns.iter().map(|state| format!(" {} |", state)).join(""))?;
+        writeln!(f, "|---|{}", columns.iter().map(|_| "---|").join(""))?;
+        for (row_index, row_state) in self.states.iter().enumerate().skip(1) {
+            let cells = columns.iter().enumerate()
+                .map(|(column_index, column_state)| {
+                    let witness = match (column_index < row_index, self.get_mark(row_state, column_state)) {
+                        (true, Some(mark)) if mark.witness.is_empty() => "ε",
+                        (true, Some(mark)) => mark.witness.as_str(),
+                        _ => "",
+                    };
+                    format!(" {} |", witness)
+                })
+                .join("");
+            writeln!(f, "| {} |{}", row_state, cells)?;
+        }
+        Ok(())
+    }
+}
+
+impl Dfa {
+    /// Fills the table of pairs of states with the table-filling algorithm. In round 0, all pairs of an accept state and another state are marked,
+    /// distinguished by the empty word. In each following round, a pair is marked if some symbol leads it to a pair marked in an earlier round,
+    /// and the witness is that symbol followed by the witness of the other pair. Symbols are tried in alphabetical order, so the witnesses are
+    /// the alphabetically smallest among the shortest ones. Missing transitions lead to the implicit error state, which is not part of the table.
+    /// Accept tags are ignored.
+    pub fn nerode_table(&self) -> NerodeTable {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let alphabet = dfa.get_alphabet().into_iter().sorted().collect_vec();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        // The implicit error state is represented by None and compared like the other states. It comes first,
+        // so that pairs are ordered the same way whether they come from the combinations or from following transitions.
+        let all_states = vec![None].into_iter().chain(states.iter().map(Some)).collect_vec();
+        let is_accepting = |state: Option<&String>| state.map_or(false, |state| dfa.accept_states.contains(state));
+        let mut marks: BTreeMap<(Option<&String>, Option<&String>), NerodeMark> = all_states.iter().cloned()
+            .tuple_combinations()
+            .filter(|(state_a, state_b)| is_accepting(*state_a) != is_accepting(*state_b))
+            .map(|pair| (pair, NerodeMark { round: 0, witness: String::new() }))
+            .collect();
+        for round in 1.. {
+            let new_marks = all_states.iter().cloned()
+                .tuple_combinations()
+                .filter(|pair| !marks.contains_key(pair))
+                .filter_map(|(state_a, state_b)| {
+                    alphabet.iter().find_map(|input| {
+                        let next_state_a = state_a.and_then(|state| dfa.get_transition(state, input)).map(|transition| &transition.next_state);
+                        let next_state_b = state_b.and_then(|state| dfa.get_transition(state, input)).map(|transition| &transition.next_state);
+                        let next_pair = match next_state_a <= next_state_b {
+                            true => (next_state_a, next_state_b),
+                            false => (next_state_b, next_state_a),
+                        };
+                        marks.get(&next_pair).map(|mark| NerodeMark { round, witness: format!("{}{}", input, mark.witness) })
+                    })
+                    .map(|mark| ((state_a, state_b), mark))
+                })
+                .collect_vec();
+            if new_marks.is_empty() {
+                break;
+            }
+            marks.extend(new_marks);
+        }
+        let marks = marks.into_iter()
+            .filter_map(|((state_a, state_b), mark)| Some(((state_a?.clone(), state_b?.clone()), mark)))
+            .collect();
+        NerodeTable { states, marks }
+    }
+}
+
+#[cfg(test)]
+mod nerode_table_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_nerode_table() {
+        // Accepts words over 'a' and 'b' ending with "ab". q3 is a redundant copy of q0.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3"],
+            &['a', 'b'],
+            &[&[Some(1), Some(3)], &[Some(1), Some(2)], &[Some(1), Some(0)], &[Some(1), Some(0)]],
+            0,
+            &[2],
+        ).unwrap();
+        let table = dfa.nerode_table();
+        assert!(!table.is_distinguishable("q3", "q0"));
+        assert_eq!(table.get_mark("q2", "q0").unwrap().witness, "");
+        let mark = table.get_mark("q0", "q1").unwrap();
+        assert_eq!((mark.round, mark.witness.as_str()), (1, "b"));
+        assert_eq!(table.get_mark("q1", "q3").unwrap().witness, "b");
+        assert_eq!(
+            table.to_string(),
+            "| | q0 | q1 | q2 |\n|---|---|---|---|\n| q1 | b |  |  |\n| q2 | ε | ε |  |\n| q3 |  | b | ε |\n",
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::{BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Transition;
+
+/// # [Nondeterministic finite acceptor](https://en.wikipedia.org/wiki/Nondeterministic_finite_automaton)
+/// Unlike in a DFA, a state may have several transitions for the same input. An input is accepted if at least
+/// one of the possible runs ends in an accept state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Nfa {
+    pub(crate) name: String,
+    pub(crate) start_state: String,
+    pub(crate) accept_states: HashSet<String>,
+    pub(crate) transitions: Vec<Transition>,
+}
+
+impl Nfa {
+    /// Creates an NFA without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> Nfa {
+        Nfa {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Creates an NFA from its parts, as read from a serialized automaton.
+    pub(crate) fn from_parts(name: String, start_state: String, accept_states: HashSet<String>, transitions: Vec<Transition>) -> Nfa {
+        Nfa { name, start_state, accept_states, transitions }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.transitions.push(Transition { state: state.to_string(), input, next_state: next_state.to_string() });
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        self.transitions.iter().flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()]).collect()
+    }
+
+    /// Checks whether a certain input is accepted by the NFA. Instead of trying out every run on its own,
+    /// the set of all states the NFA could be in is tracked while processing the input.
+    pub fn check(&self, input: &str) -> bool {
+        let mut current_states: BTreeSet<&str> = BTreeSet::new();
+        current_states.insert(&self.start_state);
+        for char in input.chars() {
+            current_states = self.transitions.iter()
+                .filter(|transition| transition.input == char && current_states.contains(transition.state.as_str()))
+                .map(|transition| transition.next_state.as_str())
+                .collect();
+        }
+        current_states.iter().any(|state| self.accept_states.contains(*state))
+    }
+}
+
+#[cfg(test)]
+mod nfa_tests {
+    use crate::Nfa;
+
+    #[test]
+    fn test_check() {
+        // Accepts all words over 'a' and 'b' whose second to last symbol is 'a'.
+        let mut nfa = Nfa::new("second to last is a", "q0");
+        nfa.add_transition("q0", 'a', "q0");
+        nfa.add_transition("q0", 'b', "q0");
+        nfa.add_transition("q0", 'a', "q1");
+        nfa.add_transition("q1", 'a', "q2");
+        nfa.add_transition("q1", 'b', "q2");
+        nfa.add_accept_state("q2");
+        assert!(nfa.check("bbab"));
+        assert!(nfa.check("aa"));
+        assert!(!nfa.check("abba"));
+        assert!(!nfa.check("a"));
+    }
+}
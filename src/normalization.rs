@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{find_nondeterministic_transition, Dfa};
+
+/// Describes why the transition labels of a DFA cannot be normalized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelNormalizationError {
+    /// The normalized form of the symbol consists of several characters, so it cannot label a transition, like the Devanagari letter qa.
+    NotSingleSymbol(char),
+    /// Two transitions of the state read symbols with the same normalized form, like the Kelvin sign and 'K',
+    /// but lead to different states, so the normalized DFA would be nondeterministic.
+    Nondeterministic { state: String, input: char },
+}
+
+impl fmt::Display for LabelNormalizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelNormalizationError::NotSingleSymbol(symbol) => write!(f, "the normalized form of {} consists of several characters", symbol),
+            LabelNormalizationError::Nondeterministic { state, input } => {
+                write!(f, "the state {} has transitions to different states for symbols normalized to {}", state, input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LabelNormalizationError {}
+
+/// Brings the text into [Normalization Form C](https://unicode.org/reports/tr15/), so that canonically equivalent texts
+/// like "é" written as one precomposed character and "e" followed by a combining acute accent become identical.
+pub fn nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// Returns the normalized form of the symbol, if it is a single character.
+fn normalize_symbol(symbol: char) -> Result<char, LabelNormalizationError> {
+    let mut normalized = symbol.to_string().nfc().collect::<Vec<_>>();
+    match normalized.len() {
+        1 => Ok(normalized.remove(0)),
+        _ => Err(LabelNormalizationError::NotSingleSymbol(symbol)),
+    }
+}
+
+impl Dfa {
+    /// Works like `check` but normalizes the input with `nfc` first, so that canonically equivalent inputs are treated identically.
+    /// The transitions should read normalized symbols, too, see `normalize_transition_labels`.
+    pub fn check_normalized(&self, input: &str) -> (bool, Vec<String>) {
+        self.check(&nfc(input))
+    }
+
+    /// Replaces the input symbols of all transitions and of the declared alphabet by their normalized forms. This only changes
+    /// symbols like the Kelvin sign, whose normalized form is a different single character. Transitions that become identical
+    /// are merged. Returns whether any symbol changed, and leaves the DFA unchanged if normalizing fails.
+    pub fn normalize_transition_labels(&mut self) -> Result<bool, LabelNormalizationError> {
+        let mut transitions = self.transitions.clone();
+        for transition in &mut transitions {
+            transition.input = normalize_symbol(transition.input)?;
+        }
+        if let Some(transition) = find_nondeterministic_transition(&transitions) {
+            return Err(LabelNormalizationError::Nondeterministic { state: transition.state.clone(), input: transition.input });
+        }
+        let alphabet = match &self.alphabet {
+            Some(alphabet) => Some(alphabet.iter().map(|symbol| normalize_symbol(*symbol)).collect::<Result<HashSet<_>, _>>()?),
+            None => None,
+        };
+        let has_changed = transitions != self.transitions;
+        let mut seen_transitions = HashSet::new();
+        transitions.retain(|transition| seen_transitions.insert(transition.clone()));
+        self.transitions = transitions;
+        self.alphabet = alphabet;
+        Ok(has_changed)
+    }
+}
+
+#[cfg(test)]
+mod normalization_tests {
+    use crate::{nfc, Dfa, LabelNormalizationError};
+
+    #[test]
+    fn test_nfc() {
+        assert_eq!(nfc("Cafe\u{301}"), "Caf\u{E9}");
+        // The dot below has a lower combining class than the circumflex, so both orders compose to the same character.
+        assert_eq!(nfc("e\u{302}\u{323}"), "\u{1EC7}");
+        assert_eq!(nfc("e\u{323}\u{302}"), "\u{1EC7}");
+        assert_eq!(nfc("\u{212B}"), "\u{C5}", "the Angstrom sign should be replaced by the letter A with ring above");
+        assert_eq!(nfc("x\u{301}"), "x\u{301}", "there is no precomposed x with acute accent");
+        assert_eq!(nfc("\u{3B1}\u{301}"), "\u{3AC}", "Greek letters should be composed, too");
+        assert_eq!(nfc("\u{1100}\u{1161}"), "\u{AC00}", "Hangul jamo should be composed into a syllable");
+    }
+
+    #[test]
+    fn test_check_normalized() {
+        let mut dfa = Dfa::from_table(&["q0", "q1"], &['\u{212A}', '\u{E9}'], &[&[Some(1), Some(1)], &[None, None]], 0, &[1]).unwrap();
+        assert!(!dfa.check_normalized("K").0);
+        assert_eq!(dfa.normalize_transition_labels(), Ok(true));
+        assert!(dfa.check_normalized("K").0);
+        assert!(dfa.check_normalized("e\u{301}").0);
+        assert!(dfa.check_normalized("\u{E9}").0);
+        let mut dfa = Dfa::from_table(&["q0", "q1"], &['\u{212A}', 'K'], &[&[Some(1), Some(0)], &[None, None]], 0, &[1]).unwrap();
+        assert_eq!(dfa.normalize_transition_labels(), Err(LabelNormalizationError::Nondeterministic { state: "q0".to_string(), input: 'K' }));
+        assert!(dfa.check("\u{212A}").0, "the DFA should be left unchanged");
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::table::escape_markdown;
+use crate::Dfa;
+
+/// The observation table of [Angluin's L* algorithm](https://en.wikipedia.org/wiki/Induction_of_regular_languages#Learning_from_queries),
+/// as computed by `Dfa::observation_table`. Its rows are labelled by prefixes and its columns by suffixes, and each cell tells whether
+/// the concatenation of both is accepted. The short prefixes stand for the states of the hypothesis, and their extensions by one symbol,
+/// the long prefixes, for its transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservationTable {
+    short_prefixes: Vec<String>,
+    long_prefixes: Vec<String>,
+    suffixes: Vec<String>,
+    /// The row of every short and long prefix, with one entry per suffix.
+    rows: BTreeMap<String, Vec<bool>>,
+}
+
+impl ObservationTable {
+    /// Returns the short prefixes in the order of their rows.
+    pub fn get_short_prefixes(&self) -> &[String] {
+        &self.short_prefixes
+    }
+
+    /// Returns the extensions of the short prefixes by one symbol that are not short prefixes themselves, in the order of their rows.
+    pub fn get_long_prefixes(&self) -> &[String] {
+        &self.long_prefixes
+    }
+
+    /// Returns the suffixes in the order of the columns.
+    pub fn get_suffixes(&self) -> &[String] {
+        &self.suffixes
+    }
+
+    /// Returns the row of the short or long prefix, or None if the table has no row for it.
+    pub fn get_row(&self, prefix: &str) -> Option<&[bool]> {
+        self.rows.get(prefix).map(|row| row.as_slice())
+    }
+
+    /// Returns the first long prefix whose row differs from the rows of all short prefixes, or None if the table is closed.
+    /// L* makes such a prefix short, as it leads to a state the hypothesis does not have yet.
+    pub fn find_unclosed_prefix(&self) -> Option<&str> {
+        let short_rows: HashSet<&Vec<bool>> = self.short_prefixes.iter().map(|prefix| &self.rows[prefix]).collect();
+        self.long_prefixes.iter()
+            .find(|prefix| !short_rows.contains(&self.rows[*prefix]))
+            .map(|prefix| prefix.as_str())
+    }
+}
+
+/// Renders the table in Markdown the way it is drawn in papers and courses: the rows of the short prefixes come first,
+/// followed by the rows of the long prefixes, which are marked with a dot. Accepted cells show 1, others 0, and ε stands for the empty word.
+impl fmt::Display for ObservationTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = |word: &str| match word.is_empty() {
+            true => "ε".to_string(),
+            false => escape_markdown(word),
+        };
+        writeln!(f, "| |{}", self.suffixes.iter().map(|suffix| format!(" {} |", label(suffix))).join(""))?;
+        writeln!(f, "|---|{}", self.suffixes.iter().map(|_| "---|").join(""))?;
+        let short_rows = self.short_prefixes.iter().map(|prefix| (label(prefix), prefix));
+        let long_rows = self.long_prefixes.iter().map(|prefix| (format!("· {}", label(prefix)), prefix));
+        for (row_label, prefix) in short_rows.chain(long_rows) {
+            let cells = self.rows[prefix].iter().map(|is_accepted| format!(" {} |", *is_accepted as u8)).join("");
+            writeln!(f, "| {} |{}", row_label, cells)?;
+        }
+        Ok(())
+    }
+}
+
+impl Dfa {
+    /// Fills the observation table for the short prefixes and suffixes, using this DFA to answer the membership queries of L*.
+    /// This shows the table of a round of L* learning the language of the DFA, given the prefixes and suffixes of that round.
+    /// Duplicate prefixes and suffixes are ignored, and the long prefixes are ordered by their short prefix and then alphabetically by symbol.
+    pub fn observation_table(&self, short_prefixes: &[&str], suffixes: &[&str]) -> ObservationTable {
+        let alphabet = self.get_alphabet().into_iter().sorted().collect_vec();
+        let short_prefixes = short_prefixes.iter().map(|prefix| prefix.to_string()).unique().collect_vec();
+        let suffixes = suffixes.iter().map(|suffix| suffix.to_string()).unique().collect_vec();
+        let long_prefixes = short_prefixes.iter()
+            .cartesian_product(&alphabet)
+            .map(|(prefix, symbol)| format!("{}{}", prefix, symbol))
+            .filter(|prefix| !short_prefixes.contains(prefix))
+            .unique()
+            .collect_vec();
+        let rows = short_prefixes.iter().chain(&long_prefixes)
+            .map(|prefix| (prefix.clone(), suffixes.iter().map(|suffix| self.check(&format!("{}{}", prefix, suffix)).0).collect()))
+            .collect();
+        ObservationTable { short_prefixes, long_prefixes, suffixes, rows }
+    }
+}
+
+#[cfg(test)]
+mod observation_table_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_observation_table() {
+        // Accepts words over 'a' and 'b' ending with "ab".
+        let dfa = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(1), Some(2)], &[Some(1), Some(0)]], 0, &[2]).unwrap();
+        let table = dfa.observation_table(&[""], &[""]);
+        assert_eq!(table.get_long_prefixes(), ["a", "b"]);
+        assert_eq!(table.find_unclosed_prefix(), None, "the first round cannot tell any prefixes apart");
+        let table = dfa.observation_table(&["", "a"], &["", "b"]);
+        assert_eq!(table.get_row("a"), Some(&[false, true][..]));
+        assert_eq!(table.find_unclosed_prefix(), Some("ab"));
+        assert_eq!(
+            table.to_string(),
+            "| | ε | b |\n|---|---|---|\n| ε | 0 | 0 |\n| a | 0 | 1 |\n| · b | 0 | 0 |\n| · aa | 0 | 1 |\n| · ab | 1 | 0 |\n",
+        );
+    }
+}
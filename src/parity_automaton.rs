@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ParityGame, Player, Transition};
+
+/// # [Parity automaton](https://en.wikipedia.org/wiki/Parity_automaton)
+/// A deterministic automaton that reads infinite words. Every state has a priority, and a word is accepted if the highest priority
+/// the run visits infinitely often is even. States without a priority have priority 0.
+/// Like in a DFA, a missing transition leads into an implicit error state, so the word is rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParityAutomaton {
+    name: String,
+    start_state: String,
+    priorities: HashMap<String, u32>,
+    transitions: Vec<Transition>,
+}
+
+impl ParityAutomaton {
+    /// Creates a parity automaton without transitions, whose start state has priority 0.
+    pub fn new(name: &str, start_state: &str) -> ParityAutomaton {
+        ParityAutomaton {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            priorities: HashMap::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn set_priority(&mut self, state: &str, priority: u32) {
+        self.priorities.insert(state.to_string(), priority);
+    }
+
+    pub fn get_priority(&self, state: &str) -> u32 {
+        self.priorities.get(state).cloned().unwrap_or(0)
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, next_state: &str) {
+        self.transitions.push(Transition { state: state.to_string(), input, next_state: next_state.to_string() });
+    }
+
+    /// Turns the automaton into a parity game with the same priorities, in which Even chooses the next state.
+    /// Even wins from a state if the automaton has an accepting run from there, and gets stuck where the run ends.
+    pub fn to_parity_game(&self) -> ParityGame {
+        let mut states: HashSet<String> = self.transitions.iter()
+            .flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()])
+            .chain(self.priorities.keys().cloned())
+            .collect();
+        states.insert(self.start_state.clone());
+        let vertices = states.into_iter()
+            .map(|state| {
+                let priority = self.get_priority(&state);
+                (state, (Player::Even, priority))
+            })
+            .collect();
+        let mut edges: HashMap<String, HashSet<String>> = HashMap::new();
+        for transition in &self.transitions {
+            edges.entry(transition.state.clone()).or_default().insert(transition.next_state.clone());
+        }
+        ParityGame::from_parts(vertices, edges)
+    }
+
+    /// Checks whether the automaton accepts no infinite word at all, which is the case if Even loses the game from the start state.
+    pub fn is_empty(&self) -> bool {
+        self.to_parity_game().solve()[&self.start_state] == Player::Odd
+    }
+}
+
+#[cfg(test)]
+mod parity_automaton_tests {
+    use crate::ParityAutomaton;
+
+    #[test]
+    fn test_is_empty() {
+        // Alternates between q0 and q1 on 'a', and can stay in q2 on 'b' after reaching it from q1.
+        let mut automaton = ParityAutomaton::new("alternation", "q0");
+        automaton.add_transition("q0", 'a', "q1");
+        automaton.add_transition("q1", 'a', "q0");
+        automaton.add_transition("q1", 'b', "q2");
+        automaton.set_priority("q0", 2);
+        automaton.set_priority("q1", 3);
+        automaton.set_priority("q2", 5);
+        assert!(automaton.is_empty(), "the highest priority of both cycles is odd");
+        automaton.add_transition("q2", 'b', "q2");
+        automaton.set_priority("q2", 4);
+        assert!(!automaton.is_empty(), "(ab)b^ω visits priority 4 infinitely often");
+        automaton.set_priority("q1", 4);
+        automaton.set_priority("q2", 1);
+        assert!(!automaton.is_empty(), "(aa)^ω visits priority 4 infinitely often");
+    }
+}
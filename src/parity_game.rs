@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use itertools::Itertools;
+
+/// One of the two players of a parity game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Player {
+    /// Wins if the highest priority that is visited infinitely often is even.
+    Even,
+    /// Wins if the highest priority that is visited infinitely often is odd.
+    Odd,
+}
+
+impl Player {
+    pub fn get_opponent(self) -> Player {
+        match self {
+            Player::Even => Player::Odd,
+            Player::Odd => Player::Even,
+        }
+    }
+
+    fn from_priority(priority: u32) -> Player {
+        match priority % 2 {
+            0 => Player::Even,
+            _ => Player::Odd,
+        }
+    }
+}
+
+/// Describes why an edge cannot be added to a parity game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParityGameError {
+    UnknownVertex(String),
+}
+
+impl fmt::Display for ParityGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParityGameError::UnknownVertex(vertex) => write!(f, "the vertex {} does not exist", vertex),
+        }
+    }
+}
+
+impl std::error::Error for ParityGameError {}
+
+/// # [Parity game](https://en.wikipedia.org/wiki/Parity_game)
+/// Two players move a token along the edges of the arena. The owner of a vertex chooses the edge along which the token
+/// leaves it. Infinite plays are won according to the priorities of the vertices (see `Player`). A player who cannot move loses.
+#[derive(Debug, Clone, Default)]
+pub struct ParityGame {
+    vertices: HashMap<String, (Player, u32)>,
+    edges: HashMap<String, HashSet<String>>,
+}
+
+impl ParityGame {
+    pub fn new() -> ParityGame {
+        ParityGame::default()
+    }
+
+    /// Creates a game from its vertices and edges, which must only connect these vertices.
+    pub(crate) fn from_parts(vertices: HashMap<String, (Player, u32)>, edges: HashMap<String, HashSet<String>>) -> ParityGame {
+        ParityGame { vertices, edges }
+    }
+
+    pub fn add_vertex(&mut self, vertex: &str, owner: Player, priority: u32) {
+        self.vertices.insert(vertex.to_string(), (owner, priority));
+    }
+
+    /// Adds an edge between two vertices, which must have been added before.
+    pub fn add_edge(&mut self, vertex: &str, next_vertex: &str) -> Result<(), ParityGameError> {
+        if let Some(unknown_vertex) = [vertex, next_vertex].iter().find(|vertex| !self.vertices.contains_key(**vertex)) {
+            return Err(ParityGameError::UnknownVertex(unknown_vertex.to_string()));
+        }
+        self.edges.entry(vertex.to_string()).or_default().insert(next_vertex.to_string());
+        Ok(())
+    }
+
+    /// Computes which player wins from which vertex, using [Zielonka's recursive algorithm](https://en.wikipedia.org/wiki/Parity_game#Recursive_algorithm_for_solving_parity_games).
+    /// The algorithm is exponential in the number of priorities, but simple and fast in practice.
+    /// Players that get stuck lose, so vertices from which the opponent can force the play into such a dead end are decided first.
+    pub fn solve(&self) -> HashMap<String, Player> {
+        let all_vertices: HashSet<String> = self.vertices.keys().cloned().collect();
+        let mut winners = HashMap::new();
+        let mut remaining_vertices = all_vertices;
+        for player in &[Player::Even, Player::Odd] {
+            let stuck_opponent_vertices = remaining_vertices.iter()
+                .filter(|vertex| self.vertices[*vertex].0 == player.get_opponent() && self.get_successors(vertex, &remaining_vertices).next().is_none())
+                .cloned()
+                .collect();
+            let won_vertices = self.get_attractor(*player, &stuck_opponent_vertices, &remaining_vertices);
+            remaining_vertices = remaining_vertices.difference(&won_vertices).cloned().collect();
+            winners.extend(won_vertices.into_iter().map(|vertex| (vertex, *player)));
+        }
+        let (even_region, odd_region) = self.solve_recursively(&remaining_vertices);
+        winners.extend(even_region.into_iter().map(|vertex| (vertex, Player::Even)));
+        winners.extend(odd_region.into_iter().map(|vertex| (vertex, Player::Odd)));
+        winners
+    }
+
+    /// Solves the subgame on the vertices, in which no player gets stuck. Returns the winning regions of Even and Odd.
+    fn solve_recursively(&self, vertices: &HashSet<String>) -> (HashSet<String>, HashSet<String>) {
+        let max_priority = match vertices.iter().map(|vertex| self.vertices[vertex].1).max() {
+            Some(priority) => priority,
+            None => return (HashSet::new(), HashSet::new()),
+        };
+        let player = Player::from_priority(max_priority);
+        let max_priority_vertices = vertices.iter().filter(|vertex| self.vertices[*vertex].1 == max_priority).cloned().collect();
+        // If the player can visit the highest priority infinitely often, the player wins.
+        // Otherwise, the opponent wins where the opponent also wins without these vertices.
+        let attractor = self.get_attractor(player, &max_priority_vertices, vertices);
+        let subgame = vertices.difference(&attractor).cloned().collect();
+        let (_, opponent_region) = self.solve_for(player, &subgame);
+        if opponent_region.is_empty() {
+            return ParityGame::order_regions(player, vertices.clone(), HashSet::new());
+        }
+        let opponent_attractor = self.get_attractor(player.get_opponent(), &opponent_region, vertices);
+        let subgame = vertices.difference(&opponent_attractor).cloned().collect();
+        let (player_region, opponent_region) = self.solve_for(player, &subgame);
+        ParityGame::order_regions(player, player_region, opponent_region.union(&opponent_attractor).cloned().collect())
+    }
+
+    /// Solves the subgame and returns the winning regions of the player and its opponent.
+    fn solve_for(&self, player: Player, vertices: &HashSet<String>) -> (HashSet<String>, HashSet<String>) {
+        let (even_region, odd_region) = self.solve_recursively(vertices);
+        match player {
+            Player::Even => (even_region, odd_region),
+            Player::Odd => (odd_region, even_region),
+        }
+    }
+
+    fn order_regions(player: Player, player_region: HashSet<String>, opponent_region: HashSet<String>) -> (HashSet<String>, HashSet<String>) {
+        match player {
+            Player::Even => (player_region, opponent_region),
+            Player::Odd => (opponent_region, player_region),
+        }
+    }
+
+    /// Returns the vertices from which the player can force the play into the target, without leaving the given vertices.
+    /// A vertex is attracted if the player owns it and one successor is attracted, or the opponent owns it and all successors are.
+    fn get_attractor(&self, player: Player, target: &HashSet<String>, vertices: &HashSet<String>) -> HashSet<String> {
+        let mut attractor = target.clone();
+        loop {
+            let attracted_vertices = vertices.iter()
+                .filter(|vertex| !attractor.contains(*vertex))
+                .filter(|vertex| {
+                    let mut successors = self.get_successors(vertex, vertices);
+                    match self.vertices[*vertex].0 == player {
+                        true => successors.any(|successor| attractor.contains(successor)),
+                        false => successors.all(|successor| attractor.contains(successor)),
+                    }
+                })
+                .cloned()
+                .collect_vec();
+            if attracted_vertices.is_empty() {
+                return attractor;
+            }
+            attractor.extend(attracted_vertices);
+        }
+    }
+
+    fn get_successors<'a>(&'a self, vertex: &str, vertices: &'a HashSet<String>) -> impl Iterator<Item = &'a String> + 'a {
+        self.edges.get(vertex).into_iter().flatten().filter(move |successor| vertices.contains(*successor))
+    }
+}
+
+#[cfg(test)]
+mod parity_game_tests {
+    use crate::{BuchiAutomaton, ParityGame, ParityGameError, Player};
+
+    #[test]
+    fn test_solve() {
+        // Odd owns "a" and can either go to "b" (priority 2) or "c" (priority 3). From "b" Even can only return to "a",
+        // while Even owns "c" and can only loop. "d" is owned by Even and stuck, and "e" can only move to "b" or "d".
+        let mut game = ParityGame::new();
+        game.add_vertex("a", Player::Odd, 0);
+        game.add_vertex("b", Player::Even, 2);
+        game.add_vertex("c", Player::Even, 3);
+        game.add_vertex("d", Player::Even, 4);
+        game.add_vertex("e", Player::Even, 1);
+        game.add_edge("a", "b").unwrap();
+        game.add_edge("a", "c").unwrap();
+        game.add_edge("b", "a").unwrap();
+        game.add_edge("c", "c").unwrap();
+        game.add_edge("e", "b").unwrap();
+        game.add_edge("e", "d").unwrap();
+        assert_eq!(game.add_edge("a", "f"), Err(ParityGameError::UnknownVertex("f".to_string())));
+        let winners = game.solve();
+        assert_eq!(winners["a"], Player::Odd);
+        assert_eq!(winners["b"], Player::Odd);
+        assert_eq!(winners["c"], Player::Odd);
+        assert_eq!(winners["d"], Player::Odd);
+        assert_eq!(winners["e"], Player::Odd);
+        game.add_vertex("c", Player::Even, 4);
+        let winners = game.solve();
+        assert_eq!(winners["a"], Player::Even);
+        assert_eq!(winners["e"], Player::Even);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut automaton = BuchiAutomaton::new("a then b forever", "q0");
+        automaton.add_transition("q0", 'a', "q1");
+        automaton.add_transition("q1", 'b', "q1");
+        automaton.add_accept_state("q0");
+        assert!(automaton.is_empty());
+        automaton.add_accept_state("q1");
+        assert!(!automaton.is_empty());
+    }
+}
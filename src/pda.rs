@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pushdown_transducer::MAX_EXPLORED_CONFIGURATIONS;
+use crate::{Bounded, Fuel};
+
+/// A configuration of a pushdown automaton during the search consists of the current state, the position within the input and the stack (top at the end).
+type SearchConfiguration<'a> = (&'a str, usize, Vec<char>);
+
+/// The part of the configuration graph explored by the breath first search of a pushdown automaton.
+struct Exploration<'a> {
+    /// The discovered configurations in the order of their discovery.
+    configurations: Vec<SearchConfiguration<'a>>,
+    /// Maps the index of every discovered configuration except the initial one to the index of the configuration it was discovered from.
+    predecessors: HashMap<usize, usize>,
+    /// The explored steps between configurations, given by their indices, together with the transitions taken.
+    edges: Vec<(usize, usize, &'a PdaTransition)>,
+    /// The index of the first accepting configuration found, if any.
+    accepting_configuration: Option<usize>,
+    is_exhausted: bool,
+}
+
+impl Exploration<'_> {
+    /// Returns the indices of the configurations on the path from the initial configuration to the given one, along which it was discovered.
+    fn get_path(&self, index: usize) -> Vec<usize> {
+        let mut path = vec![index];
+        while let Some(predecessor) = self.predecessors.get(path.last().unwrap()) {
+            path.push(*predecessor);
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Describes to which next state a pushdown automaton may switch while reading an input symbol (or nothing, if `input` is None)
+/// and popping the top of the stack (or nothing, if `pop` is None). Afterwards, `push` is pushed onto the stack such that its first
+/// character ends up on top.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct PdaTransition {
+    pub(crate) state: String,
+    pub(crate) input: Option<char>,
+    pub(crate) pop: Option<char>,
+    pub(crate) push: String,
+    pub(crate) next_state: String,
+}
+
+impl PdaTransition {
+    /// Returns the position within the input and the stack after taking the transition, or None if the next symbol or the top of the stack do not fit.
+    pub(crate) fn take(&self, position: usize, stack: &[char], input: &[char]) -> Option<(usize, Vec<char>)> {
+        let next_position = match self.input {
+            None => position,
+            Some(symbol) if input.get(position) == Some(&symbol) => position + 1,
+            Some(_) => return None,
+        };
+        let mut next_stack = stack.to_vec();
+        if let Some(symbol) = self.pop {
+            if next_stack.pop() != Some(symbol) {
+                return None;
+            }
+        }
+        next_stack.extend(self.push.chars().rev());
+        Some((next_position, next_stack))
+    }
+}
+
+/// A snapshot of a run of a pushdown automaton, as it is written in textbooks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PdaConfiguration {
+    pub state: String,
+    /// The part of the input that has not been read yet.
+    pub remaining_input: String,
+    /// The content of the stack with its top first.
+    pub stack: String,
+}
+
+impl PdaConfiguration {
+    /// Creates the configuration at the position within the input, given the stack with its top at the end.
+    pub(crate) fn new(state: &str, position: usize, stack: &[char], input: &[char]) -> PdaConfiguration {
+        PdaConfiguration {
+            state: state.to_string(),
+            remaining_input: input[position..].iter().collect(),
+            stack: stack.iter().rev().collect(),
+        }
+    }
+}
+
+impl fmt::Display for PdaConfiguration {
+    /// Renders the configuration like "(q0, ab, AZ)", using ε for an empty remaining input or stack.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_epsilon = |text: &str| if text.is_empty() { "ε".to_string() } else { text.to_string() };
+        write!(f, "({}, {}, {})", self.state, or_epsilon(&self.remaining_input), or_epsilon(&self.stack))
+    }
+}
+
+/// # [Pushdown automaton](https://en.wikipedia.org/wiki/Pushdown_automaton)
+/// A nondeterministic automaton with a stack, which recognizes exactly the context-free languages.
+/// The stack initially contains only the start stack symbol. An input is accepted if it is read completely
+/// and the automaton ends up in an accept state, no matter what is left on the stack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pda {
+    name: String,
+    start_state: String,
+    start_stack_symbol: char,
+    accept_states: HashSet<String>,
+    transitions: Vec<PdaTransition>,
+}
+
+impl Pda {
+    /// Creates a pushdown automaton without transitions and accept states.
+    pub fn new(name: &str, start_state: &str, start_stack_symbol: char) -> Pda {
+        Pda {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            start_stack_symbol,
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: Option<char>, pop: Option<char>, push: &str, next_state: &str) {
+        self.transitions.push(PdaTransition {
+            state: state.to_string(),
+            input,
+            pop,
+            push: push.to_string(),
+            next_state: next_state.to_string(),
+        });
+    }
+
+    /// Returns all states mentioned as start state, accept states or in transitions.
+    pub fn get_all_states(&self) -> HashSet<String> {
+        let mut states: HashSet<String> = self.transitions.iter()
+            .flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()])
+            .collect();
+        states.insert(self.start_state.clone());
+        states.extend(self.accept_states.iter().cloned());
+        states
+    }
+
+    /// Returns the start stack symbol and all symbols that transitions pop or push.
+    pub fn get_stack_alphabet(&self) -> HashSet<char> {
+        let mut stack_alphabet: HashSet<char> = self.transitions.iter()
+            .flat_map(|transition| transition.pop.into_iter().chain(transition.push.chars()))
+            .collect();
+        stack_alphabet.insert(self.start_stack_symbol);
+        stack_alphabet
+    }
+
+    /// Checks whether the input is accepted and returns the configurations of an accepting run, starting with the initial one,
+    /// or None if the input is not accepted. As the automaton might be nondeterministic, all runs are explored in breath first order,
+    /// so the accepting run with the fewest steps is returned. Gives up and returns None after exploring `MAX_EXPLORED_CONFIGURATIONS` configurations.
+    pub fn check(&self, input: &str) -> Option<Vec<PdaConfiguration>> {
+        self.check_with_fuel(input, &mut Fuel::new(MAX_EXPLORED_CONFIGURATIONS)).done().flatten()
+    }
+
+    /// Checks the input like `check`, but explores at most as many configurations as there is fuel,
+    /// and tells apart rejected inputs from inputs for which the fuel ran out.
+    pub fn check_with_fuel(&self, input: &str, fuel: &mut Fuel) -> Bounded<Option<Vec<PdaConfiguration>>> {
+        let input: Vec<char> = input.chars().collect();
+        let exploration = self.explore(&input, fuel);
+        match exploration.accepting_configuration {
+            Some(accepting_configuration) => {
+                let run = exploration.get_path(accepting_configuration).into_iter()
+                    .map(|index| {
+                        let (state, position, stack) = &exploration.configurations[index];
+                        PdaConfiguration::new(state, *position, stack, &input)
+                    })
+                    .collect();
+                Bounded::Done(Some(run))
+            }
+            None if exploration.is_exhausted => Bounded::Exhausted,
+            None => Bounded::Done(None),
+        }
+    }
+
+    /// Renders the configurations explored while checking the input in the [DOT language](https://graphviz.org/doc/info/lang.html)
+    /// of Graphviz, which shows how the nondeterministic automaton searches for an accepting run. Configurations are labeled like "(q0, ab, AZ)"
+    /// (see `PdaConfiguration`), and edges with the input, the popped symbol and the pushed symbols of their transition, like "a, Z → AZ".
+    /// The accepting run that `check` would return is highlighted in red. Exploration stops when the fuel runs out, so the graph may be partial.
+    pub fn configuration_graph_to_dot(&self, input: &str, fuel: &mut Fuel) -> String {
+        let input: Vec<char> = input.chars().collect();
+        let exploration = self.explore(&input, fuel);
+        let accepting_path: HashSet<usize> = exploration.accepting_configuration
+            .map(|accepting_configuration| exploration.get_path(accepting_configuration).into_iter().collect())
+            .unwrap_or_default();
+        let mut dot = format!("digraph \"{}\" {{\n    node [shape=box];\n", self.name.replace('"', "\\\""));
+        for (index, (state, position, stack)) in exploration.configurations.iter().enumerate() {
+            let label = PdaConfiguration::new(state, *position, stack, &input).to_string();
+            let color = if accepting_path.contains(&index) { ", color=red" } else { "" };
+            let peripheries = if exploration.accepting_configuration == Some(index) { ", peripheries=2" } else { "" };
+            dot += &format!("    c{} [label=\"{}\"{}{}];\n", index, label.replace('"', "\\\""), color, peripheries);
+        }
+        let or_epsilon = |symbols: String| if symbols.is_empty() { String::from("ε") } else { symbols };
+        for (index, next_index, transition) in &exploration.edges {
+            let label = format!(
+                "{}, {} → {}",
+                or_epsilon(transition.input.into_iter().collect()),
+                or_epsilon(transition.pop.into_iter().collect()),
+                or_epsilon(transition.push.clone()),
+            );
+            let is_on_accepting_path = accepting_path.contains(next_index) && exploration.predecessors.get(next_index) == Some(index);
+            let color = if is_on_accepting_path { ", color=red" } else { "" };
+            dot += &format!("    c{} -> c{} [label=\"{}\"{}];\n", index, next_index, label.replace('"', "\\\""), color);
+        }
+        dot + "}\n"
+    }
+
+    /// Explores the configurations reachable on the input in breath first order until an accepting one is found,
+    /// all of them have been explored, or the fuel runs out. Exploring the successors of a configuration consumes one unit of fuel.
+    fn explore(&self, input: &[char], fuel: &mut Fuel) -> Exploration<'_> {
+        let start: SearchConfiguration = (&self.start_state, 0, vec![self.start_stack_symbol]);
+        let mut exploration = Exploration {
+            configurations: vec![start.clone()],
+            predecessors: HashMap::new(),
+            edges: Vec::new(),
+            accepting_configuration: None,
+            is_exhausted: false,
+        };
+        let mut indices: HashMap<SearchConfiguration, usize> = HashMap::new();
+        let mut configurations_to_visit = VecDeque::new();
+        indices.insert(start, 0);
+        configurations_to_visit.push_back(0);
+        while let Some(index) = configurations_to_visit.pop_front() {
+            let configuration = exploration.configurations[index].clone();
+            if configuration.1 == input.len() && self.accept_states.contains(configuration.0) {
+                exploration.accepting_configuration = Some(index);
+                break;
+            }
+            if !fuel.consume() {
+                exploration.is_exhausted = true;
+                break;
+            }
+            for (next_configuration, transition) in self.get_successors(&configuration, input) {
+                let next_index = match indices.get(&next_configuration) {
+                    Some(next_index) => *next_index,
+                    None => {
+                        let next_index = exploration.configurations.len();
+                        indices.insert(next_configuration.clone(), next_index);
+                        exploration.configurations.push(next_configuration);
+                        exploration.predecessors.insert(next_index, index);
+                        configurations_to_visit.push_back(next_index);
+                        next_index
+                    }
+                };
+                exploration.edges.push((index, next_index, transition));
+            }
+        }
+        exploration
+    }
+
+    /// Returns the configurations that can follow the given one, together with the transitions leading there.
+    fn get_successors<'a>(&'a self, (state, position, stack): &SearchConfiguration<'a>, input: &[char]) -> Vec<(SearchConfiguration<'a>, &'a PdaTransition)> {
+        self.transitions.iter()
+            .filter(|transition| transition.state == *state)
+            .filter_map(|transition| {
+                let (next_position, next_stack) = transition.take(*position, stack, input)?;
+                Some(((transition.next_state.as_str(), next_position, next_stack), transition))
+            })
+            .collect()
+    }
+}
+#[cfg(test)]
+mod pda_tests {
+    use crate::{Bounded, Fuel, Pda};
+
+    #[test]
+    fn test_check() {
+        // Accepts even-length palindromes over 'a' and 'b', guessing the middle of the word nondeterministically.
+        let mut pda = Pda::new("even palindromes", "push", 'Z');
+        for symbol in &['a', 'b'] {
+            pda.add_transition("push", Some(*symbol), None, &symbol.to_string(), "push");
+            pda.add_transition("pop", Some(*symbol), Some(*symbol), "", "pop");
+        }
+        pda.add_transition("push", None, None, "", "pop");
+        pda.add_transition("pop", None, Some('Z'), "Z", "accept");
+        pda.add_accept_state("accept");
+        let run = pda.check("abba").unwrap();
+        let run = run.iter().map(|configuration| configuration.to_string()).collect::<Vec<_>>();
+        assert_eq!(run, vec!["(push, abba, Z)", "(push, bba, aZ)", "(push, ba, baZ)", "(pop, ba, baZ)", "(pop, a, aZ)", "(pop, ε, Z)", "(accept, ε, Z)"]);
+        assert!(pda.check("").is_some());
+        assert!(pda.check("aba").is_none());
+        assert!(pda.check("abab").is_none());
+        assert_eq!(pda.get_stack_alphabet().len(), 3);
+        assert_eq!(pda.check_with_fuel("abba", &mut Fuel::new(2)), Bounded::Exhausted);
+        let dot = pda.configuration_graph_to_dot("ab", &mut Fuel::unlimited());
+        assert!(dot.contains("    c0 [label=\"(push, ab, Z)\"];\n"), "ab is rejected, so no run is highlighted");
+        let dot = pda.configuration_graph_to_dot("aa", &mut Fuel::unlimited());
+        assert!(dot.contains("    c0 [label=\"(push, aa, Z)\", color=red];\n"));
+        assert!(dot.contains("    c0 -> c1 [label=\"a, ε → a\", color=red];\n"));
+        assert!(dot.contains("[label=\"(accept, ε, Z)\", color=red, peripheries=2];"));
+    }
+}
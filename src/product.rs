@@ -0,0 +1,144 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{AcceptTag, Dfa, StateMetadata, Transition};
+
+impl Dfa {
+    /// Runs both DFAs in parallel. A word is accepted if `combine` returns true for the acceptance of both DFAs,
+    /// e.g. `|first, second| first && second` for the intersection. States are named "(p, q)", with "∅" standing for the implicit error
+    /// state of a DFA that has no transition. The states are found using the breath first algorithm. The pair "(∅, ∅)" is only kept
+    /// if `combine(false, false)` holds, e.g. for a complement, as it cannot reach an accept state otherwise.
+    ///
+    /// The pair of original states each product state came from is stored as its origin in the metadata (see `get_origin_trace`),
+    /// so that words can be traced back to both operands. Accept states of the product keep the accept tags of the accepting operand states,
+    /// merged by priority (see `AcceptTag::merge`) if both have one.
+    pub fn product<F: Fn(bool, bool) -> bool>(&self, other: &Dfa, combine: F) -> Dfa {
+        let mut first = self.clone();
+        first.materialize_missing_transitions();
+        let mut second = other.clone();
+        second.materialize_missing_transitions();
+        let origin = |pair: &(Option<&str>, Option<&str>)| vec![pair.0.unwrap_or("∅").to_string(), pair.1.unwrap_or("∅").to_string()];
+        let state_name = |pair: &(Option<&str>, Option<&str>)| format!("({})", origin(pair).join(", "));
+        let alphabet: HashSet<char> = first.get_alphabet().union(&second.get_alphabet()).cloned().collect();
+        let start = (Some(first.start_state.as_str()), Some(second.start_state.as_str()));
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        let mut accept_states = HashSet::new();
+        let mut transitions = Vec::new();
+        let mut state_metadata = HashMap::new();
+        let mut accept_tags = HashMap::new();
+        visited_pairs.insert(start);
+        pairs_to_visit.push_back(start);
+        while let Some(pair) = pairs_to_visit.pop_front() {
+            let is_first_accepting = pair.0.map_or(false, |state| first.accept_states.contains(state));
+            let is_second_accepting = pair.1.map_or(false, |state| second.accept_states.contains(state));
+            if combine(is_first_accepting, is_second_accepting) {
+                accept_states.insert(state_name(&pair));
+                let first_tag = pair.0.filter(|_| is_first_accepting).and_then(|state| first.accept_tags.get(state));
+                let second_tag = pair.1.filter(|_| is_second_accepting).and_then(|state| second.accept_tags.get(state));
+                let tag = match (first_tag, second_tag) {
+                    (Some(first_tag), Some(second_tag)) => Some(AcceptTag::merge(first_tag, second_tag)),
+                    (tag, None) | (None, tag) => tag.cloned(),
+                };
+                if let Some(tag) = tag {
+                    accept_tags.insert(state_name(&pair), tag);
+                }
+            }
+            state_metadata.insert(state_name(&pair), StateMetadata { origin: origin(&pair), ..StateMetadata::default() });
+            for input in alphabet.iter().sorted() {
+                let next_pair = (
+                    pair.0.and_then(|state| first.get_transition(state, input)).map(|transition| transition.next_state.as_str()),
+                    pair.1.and_then(|state| second.get_transition(state, input)).map(|transition| transition.next_state.as_str()),
+                );
+                if next_pair == (None, None) && !combine(false, false) {
+                    continue;
+                }
+                transitions.push(Transition { state: state_name(&pair), input: *input, next_state: state_name(&next_pair) });
+                if visited_pairs.insert(next_pair) {
+                    pairs_to_visit.push_back(next_pair);
+                }
+            }
+        }
+        Dfa {
+            alphabet: Some(alphabet),
+            state_metadata,
+            accept_tags,
+            ..Dfa::with_parts(format!("product of {} and {}", self.name, other.name), state_name(&start), accept_states, transitions)
+        }
+    }
+
+    /// Returns a DFA accepting the words accepted by both DFAs, using the product construction (see `product`).
+    /// States are named after the pairs of states of both operands, like "(p, q)", and a pair accepts if both states accept.
+    pub fn intersection(&self, other: &Dfa) -> Dfa {
+        let mut intersection = self.product(other, |first, second| first && second);
+        intersection.name = format!("intersection of {} and {}", self.name, other.name);
+        intersection
+    }
+
+    /// Returns a DFA accepting the words accepted by at least one of the DFAs, using the product construction (see `intersection`).
+    pub fn union(&self, other: &Dfa) -> Dfa {
+        let mut union = self.product(other, |first, second| first || second);
+        union.name = format!("union of {} and {}", self.name, other.name);
+        union
+    }
+
+    /// Returns a DFA accepting the words accepted by this DFA but not by the other one, using the product construction (see `intersection`).
+    /// Missing transitions of the other DFA lead to its implicit error state, which does not accept, so no completion is needed.
+    pub fn difference(&self, other: &Dfa) -> Dfa {
+        let mut difference = self.product(other, |first, second| first && !second);
+        difference.name = format!("difference of {} and {}", self.name, other.name);
+        difference
+    }
+
+    /// Checks the input and returns the origin of each traversed state (see `product` and `Nfa::determinize`),
+    /// for example to find out in which states both operands of a product were when a counterexample was rejected.
+    /// States without an origin are represented by an empty list.
+    pub fn get_origin_trace(&self, input: &str) -> Vec<Vec<String>> {
+        self.check(input).1.iter()
+            .map(|state| self.state_metadata.get(state).map(|metadata| metadata.origin.clone()).unwrap_or_default())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod product_tests {
+    use crate::{AcceptTag, Dfa};
+
+    #[test]
+    fn test_product() {
+        // Accepts words over 'a' and 'b' with an even number of 'a's.
+        let even_a = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap();
+        // Accepts "a" followed by any number of 'b's.
+        let a_b_star = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(1), None], &[None, Some(1)]], 0, &[1]).unwrap();
+        let intersection = even_a.intersection(&a_b_star);
+        assert!(!intersection.check("abb").0 && !intersection.check("bb").0 && !intersection.check("a").0);
+        assert!(intersection.get_all_states().contains("(even, q0)") && intersection.accept_states.is_empty());
+        let union = even_a.union(&a_b_star);
+        assert!(union.check("abb").0 && union.check("bb").0 && !union.check("abaa").0);
+        let difference = a_b_star.difference(&even_a);
+        assert!(difference.check("abb").0 && !difference.check("bb").0 && !difference.check("aa").0);
+        assert!(even_a.difference(&a_b_star).check("aa").0, "a_b_star has no transition for the second 'a'");
+        assert_eq!(
+            union.get_origin_trace("abaa"),
+            vec![vec!["even", "q0"], vec!["odd", "q1"], vec!["odd", "q1"], vec!["even", "∅"], vec!["odd", "∅"]],
+            "a_b_star rejects \"abaa\" because it has no transition for the second 'a'",
+        );
+        let neither = a_b_star.product(&a_b_star, |first, second| !first && !second);
+        assert!(neither.check("aa").0 && neither.check("ba").0 && !neither.check("ab").0, "words rejected by both operands should be accepted");
+    }
+
+    #[test]
+    fn test_union_keeps_accept_tags() {
+        // Classifies "if" as a keyword and all other words over 'i' and 'f' as identifiers.
+        let mut keywords = Dfa::from_table(&["q0", "i", "if"], &['i', 'f'], &[&[Some(1), None], &[None, Some(2)], &[None, None]], 0, &[]).unwrap();
+        keywords.set_accept_tag("if", AcceptTag::new("keyword", 1));
+        let mut identifiers = Dfa::from_table(&["q0", "q1"], &['i', 'f'], &[&[Some(1), Some(1)], &[Some(1), Some(1)]], 0, &[]).unwrap();
+        identifiers.set_accept_tag("q1", AcceptTag::new("identifier", 0));
+        let union = keywords.union(&identifiers);
+        assert_eq!(union.check_tagged("if"), Some(&AcceptTag::new("keyword", 1)), "the keyword has the higher priority");
+        assert_eq!(union.check_tagged("i"), Some(&AcceptTag::new("identifier", 0)), "i is not accepted by the keywords");
+        assert_eq!(union.check_tagged("fi"), Some(&AcceptTag::new("identifier", 0)));
+        assert_eq!(keywords.union(&keywords).get_accept_tag("(if, if)"), Some(&AcceptTag::new("keyword", 1)));
+    }
+}
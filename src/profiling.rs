@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// Counts how often states and transitions of a DFA are used across many checks (see `Dfa::check_profiled`).
+/// Transitions are identified by their state and input. Implicit self-loops of the `MissingTransitionPolicy::SelfLoop` policy are counted, too.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageProfile {
+    pub check_count: usize,
+    pub accepted_count: usize,
+    /// How often each state was entered, including being the start state of a check.
+    pub state_visits: HashMap<String, usize>,
+    pub transition_uses: HashMap<(String, char), usize>,
+}
+
+impl UsageProfile {
+    pub fn new() -> UsageProfile {
+        UsageProfile::default()
+    }
+
+    pub fn get_state_visits(&self, state: &str) -> usize {
+        self.state_visits.get(state).cloned().unwrap_or(0)
+    }
+
+    pub fn get_transition_uses(&self, state: &str, input: char) -> usize {
+        self.transition_uses.get(&(state.to_string(), input)).cloned().unwrap_or(0)
+    }
+
+    /// Returns the states of the DFA that were never visited, sorted by name. They are candidates for removal,
+    /// or indicate that the checked inputs do not cover the DFA.
+    pub fn get_unvisited_states(&self, dfa: &Dfa) -> Vec<String> {
+        dfa.get_all_mentioned_states().into_iter().filter(|state| self.get_state_visits(state) == 0).sorted().collect()
+    }
+}
+
+/// Lists the states and transitions, most used first.
+impl fmt::Display for UsageProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} checks, {} accepted", self.check_count, self.accepted_count)?;
+        writeln!(f, "states:")?;
+        for (state, visits) in self.state_visits.iter().sorted_by_key(|(state, visits)| (std::cmp::Reverse(**visits), (*state).clone())) {
+            writeln!(f, "  {}: {}", state, visits)?;
+        }
+        writeln!(f, "transitions:")?;
+        for ((state, input), uses) in self.transition_uses.iter().sorted_by_key(|(transition, uses)| (std::cmp::Reverse(**uses), (*transition).clone())) {
+            writeln!(f, "  {}, {}: {}", state, input, uses)?;
+        }
+        Ok(())
+    }
+}
+
+impl Dfa {
+    /// Checks the input like `check` does and records which states and transitions were used in the profile.
+    /// Without a profile, checks are not slowed down by counting, so profiling can be switched on where needed.
+    pub fn check_profiled(&self, input: &str, profile: &mut UsageProfile) -> bool {
+        let (is_accepted, traversed_states) = self.check(input);
+        profile.check_count += 1;
+        if is_accepted {
+            profile.accepted_count += 1;
+        }
+        for state in &traversed_states {
+            *profile.state_visits.entry(state.clone()).or_insert(0) += 1;
+        }
+        for (state, input) in traversed_states.iter().zip(input.chars()).take(traversed_states.len() - 1) {
+            *profile.transition_uses.entry((state.clone(), input)).or_insert(0) += 1;
+        }
+        is_accepted
+    }
+
+    /// Checks all inputs and returns the resulting profile.
+    pub fn profile<'a, I: IntoIterator<Item = &'a str>>(&self, inputs: I) -> UsageProfile {
+        let mut profile = UsageProfile::new();
+        for input in inputs {
+            self.check_profiled(input, &mut profile);
+        }
+        profile
+    }
+}
+
+#[cfg(test)]
+mod profiling_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_profile() {
+        // Accepts "ab" followed by any number of 'b's. q3 is unreachable.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3"],
+            &['a', 'b'],
+            &[&[Some(1), None], &[None, Some(2)], &[None, Some(2)], &[Some(0), None]],
+            0,
+            &[2],
+        ).unwrap();
+        let profile = dfa.profile(vec!["ab", "abbb", "b", "aa"]);
+        assert_eq!(profile.check_count, 4);
+        assert_eq!(profile.accepted_count, 2);
+        assert_eq!(profile.get_state_visits("q0"), 4);
+        assert_eq!(profile.get_state_visits("q2"), 4);
+        assert_eq!(profile.get_transition_uses("q2", 'b'), 2);
+        assert_eq!(profile.get_transition_uses("q0", 'a'), 3);
+        assert_eq!(profile.get_unvisited_states(&dfa), vec!["q3".to_string()]);
+        assert!(profile.to_string().starts_with("4 checks, 2 accepted\nstates:\n  q0: 4\n  q2: 4\n  q1: 3\n"));
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// A split of a word into xyz as in the pumping lemma.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PumpingSplit {
+    pub x: String,
+    pub y: String,
+    pub z: String,
+}
+
+impl PumpingSplit {
+    /// Returns the word xyⁱz.
+    pub fn pump(&self, exponent: usize) -> String {
+        format!("{}{}{}", self.x, self.y.repeat(exponent), self.z)
+    }
+}
+
+impl fmt::Display for PumpingSplit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x = \"{}\", y = \"{}\", z = \"{}\"", self.x, self.y, self.z)
+    }
+}
+
+/// Describes why a word cannot be played in the pumping lemma game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PumpingGameError {
+    NotInLanguage(String),
+    TooShort { word: String, pumping_length: usize },
+}
+
+impl fmt::Display for PumpingGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PumpingGameError::NotInLanguage(word) => write!(f, "the word \"{}\" is not in the language", word),
+            PumpingGameError::TooShort { word, pumping_length } => {
+                write!(f, "the word \"{}\" is shorter than the pumping length {}", word, pumping_length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PumpingGameError {}
+
+/// The game behind the [pumping lemma](https://en.wikipedia.org/wiki/Pumping_lemma_for_regular_languages), with the crate playing the adversary:
+/// it picks the pumping length p, the student picks a word of the language with at least p symbols, the adversary splits it into xyz
+/// with |xy| ≤ p and y not empty, and the student wins by finding an i such that xyⁱz is not in the language.
+/// A student who wins against every choice of the adversary has shown that the language is not regular.
+///
+/// The language is given by a membership test, so that languages claimed to be non-regular can be explored. The adversary tries
+/// the exponents from 0 up to a maximum, so it can only find splits that survive the exponents it tried.
+pub struct PumpingLemmaGame<'a> {
+    is_member: Box<dyn Fn(&str) -> bool + 'a>,
+    pumping_length: usize,
+    max_exponent: usize,
+    dfa: Option<&'a Dfa>,
+}
+
+impl<'a> PumpingLemmaGame<'a> {
+    /// Creates a game for the language of words accepted by the membership test, with the adversary picking the pumping length.
+    /// The adversary tries the exponents up to 5.
+    pub fn new(is_member: impl Fn(&str) -> bool + 'a, pumping_length: usize) -> PumpingLemmaGame<'a> {
+        PumpingLemmaGame { is_member: Box::new(is_member), pumping_length: pumping_length.max(1), max_exponent: 5, dfa: None }
+    }
+
+    /// Creates a game for the language of a DFA, in which the adversary always wins: the pumping length is the number of states,
+    /// and the word is split where the DFA first repeats a state, so that y leads along a cycle and can be pumped arbitrarily often.
+    pub fn for_dfa(dfa: &'a Dfa) -> PumpingLemmaGame<'a> {
+        let pumping_length = dfa.get_all_states().len();
+        PumpingLemmaGame { dfa: Some(dfa), ..PumpingLemmaGame::new(move |word| dfa.check(word).0, pumping_length) }
+    }
+
+    pub fn get_pumping_length(&self) -> usize {
+        self.pumping_length
+    }
+
+    /// Sets the largest exponent the adversary tries when looking for a split, and the student's side tries when looking for a counter exponent.
+    pub fn set_max_exponent(&mut self, max_exponent: usize) {
+        self.max_exponent = max_exponent;
+    }
+
+    /// Plays the adversary's move for the word chosen by the student: returns a split that survives pumping with all exponents
+    /// up to the maximum, if there is one, or otherwise the split with the shortest x and y, which the student can then beat.
+    pub fn choose_split(&self, word: &str) -> Result<PumpingSplit, PumpingGameError> {
+        if !(self.is_member)(word) {
+            return Err(PumpingGameError::NotInLanguage(word.to_string()));
+        }
+        let chars = word.chars().collect_vec();
+        if chars.len() < self.pumping_length {
+            return Err(PumpingGameError::TooShort { word: word.to_string(), pumping_length: self.pumping_length });
+        }
+        let split_at = |start: usize, end: usize| PumpingSplit {
+            x: chars[..start].iter().collect(),
+            y: chars[start..end].iter().collect(),
+            z: chars[end..].iter().collect(),
+        };
+        if let Some(dfa) = self.dfa {
+            let traversed_states = dfa.check(word).1;
+            for end in 1..=self.pumping_length {
+                if let Some(start) = traversed_states[..end].iter().position(|state| *state == traversed_states[end]) {
+                    return Ok(split_at(start, end));
+                }
+            }
+        }
+        let splits = (1..=self.pumping_length)
+            .flat_map(|end| (0..end).rev().map(move |start| (start, end)))
+            .map(|(start, end)| split_at(start, end))
+            .collect_vec();
+        let surviving_split = splits.iter().find(|split| self.find_counter_exponent(split).is_none());
+        Ok(surviving_split.unwrap_or(&splits[0]).clone())
+    }
+
+    /// Plays the student's last move: returns the smallest exponent i up to the maximum such that xyⁱz is not in the language,
+    /// or None if the split survives all of them.
+    pub fn find_counter_exponent(&self, split: &PumpingSplit) -> Option<usize> {
+        (0..=self.max_exponent).find(|exponent| !(self.is_member)(&split.pump(*exponent)))
+    }
+}
+
+#[cfg(test)]
+mod pumping_game_tests {
+    use crate::{Dfa, PumpingGameError, PumpingLemmaGame};
+
+    #[test]
+    fn test_non_regular_language() {
+        // aⁿbⁿ is not regular: for the word aᵖbᵖ, y consists of 'a's only, so pumping breaks the balance.
+        let is_balanced = |word: &str| {
+            let a_count = word.chars().take_while(|char| *char == 'a').count();
+            word.len() == 2 * a_count && word.chars().skip(a_count).all(|char| char == 'b')
+        };
+        let game = PumpingLemmaGame::new(is_balanced, 3);
+        let split = game.choose_split("aaabbb").unwrap();
+        assert_eq!(split.to_string(), "x = \"\", y = \"a\", z = \"aabbb\"");
+        assert_eq!(game.find_counter_exponent(&split), Some(0));
+        assert_eq!(game.choose_split("ab"), Err(PumpingGameError::TooShort { word: "ab".to_string(), pumping_length: 3 }));
+        assert_eq!(game.choose_split("aab"), Err(PumpingGameError::NotInLanguage("aab".to_string())));
+    }
+
+    #[test]
+    fn test_for_dfa() {
+        // Accepts words over 'a' and 'b' with an even number of 'a's.
+        let dfa = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap();
+        let game = PumpingLemmaGame::for_dfa(&dfa);
+        assert_eq!(game.get_pumping_length(), 2);
+        let split = game.choose_split("abab").unwrap();
+        assert_eq!((split.x.as_str(), split.y.as_str(), split.z.as_str()), ("a", "b", "ab"));
+        assert_eq!(game.find_counter_exponent(&split), None);
+        assert_eq!(game.find_counter_exponent(&game.choose_split("aab").unwrap()), None);
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{Bounded, Fuel};
+
+/// The simulation of nondeterministic pushdown machines gives up after exploring this many configurations by default,
+/// because epsilon transitions that push symbols can otherwise lead to infinitely many configurations.
+pub(crate) const MAX_EXPLORED_CONFIGURATIONS: usize = 100_000;
+
+/// A configuration of a pushdown transducer consists of the current state, the position within the input and the stack (top at the end).
+type Configuration<'a> = (&'a str, usize, Vec<char>);
+
+/// Describes to which next state a pushdown transducer may switch while reading an input symbol (or nothing, if `input` is None)
+/// and popping the top of the stack (or nothing, if `pop` is None). Afterwards, `push` is pushed onto the stack such that its first
+/// character ends up on top, and `output` is appended to the output.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct PushdownTransducerTransition {
+    state: String,
+    input: Option<char>,
+    pop: Option<char>,
+    push: String,
+    output: String,
+    next_state: String,
+}
+
+/// # Pushdown transducer
+/// A pushdown automaton that writes output while it processes the input, which is enough to model
+/// simple syntax-directed translations like the conversion of infix expressions into postfix notation.
+/// The stack initially contains only the start stack symbol. An input is accepted if it is read completely
+/// and the transducer ends up in an accept state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushdownTransducer {
+    name: String,
+    start_state: String,
+    start_stack_symbol: char,
+    accept_states: HashSet<String>,
+    transitions: Vec<PushdownTransducerTransition>,
+}
+
+impl PushdownTransducer {
+    /// Creates a pushdown transducer without transitions and accept states.
+    pub fn new(name: &str, start_state: &str, start_stack_symbol: char) -> PushdownTransducer {
+        PushdownTransducer {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            start_stack_symbol,
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: Option<char>, pop: Option<char>, push: &str, output: &str, next_state: &str) {
+        self.transitions.push(PushdownTransducerTransition {
+            state: state.to_string(),
+            input,
+            pop,
+            push: push.to_string(),
+            output: output.to_string(),
+            next_state: next_state.to_string(),
+        });
+    }
+
+    /// Translates the input. Returns the output of an accepting run or None if the input is not accepted.
+    /// As the transducer might be nondeterministic, all runs are explored in breath first order, so the output of the
+    /// accepting run with the fewest steps is returned. Gives up and returns None after exploring `MAX_EXPLORED_CONFIGURATIONS` configurations.
+    pub fn apply(&self, input: &str) -> Option<String> {
+        self.apply_with_fuel(input, &mut Fuel::new(MAX_EXPLORED_CONFIGURATIONS)).done().flatten()
+    }
+
+    /// Translates the input like `apply`, but explores at most as many configurations as there is fuel,
+    /// and tells apart rejected inputs from inputs for which the fuel ran out.
+    pub fn apply_with_fuel(&self, input: &str, fuel: &mut Fuel) -> Bounded<Option<String>> {
+        let input: Vec<char> = input.chars().collect();
+        let mut visited_configurations: HashSet<Configuration> = HashSet::new();
+        let mut configurations_to_visit: VecDeque<(Configuration, String)> = VecDeque::new();
+        configurations_to_visit.push_back(((&self.start_state, 0, vec![self.start_stack_symbol]), String::new()));
+        while let Some((configuration, output)) = configurations_to_visit.pop_front() {
+            if self.is_accepting(&configuration, &input) {
+                return Bounded::Done(Some(output));
+            }
+            // The output does not influence the future of a run, so configurations only differing in their output are visited once.
+            if !visited_configurations.insert(configuration.clone()) {
+                continue;
+            }
+            if !fuel.consume() {
+                return Bounded::Exhausted;
+            }
+            for (next_configuration, transition) in self.get_successors(&configuration, &input) {
+                configurations_to_visit.push_back((next_configuration, output.clone() + &transition.output));
+            }
+        }
+        Bounded::Done(None)
+    }
+
+    /// Renders the configurations explored while translating the input in the [DOT language](https://graphviz.org/doc/info/lang.html)
+    /// of Graphviz, which shows how the nondeterministic transducer searches for an accepting run. Configurations are labeled with
+    /// their state, the remaining input and the stack with its top first, and edges with the input and output of their transition.
+    /// The accepting run that `apply` would choose is highlighted in red. Exploration stops when the fuel runs out, so the graph may be partial.
+    pub fn configuration_graph_to_dot(&self, input: &str, fuel: &mut Fuel) -> String {
+        let input: Vec<char> = input.chars().collect();
+        let start = (self.start_state.as_str(), 0, vec![self.start_stack_symbol]);
+        let mut indices: HashMap<Configuration, usize> = HashMap::new();
+        let mut predecessors: HashMap<usize, usize> = HashMap::new();
+        let mut edges: Vec<(usize, usize, String)> = Vec::new();
+        let mut configurations_to_visit = VecDeque::new();
+        let mut accepting_configuration = None;
+        indices.insert(start.clone(), 0);
+        configurations_to_visit.push_back(start);
+        while let Some(configuration) = configurations_to_visit.pop_front() {
+            let index = indices[&configuration];
+            if self.is_accepting(&configuration, &input) {
+                accepting_configuration = Some(index);
+                break;
+            }
+            if !fuel.consume() {
+                break;
+            }
+            for (next_configuration, transition) in self.get_successors(&configuration, &input) {
+                let next_index = match indices.get(&next_configuration) {
+                    Some(next_index) => *next_index,
+                    None => {
+                        let next_index = indices.len();
+                        indices.insert(next_configuration.clone(), next_index);
+                        predecessors.insert(next_index, index);
+                        configurations_to_visit.push_back(next_configuration);
+                        next_index
+                    }
+                };
+                let label = format!("{}/{}", transition.input.map(String::from).unwrap_or_else(|| String::from("ε")), transition.output);
+                edges.push((index, next_index, label));
+            }
+        }
+        let mut accepting_path = HashSet::new();
+        let mut current = accepting_configuration;
+        while let Some(index) = current {
+            accepting_path.insert(index);
+            current = predecessors.get(&index).cloned();
+        }
+        let mut dot = format!("digraph \"{}\" {{\n    node [shape=box];\n", self.name.replace('"', "\\\""));
+        for ((state, position, stack), index) in indices.iter().sorted_by_key(|(_, index)| **index) {
+            let label = format!("{} | {} | {}", state, input[*position..].iter().collect::<String>(), stack.iter().rev().collect::<String>());
+            let color = if accepting_path.contains(index) { ", color=red" } else { "" };
+            let peripheries = if accepting_configuration == Some(*index) { ", peripheries=2" } else { "" };
+            dot += &format!("    c{} [label=\"{}\"{}{}];\n", index, label.replace('"', "\\\""), color, peripheries);
+        }
+        for (index, next_index, label) in edges {
+            let color = if accepting_path.contains(&next_index) && predecessors.get(&next_index) == Some(&index) { ", color=red" } else { "" };
+            dot += &format!("    c{} -> c{} [label=\"{}\"{}];\n", index, next_index, label.replace('"', "\\\""), color);
+        }
+        dot + "}\n"
+    }
+
+    fn is_accepting(&self, (state, position, _): &Configuration, input: &[char]) -> bool {
+        *position == input.len() && self.accept_states.contains(*state)
+    }
+
+    /// Returns the configurations that can follow the given one, together with the transitions leading there.
+    fn get_successors<'a>(&'a self, (state, position, stack): &Configuration<'a>, input: &[char]) -> Vec<(Configuration<'a>, &'a PushdownTransducerTransition)> {
+        let mut successors = Vec::new();
+        for transition in self.transitions.iter().filter(|transition| transition.state == *state) {
+            let next_position = match transition.input {
+                None => *position,
+                Some(symbol) if input.get(*position) == Some(&symbol) => position + 1,
+                Some(_) => continue,
+            };
+            let mut next_stack = stack.clone();
+            if let Some(symbol) = transition.pop {
+                if next_stack.pop() != Some(symbol) {
+                    continue;
+                }
+            }
+            next_stack.extend(transition.push.chars().rev());
+            successors.push(((transition.next_state.as_str(), next_position, next_stack), transition));
+        }
+        successors
+    }
+}
+
+#[cfg(test)]
+mod pushdown_transducer_tests {
+    use crate::{Bounded, Fuel, PushdownTransducer};
+
+    /// Creates a transducer converting infix expressions over single-letter operands, '+', '*' and parentheses
+    /// into postfix notation, following the shunting-yard algorithm. The stack holds pending operators and parentheses.
+    fn create_infix_to_postfix_transducer() -> PushdownTransducer {
+        let mut transducer = PushdownTransducer::new("infix to postfix", "operand", 'Z');
+        for operand in 'a'..='z' {
+            transducer.add_transition("operand", Some(operand), None, "", &operand.to_string(), "operator");
+        }
+        transducer.add_transition("operand", Some('('), None, "(", "", "operand");
+        // Before pushing '+', all pending operators are written, as they bind at least as strong.
+        transducer.add_transition("operator", Some('+'), None, "", "", "plus");
+        transducer.add_transition("plus", None, Some('+'), "", "+", "plus");
+        transducer.add_transition("plus", None, Some('*'), "", "*", "plus");
+        transducer.add_transition("plus", None, Some('('), "+(", "", "operand");
+        transducer.add_transition("plus", None, Some('Z'), "+Z", "", "operand");
+        // Before pushing '*', only pending '*' operators are written.
+        transducer.add_transition("operator", Some('*'), None, "", "", "times");
+        transducer.add_transition("times", None, Some('*'), "", "*", "times");
+        transducer.add_transition("times", None, Some('+'), "*+", "", "operand");
+        transducer.add_transition("times", None, Some('('), "*(", "", "operand");
+        transducer.add_transition("times", None, Some('Z'), "*Z", "", "operand");
+        // A closing parenthesis writes all operators pending since the opening one.
+        transducer.add_transition("operator", Some(')'), None, "", "", "close");
+        transducer.add_transition("close", None, Some('+'), "", "+", "close");
+        transducer.add_transition("close", None, Some('*'), "", "*", "close");
+        transducer.add_transition("close", None, Some('('), "", "", "operator");
+        // At the end of the input, all pending operators are written.
+        transducer.add_transition("operator", None, None, "", "", "end");
+        transducer.add_transition("end", None, Some('+'), "", "+", "end");
+        transducer.add_transition("end", None, Some('*'), "", "*", "end");
+        transducer.add_transition("end", None, Some('Z'), "", "", "done");
+        transducer.add_accept_state("done");
+        transducer
+    }
+
+    #[test]
+    fn test_apply() {
+        let transducer = create_infix_to_postfix_transducer();
+        assert_eq!(transducer.apply("a+b*c"), Some("abc*+".to_string()));
+        assert_eq!(transducer.apply("(a+b)*c"), Some("ab+c*".to_string()));
+        assert_eq!(transducer.apply("a*b+c"), Some("ab*c+".to_string()));
+        assert_eq!(transducer.apply("a+"), None, "should reject incomplete expressions");
+        assert_eq!(transducer.apply("(a+b"), None, "should reject unbalanced parentheses");
+    }
+
+    #[test]
+    fn test_apply_with_fuel() {
+        let transducer = create_infix_to_postfix_transducer();
+        assert_eq!(transducer.apply_with_fuel("a+b*c", &mut Fuel::new(3)), Bounded::Exhausted);
+        let mut fuel = Fuel::new(1000);
+        assert_eq!(transducer.apply_with_fuel("a+b*c", &mut fuel), Bounded::Done(Some("abc*+".to_string())));
+        assert!(fuel.get_remaining() < 1000);
+        // Pushes 'A' forever without reading anything, so the configurations never repeat.
+        let mut endless_transducer = PushdownTransducer::new("endless", "q0", 'Z');
+        endless_transducer.add_transition("q0", None, None, "A", "", "q0");
+        endless_transducer.add_accept_state("q1");
+        assert!(endless_transducer.apply_with_fuel("", &mut Fuel::new(100)).is_exhausted());
+    }
+
+    #[test]
+    fn test_configuration_graph_to_dot() {
+        let transducer = create_infix_to_postfix_transducer();
+        let dot = transducer.configuration_graph_to_dot("a+b", &mut Fuel::unlimited());
+        assert!(dot.contains("    c0 [label=\"operand | a+b | Z\", color=red];\n"));
+        assert!(dot.contains("    c0 -> c1 [label=\"a/a\", color=red];\n"));
+        assert!(dot.contains("[label=\"done |  | \", color=red, peripheries=2];"));
+    }
+}
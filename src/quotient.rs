@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::FromIterator;
+
+use itertools::Itertools;
+
+use crate::{AcceptTag, Dfa, MissingTransitionPolicy, Transition};
+
+/// Describes why a user-supplied partition of states cannot be used to build a quotient automaton.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotientError {
+    /// A class does not contain any state.
+    EmptyClass,
+    /// A class contains a state that does not exist within the DFA.
+    UnknownState(String),
+    /// A state of the DFA is not contained in any class.
+    UncoveredState(String),
+    /// A state is contained in more than one class.
+    OverlappingClasses(String),
+    /// The class of `state` contains accepting and rejecting states.
+    MixedAcceptance { state: String },
+    /// The members of the class of `state` transition into different classes when reading `input`.
+    NotACongruence { state: String, input: char },
+}
+
+impl fmt::Display for QuotientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotientError::EmptyClass => write!(f, "the partition contains an empty class"),
+            QuotientError::UnknownState(state) => write!(f, "the state {} does not exist", state),
+            QuotientError::UncoveredState(state) => write!(f, "the state {} is not contained in any class", state),
+            QuotientError::OverlappingClasses(state) => write!(f, "the state {} is contained in more than one class", state),
+            QuotientError::MixedAcceptance { state } => write!(f, "the class of {} mixes accepting and rejecting states", state),
+            QuotientError::NotACongruence { state, input } => write!(f, "the members of the class of {} disagree on the next class for input {}", state, input),
+        }
+    }
+}
+
+impl std::error::Error for QuotientError {}
+
+/// A conflict that had to be resolved when a quotient was forced although the partition is not a congruence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotientConflict {
+    /// The merged state could transition to any of the listed states when reading `input`. Only the first one was kept.
+    Transition { state: String, input: char, next_states: Vec<String> },
+    /// The merged state contains accepting and rejecting states. It was made accepting.
+    Acceptance { state: String },
+}
+
+impl Dfa {
+    /// Merges the states of the DFA according to the given partition. Every class of the partition becomes one
+    /// state which is named after the smallest state name within the class, just like `minimize` does it.
+    /// The partition must be a congruence: All members of a class must agree on whether they accept and, for every
+    /// input, on the class they transition into. Otherwise the merged automaton would be nondeterministic and
+    /// an error is returned. Use `quotient_forced` if you want to merge the states anyway.
+    pub fn quotient(&self, classes: &[HashSet<String>]) -> Result<Dfa, QuotientError> {
+        let (dfa, conflicts) = self.quotient_forced(classes)?;
+        match conflicts.into_iter().next() {
+            None => Ok(dfa),
+            Some(QuotientConflict::Acceptance { state }) => Err(QuotientError::MixedAcceptance { state }),
+            Some(QuotientConflict::Transition { state, input, .. }) => Err(QuotientError::NotACongruence { state, input }),
+        }
+    }
+
+    /// Merges the states of the DFA according to the given partition, even if it is not a congruence.
+    /// Whenever the merge introduces nondeterminism, the transition to the smallest next state is kept and the conflict is reported.
+    /// A merged state accepts if any of its members accepts. Only malformed partitions lead to an error.
+    pub fn quotient_forced(&self, classes: &[HashSet<String>]) -> Result<(Dfa, Vec<QuotientConflict>), QuotientError> {
+        if self.missing_transitions == MissingTransitionPolicy::SelfLoop {
+            // Implicit self-loops must be considered when checking whether the classes agree on their next classes.
+            let mut materialized_dfa = self.clone();
+            materialized_dfa.materialize_missing_transitions();
+            return materialized_dfa.quotient_forced(classes);
+        }
+        let renaming_operations = self.validate_partition(classes)?;
+        let mut conflicts = Vec::new();
+        // The accept states are merged existentially. Classes that mix accepting and rejecting states are reported.
+        for class in classes {
+            let representative = &renaming_operations[class.iter().next().unwrap()];
+            let accepting_members = class.iter().filter(|state| self.accept_states.contains(*state)).count();
+            if accepting_members != 0 && accepting_members != class.len() {
+                conflicts.push(QuotientConflict::Acceptance { state: representative.clone() });
+            }
+        }
+        let accept_states = HashSet::from_iter(self.accept_states.iter().map(|state| renaming_operations[state].clone()));
+        // Tags of merged accept states are merged by their priority.
+        let mut accept_tags: HashMap<String, AcceptTag> = HashMap::new();
+        for (state, tag) in &self.accept_tags {
+            let merged_tag = match accept_tags.get(&renaming_operations[state]) {
+                Some(other_tag) => AcceptTag::merge(tag, other_tag),
+                None => tag.clone(),
+            };
+            accept_tags.insert(renaming_operations[state].clone(), merged_tag);
+        }
+        // Group the renamed transitions by their source and input so that we can detect nondeterminism.
+        // The members having a transition are collected as a set, as a transition may be listed more than once.
+        let mut next_states_by_source: HashMap<(String, char), Vec<String>> = HashMap::new();
+        let mut members_by_source: HashMap<(String, char), HashSet<&str>> = HashMap::new();
+        for transition in &self.transitions {
+            let source = (renaming_operations[&transition.state].clone(), transition.input);
+            members_by_source.entry(source.clone()).or_default().insert(&transition.state);
+            next_states_by_source.entry(source).or_default().push(renaming_operations[&transition.next_state].clone());
+        }
+        let mut class_sizes: HashMap<&str, usize> = HashMap::new();
+        for representative in renaming_operations.values() {
+            *class_sizes.entry(representative).or_insert(0) += 1;
+        }
+        let mut transitions = Vec::new();
+        for ((state, input), next_states) in next_states_by_source.into_iter().sorted() {
+            let next_states = next_states.into_iter().sorted().dedup().collect_vec();
+            // A class member without a transition for the input would lead into the error state,
+            // which is just as distinguishing as a transition into a different class.
+            if next_states.len() > 1 || members_by_source[&(state.clone(), input)].len() < class_sizes[state.as_str()] {
+                conflicts.push(QuotientConflict::Transition { state: state.clone(), input, next_states: next_states.clone() });
+            }
+            transitions.push(Transition { state, input, next_state: next_states[0].clone() });
+        }
+        let dfa = Dfa {
+            alphabet: self.alphabet.clone(),
+            missing_transitions: self.missing_transitions,
+            // Metadata of merged states is dropped, only the representatives keep theirs.
+            state_metadata: self.state_metadata.iter()
+                .filter(|(state, _)| renaming_operations[*state] == **state)
+                .map(|(state, metadata)| (state.clone(), metadata.clone()))
+                .collect(),
+            accept_tags,
+            // Like the transitions, costs of merged states are taken from the representatives.
+            transition_costs: self.transition_costs.iter()
+                .filter(|((state, _), _)| renaming_operations[state] == *state)
+                .map(|(key, cost)| (key.clone(), *cost))
+                .collect(),
+            ..Dfa::with_parts(self.name.clone(), renaming_operations[&self.start_state].clone(), accept_states, transitions)
+        };
+        Ok((dfa, conflicts))
+    }
+
+    /// Checks that the classes form a partition of all states of the DFA and maps every state to the name of its class.
+    fn validate_partition(&self, classes: &[HashSet<String>]) -> Result<HashMap<String, String>, QuotientError> {
+        let all_states = self.get_all_mentioned_states();
+        let mut renaming_operations = HashMap::new();
+        for class in classes {
+            let new_name = class.iter().sorted().next().ok_or(QuotientError::EmptyClass)?;
+            for state in class {
+                if !all_states.contains(state) {
+                    return Err(QuotientError::UnknownState(state.clone()));
+                }
+                if renaming_operations.insert(state.clone(), new_name.clone()).is_some() {
+                    return Err(QuotientError::OverlappingClasses(state.clone()));
+                }
+            }
+        }
+        if let Some(state) = all_states.into_iter().sorted().find(|state| !renaming_operations.contains_key(state)) {
+            return Err(QuotientError::UncoveredState(state));
+        }
+        Ok(renaming_operations)
+    }
+}
+
+#[cfg(test)]
+mod quotient_tests {
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+
+    use crate::{Dfa, QuotientConflict, QuotientError, Transition};
+
+    /// Creates a DFA accepting words over 'a' of odd length, using four states where two would suffice.
+    fn create_example_dfa() -> Dfa {
+        Dfa::with_parts(
+            String::from("Accept words of odd length."),
+            "q0".to_string(),
+            HashSet::from_iter(vec!["q1".to_string(), "q3".to_string()]),
+            vec![
+                Transition { state: "q0".to_string(), input: 'a', next_state: "q1".to_string() },
+                Transition { state: "q1".to_string(), input: 'a', next_state: "q2".to_string() },
+                Transition { state: "q2".to_string(), input: 'a', next_state: "q3".to_string() },
+                Transition { state: "q3".to_string(), input: 'a', next_state: "q0".to_string() },
+            ],
+        )
+    }
+
+    fn class(states: &[&str]) -> HashSet<String> {
+        HashSet::from_iter(states.iter().map(|state| state.to_string()))
+    }
+
+    #[test]
+    fn test_quotient() {
+        let dfa = create_example_dfa();
+        let quotient = dfa.quotient(&[class(&["q0", "q2"]), class(&["q1", "q3"])]).unwrap();
+        assert_eq!(quotient.get_all_states().len(), 2);
+        assert!(quotient.check("aaa").0, "should still accept words of odd length");
+        assert!(!quotient.check("aaaa").0, "should still reject words of even length");
+    }
+
+    #[test]
+    fn test_quotient_rejects_invalid_partitions() {
+        let dfa = create_example_dfa();
+        assert_eq!(dfa.quotient(&[class(&["q0", "q1", "q2"])]).unwrap_err(), QuotientError::UncoveredState("q3".to_string()));
+        assert_eq!(dfa.quotient(&[class(&["q0", "q1"]), class(&["q1", "q2", "q3"])]).unwrap_err(), QuotientError::OverlappingClasses("q1".to_string()));
+        assert_eq!(dfa.quotient(&[class(&["q0", "q1", "q2", "q3", "q4"])]).unwrap_err(), QuotientError::UnknownState("q4".to_string()));
+        assert_eq!(dfa.quotient(&[class(&["q0", "q3"]), class(&["q1", "q2"])]).unwrap_err(), QuotientError::MixedAcceptance { state: "q0".to_string() });
+    }
+
+    #[test]
+    fn test_quotient_forced() {
+        let dfa = create_example_dfa();
+        let (quotient, conflicts) = dfa.quotient_forced(&[class(&["q0", "q1"]), class(&["q2"]), class(&["q3"])]).unwrap();
+        assert!(conflicts.contains(&QuotientConflict::Acceptance { state: "q0".to_string() }));
+        assert!(conflicts.contains(&QuotientConflict::Transition {
+            state: "q0".to_string(),
+            input: 'a',
+            next_states: vec!["q0".to_string(), "q2".to_string()],
+        }));
+        assert_eq!(quotient.get_all_states().len(), 3);
+    }
+
+    #[test]
+    fn test_quotient_forced_with_duplicate_transitions() {
+        // q0 lists its transition twice, which must not hide that q2 has no transition for 'a'.
+        let dfa = Dfa::with_parts(
+            String::from("Duplicate transitions"),
+            "q0".to_string(),
+            HashSet::new(),
+            vec![
+                Transition { state: "q0".to_string(), input: 'a', next_state: "q1".to_string() },
+                Transition { state: "q0".to_string(), input: 'a', next_state: "q1".to_string() },
+                Transition { state: "q1".to_string(), input: 'a', next_state: "q2".to_string() },
+            ],
+        );
+        let (_, conflicts) = dfa.quotient_forced(&[class(&["q0", "q2"]), class(&["q1"])]).unwrap();
+        assert_eq!(conflicts, vec![QuotientConflict::Transition { state: "q0".to_string(), input: 'a', next_states: vec!["q1".to_string()] }]);
+    }
+}
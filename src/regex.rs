@@ -0,0 +1,150 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// # [Regular expression](https://en.wikipedia.org/wiki/Regular_expression)
+/// The abstract syntax tree of a regular expression in its textbook form. The constructors do not simplify anything,
+/// so the tree looks exactly like it was built.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub enum Regex {
+    /// Matches nothing at all, not even the empty word.
+    Empty,
+    /// Matches only the empty word.
+    Epsilon,
+    Symbol(char),
+    Concatenation(Box<Regex>, Box<Regex>),
+    Union(Box<Regex>, Box<Regex>),
+    Star(Box<Regex>),
+}
+
+impl Regex {
+    /// Creates a regex matching exactly the given word. The empty word results in `Regex::Epsilon`.
+    pub fn word(word: &str) -> Regex {
+        word.chars()
+            .map(Regex::Symbol)
+            .fold(None, |regex: Option<Regex>, symbol| Some(match regex {
+                Some(regex) => Regex::concatenation(regex, symbol),
+                None => symbol,
+            }))
+            .unwrap_or(Regex::Epsilon)
+    }
+
+    pub fn concatenation(left: Regex, right: Regex) -> Regex {
+        Regex::Concatenation(Box::new(left), Box::new(right))
+    }
+
+    pub fn union(left: Regex, right: Regex) -> Regex {
+        Regex::Union(Box::new(left), Box::new(right))
+    }
+
+    pub fn star(inner: Regex) -> Regex {
+        Regex::Star(Box::new(inner))
+    }
+
+    /// Returns all positions at which a match of this regex can end when it starts at position `start` within the input.
+    /// This is a simple backtracking-free matcher which is good enough for the short labels of generalized automata.
+    pub(crate) fn match_ends(&self, input: &[char], start: usize) -> BTreeSet<usize> {
+        match self {
+            Regex::Empty => BTreeSet::new(),
+            Regex::Epsilon => vec![start].into_iter().collect(),
+            Regex::Symbol(symbol) => match input.get(start) {
+                Some(char) if char == symbol => vec![start + 1].into_iter().collect(),
+                _ => BTreeSet::new(),
+            },
+            Regex::Concatenation(left, right) => left.match_ends(input, start).into_iter()
+                .flat_map(|middle| right.match_ends(input, middle))
+                .collect(),
+            Regex::Union(left, right) => left.match_ends(input, start).into_iter()
+                .chain(right.match_ends(input, start))
+                .collect(),
+            Regex::Star(inner) => {
+                // Repeat the inner regex until no new end positions are found.
+                let mut ends: BTreeSet<usize> = vec![start].into_iter().collect();
+                let mut positions_to_visit = vec![start];
+                while let Some(position) = positions_to_visit.pop() {
+                    for end in inner.match_ends(input, position) {
+                        if ends.insert(end) {
+                            positions_to_visit.push(end);
+                        }
+                    }
+                }
+                ends
+            }
+        }
+    }
+
+    /// Binding strength used to decide where parentheses are necessary when displaying the regex.
+    fn precedence(&self) -> u8 {
+        match self {
+            Regex::Union(_, _) => 0,
+            Regex::Concatenation(_, _) => 1,
+            _ => 2,
+        }
+    }
+
+    fn fmt_with_precedence(&self, f: &mut fmt::Formatter<'_>, minimal_precedence: u8) -> fmt::Result {
+        if self.precedence() < minimal_precedence {
+            write!(f, "(")?;
+            self.fmt_with_precedence(f, 0)?;
+            return write!(f, ")");
+        }
+        match self {
+            Regex::Empty => write!(f, "∅"),
+            Regex::Epsilon => write!(f, "ε"),
+            Regex::Symbol(symbol) if METACHARACTERS.contains(*symbol) => write!(f, "\\{}", symbol),
+            Regex::Symbol(symbol) => write!(f, "{}", symbol),
+            Regex::Concatenation(left, right) => {
+                left.fmt_with_precedence(f, 1)?;
+                right.fmt_with_precedence(f, 1)
+            }
+            Regex::Union(left, right) => {
+                left.fmt_with_precedence(f, 0)?;
+                write!(f, "|")?;
+                right.fmt_with_precedence(f, 0)
+            }
+            Regex::Star(inner) => {
+                inner.fmt_with_precedence(f, 2)?;
+                write!(f, "*")
+            }
+        }
+    }
+}
+
+/// The characters `Regex::parse` reads as operators. Symbols among them are escaped with a backslash when displayed.
+const METACHARACTERS: &str = "\\|()*+?[]{}.^$ε∅";
+
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with_precedence(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod regex_tests {
+    use crate::Regex;
+
+    #[test]
+    fn test_display() {
+        let regex = Regex::concatenation(Regex::star(Regex::union(Regex::Symbol('a'), Regex::word("bc"))), Regex::Symbol('d'));
+        assert_eq!(regex.to_string(), "(a|bc)*d");
+        assert_eq!(Regex::star(Regex::Symbol('a')).to_string(), "a*");
+        assert_eq!(Regex::word("").to_string(), "ε");
+        assert_eq!(Regex::union(Regex::word("a*"), Regex::Symbol('\\')).to_string(), "a\\*|\\\\");
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let symbols = Regex::word("\\|()*+?[]{}.^$ε∅");
+        let regex = Regex::union(Regex::star(Regex::concatenation(symbols, Regex::Epsilon)), Regex::Empty);
+        assert_eq!(Regex::parse(&regex.to_string()), Ok(regex));
+    }
+
+    #[test]
+    fn test_match_ends() {
+        let regex = Regex::star(Regex::union(Regex::Symbol('a'), Regex::word("bc")));
+        let input: Vec<char> = "abca".chars().collect();
+        assert_eq!(regex.match_ends(&input, 0).into_iter().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+        assert!(Regex::Empty.match_ends(&input, 0).is_empty());
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::Regex;
+
+/// The syntax accepted by `Regex::parse_with_dialect`. Every dialect supports single characters as literals,
+/// union with `|`, the Kleene star `*` and grouping with parentheses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegexDialect {
+    /// Only the operators of textbooks, plus `ε` for the empty word and `∅` for the empty language.
+    Textbook,
+    /// The subset of POSIX extended regular expressions describing regular languages: additionally `+`, `?`, bounded
+    /// repetitions like `a{2,3}`, bracket expressions like `[a-z0-9]` and escaping with a backslash, but neither `ε` nor `∅`.
+    /// As patterns always describe whole words and the alphabet is not known, `.`, `^` and `$` are rejected.
+    PosixEre,
+    /// Everything supported by the other dialects.
+    Extended,
+}
+
+impl Default for RegexDialect {
+    fn default() -> RegexDialect {
+        RegexDialect::Extended
+    }
+}
+
+impl fmt::Display for RegexDialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexDialect::Textbook => write!(f, "textbook"),
+            RegexDialect::PosixEre => write!(f, "POSIX ERE"),
+            RegexDialect::Extended => write!(f, "extended"),
+        }
+    }
+}
+
+/// Describes why a pattern cannot be parsed into a regex. Positions count characters, starting at 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexParseError {
+    /// The construct starting at the position exists in other dialects, but not in the chosen one.
+    UnsupportedConstruct { construct: char, position: usize, dialect: RegexDialect },
+    /// The construct starting at the position is not supported by any dialect, like `.` and negated bracket expressions,
+    /// which depend on the alphabet, or anchors.
+    Unsupported { construct: char, position: usize },
+    UnexpectedCharacter { character: char, position: usize },
+    UnexpectedEnd,
+    /// The group or bracket expression opened at the position is never closed.
+    Unclosed { position: usize },
+    /// The range of a bracket expression ends before it starts, like `[z-a]`.
+    InvalidRange { from: char, to: char },
+    /// The maximum of a bounded repetition is smaller than its minimum, like `a{3,2}`.
+    InvalidBounds { min: usize, max: usize },
+}
+
+impl fmt::Display for RegexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexParseError::UnsupportedConstruct { construct, position, dialect } => {
+                write!(f, "{} at position {} is not supported by the {} dialect", construct, position, dialect)
+            }
+            RegexParseError::Unsupported { construct, position } => write!(f, "{} at position {} is not supported", construct, position),
+            RegexParseError::UnexpectedCharacter { character, position } => write!(f, "unexpected {} at position {}", character, position),
+            RegexParseError::UnexpectedEnd => write!(f, "unexpected end of the pattern"),
+            RegexParseError::Unclosed { position } => write!(f, "the bracket at position {} is never closed", position),
+            RegexParseError::InvalidRange { from, to } => write!(f, "the range {}-{} is empty", from, to),
+            RegexParseError::InvalidBounds { min, max } => write!(f, "the repetition {{{},{}}} is empty", min, max),
+        }
+    }
+}
+
+impl std::error::Error for RegexParseError {}
+
+impl Regex {
+    /// Parses the pattern with the `RegexDialect::Extended` dialect (see `parse_with_dialect`).
+    pub fn parse(pattern: &str) -> Result<Regex, RegexParseError> {
+        Regex::parse_with_dialect(pattern, RegexDialect::Extended)
+    }
+
+    /// Parses the pattern with a recursive descent parser. Operators bind like usual: the star and the other postfix operators
+    /// bind strongest, followed by concatenation and finally union. Operators the regex has no variant for are rewritten,
+    /// e.g. `a+` becomes `aa*`, `a?` becomes `ε|a`, `a{1,2}` becomes `a(ε|a)` and `[a-c]` becomes `a|b|c`.
+    pub fn parse_with_dialect(pattern: &str, dialect: RegexDialect) -> Result<Regex, RegexParseError> {
+        let mut parser = Parser { chars: pattern.chars().collect(), position: 0, dialect };
+        let regex = parser.parse_union()?;
+        match parser.peek() {
+            Some(character) => Err(RegexParseError::UnexpectedCharacter { character, position: parser.position }),
+            None => Ok(regex),
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    dialect: RegexDialect,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Result<char, RegexParseError> {
+        let character = self.peek().ok_or(RegexParseError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(character)
+    }
+
+    /// Fails if the construct starting at the current position is only supported by the POSIX dialects.
+    fn require_posix(&self, construct: char) -> Result<(), RegexParseError> {
+        match self.dialect {
+            RegexDialect::Textbook => Err(RegexParseError::UnsupportedConstruct { construct, position: self.position, dialect: self.dialect }),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse_union(&mut self) -> Result<Regex, RegexParseError> {
+        let mut regex = self.parse_concatenation()?;
+        while self.peek() == Some('|') {
+            self.position += 1;
+            regex = Regex::union(regex, self.parse_concatenation()?);
+        }
+        Ok(regex)
+    }
+
+    fn parse_concatenation(&mut self) -> Result<Regex, RegexParseError> {
+        let mut regex = self.parse_repetition()?;
+        while let Some(character) = self.peek() {
+            if character == '|' || character == ')' {
+                break;
+            }
+            regex = Regex::concatenation(regex, self.parse_repetition()?);
+        }
+        Ok(regex)
+    }
+
+    fn parse_repetition(&mut self) -> Result<Regex, RegexParseError> {
+        let mut regex = self.parse_atom()?;
+        while let Some(operator) = self.peek() {
+            regex = match operator {
+                '*' => Regex::star(regex),
+                '+' => {
+                    self.require_posix(operator)?;
+                    Regex::concatenation(regex.clone(), Regex::star(regex))
+                }
+                '?' => {
+                    self.require_posix(operator)?;
+                    Regex::union(Regex::Epsilon, regex)
+                }
+                '{' => {
+                    self.require_posix(operator)?;
+                    let (min, max) = self.parse_bounds()?;
+                    // The minimum is required, the rest is optional or, without a maximum, repeated arbitrarily often.
+                    let optional = match max {
+                        Some(max) => vec![Regex::union(Regex::Epsilon, regex.clone()); max - min],
+                        None => vec![Regex::star(regex.clone())],
+                    };
+                    vec![regex; min].into_iter().chain(optional).fold1(Regex::concatenation).unwrap_or(Regex::Epsilon)
+                }
+                _ => break,
+            };
+            self.position += 1;
+        }
+        Ok(regex)
+    }
+
+    /// Parses bounds like `{2}`, `{2,}` or `{2,3}` into the minimum and the optional maximum. The position is left at the closing brace.
+    fn parse_bounds(&mut self) -> Result<(usize, Option<usize>), RegexParseError> {
+        let start = self.position;
+        self.position += 1;
+        let min = self.parse_number(start)?;
+        let max = match self.peek() {
+            Some(',') => {
+                self.position += 1;
+                match self.peek() {
+                    Some('}') => None,
+                    _ => Some(self.parse_number(start)?),
+                }
+            }
+            _ => Some(min),
+        };
+        match self.peek() {
+            Some('}') => {}
+            Some(character) => return Err(RegexParseError::UnexpectedCharacter { character, position: self.position }),
+            None => return Err(RegexParseError::Unclosed { position: start }),
+        }
+        match max {
+            Some(max) if max < min => Err(RegexParseError::InvalidBounds { min, max }),
+            _ => Ok((min, max)),
+        }
+    }
+
+    fn parse_number(&mut self, start: usize) -> Result<usize, RegexParseError> {
+        let position = self.position;
+        while self.peek().map_or(false, |character| character.is_ascii_digit()) {
+            self.position += 1;
+        }
+        match self.chars.get(position) {
+            None => Err(RegexParseError::Unclosed { position: start }),
+            Some(character) if position == self.position => Err(RegexParseError::UnexpectedCharacter { character: *character, position }),
+            Some(character) => self.chars[position..self.position].iter().collect::<String>().parse()
+                .map_err(|_| RegexParseError::UnexpectedCharacter { character: *character, position }),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Regex, RegexParseError> {
+        let position = self.position;
+        match self.next()? {
+            '(' => {
+                let regex = self.parse_union()?;
+                match self.peek() {
+                    Some(')') => {
+                        self.position += 1;
+                        Ok(regex)
+                    }
+                    _ => Err(RegexParseError::Unclosed { position }),
+                }
+            }
+            '[' => {
+                self.position = position;
+                self.require_posix('[')?;
+                self.position += 1;
+                self.parse_bracket_expression(position)
+            }
+            '\\' => {
+                self.position = position;
+                self.require_posix('\\')?;
+                self.position += 1;
+                Ok(Regex::Symbol(self.next()?))
+            }
+            construct @ 'ε' | construct @ '∅' if self.dialect == RegexDialect::PosixEre => {
+                Err(RegexParseError::UnsupportedConstruct { construct, position, dialect: self.dialect })
+            }
+            'ε' => Ok(Regex::Epsilon),
+            '∅' => Ok(Regex::Empty),
+            construct @ '+' | construct @ '?' | construct @ ']' | construct @ '{' if self.dialect == RegexDialect::Textbook => {
+                Err(RegexParseError::UnsupportedConstruct { construct, position, dialect: self.dialect })
+            }
+            // Textbooks have no such metacharacters, so they are ordinary symbols there.
+            construct @ '.' | construct @ '^' | construct @ '$' if self.dialect != RegexDialect::Textbook => {
+                Err(RegexParseError::Unsupported { construct, position })
+            }
+            character @ '|' | character @ ')' | character @ '*' | character @ '+' | character @ '?' | character @ ']' | character @ '{' => {
+                Err(RegexParseError::UnexpectedCharacter { character, position })
+            }
+            symbol => Ok(Regex::Symbol(symbol)),
+        }
+    }
+
+    /// Parses the rest of a bracket expression like `[a-z_]` into a union of its symbols. Negated expressions are not supported,
+    /// as they depend on the alphabet.
+    fn parse_bracket_expression(&mut self, start: usize) -> Result<Regex, RegexParseError> {
+        let mut symbols = Vec::new();
+        loop {
+            let position = self.position;
+            let from = match self.next() {
+                Ok(']') if !symbols.is_empty() => break,
+                Ok('^') if symbols.is_empty() => {
+                    return Err(RegexParseError::Unsupported { construct: '^', position });
+                }
+                Ok('\\') => self.next().map_err(|_| RegexParseError::Unclosed { position: start })?,
+                Ok(symbol) => symbol,
+                Err(_) => return Err(RegexParseError::Unclosed { position: start }),
+            };
+            if self.peek() == Some('-') && self.chars.get(self.position + 1).map_or(false, |to| *to != ']') {
+                self.position += 1;
+                let to = self.next()?;
+                if to < from {
+                    return Err(RegexParseError::InvalidRange { from, to });
+                }
+                symbols.extend(from..=to);
+            } else {
+                symbols.push(from);
+            }
+        }
+        Ok(symbols.into_iter().sorted().dedup().map(Regex::Symbol).fold1(Regex::union).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod regex_parser_tests {
+    use itertools::Itertools;
+
+    use crate::{Regex, RegexDialect, RegexParseError};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Regex::parse("(a|bc)*d").unwrap().to_string(), "(a|bc)*d");
+        assert_eq!(Regex::parse("a+b?").unwrap().to_string(), "aa*(ε|b)");
+        let class = ['_', 'a', 'b', 'c'].iter().map(|symbol| Regex::Symbol(*symbol)).fold1(Regex::union).unwrap();
+        assert_eq!(Regex::parse("[a-c_]\\*").unwrap(), Regex::concatenation(class, Regex::Symbol('*')), "the star is escaped");
+        assert_eq!(Regex::parse("ε|∅").unwrap(), Regex::union(Regex::Epsilon, Regex::Empty));
+        assert_eq!(Regex::parse("(ab"), Err(RegexParseError::Unclosed { position: 0 }));
+        assert_eq!(Regex::parse("a|*"), Err(RegexParseError::UnexpectedCharacter { character: '*', position: 2 }));
+        assert_eq!(Regex::parse("[z-a]"), Err(RegexParseError::InvalidRange { from: 'z', to: 'a' }));
+    }
+
+    #[test]
+    fn test_parse_with_dialect() {
+        let error = Regex::parse_with_dialect("ab+", RegexDialect::Textbook).unwrap_err();
+        assert_eq!(error, RegexParseError::UnsupportedConstruct { construct: '+', position: 2, dialect: RegexDialect::Textbook });
+        assert_eq!(error.to_string(), "+ at position 2 is not supported by the textbook dialect");
+        assert!(Regex::parse_with_dialect("a*|ε", RegexDialect::Textbook).is_ok());
+        assert!(Regex::parse_with_dialect("a*|ε", RegexDialect::PosixEre).is_err());
+        assert!(Regex::parse_with_dialect("[0-9]+", RegexDialect::PosixEre).is_ok());
+        let word = |word: &str| word.chars().map(Regex::Symbol).fold1(Regex::concatenation).unwrap();
+        assert_eq!(Regex::parse_with_dialect("a.b$", RegexDialect::Textbook).unwrap(), word("a.b$"), "there are no anchors in textbooks");
+        let error = Regex::parse_with_dialect("[^a]", RegexDialect::PosixEre).unwrap_err();
+        assert_eq!(error, RegexParseError::Unsupported { construct: '^', position: 1 });
+        assert_eq!(error.to_string(), "^ at position 1 is not supported");
+        assert_eq!(Regex::parse_with_dialect("a.b", RegexDialect::PosixEre), Err(RegexParseError::Unsupported { construct: '.', position: 1 }));
+        assert_eq!(Regex::parse_with_dialect("^ab$", RegexDialect::Extended), Err(RegexParseError::Unsupported { construct: '^', position: 0 }));
+        assert_eq!(Regex::parse_with_dialect("a\\.b", RegexDialect::PosixEre).unwrap(), word("a.b"), "escaped dots are symbols");
+    }
+
+    #[test]
+    fn test_parse_bounded_repetition() {
+        assert_eq!(Regex::parse("a{3}").unwrap().to_string(), "aaa");
+        assert_eq!(Regex::parse("(ab){1,3}").unwrap().to_string(), "ab(ε|ab)(ε|ab)");
+        assert_eq!(Regex::parse("a{2,}").unwrap().to_string(), "aaa*");
+        assert_eq!(Regex::parse("ba{0}").unwrap().to_string(), "bε");
+        let dfa = Regex::parse_with_dialect("[ab]{2,3}", RegexDialect::PosixEre).unwrap().to_dfa();
+        assert!(dfa.check("ab").0 && dfa.check("bab").0);
+        assert!(!dfa.check("a").0 && !dfa.check("abab").0);
+        assert_eq!(Regex::parse("a{3,2}"), Err(RegexParseError::InvalidBounds { min: 3, max: 2 }));
+        assert_eq!(Regex::parse("a{2"), Err(RegexParseError::Unclosed { position: 1 }));
+        assert_eq!(Regex::parse("a{x}"), Err(RegexParseError::UnexpectedCharacter { character: 'x', position: 2 }));
+        assert_eq!(Regex::parse("{2}"), Err(RegexParseError::UnexpectedCharacter { character: '{', position: 0 }));
+        let error = Regex::parse_with_dialect("a{2}", RegexDialect::Textbook).unwrap_err();
+        assert_eq!(error, RegexParseError::UnsupportedConstruct { construct: '{', position: 1, dialect: RegexDialect::Textbook });
+    }
+}
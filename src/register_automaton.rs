@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+/// Compares the data value that is currently read with the content of a register.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum RegisterTest {
+    /// Satisfied if the register holds the current data value. An empty register never satisfies this test.
+    Equal(usize),
+    /// Satisfied if the register does not hold the current data value. An empty register always satisfies this test.
+    NotEqual(usize),
+}
+
+/// Describes to which next state a register automaton may switch when it reads a certain label while being in a certain state
+/// and all register tests are satisfied. Afterwards, the current data value is stored into the registers listed in `stores`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct RegisterTransition {
+    state: String,
+    input: char,
+    tests: Vec<RegisterTest>,
+    stores: Vec<usize>,
+    next_state: String,
+}
+
+/// # Register automaton
+/// A nondeterministic automaton reading data words, i.e. sequences of labels paired with data values from a possibly
+/// infinite domain like session IDs. The automaton has finitely many registers which start empty and can store data values,
+/// so transitions can compare the current data value with values that have been read before.
+/// The automaton does not depend on the type of the data values, which is only determined when a data word is checked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterAutomaton {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<RegisterTransition>,
+}
+
+impl RegisterAutomaton {
+    /// Creates a register automaton without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> RegisterAutomaton {
+        RegisterAutomaton {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, tests: Vec<RegisterTest>, stores: Vec<usize>, next_state: &str) {
+        self.transitions.push(RegisterTransition { state: state.to_string(), input, tests, stores, next_state: next_state.to_string() });
+    }
+
+    /// The number of registers is determined by the highest register index used by any transition.
+    pub fn get_register_count(&self) -> usize {
+        self.transitions.iter()
+            .flat_map(|transition| transition.tests.iter()
+                .map(|test| match test {
+                    RegisterTest::Equal(register) | RegisterTest::NotEqual(register) => *register,
+                })
+                .chain(transition.stores.iter().cloned()))
+            .map(|register| register + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Checks whether a data word is accepted. As the automaton might be nondeterministic,
+    /// all possible combinations of states and register contents are tracked simultaneously.
+    pub fn check<D: Eq + Hash + Clone>(&self, data_word: &[(char, D)]) -> bool {
+        let mut configurations: HashSet<(&str, Vec<Option<D>>)> = HashSet::new();
+        configurations.insert((&self.start_state, vec![None; self.get_register_count()]));
+        for (input, value) in data_word {
+            let mut next_configurations = HashSet::new();
+            for (state, registers) in &configurations {
+                let enabled_transitions = self.transitions.iter().filter(|transition| {
+                    transition.state == *state && transition.input == *input && transition.tests.iter().all(|test| match test {
+                        RegisterTest::Equal(register) => registers[*register].as_ref() == Some(value),
+                        RegisterTest::NotEqual(register) => registers[*register].as_ref() != Some(value),
+                    })
+                });
+                for transition in enabled_transitions {
+                    let mut next_registers = registers.clone();
+                    for register in &transition.stores {
+                        next_registers[*register] = Some(value.clone());
+                    }
+                    next_configurations.insert((&transition.next_state[..], next_registers));
+                }
+            }
+            configurations = next_configurations;
+        }
+        configurations.iter().any(|(state, _)| self.accept_states.contains(*state))
+    }
+}
+
+#[cfg(test)]
+mod register_automaton_tests {
+    use crate::{RegisterAutomaton, RegisterTest};
+
+    #[test]
+    fn test_check_sessions() {
+        // Accepts logs in which every session is closed with the ID it was opened with, before the next one is opened.
+        let mut automaton = RegisterAutomaton::new("well-nested sessions", "idle");
+        automaton.add_transition("idle", 'o', vec![], vec![0], "open");
+        automaton.add_transition("open", 'c', vec![RegisterTest::Equal(0)], vec![], "idle");
+        automaton.add_accept_state("idle");
+        assert_eq!(automaton.get_register_count(), 1);
+        assert!(automaton.check(&[('o', "s1"), ('c', "s1"), ('o', "s2"), ('c', "s2")]));
+        assert!(!automaton.check(&[('o', "s1"), ('c', "s2")]));
+        assert!(!automaton.check(&[('o', "s1")]));
+    }
+
+    #[test]
+    fn test_check_nondeterministic() {
+        // Accepts data words in which some value occurs twice. The automaton guesses the first occurrence.
+        let mut automaton = RegisterAutomaton::new("some value repeats", "q0");
+        automaton.add_transition("q0", 'x', vec![], vec![], "q0");
+        automaton.add_transition("q0", 'x', vec![], vec![0], "q1");
+        automaton.add_transition("q1", 'x', vec![RegisterTest::NotEqual(0)], vec![], "q1");
+        automaton.add_transition("q1", 'x', vec![RegisterTest::Equal(0)], vec![], "q2");
+        automaton.add_transition("q2", 'x', vec![], vec![], "q2");
+        automaton.add_accept_state("q2");
+        assert!(automaton.check(&[('x', 1), ('x', 2), ('x', 1), ('x', 3)]));
+        assert!(!automaton.check(&[('x', 1), ('x', 2), ('x', 3)]));
+    }
+}
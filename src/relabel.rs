@@ -0,0 +1,136 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::language_hash::{FNV_OFFSET_BASIS, FNV_PRIME};
+use crate::{fresh_state_name, Dfa, Transition};
+
+/// How `Dfa::relabel` chooses the new names of the states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelabelScheme {
+    /// Names the states "q0", "q1" and so on in the order in which the breath first algorithm discovers them, reading symbols in alphabetical order.
+    /// Unreachable states follow in the alphabetical order of their old names.
+    Sequential,
+    /// Names the states "q0", "q1" and so on in an order shuffled with the seed, so that the numbering does not reveal the structure.
+    /// The same seed always leads to the same names.
+    Random(u64),
+    /// Names every state after a hash of its old name and the key, like "s3f9a1c2e7b6d5041", so that a state gets the same name
+    /// in every automaton relabeled with the same key. Without the key, the old names cannot be recovered by hashing guessed names.
+    Hash(u64),
+}
+
+/// Advances the state of the [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator and returns the next pseudorandom number.
+fn next_random_number(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut number = *state;
+    number = (number ^ (number >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    number = (number ^ (number >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    number ^ (number >> 31)
+}
+
+impl Dfa {
+    /// Renames all states according to the scheme without changing the accepted language, e.g. to strip meaningful internal names
+    /// before sharing the automaton. The start state, accept states, transitions, accept tags, costs and metadata are renamed consistently.
+    /// The origins in the metadata are removed, as they would reveal the names of the automata the states were constructed from.
+    /// Returns a hash map from the old to the new names of all states.
+    pub fn relabel(&mut self, scheme: RelabelScheme) -> HashMap<String, String> {
+        let states = self.get_states_in_breath_first_order();
+        let renamings: HashMap<String, String> = match scheme {
+            RelabelScheme::Sequential => states.into_iter().enumerate().map(|(index, state)| (state, format!("q{}", index))).collect(),
+            RelabelScheme::Random(seed) => {
+                let mut states = states.into_iter().sorted().collect_vec();
+                let mut random_state = seed;
+                // Shuffle with the algorithm of Fisher and Yates.
+                for index in (1..states.len()).rev() {
+                    states.swap(index, (next_random_number(&mut random_state) % (index as u64 + 1)) as usize);
+                }
+                states.into_iter().enumerate().map(|(index, state)| (state, format!("q{}", index))).collect()
+            }
+            RelabelScheme::Hash(key) => {
+                let mut new_names = HashSet::new();
+                let mut renamings = HashMap::new();
+                for state in states.into_iter().sorted() {
+                    let hash = key.to_le_bytes().iter().chain(state.as_bytes())
+                        .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME));
+                    // Hashes of different names collide very rarely, but the names must stay distinct.
+                    let new_name = fresh_state_name(&format!("s{:016x}", hash), &new_names);
+                    new_names.insert(new_name.clone());
+                    renamings.insert(state, new_name);
+                }
+                renamings
+            }
+        };
+        self.rename_states(&renamings);
+        for metadata in self.state_metadata.values_mut() {
+            metadata.origin.clear();
+        }
+        renamings
+    }
+
+    /// Returns all states in the order in which the breath first algorithm discovers them, reading symbols in alphabetical order,
+    /// followed by the unreachable states in alphabetical order.
+    fn get_states_in_breath_first_order(&self) -> Vec<String> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let mut visited_states = HashSet::new();
+        let mut states = Vec::new();
+        let mut states_to_visit = VecDeque::new();
+        visited_states.insert(dfa.start_state.clone());
+        states_to_visit.push_back(dfa.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in dfa.transitions.iter().filter(|transition| transition.state == state).sorted() {
+                if visited_states.insert(transition.next_state.clone()) {
+                    states_to_visit.push_back(transition.next_state.clone());
+                }
+            }
+            states.push(state);
+        }
+        states.extend(self.get_all_mentioned_states().into_iter().filter(|state| !visited_states.contains(state)).sorted());
+        states
+    }
+
+    /// Renames the states according to the hash map, which must not map two states to the same name. States without an entry keep their names.
+    pub(crate) fn rename_states(&mut self, renamings: &HashMap<String, String>) {
+        let rename = |state: &String| renamings.get(state).unwrap_or(state).clone();
+        self.start_state = rename(&self.start_state);
+        self.accept_states = self.accept_states.iter().map(rename).collect();
+        self.transitions = self.transitions.iter()
+            .map(|transition| Transition { state: rename(&transition.state), input: transition.input, next_state: rename(&transition.next_state) })
+            .collect();
+        self.accept_tags = self.accept_tags.iter().map(|(state, tag)| (rename(state), tag.clone())).collect();
+        self.state_metadata = self.state_metadata.iter().map(|(state, metadata)| (rename(state), metadata.clone())).collect();
+        self.transition_costs = self.transition_costs.iter().map(|((state, input), cost)| ((rename(state), *input), *cost)).collect();
+    }
+}
+
+#[cfg(test)]
+mod relabel_tests {
+    use crate::{Dfa, RelabelScheme};
+
+    #[test]
+    fn test_relabel() {
+        // Accepts words over 'a' and 'b' ending with "ab".
+        let dfa = Dfa::from_table(
+            &["secret_start", "seen_a", "seen_ab"],
+            &['a', 'b'],
+            &[&[Some(1), Some(0)], &[Some(1), Some(2)], &[Some(1), Some(0)]],
+            0,
+            &[2],
+        ).unwrap();
+        let mut sequential = dfa.clone();
+        let renamings = sequential.relabel(RelabelScheme::Sequential);
+        assert_eq!(renamings["seen_ab"], "q2");
+        assert_eq!(sequential.start_state, "q0");
+        assert!(sequential.is_equivalent(&dfa));
+        let mut random = dfa.clone();
+        let renamings = random.relabel(RelabelScheme::Random(42));
+        let mut random_again = dfa.clone();
+        assert_eq!(random_again.relabel(RelabelScheme::Random(42)), renamings, "the same seed should lead to the same names");
+        assert!(random.is_equivalent(&dfa));
+        let mut hashed = dfa.clone();
+        let renamings = hashed.relabel(RelabelScheme::Hash(7));
+        assert!(renamings.values().all(|name| name.len() == 17 && name.starts_with('s')));
+        assert!(hashed.accept_states.contains(&renamings["seen_ab"]));
+        assert!(hashed.is_equivalent(&dfa));
+    }
+}
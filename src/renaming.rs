@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Dfa;
+
+/// Describes why a renaming of states cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenamingError {
+    /// The state is renamed to a state that is renamed itself, so the renaming does not map merged states to one representative.
+    ChainedRenaming { state: String, new_name: String },
+    /// The renaming leads to a state that does not exist in the DFA it was stored with.
+    UnknownState(String),
+}
+
+impl fmt::Display for RenamingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenamingError::ChainedRenaming { state, new_name } => write!(f, "the state {} is renamed to {}, which is renamed itself", state, new_name),
+            RenamingError::UnknownState(state) => write!(f, "the state {} does not exist", state),
+        }
+    }
+}
+
+impl std::error::Error for RenamingError {}
+
+/// The renaming operations of `Dfa::minimize`, which map every merged state to the state it was merged into.
+/// States without an entry kept their names. Unlike the plain hash map, it can be serialized, which checks it on deserialization,
+/// and it can translate state names in both directions, e.g. to map logged traces of the original DFA onto the minimized one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateRenaming {
+    renamings: BTreeMap<String, String>,
+}
+
+impl StateRenaming {
+    /// Checks that the renaming maps states to representatives, which keep their names if they are renamed at all.
+    pub fn new(renamings: HashMap<String, String>) -> Result<StateRenaming, RenamingError> {
+        let renamings: BTreeMap<String, String> = renamings.into_iter().collect();
+        for (state, new_name) in &renamings {
+            if renamings.get(new_name).map_or(false, |representative| representative != new_name) {
+                return Err(RenamingError::ChainedRenaming { state: state.clone(), new_name: new_name.clone() });
+            }
+        }
+        Ok(StateRenaming { renamings })
+    }
+
+    /// Returns the new name of the state.
+    pub fn apply<'a>(&'a self, state: &'a str) -> &'a str {
+        self.renamings.get(state).map(String::as_str).unwrap_or(state)
+    }
+
+    /// Returns the new names of the states, e.g. of a trace of states logged by the original DFA.
+    pub fn apply_to_trace(&self, states: &[&str]) -> Vec<String> {
+        states.iter().map(|state| self.apply(state).to_string()).collect()
+    }
+
+    /// Returns the old names of all states that were renamed to the state, sorted by name, or the state itself if no state was renamed to it.
+    pub fn undo(&self, state: &str) -> Vec<String> {
+        let old_names = self.renamings.iter().filter(|(_, new_name)| *new_name == state).map(|(old_name, _)| old_name.clone()).collect_vec();
+        match old_names.is_empty() {
+            true => vec![state.to_string()],
+            false => old_names,
+        }
+    }
+}
+
+impl Serialize for StateRenaming {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.renamings.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StateRenaming {
+    /// Fails if a state is renamed to a state that is renamed itself (see `StateRenaming::new`).
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<StateRenaming, D::Error> {
+        let renamings = HashMap::<String, String>::deserialize(deserializer)?;
+        StateRenaming::new(renamings).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A minimized DFA together with the renaming that produced it, so that both can be stored and loaded together.
+#[derive(Debug, Clone, Serialize)]
+pub struct MinimizedDfa {
+    pub dfa: Dfa,
+    pub renaming: StateRenaming,
+}
+
+#[derive(Deserialize)]
+struct SerializedMinimizedDfa {
+    dfa: Dfa,
+    renaming: StateRenaming,
+}
+
+impl<'de> Deserialize<'de> for MinimizedDfa {
+    /// Fails if the renaming leads to a state that does not exist in the DFA, which means they do not belong together.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<MinimizedDfa, D::Error> {
+        let SerializedMinimizedDfa { dfa, renaming } = SerializedMinimizedDfa::deserialize(deserializer)?;
+        let states = dfa.get_all_mentioned_states();
+        if let Some(new_name) = renaming.renamings.values().sorted().find(|new_name| !states.contains(*new_name)) {
+            return Err(serde::de::Error::custom(RenamingError::UnknownState(new_name.clone())));
+        }
+        Ok(MinimizedDfa { dfa, renaming })
+    }
+}
+
+impl Dfa {
+    /// Minimizes a copy of the DFA like `minimize` and keeps the renaming operations along with it.
+    pub fn to_minimized(&self) -> MinimizedDfa {
+        let mut dfa = self.clone();
+        let renamings = dfa.minimize();
+        // Every merged state is mapped to the representative of its class, which maps to itself, so no check is needed.
+        MinimizedDfa { dfa, renaming: StateRenaming { renamings: renamings.into_iter().collect() } }
+    }
+}
+
+#[cfg(test)]
+mod renaming_tests {
+    use serde::de::value::{Error, MapDeserializer};
+    use serde::Deserialize;
+
+    use crate::{Dfa, StateRenaming};
+
+    #[test]
+    fn test_state_renaming() {
+        // Accepts words over 'a' of odd length, using four states where two would suffice.
+        let dfa = Dfa::from_table(&["q0", "q1", "q2", "q3"], &['a'], &[&[Some(1)], &[Some(2)], &[Some(3)], &[Some(0)]], 0, &[1, 3]).unwrap();
+        let minimized = dfa.to_minimized();
+        assert_eq!(minimized.renaming.apply_to_trace(&["q0", "q1", "q2", "q3"]), vec!["q0", "q1", "q0", "q1"]);
+        assert_eq!(minimized.renaming.undo("q1"), vec!["q1", "q3"]);
+        let deserialize = |renamings: Vec<(&str, &str)>| StateRenaming::deserialize(MapDeserializer::<_, Error>::new(renamings.into_iter()));
+        assert_eq!(deserialize(vec![("q0", "q0"), ("q2", "q0")]).unwrap().apply("q2"), "q0");
+        assert!(deserialize(vec![("q0", "q2"), ("q2", "q4")]).is_err(), "q2 cannot be a representative and be renamed");
+    }
+}
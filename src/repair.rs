@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::{Bounded, Dfa, Fuel, Mutation};
+
+impl Dfa {
+    /// Searches a smallest set of edits that makes the DFA accept all positive and reject all negative samples.
+    /// Edits retarget, add or remove transitions (see `Mutation`) or toggle the acceptance of states, but never introduce new states,
+    /// so the repaired DFA stays as close to the original as possible. Apply the returned edit script with `apply_mutation`.
+    /// Returns None if no repair with at most `max_edits` edits exists. The search tries all combinations of edits
+    /// with increasing size, so it is only feasible for small DFAs and small numbers of edits, see `repair_with_fuel` for a bounded search.
+    pub fn repair(&self, positive: &[&str], negative: &[&str], max_edits: usize) -> Option<Vec<Mutation>> {
+        self.repair_with_fuel(positive, negative, max_edits, &mut Fuel::unlimited()).done().flatten()
+    }
+
+    /// Searches a repair like `repair`, but tries at most as many edit scripts as there is fuel,
+    /// and tells apart DFAs without a repair from searches for which the fuel ran out.
+    pub fn repair_with_fuel(&self, positive: &[&str], negative: &[&str], max_edits: usize, fuel: &mut Fuel) -> Bounded<Option<Vec<Mutation>>> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        let mut alphabet = dfa.get_alphabet();
+        alphabet.extend(positive.iter().chain(negative.iter()).flat_map(|word| word.chars()));
+        let mut candidates = Vec::new();
+        for state in &states {
+            candidates.push(Mutation::FlipAcceptance { state: state.clone() });
+        }
+        for state in &states {
+            for input in alphabet.iter().sorted() {
+                let current_next_state = dfa.get_transition(state, input).map(|transition| transition.next_state.clone());
+                if current_next_state.is_some() {
+                    candidates.push(Mutation::RemoveTransition { state: state.clone(), input: *input });
+                }
+                for next_state in states.iter().filter(|next_state| Some(*next_state) != current_next_state.as_ref()) {
+                    candidates.push(Mutation::RedirectTransition { state: state.clone(), input: *input, next_state: next_state.clone() });
+                }
+            }
+        }
+        let is_consistent = |dfa: &Dfa| positive.iter().all(|word| dfa.check(word).0) && negative.iter().all(|word| !dfa.check(word).0);
+        for edit_count in 0..=max_edits {
+            for edits in candidates.iter().combinations(edit_count) {
+                // Two edits of the same state or transition would overwrite each other, so a smaller edit script exists.
+                let mut edited_positions = HashSet::new();
+                let has_conflict = edits.iter().any(|edit| !edited_positions.insert(match edit {
+                    Mutation::FlipAcceptance { state } => (state, None),
+                    Mutation::RedirectTransition { state, input, .. } | Mutation::RemoveTransition { state, input } => (state, Some(*input)),
+                }));
+                if has_conflict {
+                    continue;
+                }
+                if !fuel.consume() {
+                    return Bounded::Exhausted;
+                }
+                let mut repaired_dfa = dfa.clone();
+                for edit in &edits {
+                    repaired_dfa.apply_mutation(edit);
+                }
+                if is_consistent(&repaired_dfa) {
+                    return Bounded::Done(Some(edits.into_iter().cloned().collect()));
+                }
+            }
+        }
+        Bounded::Done(None)
+    }
+}
+
+#[cfg(test)]
+mod repair_tests {
+    use crate::{Bounded, Dfa, Fuel, Mutation};
+
+    #[test]
+    fn test_repair() {
+        // Supposed to accept words over 'a' and 'b' ending with 'b', but 'b' in state q1 wrongly leads back to q0.
+        let dfa = Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(0)]], 0, &[1]).unwrap();
+        let positive = ["b", "ab", "bb", "abb"];
+        let negative = ["", "a", "ba", "bba"];
+        let edits = dfa.repair(&positive, &negative, 2).unwrap();
+        assert_eq!(edits, vec![Mutation::RedirectTransition { state: "q1".to_string(), input: 'b', next_state: "q1".to_string() }]);
+        let mut repaired_dfa = dfa.clone();
+        repaired_dfa.apply_mutation(&edits[0]);
+        assert!(repaired_dfa.check("abbb").0);
+        assert_eq!(dfa.repair(&["a"], &["a"], 3), None, "contradicting samples cannot be repaired");
+        assert_eq!(dfa.repair_with_fuel(&["a"], &["a"], 3, &mut Fuel::new(100)), Bounded::Exhausted);
+    }
+}
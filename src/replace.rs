@@ -0,0 +1,53 @@
+use crate::Dfa;
+
+impl Dfa {
+    /// Replaces every substring accepted by the DFA by the replacement, like the replacement function of regex libraries.
+    /// See `replace_with` for how the substrings are found.
+    pub fn replace(&self, text: &str, replacement: &str) -> String {
+        self.replace_with(text, |_| replacement.to_string())
+    }
+
+    /// Replaces every substring accepted by the DFA by the result of the callback, which receives the accepted substring.
+    /// The text is scanned from left to right. At every position, the longest accepted substring is replaced and scanning
+    /// continues after it. Characters that are not part of any non-empty accepted substring are copied unchanged.
+    pub fn replace_with<F: FnMut(&str) -> String>(&self, text: &str, mut replacement: F) -> String {
+        let alphabet = self.get_alphabet();
+        let mut result = String::new();
+        let mut position = 0;
+        while position < text.len() {
+            match self.find_longest_match_end(text, position, &alphabet) {
+                Some(end) if end > position => {
+                    result += &replacement(&text[position..end]);
+                    position = end;
+                }
+                _ => {
+                    let char = text[position..].chars().next().unwrap();
+                    result.push(char);
+                    position += char.len_utf8();
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod replace_tests {
+    use crate::Dfa;
+
+    /// Creates a DFA accepting non-empty binary numbers.
+    fn create_example_dfa() -> Dfa {
+        Dfa::from_table(&["q0", "q1"], &['0', '1'], &[&[Some(1), Some(1)], &[Some(1), Some(1)]], 0, &[1]).unwrap()
+    }
+
+    #[test]
+    fn test_replace() {
+        assert_eq!(create_example_dfa().replace("10 or 011, not 2", "#"), "# or #, not 2");
+    }
+
+    #[test]
+    fn test_replace_with() {
+        let replaced = create_example_dfa().replace_with("10 or 011", |number| u32::from_str_radix(number, 2).unwrap().to_string());
+        assert_eq!(replaced, "2 or 3");
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Checks whether the DFA is reversible, meaning that no symbol leads
+    /// from two different states into the same state. Then the previous state can always be recovered from the current one
+    /// and the last symbol, so the reversed automaton is deterministic except for having several start states.
+    /// Only states that can be reached and from which an accept state can be reached are considered.
+    pub fn is_reversible(&self) -> bool {
+        let dfa = self.get_trimmed();
+        let transitions: HashSet<_> = dfa.transitions.iter().collect();
+        let reversed_transitions: HashSet<_> = transitions.iter().map(|transition| (&transition.next_state, transition.input)).collect();
+        transitions.len() == reversed_transitions.len()
+    }
+
+    /// Checks whether both the DFA and its reversal are deterministic, i.e. whether the DFA is reversible and has exactly one accept state.
+    /// These are the zero-reversible automata in the sense of Angluin.
+    pub fn is_bideterministic(&self) -> bool {
+        self.get_trimmed().accept_states.len() == 1 && self.is_reversible()
+    }
+
+    /// Checks whether the DFA is k-reversible in the sense of Angluin, i.e. its reversal is deterministic with a lookahead of k symbols.
+    /// This is the case if no two different states that are both accepting, or that both lead into the same state with the same symbol,
+    /// have a common k-leader. A k-leader of a state is a word of length k that leads into it from some state.
+    /// A DFA is zero-reversible if and only if it is bideterministic.
+    pub fn is_k_reversible(&self, k: usize) -> bool {
+        let dfa = self.get_trimmed();
+        let mut conflicting_pairs: HashSet<(&String, &String)> = dfa.accept_states.iter()
+            .tuple_combinations()
+            .collect();
+        for (first, second) in dfa.transitions.iter().tuple_combinations() {
+            if first.next_state == second.next_state && first.input == second.input && first.state != second.state {
+                conflicting_pairs.insert((&first.state, &second.state));
+            }
+        }
+        // Pairs of states that are reached by a common word from the conflicting pairs, read backwards.
+        let mut pairs = conflicting_pairs;
+        for _ in 0..k {
+            pairs = dfa.transitions.iter()
+                .cartesian_product(dfa.transitions.iter())
+                .filter(|(first, second)| first.input == second.input && pairs.contains(&(&first.next_state, &second.next_state)))
+                .map(|(first, second)| (&first.state, &second.state))
+                .collect();
+        }
+        pairs.is_empty()
+    }
+
+    /// Returns a copy of the DFA restricted to the states that are reachable and from which an accept state can be reached.
+    fn get_trimmed(&self) -> Dfa {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let useful_states: HashSet<String> = dfa.get_reachable_states().intersection(&dfa.get_productive_states()).cloned().collect();
+        dfa.transitions.retain(|transition| useful_states.contains(&transition.state) && useful_states.contains(&transition.next_state));
+        dfa.accept_states.retain(|state| useful_states.contains(state));
+        dfa
+    }
+}
+
+#[cfg(test)]
+mod reversibility_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_is_k_reversible() {
+        // Accepts words over 'a' of even length.
+        let even = Dfa::from_table(&["even", "odd"], &['a'], &[&[Some(1)], &[Some(0)]], 0, &[0]).unwrap();
+        assert!(even.is_reversible());
+        assert!(even.is_bideterministic());
+        assert!(even.is_k_reversible(0));
+        // Accepts "ab" and "bb": 'b' leads from q1 and q2 into q3, and both states have the 0-leader but no common 1-leader.
+        let ab_or_bb = Dfa::from_table(
+            &["q0", "q1", "q2", "q3"],
+            &['a', 'b'],
+            &[&[Some(1), Some(2)], &[None, Some(3)], &[None, Some(3)], &[None, None]],
+            0,
+            &[3],
+        ).unwrap();
+        assert!(!ab_or_bb.is_reversible());
+        assert!(!ab_or_bb.is_k_reversible(0));
+        assert!(ab_or_bb.is_k_reversible(1));
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::export::symbol_runs;
+use crate::{find_nondeterministic_transition, AcceptTag, Dfa, MissingTransitionPolicy, Nfa, StateMetadata, Transition};
+
+/// Named symbol classes that can be used instead of a single symbol in serialized transitions.
+/// The class "any", standing for the whole declared alphabet, is handled separately.
+const NAMED_SYMBOL_CLASSES: [(&str, char, char); 3] = [("digits", '0', '9'), ("lowercase", 'a', 'z'), ("uppercase", 'A', 'Z')];
+
+/// Describes why the input of a serialized transition cannot be expanded into symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolClassError {
+    /// The input is neither a single symbol, nor a named class, nor a range like "a-f".
+    UnknownSymbolClass(String),
+    /// The class "any" stands for the declared alphabet, so it can only be used if the alphabet is declared.
+    AnyWithoutAlphabet,
+}
+
+impl fmt::Display for SymbolClassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolClassError::UnknownSymbolClass(class) => write!(f, "{} is neither a symbol nor a known symbol class", class),
+            SymbolClassError::AnyWithoutAlphabet => write!(f, "the symbol class any requires a declared alphabet"),
+        }
+    }
+}
+
+impl std::error::Error for SymbolClassError {}
+
+/// An automaton read from a serialized DFA, whose transitions might turn out to be nondeterministic.
+/// Deserializing a `Dfa` fails for nondeterministic transitions, while deserializing a `LoadedAutomaton` falls back to an `Nfa`.
+/// As NFAs have no alphabet, missing transition policy and state metadata, those are dropped in that case.
+#[derive(Debug, Clone)]
+pub enum LoadedAutomaton {
+    Dfa(Dfa),
+    Nfa(Nfa),
+}
+
+/// A transition as it is serialized. The input is either a single symbol or a symbol class:
+/// "digits", "lowercase", "uppercase", "any" (the declared alphabet) or an inclusive range like "a-f".
+#[derive(Serialize, Deserialize)]
+struct SerializedTransition {
+    state: String,
+    input: String,
+    next_state: String,
+}
+
+/// The serialized form of a DFA, which only differs from the DFA itself in its transitions and the layout of transition costs.
+#[derive(Serialize, Deserialize)]
+struct SerializedDfa {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<SerializedTransition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alphabet: Option<HashSet<char>>,
+    #[serde(default)]
+    missing_transitions: MissingTransitionPolicy,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    state_metadata: HashMap<String, StateMetadata>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    accept_tags: HashMap<String, AcceptTag>,
+    /// The costs of transitions by state and input, as tuples cannot be keys of JSON objects.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    transition_costs: BTreeMap<String, BTreeMap<char, u64>>,
+}
+
+/// Expands the input of a serialized transition into the symbols it stands for.
+fn expand_symbol_class(class: &str, alphabet: &Option<HashSet<char>>) -> Result<Vec<char>, SymbolClassError> {
+    let characters = class.chars().collect_vec();
+    if let [symbol] = characters[..] {
+        return Ok(vec![symbol]);
+    }
+    if class == "any" {
+        return alphabet.as_ref().map(|alphabet| alphabet.iter().cloned().collect()).ok_or(SymbolClassError::AnyWithoutAlphabet);
+    }
+    if let Some((_, from, to)) = NAMED_SYMBOL_CLASSES.iter().find(|(name, _, _)| *name == class) {
+        return Ok((*from..=*to).collect());
+    }
+    match characters[..] {
+        [from, '-', to] if from < to => Ok((from..=to).collect()),
+        _ => Err(SymbolClassError::UnknownSymbolClass(class.to_string())),
+    }
+}
+
+/// Compresses the symbols of parallel transitions into as few symbol classes as possible.
+fn compress_symbol_classes(symbols: &[char], alphabet: &Option<HashSet<char>>) -> Vec<String> {
+    if let Some(alphabet) = alphabet {
+        if alphabet.len() > 1 && symbols.len() == alphabet.len() && symbols.iter().all(|symbol| alphabet.contains(symbol)) {
+            return vec!["any".to_string()];
+        }
+    }
+    symbol_runs(symbols).into_iter()
+        .flat_map(|(from, to)| {
+            if let Some((name, _, _)) = NAMED_SYMBOL_CLASSES.iter().find(|(_, class_from, class_to)| (*class_from, *class_to) == (from, to)) {
+                return vec![name.to_string()];
+            }
+            match to as u32 - from as u32 {
+                0 => vec![from.to_string()],
+                1 => vec![from.to_string(), to.to_string()],
+                _ => vec![format!("{}-{}", from, to)],
+            }
+        })
+        .collect()
+}
+
+impl SerializedDfa {
+    fn from_dfa(dfa: &Dfa) -> SerializedDfa {
+        let mut parallel_transitions: BTreeMap<(&str, &str), Vec<char>> = BTreeMap::new();
+        for transition in &dfa.transitions {
+            parallel_transitions.entry((&transition.state, &transition.next_state)).or_default().push(transition.input);
+        }
+        let transitions = parallel_transitions.into_iter()
+            .flat_map(|((state, next_state), symbols)| {
+                compress_symbol_classes(&symbols, &dfa.alphabet).into_iter().map(move |input| SerializedTransition {
+                    state: state.to_string(),
+                    input,
+                    next_state: next_state.to_string(),
+                })
+            })
+            .collect();
+        SerializedDfa {
+            name: dfa.name.clone(),
+            start_state: dfa.start_state.clone(),
+            accept_states: dfa.accept_states.clone(),
+            transitions,
+            alphabet: dfa.alphabet.clone(),
+            missing_transitions: dfa.missing_transitions,
+            state_metadata: dfa.state_metadata.clone(),
+            accept_tags: dfa.accept_tags.clone(),
+            transition_costs: dfa.transition_costs.iter().fold(BTreeMap::new(), |mut transition_costs, ((state, input), cost)| {
+                transition_costs.entry(state.clone()).or_insert_with(BTreeMap::new).insert(*input, *cost);
+                transition_costs
+            }),
+        }
+    }
+
+    /// Expands the symbol classes of the transitions. Transitions for single symbols take precedence over symbol classes,
+    /// so "any symbol except 'a'" can be expressed by a transition for "any" and another one for 'a'.
+    /// The resulting DFA might be nondeterministic, which has to be checked by the caller.
+    fn into_dfa(self) -> Result<Dfa, SymbolClassError> {
+        let explicit_transitions: HashSet<(&str, char)> = self.transitions.iter()
+            .filter(|transition| transition.input.chars().count() == 1)
+            .map(|transition| (transition.state.as_str(), transition.input.chars().next().unwrap()))
+            .collect();
+        let mut transitions = Vec::new();
+        for transition in &self.transitions {
+            let is_class = transition.input.chars().count() != 1;
+            for symbol in expand_symbol_class(&transition.input, &self.alphabet)?.into_iter().sorted() {
+                if is_class && explicit_transitions.contains(&(transition.state.as_str(), symbol)) {
+                    continue;
+                }
+                transitions.push(Transition { state: transition.state.clone(), input: symbol, next_state: transition.next_state.clone() });
+            }
+        }
+        Ok(Dfa {
+            alphabet: self.alphabet,
+            missing_transitions: self.missing_transitions,
+            state_metadata: self.state_metadata,
+            accept_tags: self.accept_tags,
+            transition_costs: self.transition_costs.into_iter()
+                .flat_map(|(state, costs)| costs.into_iter().map(move |(input, cost)| ((state.clone(), input), cost)))
+                .collect(),
+            ..Dfa::with_parts(self.name, self.start_state, self.accept_states, transitions)
+        })
+    }
+
+    /// Like `into_dfa`, but also rejects nondeterministic transitions and transitions reading symbols outside of the declared alphabet.
+    fn into_validated_dfa(self) -> Result<Dfa, String> {
+        let dfa = self.into_dfa().map_err(|error| error.to_string())?;
+        if let Some(transition) = find_nondeterministic_transition(&dfa.transitions) {
+            return Err(format!("the state {} has more than one transition for input {}", transition.state, transition.input));
+        }
+        dfa.validate_alphabet().map_err(|error| error.to_string())?;
+        Ok(dfa)
+    }
+}
+
+impl Serialize for Dfa {
+    /// Serializes the DFA with parallel transitions compressed into symbol classes where possible.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedDfa::from_dfa(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Dfa {
+    /// Deserializes the DFA and expands the symbol classes used by its transitions.
+    /// Fails if the transitions are nondeterministic, instead of constructing a DFA whose `check` picks the first matching transition,
+    /// or if they read symbols outside of the declared alphabet.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Dfa, D::Error> {
+        SerializedDfa::deserialize(deserializer)?.into_validated_dfa().map_err(serde::de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for LoadedAutomaton {
+    /// Deserializes a DFA, falling back to an NFA if its transitions are nondeterministic.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LoadedAutomaton, D::Error> {
+        let dfa = SerializedDfa::deserialize(deserializer)?.into_dfa().map_err(serde::de::Error::custom)?;
+        Ok(LoadedAutomaton::from(dfa))
+    }
+}
+
+impl From<Dfa> for LoadedAutomaton {
+    /// Keeps the DFA if it is deterministic and turns it into an NFA otherwise.
+    fn from(dfa: Dfa) -> LoadedAutomaton {
+        if dfa.is_deterministic() {
+            return LoadedAutomaton::Dfa(dfa);
+        }
+        LoadedAutomaton::Nfa(Nfa::from_parts(dfa.name, dfa.start_state, dfa.accept_states, dfa.transitions))
+    }
+}
+
+#[cfg(test)]
+mod serialization_tests {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+
+    use crate::serialization::{SerializedDfa, SerializedTransition};
+    use crate::{LoadedAutomaton, MissingTransitionPolicy, SymbolClassError};
+
+    fn create_serialized_dfa(transitions: &[(&str, &str, &str)], alphabet: Option<HashSet<char>>) -> SerializedDfa {
+        SerializedDfa {
+            name: "hex numbers".to_string(),
+            start_state: "q0".to_string(),
+            accept_states: ["q1".to_string()].iter().cloned().collect(),
+            transitions: transitions.iter()
+                .map(|(state, input, next_state)| SerializedTransition { state: state.to_string(), input: input.to_string(), next_state: next_state.to_string() })
+                .collect(),
+            alphabet,
+            missing_transitions: MissingTransitionPolicy::Reject,
+            state_metadata: HashMap::new(),
+            accept_tags: HashMap::new(),
+            transition_costs: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_symbol_classes() {
+        let dfa = create_serialized_dfa(&[("q0", "digits", "q1"), ("q0", "a-f", "q1"), ("q1", "any", "q1"), ("q1", "x", "q0")], Some("0123456789abcdefx".chars().collect()))
+            .into_dfa()
+            .unwrap();
+        assert!(dfa.check("f00").0);
+        assert!(!dfa.check("g").0, "g is not part of the range a-f");
+        assert!(!dfa.check("1x").0, "the explicit transition for x should take precedence over any");
+        let serialized = SerializedDfa::from_dfa(&dfa);
+        let inputs = serialized.transitions.iter().map(|transition| (transition.state.as_str(), transition.input.as_str(), transition.next_state.as_str())).collect::<Vec<_>>();
+        assert_eq!(inputs, vec![("q0", "digits", "q1"), ("q0", "a-f", "q1"), ("q1", "x", "q0"), ("q1", "digits", "q1"), ("q1", "a-f", "q1")]);
+    }
+
+    #[test]
+    fn test_symbol_class_errors() {
+        assert_eq!(create_serialized_dfa(&[("q0", "hexdigits", "q1")], None).into_dfa().unwrap_err(), SymbolClassError::UnknownSymbolClass("hexdigits".to_string()));
+        assert_eq!(create_serialized_dfa(&[("q0", "f-a", "q1")], None).into_dfa().unwrap_err(), SymbolClassError::UnknownSymbolClass("f-a".to_string()));
+        assert_eq!(create_serialized_dfa(&[("q0", "any", "q1")], None).into_dfa().unwrap_err(), SymbolClassError::AnyWithoutAlphabet);
+    }
+
+    #[test]
+    fn test_nondeterministic_fallback() {
+        let dfa = create_serialized_dfa(&[("q0", "a", "q0"), ("q0", "b", "q0"), ("q0", "a", "q1")], None).into_dfa().unwrap();
+        assert!(!dfa.is_deterministic());
+        match LoadedAutomaton::from(dfa) {
+            LoadedAutomaton::Nfa(nfa) => assert!(nfa.check("ba"), "the NFA should explore both transitions for a"),
+            LoadedAutomaton::Dfa(_) => panic!("nondeterministic transitions should be loaded as an NFA"),
+        }
+        let dfa = create_serialized_dfa(&[("q0", "a", "q1"), ("q0", "a", "q1")], None).into_dfa().unwrap();
+        assert!(dfa.is_deterministic(), "duplicated transitions are not nondeterministic");
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(create_serialized_dfa(&[("q0", "a", "q1"), ("q0", "a", "q0")], None).into_validated_dfa().is_err());
+        let serialized = create_serialized_dfa(&[("q0", "0", "q1"), ("q1", "2", "q1")], Some("01".chars().collect()));
+        assert_eq!(serialized.into_validated_dfa().unwrap_err(), "the transition from q1 reads the undeclared symbol 2");
+    }
+}
@@ -0,0 +1,138 @@
+use itertools::Itertools;
+
+use crate::Regex;
+
+impl Regex {
+    /// Rewrites the regex into a shorter one matching the same words, by applying algebraic laws until nothing changes anymore:
+    /// - ∅ annihilates concatenations and is the identity of unions, while ε is the identity of concatenations.
+    /// - Unions drop duplicate alternatives, and alternatives with a common first factor are factored, e.g. ab|ac becomes a(b|c).
+    /// - Stars of ∅, ε and stars are simplified, ε is dropped within stars, e.g. (ε|a)* becomes a*, and a*a* becomes a*.
+    /// - ε is dropped next to a star in a union, e.g. ε|a* becomes a*.
+    ///
+    /// This makes the labels created by state elimination (see `Gnfa::eliminate_state`) readable.
+    /// The order of alternatives is kept, so the result is not a canonical form.
+    pub fn simplify(&self) -> Regex {
+        let mut regex = self.clone();
+        loop {
+            let simplified_regex = regex.simplify_once();
+            if simplified_regex == regex {
+                return regex;
+            }
+            regex = simplified_regex;
+        }
+    }
+
+    fn simplify_once(&self) -> Regex {
+        match self {
+            Regex::Empty | Regex::Epsilon | Regex::Symbol(_) => self.clone(),
+            Regex::Star(inner) => match inner.simplify_once() {
+                Regex::Empty | Regex::Epsilon => Regex::Epsilon,
+                Regex::Star(inner) => Regex::Star(inner),
+                inner => {
+                    let alternatives = inner.get_alternatives().into_iter().filter(|alternative| *alternative != Regex::Epsilon).collect_vec();
+                    Regex::star(build_union(alternatives))
+                }
+            },
+            Regex::Concatenation(left, right) => {
+                let factors = left.simplify_once().get_factors().into_iter().chain(right.simplify_once().get_factors()).collect_vec();
+                if factors.contains(&Regex::Empty) {
+                    return Regex::Empty;
+                }
+                let mut simplified_factors: Vec<Regex> = Vec::new();
+                for factor in factors.into_iter().filter(|factor| *factor != Regex::Epsilon) {
+                    if matches!(factor, Regex::Star(_)) && simplified_factors.last() == Some(&factor) {
+                        continue;
+                    }
+                    simplified_factors.push(factor);
+                }
+                build_concatenation(simplified_factors)
+            }
+            Regex::Union(left, right) => {
+                let mut alternatives: Vec<Regex> = Vec::new();
+                for alternative in left.simplify_once().get_alternatives().into_iter().chain(right.simplify_once().get_alternatives()) {
+                    if alternative != Regex::Empty && !alternatives.contains(&alternative) {
+                        alternatives.push(alternative);
+                    }
+                }
+                if alternatives.iter().any(|alternative| matches!(alternative, Regex::Star(_))) {
+                    alternatives.retain(|alternative| *alternative != Regex::Epsilon);
+                }
+                build_union(factor_common_prefixes(alternatives))
+            }
+        }
+    }
+
+    /// Returns the alternatives of a union, flattening nested unions. Other regexes are their only alternative.
+    fn get_alternatives(&self) -> Vec<Regex> {
+        match self {
+            Regex::Union(left, right) => left.get_alternatives().into_iter().chain(right.get_alternatives()).collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Returns the factors of a concatenation, flattening nested concatenations. Other regexes are their only factor.
+    fn get_factors(&self) -> Vec<Regex> {
+        match self {
+            Regex::Concatenation(left, right) => left.get_factors().into_iter().chain(right.get_factors()).collect(),
+            _ => vec![self.clone()],
+        }
+    }
+}
+
+/// Groups the alternatives by their first factor and factors it out of groups with several alternatives.
+/// The groups are kept in the order of their first alternative.
+fn factor_common_prefixes(alternatives: Vec<Regex>) -> Vec<Regex> {
+    let mut groups: Vec<(Regex, Vec<Regex>)> = Vec::new();
+    for alternative in alternatives {
+        let mut factors = alternative.get_factors();
+        let rest = build_concatenation(factors.split_off(1));
+        let prefix = factors.remove(0);
+        match groups.iter_mut().find(|(other_prefix, _)| *other_prefix == prefix) {
+            Some((_, rests)) => rests.push(rest),
+            None => groups.push((prefix, vec![rest])),
+        }
+    }
+    groups.into_iter()
+        .map(|(prefix, rests)| match rests.len() {
+            1 => build_concatenation(vec![prefix, rests.into_iter().next().unwrap()]),
+            _ => Regex::concatenation(prefix, build_union(rests)),
+        })
+        .collect()
+}
+
+/// Concatenates the factors, leaving out epsilon. No factors result in `Regex::Epsilon`.
+fn build_concatenation(factors: Vec<Regex>) -> Regex {
+    factors.into_iter()
+        .filter(|factor| *factor != Regex::Epsilon)
+        .fold1(Regex::concatenation)
+        .unwrap_or(Regex::Epsilon)
+}
+
+/// Combines the alternatives into a union. No alternatives result in `Regex::Empty`.
+fn build_union(alternatives: Vec<Regex>) -> Regex {
+    alternatives.into_iter().fold1(Regex::union).unwrap_or(Regex::Empty)
+}
+
+#[cfg(test)]
+mod simplification_tests {
+    use crate::Regex;
+
+    #[test]
+    fn test_simplify() {
+        let a = || Regex::Symbol('a');
+        let b = || Regex::Symbol('b');
+        assert_eq!(Regex::star(Regex::star(a())).simplify().to_string(), "a*");
+        assert_eq!(Regex::star(Regex::union(Regex::Epsilon, a())).simplify().to_string(), "a*");
+        assert_eq!(Regex::concatenation(Regex::star(a()), Regex::star(a())).simplify().to_string(), "a*");
+        assert_eq!(Regex::concatenation(a(), Regex::Empty).simplify(), Regex::Empty);
+        assert_eq!(Regex::union(Regex::word("ab"), Regex::union(Regex::Empty, Regex::word("ac"))).simplify().to_string(), "a(b|c)");
+        // As state elimination might produce it for the words "a" and "ab".
+        let regex = Regex::union(Regex::concatenation(a(), Regex::Epsilon), Regex::union(Regex::concatenation(a(), b()), a()));
+        let simplified_regex = regex.simplify();
+        assert_eq!(simplified_regex.to_string(), "a(ε|b)");
+        for word in &["", "a", "ab", "abb", "b"] {
+            let input: Vec<char> = word.chars().collect();
+            assert_eq!(simplified_regex.match_ends(&input, 0).contains(&input.len()), regex.match_ends(&input, 0).contains(&input.len()));
+        }
+    }
+}
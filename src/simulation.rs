@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use crate::Nfa;
+
+impl Nfa {
+    /// Computes the [simulation](https://en.wikipedia.org/wiki/Simulation_preorder) relation between the states of this NFA
+    /// and the states of the other one. A pair (p, q) is contained if q simulates p: q is accepting whenever p is,
+    /// and every transition of p can be answered by a transition of q for the same input that leads into a state simulating
+    /// the next state of p. Then every word accepted from p is also accepted from q.
+    /// The relation is computed as a greatest fixed point by starting with all pairs that respect acceptance
+    /// and removing pairs violating the transition condition until nothing changes.
+    pub fn compute_simulation(&self, other: &Nfa) -> HashSet<(String, String)> {
+        let mut relation: HashSet<(String, String)> = self.get_all_states_including_start().into_iter()
+            .cartesian_product(other.get_all_states_including_start().into_iter().collect_vec())
+            .filter(|(state, other_state)| !self.accept_states.contains(state) || other.accept_states.contains(other_state))
+            .collect();
+        loop {
+            let violating_pairs = relation.iter()
+                .filter(|(state, other_state)| {
+                    self.transitions.iter().filter(|transition| transition.state == *state).any(|transition| {
+                        !other.transitions.iter()
+                            .filter(|other_transition| other_transition.state == *other_state && other_transition.input == transition.input)
+                            .any(|other_transition| relation.contains(&(transition.next_state.clone(), other_transition.next_state.clone())))
+                    })
+                })
+                .cloned()
+                .collect_vec();
+            if violating_pairs.is_empty() {
+                return relation;
+            }
+            for pair in violating_pairs {
+                relation.remove(&pair);
+            }
+        }
+    }
+
+    /// Computes the simulation preorder on the states of the NFA (see `compute_simulation`). States that simulate each
+    /// other accept the same words and could be merged, while transitions into simulated states are candidates for removal.
+    pub fn compute_simulation_preorder(&self) -> HashSet<(String, String)> {
+        self.compute_simulation(self)
+    }
+
+    /// Checks whether the start state of the other NFA simulates the start state of this one. This implies that
+    /// the language of this NFA is a subset of the other's language, without determinizing any of them.
+    /// The converse does not hold, so false does not rule out inclusion.
+    pub fn is_simulated_by(&self, other: &Nfa) -> bool {
+        self.compute_simulation(other).contains(&(self.start_state.clone(), other.start_state.clone()))
+    }
+
+    fn get_all_states_including_start(&self) -> HashSet<String> {
+        let mut states = self.get_all_states();
+        states.insert(self.start_state.clone());
+        states
+    }
+}
+
+#[cfg(test)]
+mod simulation_tests {
+    use crate::Nfa;
+
+    #[test]
+    fn test_is_simulated_by() {
+        // Accepts any number of 'a's followed by 'b'.
+        let mut nfa = Nfa::new("a*b", "p0");
+        nfa.add_transition("p0", 'a', "p0");
+        nfa.add_transition("p0", 'b', "p1");
+        nfa.add_accept_state("p1");
+        // Accepts all words over 'a' and 'b' ending with 'b'.
+        let mut other = Nfa::new("(a|b)*b", "q0");
+        other.add_transition("q0", 'a', "q0");
+        other.add_transition("q0", 'b', "q0");
+        other.add_transition("q0", 'b', "q1");
+        other.add_accept_state("q1");
+        assert!(nfa.is_simulated_by(&other));
+        assert!(!other.is_simulated_by(&nfa));
+        let preorder = other.compute_simulation_preorder();
+        assert!(preorder.contains(&("q1".to_string(), "q1".to_string())));
+        assert!(!preorder.contains(&("q1".to_string(), "q0".to_string())), "q0 is not accepting");
+    }
+}
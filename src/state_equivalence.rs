@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// The equivalence of all pairs of states of a DFA, computed once, so that many queries can be answered in constant time
+/// (see `Dfa::get_state_equivalence`). Two states are equivalent if the same words are accepted when starting in them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateEquivalence {
+    /// Maps each state to the index of its equivalence class.
+    class_indices: HashMap<String, usize>,
+    /// The index of the class of states from which no word is accepted, like the implicit error state.
+    dead_class_index: usize,
+}
+
+impl StateEquivalence {
+    /// Checks whether both states are equivalent. States the DFA does not have are treated like its implicit error state.
+    pub fn are_equivalent(&self, state_a: &str, state_b: &str) -> bool {
+        self.get_class_index(state_a) == self.get_class_index(state_b)
+    }
+
+    /// Returns the equivalence classes with their states sorted by name, sorted by their first state.
+    /// Unlike during minimization, unreachable states are kept.
+    pub fn get_classes(&self) -> Vec<Vec<String>> {
+        self.class_indices.iter()
+            .map(|(state, class_index)| (*class_index, state.clone()))
+            .into_group_map()
+            .into_iter().map(|(_, states)| states)
+            .map(|states| states.into_iter().sorted().collect_vec())
+            .sorted()
+            .collect()
+    }
+
+    fn get_class_index(&self, state: &str) -> usize {
+        self.class_indices.get(state).cloned().unwrap_or(self.dead_class_index)
+    }
+}
+
+impl Dfa {
+    /// Checks whether the same words are accepted when starting in either state, see `get_state_equivalence`.
+    /// When asking for many pairs, keep the result of `get_state_equivalence` instead, which answers every further query in constant time.
+    /// Accept tags are ignored.
+    pub fn are_equivalent_states(&self, state_a: &str, state_b: &str) -> bool {
+        state_a == state_b || self.get_state_equivalence().are_equivalent(state_a, state_b)
+    }
+
+    /// Computes the equivalence of all pairs of states at once by refining the partition into accept and other states
+    /// until the states of each class lead into the same classes for every symbol, as in Moore's algorithm. Accept tags are ignored.
+    pub fn get_state_equivalence(&self) -> StateEquivalence {
+        self.compute_state_equivalence(false)
+    }
+
+    /// Like `get_state_equivalence`, but if `distinguish_tags` holds, accept states with different accept tags are never equivalent,
+    /// which is what `minimize` needs.
+    pub(crate) fn compute_state_equivalence(&self, distinguish_tags: bool) -> StateEquivalence {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let alphabet = dfa.get_alphabet().into_iter().sorted().collect_vec();
+        let states = dfa.get_all_mentioned_states().into_iter().sorted().collect_vec();
+        // The implicit error state is represented by None. Initially, states are only told apart by their acceptance
+        // and, if requested, by their accept tags.
+        let initial_class = |state: &String| match dfa.accept_states.contains(state) {
+            true => Some(dfa.accept_tags.get(state).filter(|_| distinguish_tags)),
+            false => None,
+        };
+        let mut initial_classes = vec![None];
+        let mut class_indices: HashMap<Option<&String>, usize> = HashMap::new();
+        class_indices.insert(None, 0);
+        for state in &states {
+            let class = initial_class(state);
+            let class_index = match initial_classes.iter().position(|other_class| *other_class == class) {
+                Some(class_index) => class_index,
+                None => {
+                    initial_classes.push(class);
+                    initial_classes.len() - 1
+                }
+            };
+            class_indices.insert(Some(state), class_index);
+        }
+        loop {
+            let signatures = class_indices.iter()
+                .map(|(state, class_index)| {
+                    let next_class_indices = alphabet.iter()
+                        .map(|input| {
+                            let next_state = state.and_then(|state| dfa.get_transition(state, input)).map(|transition| &transition.next_state);
+                            class_indices[&next_state]
+                        })
+                        .collect_vec();
+                    (*state, (*class_index, next_class_indices))
+                })
+                .collect_vec();
+            let new_class_indices: HashMap<_, _> = signatures.iter().map(|(_, signature)| signature).unique().sorted().enumerate()
+                .map(|(class_index, signature)| (signature, class_index))
+                .collect();
+            let has_split_occurred = new_class_indices.len() > class_indices.values().unique().count();
+            class_indices = signatures.iter().map(|(state, signature)| (*state, new_class_indices[signature])).collect();
+            if !has_split_occurred {
+                break;
+            }
+        }
+        StateEquivalence {
+            dead_class_index: class_indices[&None],
+            class_indices: class_indices.into_iter().filter_map(|(state, class_index)| state.map(|state| (state.clone(), class_index))).collect(),
+        }
+    }
+
+    /// Returns a shortest word that is accepted when starting in exactly one of the two states, or None if the states are equivalent.
+    /// Among the shortest words, the alphabetically smallest one is returned. This explains why minimization keeps two states apart,
+    /// and answers the counterexample queries of learning algorithms.
+    pub fn distinguishing_word(&self, state_a: &str, state_b: &str) -> Option<String> {
+        let mut first = self.clone();
+        first.start_state = state_a.to_string();
+        let mut second = self.clone();
+        second.start_state = state_b.to_string();
+        first.find_distinguishing_word(&second)
+    }
+}
+
+#[cfg(test)]
+mod state_equivalence_tests {
+    use crate::Dfa;
+
+    #[test]
+    fn test_distinguishing_word() {
+        // Accepts words over 'a' and 'b' ending with "ab". q3 is a redundant copy of q0.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3"],
+            &['a', 'b'],
+            &[&[Some(1), Some(3)], &[Some(1), Some(2)], &[Some(1), Some(0)], &[Some(1), Some(0)]],
+            0,
+            &[2],
+        ).unwrap();
+        assert_eq!(dfa.distinguishing_word("q0", "q3"), None);
+        assert_eq!(dfa.distinguishing_word("q0", "q2"), Some(String::new()));
+        assert_eq!(dfa.distinguishing_word("q0", "q1"), Some("b".to_string()));
+    }
+
+    #[test]
+    fn test_state_equivalence() {
+        // Accepts words over 'a' and 'b' ending with "ab". q3 is a redundant copy of q0 and q4 is an unreachable dead state.
+        let dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3", "q4"],
+            &['a', 'b'],
+            &[&[Some(1), Some(3)], &[Some(1), Some(2)], &[Some(1), Some(0)], &[Some(1), Some(0)], &[Some(4), None]],
+            0,
+            &[2],
+        ).unwrap();
+        let equivalence = dfa.get_state_equivalence();
+        assert!(equivalence.are_equivalent("q0", "q3"));
+        assert!(equivalence.are_equivalent("q4", "error"), "unknown states are treated like the implicit error state");
+        assert!(!equivalence.are_equivalent("q0", "q1"));
+        assert_eq!(equivalence.get_classes(), vec![vec!["q0", "q3"], vec!["q1"], vec!["q2"], vec!["q4"]]);
+        assert!(dfa.are_equivalent_states("q3", "q0") && !dfa.are_equivalent_states("q1", "q2"));
+    }
+}
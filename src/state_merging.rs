@@ -0,0 +1,308 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::{Dfa, Transition};
+
+/// How `Dfa::learn_by_state_merging` chooses the next pair of states to merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeStrategy {
+    /// [RPNI](https://en.wikipedia.org/wiki/Induction_of_regular_languages): the smallest blue state is merged with the first red state
+    /// it can be merged with, trying red states in shortlex order of their access words.
+    Rpni,
+    /// Evidence driven state merging: among all pairs of red and blue states, the merge with the highest score is chosen, where the score
+    /// counts the states with the same label that are merged along the way.
+    Edsm,
+}
+
+/// Describes why the examples cannot be learned from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LearningError {
+    /// The word is given as positive and as negative example.
+    ContradictoryExample(String),
+}
+
+impl fmt::Display for LearningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LearningError::ContradictoryExample(word) => write!(f, "the word \"{}\" is a positive and a negative example", word),
+        }
+    }
+}
+
+impl std::error::Error for LearningError {}
+
+/// A decision of a state merging learner. States are named after their access words in the prefix tree of the examples, with "ε" for the empty word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeDecision {
+    /// The blue state was merged into the red state. For `MergeStrategy::Edsm`, the score of the merge is given.
+    Merged { red: String, blue: String, score: Option<usize> },
+    /// Merging the blue state into the red state would make the hypothesis accept a negative or reject a positive example.
+    Rejected { red: String, blue: String },
+    /// The blue state could not be merged with any red state, so it became red itself.
+    Promoted { state: String },
+}
+
+/// One step of `Dfa::learn_by_state_merging`: a decision together with the hypothesis after it.
+#[derive(Debug, Clone)]
+pub struct LearningStep {
+    pub decision: MergeDecision,
+    pub hypothesis: Dfa,
+    /// The states that are part of the final automaton for sure.
+    pub red_states: BTreeSet<String>,
+    /// The states reached from red states that are not red themselves, which are the candidates for the next merge.
+    pub blue_states: BTreeSet<String>,
+}
+
+impl LearningStep {
+    /// Renders the hypothesis like `Dfa::to_dot`, with red and blue states filled in their colors and the states of the decision drawn bold,
+    /// so that a sequence of steps shows how the hypothesis evolves.
+    pub fn to_dot(&self) -> String {
+        let mut dot = self.hypothesis.to_dot();
+        dot.truncate(dot.len() - "}\n".len());
+        for state in &self.red_states {
+            dot += &format!("    \"{}\" [style=filled, fillcolor=\"#f4a6a6\"];\n", state.replace('"', "\\\""));
+        }
+        for state in &self.blue_states {
+            dot += &format!("    \"{}\" [style=filled, fillcolor=\"#a6c8f4\"];\n", state.replace('"', "\\\""));
+        }
+        let involved_states = match &self.decision {
+            MergeDecision::Merged { red, .. } => vec![red],
+            MergeDecision::Rejected { red, blue } => vec![red, blue],
+            MergeDecision::Promoted { state } => vec![state],
+        };
+        for state in involved_states {
+            dot += &format!("    \"{}\" [penwidth=3];\n", state.replace('"', "\\\""));
+        }
+        dot + "}\n"
+    }
+}
+
+/// An automaton during state merging, whose states are the indices of the prefix tree of the examples. States may be labeled
+/// as accepting (true) or rejecting (false) or be unlabeled. Merged states keep their index but can no longer be reached.
+#[derive(Debug, Clone)]
+struct Hypothesis {
+    transitions: Vec<BTreeMap<char, usize>>,
+    labels: Vec<Option<bool>>,
+}
+
+impl Hypothesis {
+    /// Returns a copy in which the blue state is merged into the red state, together with the number of states with the same label
+    /// that were merged, or None if states with different labels would be merged. The blue state must be the root of a tree,
+    /// which holds for the states that the red states lead to, as only red states are merged into.
+    fn merge(&self, red: usize, blue: usize) -> Option<(Hypothesis, usize)> {
+        let mut hypothesis = self.clone();
+        for transitions in hypothesis.transitions.iter_mut() {
+            for next_state in transitions.values_mut().filter(|next_state| **next_state == blue) {
+                *next_state = red;
+            }
+        }
+        let mut score = 0;
+        match hypothesis.fold(red, blue, &mut score) {
+            true => Some((hypothesis, score)),
+            false => None,
+        }
+    }
+
+    /// Folds the tree starting at the blue state into the state, so that its words lead into the corresponding states.
+    fn fold(&mut self, state: usize, blue: usize, score: &mut usize) -> bool {
+        match (self.labels[state], self.labels[blue]) {
+            (Some(label), Some(blue_label)) if label != blue_label => return false,
+            (Some(_), Some(_)) => *score += 1,
+            (None, blue_label) => self.labels[state] = blue_label,
+            (Some(_), None) => {}
+        }
+        for (symbol, blue_next_state) in self.transitions[blue].clone() {
+            match self.transitions[state].get(&symbol) {
+                Some(next_state) => {
+                    if !self.fold(*next_state, blue_next_state, score) {
+                        return false;
+                    }
+                }
+                None => {
+                    self.transitions[state].insert(symbol, blue_next_state);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Keeps track of the state merging: the hypothesis, its red states and the access words naming the states.
+struct Learner {
+    hypothesis: Hypothesis,
+    access_words: Vec<String>,
+    red_states: Vec<usize>,
+    steps: Vec<LearningStep>,
+}
+
+impl Learner {
+    fn get_name(&self, state: usize) -> String {
+        match self.access_words[state].is_empty() {
+            true => String::from("ε"),
+            false => self.access_words[state].clone(),
+        }
+    }
+
+    /// Returns the blue states in shortlex order of their access words.
+    fn get_blue_states(&self) -> Vec<usize> {
+        self.red_states.iter()
+            .flat_map(|red| self.hypothesis.transitions[*red].values().cloned())
+            .filter(|state| !self.red_states.contains(state))
+            .unique()
+            .sorted_by_key(|state| (self.access_words[*state].chars().count(), self.access_words[*state].clone()))
+            .collect()
+    }
+
+    fn record(&mut self, decision: MergeDecision) {
+        let red_states = self.red_states.iter().map(|state| self.get_name(*state)).collect();
+        let blue_states = self.get_blue_states().into_iter().map(|state| self.get_name(state)).collect();
+        let hypothesis = self.to_dfa();
+        self.steps.push(LearningStep { decision, hypothesis, red_states, blue_states });
+    }
+
+    /// Tries to merge the blue state into the red states in order and records every decision. Returns whether a merge succeeded.
+    fn merge_into_first_red_state(&mut self, blue: usize) -> bool {
+        for red in self.red_states.clone() {
+            match self.hypothesis.merge(red, blue) {
+                Some((hypothesis, _)) => {
+                    self.hypothesis = hypothesis;
+                    self.record(MergeDecision::Merged { red: self.get_name(red), blue: self.get_name(blue), score: None });
+                    return true;
+                }
+                None => self.record(MergeDecision::Rejected { red: self.get_name(red), blue: self.get_name(blue) }),
+            }
+        }
+        false
+    }
+
+    fn promote(&mut self, state: usize) {
+        let access_words = &self.access_words;
+        self.red_states.push(state);
+        self.red_states.sort_by_key(|state| (access_words[*state].chars().count(), access_words[*state].clone()));
+        self.record(MergeDecision::Promoted { state: self.get_name(state) });
+    }
+
+    /// Converts the reachable part of the hypothesis into a DFA. Unlabeled states reject.
+    fn to_dfa(&self) -> Dfa {
+        let mut reachable_states = vec![0];
+        let mut visited_states: HashSet<usize> = reachable_states.iter().cloned().collect();
+        let mut states_to_visit: VecDeque<usize> = reachable_states.iter().cloned().collect();
+        while let Some(state) = states_to_visit.pop_front() {
+            for next_state in self.hypothesis.transitions[state].values() {
+                if visited_states.insert(*next_state) {
+                    reachable_states.push(*next_state);
+                    states_to_visit.push_back(*next_state);
+                }
+            }
+        }
+        let transitions = reachable_states.iter()
+            .flat_map(|state| self.hypothesis.transitions[*state].iter().map(move |(symbol, next_state)| (*state, *symbol, *next_state)))
+            .map(|(state, input, next_state)| Transition { state: self.get_name(state), input, next_state: self.get_name(next_state) })
+            .collect();
+        Dfa::with_parts(
+            String::from("hypothesis"),
+            self.get_name(0),
+            reachable_states.iter().filter(|state| self.hypothesis.labels[**state] == Some(true)).map(|state| self.get_name(*state)).collect(),
+            transitions,
+        )
+    }
+}
+
+impl Dfa {
+    /// Learns a DFA that accepts the positive and rejects the negative examples by state merging, starting with the prefix tree of the examples.
+    /// Following the blue fringe framework, states are either red (kept), blue (reached from red states) or neither. In every step,
+    /// a blue state is merged into a red one, as chosen by the strategy, or promoted to red if it cannot be merged with any.
+    /// Unlike the final DFA alone, the returned steps show how the hypothesis evolved: every merge, rejected merge and promotion is recorded
+    /// together with the hypothesis after it, which can be rendered with `LearningStep::to_dot`.
+    pub fn learn_by_state_merging(positive: &[&str], negative: &[&str], strategy: MergeStrategy) -> Result<(Dfa, Vec<LearningStep>), LearningError> {
+        let mut hypothesis = Hypothesis { transitions: vec![BTreeMap::new()], labels: vec![None] };
+        let mut access_words = vec![String::new()];
+        let examples = positive.iter().map(|word| (word, true)).chain(negative.iter().map(|word| (word, false)));
+        for (word, label) in examples.sorted() {
+            let mut state = 0;
+            for symbol in word.chars() {
+                state = match hypothesis.transitions[state].get(&symbol) {
+                    Some(next_state) => *next_state,
+                    None => {
+                        let next_state = hypothesis.labels.len();
+                        hypothesis.transitions[state].insert(symbol, next_state);
+                        hypothesis.transitions.push(BTreeMap::new());
+                        hypothesis.labels.push(None);
+                        access_words.push(format!("{}{}", access_words[state], symbol));
+                        next_state
+                    }
+                };
+            }
+            if hypothesis.labels[state] == Some(!label) {
+                return Err(LearningError::ContradictoryExample(word.to_string()));
+            }
+            hypothesis.labels[state] = Some(label);
+        }
+        let mut learner = Learner { hypothesis, access_words, red_states: vec![0], steps: Vec::new() };
+        while let Some(smallest_blue) = learner.get_blue_states().first().cloned() {
+            match strategy {
+                MergeStrategy::Rpni => {
+                    if !learner.merge_into_first_red_state(smallest_blue) {
+                        learner.promote(smallest_blue);
+                    }
+                }
+                MergeStrategy::Edsm => {
+                    let mut best_merge: Option<(usize, usize, usize, Hypothesis)> = None;
+                    let mut blue_to_promote = None;
+                    for blue in learner.get_blue_states() {
+                        let mut can_be_merged = false;
+                        for red in learner.red_states.clone() {
+                            match learner.hypothesis.merge(red, blue) {
+                                Some((hypothesis, score)) => {
+                                    can_be_merged = true;
+                                    if best_merge.as_ref().map_or(true, |(best_score, _, _, _)| score > *best_score) {
+                                        best_merge = Some((score, red, blue, hypothesis));
+                                    }
+                                }
+                                None => learner.record(MergeDecision::Rejected { red: learner.get_name(red), blue: learner.get_name(blue) }),
+                            }
+                        }
+                        // A blue state that cannot be merged with any red state must become red, which is done before any merge.
+                        if !can_be_merged {
+                            blue_to_promote = Some(blue);
+                            break;
+                        }
+                    }
+                    if let Some(blue) = blue_to_promote {
+                        learner.promote(blue);
+                    } else if let Some((score, red, blue, hypothesis)) = best_merge {
+                        learner.hypothesis = hypothesis;
+                        learner.record(MergeDecision::Merged { red: learner.get_name(red), blue: learner.get_name(blue), score: Some(score) });
+                    }
+                }
+            }
+        }
+        Ok((learner.to_dfa(), learner.steps))
+    }
+}
+
+#[cfg(test)]
+mod state_merging_tests {
+    use crate::{Dfa, LearningError, MergeDecision, MergeStrategy};
+
+    #[test]
+    fn test_learn_by_state_merging() {
+        // Examples of words over 'a' and 'b' with an even number of 'a'.
+        let positive = ["", "b", "aa", "bb", "aab", "aba", "baa", "abab"];
+        let negative = ["a", "ab", "ba", "aaa", "abb", "bab", "bba"];
+        for strategy in &[MergeStrategy::Rpni, MergeStrategy::Edsm] {
+            let (dfa, steps) = Dfa::learn_by_state_merging(&positive, &negative, *strategy).unwrap();
+            assert!(positive.iter().all(|word| dfa.check(word).0) && negative.iter().all(|word| !dfa.check(word).0));
+            assert_eq!(dfa.get_all_states().len(), 2, "{:?}", strategy);
+            assert!(steps.iter().any(|step| matches!(step.decision, MergeDecision::Rejected { .. })));
+            assert_eq!(steps.last().unwrap().hypothesis.get_all_states(), dfa.get_all_states());
+        }
+        let (_, steps) = Dfa::learn_by_state_merging(&positive, &negative, MergeStrategy::Rpni).unwrap();
+        assert_eq!(steps[0].decision, MergeDecision::Rejected { red: "ε".to_string(), blue: "a".to_string() });
+        assert_eq!(steps[1].decision, MergeDecision::Promoted { state: "a".to_string() });
+        assert!(steps[1].to_dot().contains("    \"ε\" [style=filled, fillcolor=\"#f4a6a6\"];\n    \"aa\" [style=filled, fillcolor=\"#a6c8f4\"];\n"));
+        assert_eq!(Dfa::learn_by_state_merging(&["a"], &["a"], MergeStrategy::Rpni).unwrap_err(), LearningError::ContradictoryExample("a".to_string()));
+    }
+}
@@ -0,0 +1,231 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+/// The longest record a `RecordReader` accepts unless another maximum is set, in bytes.
+const DEFAULT_MAX_RECORD_LENGTH: usize = 64 * 1024 * 1024;
+
+/// How records, e.g. serialized automata, are separated within a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// One record per line, as in newline-delimited JSON. Empty lines are skipped and records must not contain line breaks.
+    Ndjson,
+    /// Every record is preceded by its length in bytes as a 32 bit big-endian integer, which allows binary records.
+    LengthPrefixed,
+}
+
+/// Describes why a stream of records could not be processed. Records are counted from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamError {
+    /// Reading or writing failed, e.g. because the stream ended within a length-prefixed record.
+    Io { kind: io::ErrorKind, message: String },
+    /// The record could not be decoded, processed or encoded by the supplied functions.
+    InvalidRecord { record: usize, message: String },
+    /// The record is longer than the maximum length of the reader, which protects against corrupt or malicious length prefixes.
+    RecordTooLong { record: usize, max_length: usize },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io { message, .. } => write!(f, "reading or writing the stream failed: {}", message),
+            StreamError::InvalidRecord { record, message } => write!(f, "record {} is invalid: {}", record, message),
+            StreamError::RecordTooLong { record, max_length } => write!(f, "record {} is longer than the maximum of {} bytes", record, max_length),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> StreamError {
+        StreamError::Io { kind: error.kind(), message: error.to_string() }
+    }
+}
+
+/// Reads one record after the other from a stream, so that collections of automata larger than the memory can be processed.
+/// The records are returned as bytes and decoded by the caller, e.g. with `serde_json::from_slice`.
+pub struct RecordReader<R: BufRead> {
+    reader: R,
+    format: RecordFormat,
+    max_record_length: usize,
+    read_records: usize,
+}
+
+impl<R: BufRead> RecordReader<R> {
+    pub fn new(reader: R, format: RecordFormat) -> RecordReader<R> {
+        RecordReader { reader, format, max_record_length: DEFAULT_MAX_RECORD_LENGTH, read_records: 0 }
+    }
+
+    /// Sets the length of the longest record that is read, in bytes. Longer records are reported as errors without reading them into memory.
+    /// Line breaks of newline-delimited records do not count.
+    pub fn set_max_record_length(&mut self, max_record_length: usize) {
+        self.max_record_length = max_record_length;
+    }
+
+    /// Returns the next record, or None at the end of the stream.
+    pub fn read_record(&mut self) -> Result<Option<Vec<u8>>, StreamError> {
+        let too_long = StreamError::RecordTooLong { record: self.read_records, max_length: self.max_record_length };
+        // At most one byte more than the maximum is read, which is enough to detect that a record is too long.
+        let limit = self.max_record_length as u64 + 1;
+        let record = match self.format {
+            RecordFormat::Ndjson => loop {
+                let mut line = Vec::new();
+                if (&mut self.reader).take(limit + 2).read_until(b'\n', &mut line)? == 0 {
+                    return Ok(None);
+                }
+                while line.last().map_or(false, |byte| *byte == b'\n' || *byte == b'\r') {
+                    line.pop();
+                }
+                if line.len() > self.max_record_length {
+                    return Err(too_long);
+                }
+                if !line.is_empty() {
+                    break line;
+                }
+            },
+            RecordFormat::LengthPrefixed => {
+                let mut length = [0; 4];
+                // Only an end of the stream before the first byte of the length is a regular end.
+                if self.reader.fill_buf()?.is_empty() {
+                    return Ok(None);
+                }
+                self.reader.read_exact(&mut length)?;
+                let length = u32::from_be_bytes(length) as u64;
+                if length > self.max_record_length as u64 {
+                    return Err(too_long);
+                }
+                // The record grows while it is read instead of trusting the length for allocating it.
+                let mut record = Vec::new();
+                if (&mut self.reader).take(length).read_to_end(&mut record)? < length as usize {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+                record
+            }
+        };
+        self.read_records += 1;
+        Ok(Some(record))
+    }
+}
+
+impl<R: BufRead> Iterator for RecordReader<R> {
+    type Item = Result<Vec<u8>, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Writes records to a stream in the format a `RecordReader` reads them.
+pub struct RecordWriter<W: Write> {
+    writer: W,
+    format: RecordFormat,
+    written_records: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(writer: W, format: RecordFormat) -> RecordWriter<W> {
+        RecordWriter { writer, format, written_records: 0 }
+    }
+
+    pub fn write_record(&mut self, record: &[u8]) -> Result<(), StreamError> {
+        match self.format {
+            RecordFormat::Ndjson => {
+                if record.is_empty() || record.contains(&b'\n') {
+                    return Err(StreamError::InvalidRecord {
+                        record: self.written_records,
+                        message: "records of newline-delimited streams must neither be empty nor contain line breaks".to_string(),
+                    });
+                }
+                self.writer.write_all(record)?;
+                self.writer.write_all(b"\n")?;
+            }
+            RecordFormat::LengthPrefixed => {
+                let length = u32::try_from(record.len()).map_err(|_| StreamError::InvalidRecord {
+                    record: self.written_records,
+                    message: "the record is too long for a 32 bit length".to_string(),
+                })?;
+                self.writer.write_all(&length.to_be_bytes())?;
+                self.writer.write_all(record)?;
+            }
+        }
+        self.written_records += 1;
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying stream.
+    pub fn into_inner(mut self) -> Result<W, StreamError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Decodes every record of the input, applies the operation, e.g. `Dfa::minimize` or `Dfa::classify`, and writes the encoded result
+/// to the output before the next record is read, so only one record is held in memory at a time. Returns the number of processed records.
+/// Processing stops at the first record that cannot be decoded, processed or encoded.
+pub fn process_records<T, U>(
+    input: impl BufRead,
+    output: impl Write,
+    format: RecordFormat,
+    mut decode: impl FnMut(&[u8]) -> Result<T, String>,
+    mut operation: impl FnMut(T) -> Result<U, String>,
+    mut encode: impl FnMut(&U) -> Result<Vec<u8>, String>,
+) -> Result<usize, StreamError> {
+    let mut writer = RecordWriter::new(output, format);
+    let mut processed_records = 0;
+    for record in RecordReader::new(input, format) {
+        let invalid_record = |message| StreamError::InvalidRecord { record: processed_records, message };
+        let result = operation(decode(&record?).map_err(invalid_record)?).map_err(invalid_record)?;
+        writer.write_record(&encode(&result).map_err(invalid_record)?)?;
+        processed_records += 1;
+    }
+    writer.into_inner()?;
+    Ok(processed_records)
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use crate::{process_records, Dfa, RecordFormat, RecordReader, RecordWriter, Regex, StreamError};
+
+    #[test]
+    fn test_process_records() {
+        // Regular expressions stand in for serialized automata. Each one is replaced by the state count of its minimal DFA.
+        let input = "a*\n\n(a|b)*abb\r\n";
+        let decode = |record: &[u8]| Regex::parse(&String::from_utf8_lossy(record)).map(|regex| regex.to_dfa()).map_err(|error| error.to_string());
+        let minimize = |mut dfa: Dfa| {
+            dfa.minimize();
+            Ok(dfa.get_all_mentioned_states().len())
+        };
+        let encode = |state_count: &usize| Ok(state_count.to_string().into_bytes());
+        let mut output = Vec::new();
+        assert_eq!(process_records(input.as_bytes(), &mut output, RecordFormat::Ndjson, decode, minimize, encode), Ok(2));
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n4\n");
+        let error = process_records("a*\n(a\n".as_bytes(), Vec::new(), RecordFormat::Ndjson, decode, minimize, encode).unwrap_err();
+        assert!(matches!(error, StreamError::InvalidRecord { record: 1, .. }));
+    }
+
+    #[test]
+    fn test_length_prefixed_records() {
+        let mut writer = RecordWriter::new(Vec::new(), RecordFormat::LengthPrefixed);
+        writer.write_record(b"first\nrecord").unwrap();
+        writer.write_record(b"").unwrap();
+        let bytes = writer.into_inner().unwrap();
+        let records: Vec<Vec<u8>> = RecordReader::new(bytes.as_slice(), RecordFormat::LengthPrefixed).collect::<Result<_, _>>().unwrap();
+        assert_eq!(records, vec![b"first\nrecord".to_vec(), Vec::new()]);
+        let truncated = RecordReader::new(&bytes[..6], RecordFormat::LengthPrefixed).next().unwrap();
+        assert!(matches!(truncated, Err(StreamError::Io { kind: std::io::ErrorKind::UnexpectedEof, .. })));
+    }
+
+    #[test]
+    fn test_max_record_length() {
+        // The length prefix claims 4 GiB, but the stream ends right after it.
+        let mut reader = RecordReader::new(&[0xff, 0xff, 0xff, 0xff][..], RecordFormat::LengthPrefixed);
+        assert_eq!(reader.read_record(), Err(StreamError::RecordTooLong { record: 0, max_length: 64 * 1024 * 1024 }));
+        let mut reader = RecordReader::new("abc\r\nabcd\n".as_bytes(), RecordFormat::Ndjson);
+        reader.set_max_record_length(3);
+        assert_eq!(reader.read_record(), Ok(Some(b"abc".to_vec())));
+        let error = reader.read_record().unwrap_err();
+        assert_eq!(error, StreamError::RecordTooLong { record: 1, max_length: 3 });
+        assert_eq!(error.to_string(), "record 1 is longer than the maximum of 3 bytes");
+    }
+}
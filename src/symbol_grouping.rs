@@ -0,0 +1,133 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::{fresh_state_name, Dfa, StateMetadata, Transition};
+
+/// How `Dfa::group_symbols` decides whether a word over the abstract symbol is accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupingSemantics {
+    /// The abstract word is accepted if the original DFA accepts at least one word it stands for. The result over-approximates
+    /// the language, so if it satisfies a safety property (does not accept a bad word), the original DFA does, too.
+    Existential,
+    /// The abstract word is accepted if the original DFA accepts every word it stands for. The result under-approximates
+    /// the language, so every word it accepts is known to be accepted by the original DFA, whichever concrete symbols are read.
+    Universal,
+}
+
+/// Describes why a group of symbols cannot be merged into one abstract symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolGroupingError {
+    /// The group does not contain any symbol.
+    EmptyGroup,
+    /// The abstract symbol is already used for a symbol outside of the group, so both could not be told apart afterwards.
+    AbstractSymbolInUse(char),
+}
+
+impl fmt::Display for SymbolGroupingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolGroupingError::EmptyGroup => write!(f, "the group does not contain any symbol"),
+            SymbolGroupingError::AbstractSymbolInUse(symbol) => write!(f, "the abstract symbol {} is already used outside of the group", symbol),
+        }
+    }
+}
+
+impl std::error::Error for SymbolGroupingError {}
+
+impl Dfa {
+    /// Merges the symbols of the group into one abstract symbol, e.g. to shrink a large alphabet before an expensive analysis.
+    /// Every word over the new alphabet stands for all words in which the abstract symbol is replaced by symbols of the group,
+    /// and the semantics decides whether one or all of them must be accepted. The result is computed exactly with the subset construction,
+    /// tracking all states the original DFA could be in. States are named after these subsets, e.g. "{q0,q1}",
+    /// which are also stored as the origin in the metadata of the state.
+    pub fn group_symbols(&self, group: &HashSet<char>, abstract_symbol: char, semantics: GroupingSemantics) -> Result<Dfa, SymbolGroupingError> {
+        if group.is_empty() {
+            return Err(SymbolGroupingError::EmptyGroup);
+        }
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let alphabet = dfa.get_alphabet();
+        if alphabet.contains(&abstract_symbol) && !group.contains(&abstract_symbol) {
+            return Err(SymbolGroupingError::AbstractSymbolInUse(abstract_symbol));
+        }
+        let mut abstract_alphabet: HashSet<char> = alphabet.difference(group).cloned().collect();
+        abstract_alphabet.insert(abstract_symbol);
+        let concrete_symbols = |symbol: char| match symbol == abstract_symbol {
+            true => group.iter().cloned().sorted().collect_vec(),
+            false => vec![symbol],
+        };
+        let start_subset: BTreeSet<&str> = vec![dfa.start_state.as_str()].into_iter().collect();
+        let mut state_names: HashMap<BTreeSet<&str>, String> = HashMap::new();
+        let mut used_names = HashSet::new();
+        let mut subsets_to_visit = VecDeque::new();
+        let mut accept_states = HashSet::new();
+        let mut transitions = Vec::new();
+        let start_state = fresh_state_name(&format!("{{{}}}", start_subset.iter().join(",")), &used_names);
+        used_names.insert(start_state.clone());
+        state_names.insert(start_subset.clone(), start_state.clone());
+        subsets_to_visit.push_back(start_subset);
+        while let Some(subset) = subsets_to_visit.pop_front() {
+            let state = state_names[&subset].clone();
+            let is_accepting = match semantics {
+                GroupingSemantics::Existential => subset.iter().any(|state| dfa.accept_states.contains(*state)),
+                GroupingSemantics::Universal => subset.iter().all(|state| dfa.accept_states.contains(*state)),
+            };
+            if is_accepting {
+                accept_states.insert(state.clone());
+            }
+            for symbol in abstract_alphabet.iter().sorted() {
+                let next_states = subset.iter()
+                    .cartesian_product(concrete_symbols(*symbol))
+                    .map(|(state, concrete_symbol)| dfa.get_transition(state, &concrete_symbol).map(|transition| transition.next_state.as_str()))
+                    .collect_vec();
+                // Under universal semantics, a missing transition rejects one of the words, and thereby the abstract word.
+                if semantics == GroupingSemantics::Universal && next_states.contains(&None) {
+                    continue;
+                }
+                let next_subset: BTreeSet<&str> = next_states.into_iter().flatten().collect();
+                if next_subset.is_empty() {
+                    continue;
+                }
+                if !state_names.contains_key(&next_subset) {
+                    let next_state = fresh_state_name(&format!("{{{}}}", next_subset.iter().join(",")), &used_names);
+                    used_names.insert(next_state.clone());
+                    state_names.insert(next_subset.clone(), next_state);
+                    subsets_to_visit.push_back(next_subset.clone());
+                }
+                transitions.push(Transition { state: state.clone(), input: *symbol, next_state: state_names[&next_subset].clone() });
+            }
+        }
+        let state_metadata = state_names.into_iter()
+            .map(|(subset, state)| (state, StateMetadata { origin: subset.into_iter().map(String::from).collect(), ..StateMetadata::default() }))
+            .collect();
+        Ok(Dfa {
+            alphabet: Some(abstract_alphabet),
+            state_metadata,
+            ..Dfa::with_parts(dfa.name.clone(), start_state, accept_states, transitions)
+        })
+    }
+}
+
+#[cfg(test)]
+mod symbol_grouping_tests {
+    use crate::{Dfa, GroupingSemantics, SymbolGroupingError};
+
+    #[test]
+    fn test_group_symbols() {
+        // Accepts words over 'a', 'b' and 'c' that end with 'a'.
+        let dfa = Dfa::from_table(&["other", "a"], &['a', 'b', 'c'], &[&[Some(1), Some(0), Some(0)], &[Some(1), Some(0), Some(0)]], 0, &[1]).unwrap();
+        let group = vec!['a', 'b'].into_iter().collect();
+        let over_approximation = dfa.group_symbols(&group, 'x', GroupingSemantics::Existential).unwrap();
+        assert!(over_approximation.check("cx").0, "cb is rejected, but ca is accepted");
+        assert!(!over_approximation.check("xc").0);
+        let under_approximation = dfa.group_symbols(&group, 'x', GroupingSemantics::Universal).unwrap();
+        assert!(!under_approximation.check("cx").0, "cb is rejected");
+        assert_eq!(under_approximation.check("").0, dfa.check("").0);
+        let group = vec!['b', 'c'].into_iter().collect();
+        let under_approximation = dfa.group_symbols(&group, 'x', GroupingSemantics::Universal).unwrap();
+        assert!(under_approximation.check("xa").0);
+        assert_eq!(dfa.group_symbols(&group, 'a', GroupingSemantics::Universal).unwrap_err(), SymbolGroupingError::AbstractSymbolInUse('a'));
+    }
+}
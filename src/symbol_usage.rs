@@ -0,0 +1,87 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::Dfa;
+
+impl Dfa {
+    /// Returns the symbols of the declared alphabet that are irrelevant to the accepted language, because no accepted word
+    /// contains them. Such symbols are either not used by any transition or only by transitions from unreachable states or
+    /// into dead states, from which no accept state can be reached.
+    pub fn unused_symbols(&self, declared_alphabet: &HashSet<char>) -> HashSet<char> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let reachable_states = dfa.get_reachable_states();
+        let productive_states = dfa.get_productive_states();
+        let used_symbols: HashSet<char> = dfa.transitions.iter()
+            .filter(|transition| reachable_states.contains(&transition.state) && productive_states.contains(&transition.next_state))
+            .map(|transition| transition.input)
+            .collect();
+        declared_alphabet.difference(&used_symbols).cloned().collect()
+    }
+
+    /// Removes the symbols from the DFA, i.e. all transitions reading them and their declaration in the alphabet.
+    /// The language only stays the same if the symbols are unused (see `unused_symbols`).
+    /// Returns the number of removed transitions.
+    pub fn prune_symbols(&mut self, symbols: &[char]) -> usize {
+        let transition_count = self.transitions.len();
+        self.transitions.retain(|transition| !symbols.contains(&transition.input));
+        if let Some(alphabet) = &mut self.alphabet {
+            alphabet.retain(|symbol| !symbols.contains(symbol));
+        }
+        transition_count - self.transitions.len()
+    }
+
+    /// Returns the states that can be reached from the start state, using the breath first algorithm.
+    pub(crate) fn get_reachable_states(&self) -> HashSet<String> {
+        let mut visited_states = HashSet::new();
+        let mut states_to_visit = VecDeque::new();
+        visited_states.insert(self.start_state.clone());
+        states_to_visit.push_back(self.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in self.transitions.iter().filter(|transition| transition.state == state) {
+                if visited_states.insert(transition.next_state.clone()) {
+                    states_to_visit.push_back(transition.next_state.clone());
+                }
+            }
+        }
+        visited_states
+    }
+
+    /// Returns the states from which an accept state can be reached, using the breath first algorithm on the reversed transitions.
+    pub(crate) fn get_productive_states(&self) -> HashSet<String> {
+        let mut visited_states: HashSet<String> = self.accept_states.clone();
+        let mut states_to_visit: VecDeque<String> = self.accept_states.iter().cloned().collect();
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in self.transitions.iter().filter(|transition| transition.next_state == state) {
+                if visited_states.insert(transition.state.clone()) {
+                    states_to_visit.push_back(transition.state.clone());
+                }
+            }
+        }
+        visited_states
+    }
+}
+
+#[cfg(test)]
+mod symbol_usage_tests {
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+
+    use crate::Dfa;
+
+    #[test]
+    fn test_unused_symbols() {
+        // 'a' leads to the accept state, 'b' only into the dead state q2 and 'c' is only read by the unreachable state q3.
+        let mut dfa = Dfa::from_table(
+            &["q0", "q1", "q2", "q3"],
+            &['a', 'b', 'c', 'd'],
+            &[&[Some(1), Some(2), None, None], &[Some(1), None, None, None], &[Some(2), None, None, None], &[None, None, Some(1), None]],
+            0,
+            &[1],
+        ).unwrap();
+        let unused_symbols = dfa.unused_symbols(&dfa.get_alphabet());
+        assert_eq!(unused_symbols, HashSet::from_iter(vec!['b', 'c', 'd']));
+        assert_eq!(dfa.prune_symbols(&['b', 'c', 'd']), 2);
+        assert_eq!(dfa.get_alphabet(), HashSet::from_iter(vec!['a']));
+        assert!(dfa.check("aaa").0);
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use itertools::Itertools;
+
+use crate::fresh_state_name;
+
+/// An effective [Boolean algebra](https://en.wikipedia.org/wiki/Boolean_algebra_(structure)) of predicates over some
+/// domain of elements. Symbolic automata label their transitions with such predicates instead of single symbols,
+/// which keeps automata over huge alphabets like Unicode small.
+pub trait BooleanAlgebra {
+    type Element;
+    type Predicate: Clone + Eq + Hash + Debug;
+
+    /// The predicate that is satisfied by every element.
+    fn top(&self) -> Self::Predicate;
+    /// The predicate that is satisfied by no element.
+    fn bottom(&self) -> Self::Predicate;
+    fn and(&self, left: &Self::Predicate, right: &Self::Predicate) -> Self::Predicate;
+    fn or(&self, left: &Self::Predicate, right: &Self::Predicate) -> Self::Predicate;
+    fn not(&self, predicate: &Self::Predicate) -> Self::Predicate;
+    /// Checks whether there is at least one element satisfying the predicate.
+    fn is_satisfiable(&self, predicate: &Self::Predicate) -> bool;
+    fn evaluate(&self, predicate: &Self::Predicate, element: &Self::Element) -> bool;
+}
+
+/// A set of characters represented by sorted, disjoint and non-adjacent ranges. Used as the predicates of `CharRangeAlgebra`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct CharRanges {
+    ranges: Vec<(char, char)>,
+}
+
+impl CharRanges {
+    /// Creates the set of characters contained in any of the given inclusive ranges.
+    pub fn new(ranges: &[(char, char)]) -> CharRanges {
+        CharRanges::from_code_points(ranges.iter().map(|(from, to)| (*from as u32, *to as u32)).collect())
+    }
+
+    pub fn contains(&self, char: char) -> bool {
+        self.ranges.iter().any(|(from, to)| *from <= char && char <= *to)
+    }
+
+    /// Normalizes ranges of code points into sorted, disjoint ranges of valid characters.
+    fn from_code_points(mut code_point_ranges: Vec<(u32, u32)>) -> CharRanges {
+        // Surrogates are no valid characters and therefore cut out of every range.
+        code_point_ranges = code_point_ranges.into_iter()
+            .flat_map(|(from, to)| vec![(from, to.min(0xD7FF)), (from.max(0xE000), to)])
+            .filter(|(from, to)| from <= to)
+            .sorted()
+            .collect();
+        let mut merged_ranges: Vec<(u32, u32)> = Vec::new();
+        for (from, to) in code_point_ranges {
+            match merged_ranges.last_mut() {
+                Some(last) if from <= last.1.saturating_add(1) || (last.1 == 0xD7FF && from == 0xE000) => last.1 = last.1.max(to),
+                _ => merged_ranges.push((from, to)),
+            }
+        }
+        CharRanges {
+            ranges: merged_ranges.into_iter()
+                .map(|(from, to)| (std::char::from_u32(from).unwrap(), std::char::from_u32(to).unwrap()))
+                .collect(),
+        }
+    }
+
+    fn code_point_ranges(&self) -> Vec<(u32, u32)> {
+        self.ranges.iter().map(|(from, to)| (*from as u32, *to as u32)).collect()
+    }
+}
+
+/// The Boolean algebra of character sets, which is what symbolic automata over text usually need.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CharRangeAlgebra;
+
+impl BooleanAlgebra for CharRangeAlgebra {
+    type Element = char;
+    type Predicate = CharRanges;
+
+    fn top(&self) -> CharRanges {
+        CharRanges::new(&[('\0', std::char::MAX)])
+    }
+
+    fn bottom(&self) -> CharRanges {
+        CharRanges { ranges: Vec::new() }
+    }
+
+    fn and(&self, left: &CharRanges, right: &CharRanges) -> CharRanges {
+        let intersections = left.code_point_ranges().into_iter()
+            .cartesian_product(right.code_point_ranges())
+            .map(|((left_from, left_to), (right_from, right_to))| (left_from.max(right_from), left_to.min(right_to)))
+            .collect();
+        CharRanges::from_code_points(intersections)
+    }
+
+    fn or(&self, left: &CharRanges, right: &CharRanges) -> CharRanges {
+        CharRanges::from_code_points(left.code_point_ranges().into_iter().chain(right.code_point_ranges()).collect())
+    }
+
+    fn not(&self, predicate: &CharRanges) -> CharRanges {
+        let mut gaps = Vec::new();
+        let mut next_uncovered = 0;
+        for (from, to) in predicate.code_point_ranges() {
+            if from > next_uncovered {
+                gaps.push((next_uncovered, from - 1));
+            }
+            next_uncovered = to + 1;
+        }
+        gaps.push((next_uncovered, std::char::MAX as u32));
+        CharRanges::from_code_points(gaps)
+    }
+
+    fn is_satisfiable(&self, predicate: &CharRanges) -> bool {
+        !predicate.ranges.is_empty()
+    }
+
+    fn evaluate(&self, predicate: &CharRanges, element: &char) -> bool {
+        predicate.contains(*element)
+    }
+}
+
+/// Describes to which next state a symbolic automaton may switch when it reads an element satisfying the guard.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct SymbolicTransition<P> {
+    state: String,
+    guard: P,
+    next_state: String,
+}
+
+/// # [Symbolic finite automaton](https://en.wikipedia.org/wiki/Symbolic_automaton)
+/// A possibly nondeterministic finite automaton whose transitions are guarded by predicates of a Boolean algebra.
+pub struct SymbolicAutomaton<A: BooleanAlgebra> {
+    algebra: A,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<SymbolicTransition<A::Predicate>>,
+}
+
+impl<A: BooleanAlgebra + Clone> Clone for SymbolicAutomaton<A> {
+    fn clone(&self) -> Self {
+        SymbolicAutomaton {
+            algebra: self.algebra.clone(),
+            start_state: self.start_state.clone(),
+            accept_states: self.accept_states.clone(),
+            transitions: self.transitions.clone(),
+        }
+    }
+}
+
+impl<A: BooleanAlgebra + Clone> SymbolicAutomaton<A> {
+    /// Creates a symbolic automaton without transitions and accept states.
+    pub fn new(algebra: A, start_state: &str) -> SymbolicAutomaton<A> {
+        SymbolicAutomaton { algebra, start_state: start_state.to_string(), accept_states: HashSet::new(), transitions: Vec::new() }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    /// Adds a guarded transition. Transitions with unsatisfiable guards are dropped, as they can never be taken.
+    pub fn add_transition(&mut self, state: &str, guard: A::Predicate, next_state: &str) {
+        if !self.algebra.is_satisfiable(&guard) {
+            return;
+        }
+        let existing_transition = self.transitions.iter_mut()
+            .find(|transition| transition.state == state && transition.next_state == next_state);
+        match existing_transition {
+            Some(transition) => transition.guard = self.algebra.or(&transition.guard, &guard),
+            None => self.transitions.push(SymbolicTransition { state: state.to_string(), guard, next_state: next_state.to_string() }),
+        }
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        let mut all_states: HashSet<String> = self.transitions.iter()
+            .flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()])
+            .collect();
+        all_states.insert(self.start_state.clone());
+        all_states.extend(self.accept_states.iter().cloned());
+        all_states
+    }
+
+    /// Checks whether a sequence of elements is accepted, simulating all runs simultaneously.
+    pub fn check(&self, input: &[A::Element]) -> bool {
+        let mut current_states: HashSet<&str> = vec![&self.start_state[..]].into_iter().collect();
+        for element in input {
+            current_states = self.transitions.iter()
+                .filter(|transition| current_states.contains(&transition.state[..]) && self.algebra.evaluate(&transition.guard, element))
+                .map(|transition| &transition.next_state[..])
+                .collect();
+        }
+        current_states.iter().any(|state| self.accept_states.contains(*state))
+    }
+
+    /// The automaton is deterministic if the guards of the outgoing transitions of every state are pairwise disjoint.
+    pub fn is_deterministic(&self) -> bool {
+        self.transitions.iter().tuple_combinations().all(|(first, second)| {
+            first.state != second.state || !self.algebra.is_satisfiable(&self.algebra.and(&first.guard, &second.guard))
+        })
+    }
+
+    /// Converts the automaton into a deterministic one with the subset construction. The alphabet of each subset
+    /// is split into minterms, the satisfiable Boolean combinations of the outgoing guards.
+    /// The states of the result are named after their subsets, e.g. "{q0,q1}".
+    pub fn determinize(&self) -> SymbolicAutomaton<A> {
+        let name_of_subset = |subset: &BTreeSet<String>| format!("{{{}}}", subset.iter().join(","));
+        let start_subset: BTreeSet<String> = vec![self.start_state.clone()].into_iter().collect();
+        let mut deterministic_automaton = SymbolicAutomaton::new(self.algebra.clone(), &name_of_subset(&start_subset));
+        let mut visited_subsets = HashSet::new();
+        let mut subsets_to_visit = VecDeque::new();
+        visited_subsets.insert(start_subset.clone());
+        subsets_to_visit.push_back(start_subset);
+        while let Some(subset) = subsets_to_visit.pop_front() {
+            if subset.iter().any(|state| self.accept_states.contains(state)) {
+                deterministic_automaton.add_accept_state(&name_of_subset(&subset));
+            }
+            let outgoing_transitions = self.transitions.iter().filter(|transition| subset.contains(&transition.state)).collect_vec();
+            let guards = outgoing_transitions.iter().map(|transition| transition.guard.clone()).collect_vec();
+            for minterm in self.minterms(&guards) {
+                let next_subset: BTreeSet<String> = outgoing_transitions.iter()
+                    .filter(|transition| self.algebra.is_satisfiable(&self.algebra.and(&transition.guard, &minterm)))
+                    .map(|transition| transition.next_state.clone())
+                    .collect();
+                if next_subset.is_empty() {
+                    continue;
+                }
+                deterministic_automaton.add_transition(&name_of_subset(&subset), minterm, &name_of_subset(&next_subset));
+                if visited_subsets.insert(next_subset.clone()) {
+                    subsets_to_visit.push_back(next_subset);
+                }
+            }
+        }
+        deterministic_automaton
+    }
+
+    /// Adds a rejecting trap state and routes every element without a transition into it.
+    pub fn complete(&self) -> SymbolicAutomaton<A> {
+        let mut complete_automaton = self.clone();
+        let trap_state = fresh_state_name("trap", &self.get_all_states());
+        let mut is_trap_state_used = false;
+        for state in self.get_all_states().into_iter().sorted() {
+            let covered_elements = self.transitions.iter()
+                .filter(|transition| transition.state == state)
+                .fold(self.algebra.bottom(), |covered, transition| self.algebra.or(&covered, &transition.guard));
+            let missing_elements = self.algebra.not(&covered_elements);
+            if self.algebra.is_satisfiable(&missing_elements) {
+                complete_automaton.add_transition(&state, missing_elements, &trap_state);
+                is_trap_state_used = true;
+            }
+        }
+        if is_trap_state_used {
+            complete_automaton.add_transition(&trap_state, self.algebra.top(), &trap_state);
+        }
+        complete_automaton
+    }
+
+    /// Returns an automaton accepting exactly the sequences this automaton rejects.
+    pub fn complement(&self) -> SymbolicAutomaton<A> {
+        let mut complement = self.determinize().complete();
+        complement.accept_states = complement.get_all_states().difference(&complement.accept_states).cloned().collect();
+        complement
+    }
+
+    /// Builds the product automaton of both automata, whose states are named "(p, q)". The predicate decides whether a pair
+    /// is accepting, given whether p and q are accepting. If a pair may accept although one side is rejecting, like for the union,
+    /// both automata are completed first so that no run gets lost.
+    pub fn product(&self, other: &SymbolicAutomaton<A>, is_accepting: impl Fn(bool, bool) -> bool) -> SymbolicAutomaton<A> {
+        let (left, right) = if is_accepting(true, false) || is_accepting(false, true) {
+            (self.complete(), other.complete())
+        } else {
+            (self.clone(), other.clone())
+        };
+        let name_of_pair = |pair: &(String, String)| format!("({}, {})", pair.0, pair.1);
+        let start_pair = (left.start_state.clone(), right.start_state.clone());
+        let mut product = SymbolicAutomaton::new(self.algebra.clone(), &name_of_pair(&start_pair));
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        visited_pairs.insert(start_pair.clone());
+        pairs_to_visit.push_back(start_pair);
+        while let Some(pair) = pairs_to_visit.pop_front() {
+            if is_accepting(left.accept_states.contains(&pair.0), right.accept_states.contains(&pair.1)) {
+                product.add_accept_state(&name_of_pair(&pair));
+            }
+            let left_transitions = left.transitions.iter().filter(|transition| transition.state == pair.0);
+            for left_transition in left_transitions {
+                for right_transition in right.transitions.iter().filter(|transition| transition.state == pair.1) {
+                    let guard = self.algebra.and(&left_transition.guard, &right_transition.guard);
+                    if !self.algebra.is_satisfiable(&guard) {
+                        continue;
+                    }
+                    let next_pair = (left_transition.next_state.clone(), right_transition.next_state.clone());
+                    product.add_transition(&name_of_pair(&pair), guard, &name_of_pair(&next_pair));
+                    if visited_pairs.insert(next_pair.clone()) {
+                        pairs_to_visit.push_back(next_pair);
+                    }
+                }
+            }
+        }
+        product
+    }
+
+    /// Minimizes the automaton. It is determinized and completed first, then equivalent states are found by partition
+    /// refinement over the minterms of all guards. Just like `Dfa::minimize`, each class is named after its smallest state.
+    pub fn minimize(&self) -> SymbolicAutomaton<A> {
+        let automaton = self.determinize().complete();
+        let all_states = automaton.get_all_states().into_iter().sorted().collect_vec();
+        let minterms = automaton.minterms(&automaton.transitions.iter().map(|transition| transition.guard.clone()).collect_vec());
+        // As the automaton is deterministic and complete, every state has exactly one next state for each minterm.
+        let next_state = |state: &str, minterm: &A::Predicate| -> String {
+            automaton.transitions.iter()
+                .find(|transition| transition.state == state && automaton.algebra.is_satisfiable(&automaton.algebra.and(&transition.guard, minterm)))
+                .map(|transition| transition.next_state.clone())
+                .unwrap()
+        };
+        let mut class_of_state: HashMap<String, usize> = all_states.iter()
+            .map(|state| (state.clone(), if automaton.accept_states.contains(state) { 1 } else { 0 }))
+            .collect();
+        loop {
+            let mut classes_by_signature: HashMap<(usize, Vec<usize>), usize> = HashMap::new();
+            let mut new_class_of_state = HashMap::new();
+            for state in &all_states {
+                let signature = minterms.iter().map(|minterm| class_of_state[&next_state(state, minterm)]).collect_vec();
+                let class_count = classes_by_signature.len();
+                let class = *classes_by_signature.entry((class_of_state[state], signature)).or_insert(class_count);
+                new_class_of_state.insert(state.clone(), class);
+            }
+            let has_split_occurred = new_class_of_state.values().unique().count() > class_of_state.values().unique().count();
+            class_of_state = new_class_of_state;
+            if !has_split_occurred {
+                break;
+            }
+        }
+        let representative = |state: &str| all_states.iter().find(|other| class_of_state[*other] == class_of_state[state]).unwrap().clone();
+        let mut minimal_automaton = SymbolicAutomaton::new(self.algebra.clone(), &representative(&automaton.start_state));
+        for accept_state in &automaton.accept_states {
+            minimal_automaton.add_accept_state(&representative(accept_state));
+        }
+        for transition in &automaton.transitions {
+            minimal_automaton.add_transition(&representative(&transition.state), transition.guard.clone(), &representative(&transition.next_state));
+        }
+        minimal_automaton
+    }
+
+    /// Splits the domain into the satisfiable Boolean combinations of the given predicates. Every minterm is either
+    /// contained in a predicate or disjoint from it.
+    fn minterms(&self, predicates: &[A::Predicate]) -> Vec<A::Predicate> {
+        let mut minterms = vec![self.algebra.top()];
+        for predicate in predicates.iter().unique() {
+            let negated_predicate = self.algebra.not(predicate);
+            minterms = minterms.iter()
+                .flat_map(|minterm| vec![self.algebra.and(minterm, predicate), self.algebra.and(minterm, &negated_predicate)])
+                .filter(|minterm| self.algebra.is_satisfiable(minterm))
+                .collect();
+        }
+        minterms
+    }
+}
+
+#[cfg(test)]
+mod symbolic_automaton_tests {
+    use crate::{BooleanAlgebra, CharRangeAlgebra, CharRanges, SymbolicAutomaton};
+
+    /// Creates an automaton accepting identifiers: a letter followed by letters or digits.
+    fn create_identifier_automaton() -> SymbolicAutomaton<CharRangeAlgebra> {
+        let letters = CharRanges::new(&[('a', 'z'), ('A', 'Z')]);
+        let letters_and_digits = CharRanges::new(&[('a', 'z'), ('A', 'Z'), ('0', '9')]);
+        let mut automaton = SymbolicAutomaton::new(CharRangeAlgebra, "start");
+        automaton.add_transition("start", letters, "identifier");
+        automaton.add_transition("identifier", letters_and_digits, "identifier");
+        automaton.add_accept_state("identifier");
+        automaton
+    }
+
+    fn chars(input: &str) -> Vec<char> {
+        input.chars().collect()
+    }
+
+    #[test]
+    fn test_char_range_algebra() {
+        let algebra = CharRangeAlgebra;
+        let digits = CharRanges::new(&[('0', '9')]);
+        let letters = CharRanges::new(&[('a', 'z')]);
+        assert!(!algebra.is_satisfiable(&algebra.and(&digits, &letters)));
+        assert_eq!(algebra.or(&digits, &CharRanges::new(&[('5', 'c')])), CharRanges::new(&[('0', 'c')]));
+        assert_eq!(algebra.not(&algebra.not(&digits)), digits);
+        assert!(!algebra.is_satisfiable(&algebra.and(&digits, &algebra.not(&digits))));
+        assert_eq!(algebra.not(&algebra.bottom()), algebra.top());
+    }
+
+    #[test]
+    fn test_check_and_complement() {
+        let automaton = create_identifier_automaton();
+        assert!(automaton.is_deterministic());
+        assert!(automaton.check(&chars("x42")));
+        assert!(!automaton.check(&chars("42x")));
+        let complement = automaton.complement();
+        assert!(!complement.check(&chars("x42")));
+        assert!(complement.check(&chars("42x")));
+        assert!(complement.check(&chars("")));
+    }
+
+    #[test]
+    fn test_product_and_minimize() {
+        let automaton = create_identifier_automaton();
+        // Accepts words of even length.
+        let mut even_length = SymbolicAutomaton::new(CharRangeAlgebra, "even");
+        even_length.add_transition("even", CharRangeAlgebra.top(), "odd");
+        even_length.add_transition("odd", CharRangeAlgebra.top(), "even");
+        even_length.add_accept_state("even");
+        let intersection = automaton.product(&even_length, |a, b| a && b);
+        assert!(intersection.check(&chars("x4")));
+        assert!(!intersection.check(&chars("x42")));
+        let union = automaton.product(&even_length, |a, b| a || b);
+        assert!(union.check(&chars("42")));
+        assert!(union.check(&chars("x42")));
+        assert!(!union.check(&chars("42x")));
+        // The minimal union needs the start state and two parity states for identifiers as well as for non-identifiers.
+        let minimal_union = union.minimize();
+        assert_eq!(minimal_union.get_all_states().len(), 5);
+        assert!(minimal_union.check(&chars("42")));
+        assert!(!minimal_union.check(&chars("42x")));
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::{HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{Bounded, Dfa, Fuel, Transition};
+
+/// The [prefix tree acceptor](https://en.wikipedia.org/wiki/Induction_of_regular_languages) of the samples: a tree with one node
+/// per prefix of a sample, labeled with whether the prefix is a positive or negative sample.
+struct PrefixTree {
+    children: Vec<HashMap<char, usize>>,
+    labels: Vec<Option<bool>>,
+}
+
+impl PrefixTree {
+    /// Builds the tree. Returns None if a word is both a positive and a negative sample.
+    fn new(positive: &[&str], negative: &[&str]) -> Option<PrefixTree> {
+        let mut tree = PrefixTree { children: vec![HashMap::new()], labels: vec![None] };
+        for (words, label) in &[(positive, true), (negative, false)] {
+            for word in words.iter() {
+                let mut node = 0;
+                for char in word.chars() {
+                    node = match tree.children[node].get(&char) {
+                        Some(child) => *child,
+                        None => {
+                            tree.children.push(HashMap::new());
+                            tree.labels.push(None);
+                            let child = tree.children.len() - 1;
+                            tree.children[node].insert(char, child);
+                            child
+                        }
+                    };
+                }
+                if tree.labels[node] == Some(!label) {
+                    return None;
+                }
+                tree.labels[node] = Some(*label);
+            }
+        }
+        Some(tree)
+    }
+
+    /// Lists all nodes except the root in breath first order, together with their parent and the symbol leading to them.
+    fn get_edges_in_breath_first_order(&self) -> Vec<(usize, char, usize)> {
+        let mut edges = Vec::new();
+        let mut nodes_to_visit = VecDeque::new();
+        nodes_to_visit.push_back(0);
+        while let Some(node) = nodes_to_visit.pop_front() {
+            for (symbol, child) in self.children[node].iter().sorted() {
+                edges.push((node, *symbol, *child));
+                nodes_to_visit.push_back(*child);
+            }
+        }
+        edges
+    }
+}
+
+/// The state of the backtracking search: every node of the prefix tree is colored with the DFA state it is mapped to.
+struct Coloring {
+    colors: Vec<usize>,
+    transitions: HashMap<(usize, char), usize>,
+    labels: Vec<Option<bool>>,
+    used_colors: usize,
+}
+
+impl Dfa {
+    /// Finds a DFA with as few states as possible, but at most `max_states`, that accepts all positive and rejects all negative samples.
+    /// Unlike heuristic learners, the search is exact: If None is returned, no such DFA exists. The search maps the prefixes of
+    /// the samples onto states by backtracking in breath first order, where a new state is only introduced if no existing one fits.
+    /// The resulting DFA only has the transitions needed by the samples and names its states "q0", "q1" and so on.
+    /// The search may take exponential time, see `synthesize_with_fuel` for a bounded search.
+    pub fn synthesize(positive: &[&str], negative: &[&str], max_states: usize) -> Option<Dfa> {
+        Dfa::synthesize_with_fuel(positive, negative, max_states, &mut Fuel::unlimited()).done().flatten()
+    }
+
+    /// Synthesizes a DFA like `synthesize`, but tries at most as many colors for nodes of the prefix tree as there is fuel,
+    /// and tells apart samples that need more states from searches for which the fuel ran out.
+    pub fn synthesize_with_fuel(positive: &[&str], negative: &[&str], max_states: usize, fuel: &mut Fuel) -> Bounded<Option<Dfa>> {
+        let tree = match PrefixTree::new(positive, negative) {
+            Some(tree) => tree,
+            None => return Bounded::Done(None),
+        };
+        let edges = tree.get_edges_in_breath_first_order();
+        for state_count in 1..=max_states {
+            let mut coloring = Coloring {
+                colors: vec![0; tree.labels.len()],
+                transitions: HashMap::new(),
+                labels: vec![tree.labels[0]; 1].into_iter().chain(vec![None; state_count - 1]).collect(),
+                used_colors: 1,
+            };
+            let is_colored = match Dfa::color_nodes(&tree, &edges, 0, &mut coloring, state_count, fuel) {
+                Bounded::Done(is_colored) => is_colored,
+                Bounded::Exhausted => return Bounded::Exhausted,
+            };
+            if is_colored {
+                let state_name = |color: usize| format!("q{}", color);
+                return Bounded::Done(Some(Dfa::with_parts(
+                    String::new(),
+                    state_name(0),
+                    (0..coloring.used_colors).filter(|color| coloring.labels[*color] == Some(true)).map(state_name).collect(),
+                    coloring.transitions.iter()
+                        .map(|((color, input), next_color)| Transition { state: state_name(*color), input: *input, next_state: state_name(*next_color) })
+                        .sorted()
+                        .collect(),
+                )));
+            }
+        }
+        Bounded::Done(None)
+    }
+
+    /// Colors the node of the edge with the given index and all following ones. Returns false if no consistent coloring exists,
+    /// in which case the coloring is left as it was. Every color tried consumes one unit of fuel.
+    fn color_nodes(tree: &PrefixTree, edges: &[(usize, char, usize)], index: usize, coloring: &mut Coloring, state_count: usize, fuel: &mut Fuel) -> Bounded<bool> {
+        let (parent, symbol, node) = match edges.get(index) {
+            Some(edge) => *edge,
+            None => return Bounded::Done(true),
+        };
+        let parent_color = coloring.colors[parent];
+        // If the state of the parent already has a transition for the symbol, the color of the node is determined.
+        let candidates = match coloring.transitions.get(&(parent_color, symbol)) {
+            Some(color) => vec![*color],
+            // Trying only one new color avoids exploring colorings that only differ in the naming of the states.
+            None => (0..(coloring.used_colors + 1).min(state_count)).collect(),
+        };
+        let is_transition_new = !coloring.transitions.contains_key(&(parent_color, symbol));
+        for color in candidates {
+            let label = tree.labels[node];
+            if label.is_some() && coloring.labels[color].is_some() && coloring.labels[color] != label {
+                continue;
+            }
+            if !fuel.consume() {
+                return Bounded::Exhausted;
+            }
+            let previous_label = coloring.labels[color];
+            let previous_used_colors = coloring.used_colors;
+            if label.is_some() {
+                coloring.labels[color] = label;
+            }
+            coloring.used_colors = coloring.used_colors.max(color + 1);
+            coloring.colors[node] = color;
+            coloring.transitions.insert((parent_color, symbol), color);
+            match Dfa::color_nodes(tree, edges, index + 1, coloring, state_count, fuel) {
+                Bounded::Done(false) => {}
+                result => return result,
+            }
+            coloring.labels[color] = previous_label;
+            coloring.used_colors = previous_used_colors;
+            if is_transition_new {
+                coloring.transitions.remove(&(parent_color, symbol));
+            }
+        }
+        Bounded::Done(false)
+    }
+}
+
+#[cfg(test)]
+mod synthesis_tests {
+    use crate::{Bounded, Dfa, Fuel};
+
+    #[test]
+    fn test_synthesize() {
+        // Samples of words over 'a' of even length.
+        let positive = ["", "aa", "aaaa"];
+        let negative = ["a", "aaa"];
+        let dfa = Dfa::synthesize(&positive, &negative, 5).unwrap();
+        assert_eq!(dfa.get_all_states().len(), 2, "two states are enough");
+        assert!(dfa.check("aaaaaa").0);
+        assert!(!dfa.check("aaaaa").0);
+        assert!(Dfa::synthesize(&positive, &negative, 1).is_none(), "one state cannot separate the samples");
+        assert!(Dfa::synthesize(&["ab"], &["ab"], 3).is_none(), "contradicting samples cannot be separated");
+        assert!(Dfa::synthesize_with_fuel(&positive, &negative, 5, &mut Fuel::new(3)).is_exhausted());
+        assert!(matches!(Dfa::synthesize_with_fuel(&positive, &negative, 1, &mut Fuel::new(100)), Bounded::Done(None)));
+    }
+}
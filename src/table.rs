@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::{Dfa, MissingTransitionPolicy, Transition};
+
+/// Describes why a transition table cannot be turned into a DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    /// The table must have one row per state.
+    RowCountMismatch { expected: usize, actual: usize },
+    /// Every row must have one column per symbol of the alphabet.
+    ColumnCountMismatch { row: usize, expected: usize, actual: usize },
+    /// A state index in the table, the start state or the accept states does not refer to a state.
+    StateIndexOutOfRange(usize),
+    DuplicateState(String),
+    DuplicateSymbol(char),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::RowCountMismatch { expected, actual } => write!(f, "expected {} rows but found {}", expected, actual),
+            TableError::ColumnCountMismatch { row, expected, actual } => write!(f, "expected {} columns in row {} but found {}", expected, row, actual),
+            TableError::StateIndexOutOfRange(index) => write!(f, "there is no state with index {}", index),
+            TableError::DuplicateState(state) => write!(f, "the state {} occurs more than once", state),
+            TableError::DuplicateSymbol(symbol) => write!(f, "the symbol {} occurs more than once", symbol),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl Dfa {
+    /// Creates a DFA from a dense transition table, which is how solvers and other tools usually produce automata.
+    /// The entry `table[i][j]` is the index of the state the DFA switches to when it reads `alphabet[j]` in state `states[i]`,
+    /// or None if there is no such transition. The start state and the accept states are given as indices into `states`, too.
+    /// The alphabet is declared on the resulting DFA, so symbols without any transition are kept.
+    pub fn from_table(states: &[&str], alphabet: &[char], table: &[&[Option<usize>]], start: usize, accepts: &[usize]) -> Result<Dfa, TableError> {
+        if table.len() != states.len() {
+            return Err(TableError::RowCountMismatch { expected: states.len(), actual: table.len() });
+        }
+        let mut unique_states = HashSet::new();
+        if let Some(state) = states.iter().find(|state| !unique_states.insert(**state)) {
+            return Err(TableError::DuplicateState(state.to_string()));
+        }
+        let mut unique_symbols = HashSet::new();
+        if let Some(symbol) = alphabet.iter().find(|symbol| !unique_symbols.insert(**symbol)) {
+            return Err(TableError::DuplicateSymbol(*symbol));
+        }
+        let state_name = |index: usize| states.get(index).map(|state| state.to_string()).ok_or(TableError::StateIndexOutOfRange(index));
+        let mut transitions = Vec::new();
+        for (row_index, row) in table.iter().enumerate() {
+            if row.len() != alphabet.len() {
+                return Err(TableError::ColumnCountMismatch { row: row_index, expected: alphabet.len(), actual: row.len() });
+            }
+            for (symbol, next_state_index) in alphabet.iter().zip(row.iter()) {
+                if let Some(next_state_index) = next_state_index {
+                    transitions.push(Transition { state: states[row_index].to_string(), input: *symbol, next_state: state_name(*next_state_index)? });
+                }
+            }
+        }
+        Ok(Dfa {
+            alphabet: Some(unique_symbols),
+            ..Dfa::with_parts(
+                String::new(),
+                state_name(start)?,
+                accepts.iter().map(|index| state_name(*index)).collect::<Result<HashSet<String>, TableError>>()?,
+                transitions,
+            )
+        })
+    }
+
+    /// Renders the transition function as a GitHub-flavored Markdown table with one row per state and one column per symbol
+    /// of the alphabet. The start state is marked with "→" and accept states with "*". Missing transitions are shown as "∅",
+    /// unless they are implicit self-loops. The start state comes first, the other states are sorted by name.
+    pub fn to_markdown_table(&self) -> String {
+        let alphabet = self.get_alphabet().into_iter().sorted().collect_vec();
+        let states = self.get_all_mentioned_states().into_iter()
+            .sorted_by_key(|state| (*state != self.start_state, state.clone()))
+            .collect_vec();
+        let mut markdown = String::from("| δ |");
+        for symbol in &alphabet {
+            markdown += &format!(" {} |", escape_markdown(&symbol.to_string()));
+        }
+        markdown += "\n|---|";
+        markdown += &"---|".repeat(alphabet.len());
+        for state in &states {
+            let start_marker = if *state == self.start_state { "→" } else { "" };
+            let accept_marker = if self.accept_states.contains(state) { "*" } else { "" };
+            markdown += &format!("\n| {}{}{} |", start_marker, accept_marker, escape_markdown(state));
+            for symbol in &alphabet {
+                let next_state = match self.get_transition(state, symbol) {
+                    Some(transition) => escape_markdown(&transition.next_state),
+                    None if self.missing_transitions == MissingTransitionPolicy::SelfLoop => escape_markdown(state),
+                    None => "∅".to_string(),
+                };
+                markdown += &format!(" {} |", next_state);
+            }
+        }
+        markdown + "\n"
+    }
+}
+
+/// Escapes characters that would break the structure of a Markdown table.
+pub(crate) fn escape_markdown(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod table_tests {
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+
+    use crate::{Dfa, TableError};
+
+    #[test]
+    fn test_from_table() {
+        // Accepts binary numbers that are divisible by three.
+        let dfa = Dfa::from_table(
+            &["r0", "r1", "r2"],
+            &['0', '1'],
+            &[&[Some(0), Some(1)], &[Some(2), Some(0)], &[Some(1), Some(2)]],
+            0,
+            &[0],
+        ).unwrap();
+        assert!(dfa.check("110").0, "6 is divisible by three");
+        assert!(!dfa.check("111").0, "7 is not divisible by three");
+        assert_eq!(dfa.get_alphabet(), HashSet::from_iter(vec!['0', '1']));
+    }
+
+    #[test]
+    fn test_from_table_keeps_unused_symbols() {
+        let dfa = Dfa::from_table(&["q0"], &['a', 'b'], &[&[Some(0), None]], 0, &[0]).unwrap();
+        assert!(dfa.check("aa").0);
+        assert!(!dfa.check("ab").0);
+        assert_eq!(dfa.get_alphabet().len(), 2);
+    }
+
+    #[test]
+    fn test_to_markdown_table() {
+        let dfa = Dfa::from_table(&["q0", "q1"], &['1', '0', '|'], &[&[Some(1), Some(0), None], &[Some(1), None, None]], 0, &[1]).unwrap();
+        assert_eq!(dfa.to_markdown_table(), "| δ | 0 | 1 | \\| |\n|---|---|---|---|\n| →q0 | q0 | q1 | ∅ |\n| *q1 | ∅ | q1 | ∅ |\n");
+    }
+
+    #[test]
+    fn test_from_table_errors() {
+        assert_eq!(Dfa::from_table(&["q0", "q1"], &['a'], &[&[Some(0)]], 0, &[]).unwrap_err(), TableError::RowCountMismatch { expected: 2, actual: 1 });
+        assert_eq!(Dfa::from_table(&["q0"], &['a'], &[&[Some(0), None]], 0, &[]).unwrap_err(), TableError::ColumnCountMismatch { row: 0, expected: 1, actual: 2 });
+        assert_eq!(Dfa::from_table(&["q0"], &['a'], &[&[Some(1)]], 0, &[]).unwrap_err(), TableError::StateIndexOutOfRange(1));
+        assert_eq!(Dfa::from_table(&["q0"], &['a'], &[&[Some(0)]], 0, &[3]).unwrap_err(), TableError::StateIndexOutOfRange(3));
+        assert_eq!(Dfa::from_table(&["q0", "q0"], &['a'], &[&[None], &[None]], 0, &[]).unwrap_err(), TableError::DuplicateState("q0".to_string()));
+        assert_eq!(Dfa::from_table(&["q0"], &['a', 'a'], &[&[None, None]], 0, &[]).unwrap_err(), TableError::DuplicateSymbol('a'));
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{Dfa, Regex};
+
+/// Describes to which next state a tagged DFA switches when it reads a certain input while being in a certain state.
+/// Before the input is read, the current position is recorded into the registers listed in `tags`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct TaggedTransition {
+    pub(crate) state: String,
+    pub(crate) input: char,
+    pub(crate) tags: Vec<usize>,
+    pub(crate) next_state: String,
+}
+
+/// # Tagged DFA
+/// A DFA whose transitions record positions of the input into registers, which is how submatches like the capture groups
+/// of a regex are extracted without backtracking. Typically, a group `i` is described by the registers `2 * i` (its start)
+/// and `2 * i + 1` (its end). Positions are byte offsets into the input, so they can be used for slicing it.
+/// Accept states may record the position at the end of the input, which is needed for groups ending there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaggedDfa {
+    pub(crate) name: String,
+    pub(crate) start_state: String,
+    /// Maps every accept state to the registers that record the end of the input when the DFA accepts in that state.
+    pub(crate) accept_states: HashMap<String, Vec<usize>>,
+    pub(crate) transitions: Vec<TaggedTransition>,
+}
+
+/// Describes why a sequence of regexes cannot be turned into a tagged DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaggedDfaError {
+    /// After a prefix matched by the group, the symbol can both continue the group and start a later one,
+    /// so the next symbol does not determine where the group ends.
+    AmbiguousBoundary { group: usize, symbol: char },
+}
+
+impl fmt::Display for TaggedDfaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaggedDfaError::AmbiguousBoundary { group, symbol } => {
+                write!(f, "the symbol {} can both continue group {} and start a later group", symbol, group)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TaggedDfaError {}
+
+/// A state of the chained group DFAs, i.e. the index of the group and the state of its DFA,
+/// together with the registers recorded when entering it without reading a symbol.
+type GroupPosition<'a> = (usize, &'a str, Vec<usize>);
+
+impl TaggedDfa {
+    /// Creates a tagged DFA accepting the concatenation of the regexes, in which the group `i` is the part of the input matched
+    /// by the `i`-th regex. Every regex is turned into a DFA (see `Regex::to_dfa`) and the DFAs are chained, so the states are named
+    /// like "1:q0" after the group and the state of its DFA, plus the state "start". As a register only holds the last position
+    /// written to it, the next symbol must always determine whether a group ends. Otherwise, the ambiguous group boundary is reported.
+    pub fn from_groups(name: &str, groups: &[Regex]) -> Result<TaggedDfa, TaggedDfaError> {
+        let dfas = groups.iter()
+            .map(|group| {
+                let dfa = group.to_dfa();
+                let productive_states = dfa.get_productive_states();
+                (dfa, productive_states)
+            })
+            .collect_vec();
+        let state_name = |position: Option<(usize, &str)>| match position {
+            Some((group, state)) => format!("{}:{}", group, state),
+            None => "start".to_string(),
+        };
+        let mut tagged_dfa = TaggedDfa::new(name, &state_name(None));
+        let mut visited_positions = HashSet::new();
+        let mut positions_to_visit = VecDeque::new();
+        positions_to_visit.push_back(None);
+        while let Some(position) = positions_to_visit.pop_front() {
+            let closure = match position {
+                Some((group, state)) => get_group_closure(&dfas, group, state, Vec::new()),
+                // The first group starts before the first symbol. The start state is never entered again, so this is recorded once.
+                None if dfas.is_empty() => Vec::new(),
+                None => get_group_closure(&dfas, 0, &dfas[0].0.start_state, vec![0]),
+            };
+            match closure.last() {
+                Some((group, state, tags)) if *group == dfas.len() - 1 && dfas[*group].0.accept_states.contains(*state) => {
+                    let final_tags = tags.iter().cloned().chain(std::iter::once(2 * group + 1)).collect_vec();
+                    tagged_dfa.add_accept_state(&state_name(position), &final_tags);
+                }
+                None => tagged_dfa.add_accept_state(&state_name(position), &[]),
+                _ => {}
+            }
+            let mut transitions: BTreeMap<char, (usize, &Vec<usize>, &str)> = BTreeMap::new();
+            for (group, state, tags) in &closure {
+                let (dfa, productive_states) = &dfas[*group];
+                for transition in dfa.transitions.iter().filter(|transition| transition.state == *state && productive_states.contains(&transition.next_state)) {
+                    if let Some((earlier_group, _, _)) = transitions.get(&transition.input) {
+                        return Err(TaggedDfaError::AmbiguousBoundary { group: *earlier_group, symbol: transition.input });
+                    }
+                    transitions.insert(transition.input, (*group, tags, &transition.next_state));
+                }
+            }
+            for (input, (group, tags, next_state)) in transitions {
+                tagged_dfa.add_transition(&state_name(position), input, tags, &state_name(Some((group, next_state))));
+                if visited_positions.insert((group, next_state)) {
+                    positions_to_visit.push_back(Some((group, next_state)));
+                }
+            }
+        }
+        Ok(tagged_dfa)
+    }
+
+    /// Creates a tagged DFA without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> TaggedDfa {
+        TaggedDfa {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashMap::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Makes the state an accept state which records the end of the input into the registers listed in `final_tags`.
+    pub fn add_accept_state(&mut self, state: &str, final_tags: &[usize]) {
+        self.accept_states.insert(state.to_string(), final_tags.to_vec());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, tags: &[usize], next_state: &str) {
+        self.transitions.push(TaggedTransition { state: state.to_string(), input, tags: tags.to_vec(), next_state: next_state.to_string() });
+    }
+
+    pub fn get_transition(&self, state: &str, input: &char) -> Option<&TaggedTransition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    /// The number of registers is determined by the highest register index used by any transition or accept state.
+    pub fn get_register_count(&self) -> usize {
+        self.transitions.iter()
+            .flat_map(|transition| transition.tags.iter())
+            .chain(self.accept_states.values().flatten())
+            .map(|register| register + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Checks whether a certain input is accepted. If so, returns the content of all registers. Registers that
+    /// have not been written by the run are None. If a register is written several times, the last position is kept.
+    pub fn check(&self, input: &str) -> Option<Vec<Option<usize>>> {
+        let mut registers = vec![None; self.get_register_count()];
+        let mut current_state = &self.start_state;
+        for (position, char) in input.char_indices() {
+            let transition = self.get_transition(current_state, &char)?;
+            for register in &transition.tags {
+                registers[*register] = Some(position);
+            }
+            current_state = &transition.next_state;
+        }
+        for register in self.accept_states.get(current_state)? {
+            registers[*register] = Some(input.len());
+        }
+        Some(registers)
+    }
+}
+
+/// Returns the positions that can be entered from the state of the group without reading a symbol: the state itself and,
+/// while the current state accepts, the start state of the next group, after recording the end of one group and the start of the next.
+fn get_group_closure<'a>(dfas: &'a [(Dfa, HashSet<String>)], group: usize, state: &'a str, tags: Vec<usize>) -> Vec<GroupPosition<'a>> {
+    let mut closure = vec![(group, state, tags)];
+    loop {
+        let (group, state, tags) = closure.last().unwrap();
+        if group + 1 == dfas.len() || !dfas[*group].0.accept_states.contains(*state) {
+            return closure;
+        }
+        let next_tags = tags.iter().cloned().chain(vec![2 * group + 1, 2 * group + 2]).collect();
+        closure.push((group + 1, &dfas[group + 1].0.start_state, next_tags));
+    }
+}
+
+#[cfg(test)]
+mod tagged_dfa_tests {
+    use crate::{Regex, TaggedDfa, TaggedDfaError};
+
+    /// Creates a tagged DFA for lines like "key=value" over lowercase letters. Group 0 is the key, group 1 the value.
+    fn create_key_value_dfa() -> TaggedDfa {
+        let mut dfa = TaggedDfa::new("key value pairs", "start");
+        for letter in 'a'..='z' {
+            dfa.add_transition("start", letter, &[0], "key");
+            dfa.add_transition("key", letter, &[], "key");
+            dfa.add_transition("equals", letter, &[2], "value");
+            dfa.add_transition("value", letter, &[], "value");
+        }
+        dfa.add_transition("key", '=', &[1], "equals");
+        // An empty value starts and ends at the end of the input.
+        dfa.add_accept_state("equals", &[2, 3]);
+        dfa.add_accept_state("value", &[3]);
+        dfa
+    }
+
+    #[test]
+    fn test_check() {
+        let dfa = create_key_value_dfa();
+        assert_eq!(dfa.get_register_count(), 4);
+        assert_eq!(dfa.check("name=lammes"), Some(vec![Some(0), Some(4), Some(5), Some(11)]));
+        assert_eq!(dfa.check("empty="), Some(vec![Some(0), Some(5), Some(6), Some(6)]));
+        assert_eq!(dfa.check("=value"), None);
+    }
+    #[test]
+    fn test_from_groups() {
+        let groups = ["[a-z]+", "=", "[a-z]*"].iter().map(|pattern| Regex::parse(pattern).unwrap()).collect::<Vec<Regex>>();
+        let dfa = TaggedDfa::from_groups("key value pairs", &groups).unwrap();
+        assert_eq!(dfa.check("name=lammes"), Some(vec![Some(0), Some(4), Some(4), Some(5), Some(5), Some(11)]));
+        assert_eq!(dfa.check("empty="), Some(vec![Some(0), Some(5), Some(5), Some(6), Some(6), Some(6)]));
+        assert_eq!(dfa.check("=value"), None);
+        assert_eq!(dfa.captures("set x=yz;").unwrap().get_group(2), Some(6..8));
+        // The empty middle group starts and ends where the last group starts.
+        let groups = ["a+", "b*", "c"].iter().map(|pattern| Regex::parse(pattern).unwrap()).collect::<Vec<Regex>>();
+        let dfa = TaggedDfa::from_groups("skipped group", &groups).unwrap();
+        assert_eq!(dfa.check("aac"), Some(vec![Some(0), Some(2), Some(2), Some(2), Some(2), Some(3)]));
+        let groups = ["a*", "ab"].iter().map(|pattern| Regex::parse(pattern).unwrap()).collect::<Vec<Regex>>();
+        let error = TaggedDfa::from_groups("ambiguous", &groups).unwrap_err();
+        assert_eq!(error, TaggedDfaError::AmbiguousBoundary { group: 0, symbol: 'a' });
+        assert_eq!(error.to_string(), "the symbol a can both continue group 0 and start a later group");
+    }
+}
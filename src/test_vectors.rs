@@ -0,0 +1,131 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use itertools::Itertools;
+
+use crate::Dfa;
+
+/// Explains why a word of a test vector is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// The DFA has no transition for the input while being in the state.
+    MissingTransition { state: String, input: char },
+    /// The whole word is read but the DFA ends up in a state that is not accepting.
+    RejectingState(String),
+}
+
+/// A word together with the expected verdict of the DFA and the rationale behind it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    pub word: String,
+    pub is_accepted: bool,
+    /// The states traversed while reading the word, starting with the start state. Together with the word, they describe
+    /// which transitions the test vector exercises.
+    pub traversed_states: Vec<String>,
+    /// Why the word is rejected, or None if it is accepted.
+    pub rejection_reason: Option<RejectionReason>,
+}
+
+impl Dfa {
+    /// Generates up to `n` test vectors, half of them accepted and half of them rejected, so that they can serve as
+    /// a regression suite for implementations of the DFA. The words are chosen to cover the DFA systematically: every reachable
+    /// transition, every missing transition of a reachable state and every reachable rejecting state is exercised by a short word,
+    /// which is extended to an accepted word whenever possible. If one verdict has too few candidates, the other one fills up.
+    pub fn generate_test_vectors(&self, n: usize) -> Vec<TestVector> {
+        let mut dfa = self.clone();
+        dfa.materialize_missing_transitions();
+        let access_words = dfa.get_access_words();
+        let completion_words = dfa.get_completion_words();
+        let mut candidates = BTreeSet::new();
+        for (state, access_word) in access_words.iter().sorted() {
+            candidates.insert(access_word.clone());
+            if let Some(completion_word) = completion_words.get(state) {
+                candidates.insert(format!("{}{}", access_word, completion_word));
+            }
+            for input in dfa.get_alphabet().into_iter().sorted() {
+                let word = format!("{}{}", access_word, input);
+                match dfa.get_transition(state, &input) {
+                    Some(transition) => match completion_words.get(&transition.next_state) {
+                        Some(completion_word) => candidates.insert(format!("{}{}", word, completion_word)),
+                        None => candidates.insert(word),
+                    },
+                    None => candidates.insert(word),
+                };
+            }
+        }
+        // Shorter words come first, as they are easier to debug.
+        let candidates = candidates.into_iter().sorted_by_key(|word| (word.chars().count(), word.clone())).collect_vec();
+        let (accepted, rejected): (Vec<String>, Vec<String>) = candidates.into_iter().partition(|word| self.check(word).0);
+        let accepted_count = (n - n / 2).max(n.saturating_sub(rejected.len())).min(accepted.len());
+        let rejected_count = n - accepted_count;
+        accepted.into_iter().take(accepted_count)
+            .chain(rejected.into_iter().take(rejected_count))
+            .map(|word| self.create_test_vector(word))
+            .collect()
+    }
+
+    fn create_test_vector(&self, word: String) -> TestVector {
+        let (is_accepted, traversed_states) = self.check(&word);
+        let last_state = traversed_states.last().unwrap().clone();
+        let rejection_reason = match word.chars().nth(traversed_states.len() - 1) {
+            _ if is_accepted => None,
+            Some(input) => Some(RejectionReason::MissingTransition { state: last_state, input }),
+            None => Some(RejectionReason::RejectingState(last_state)),
+        };
+        TestVector { word, is_accepted, traversed_states, rejection_reason }
+    }
+
+    /// Maps every reachable state to the shortest word leading into it, preferring alphabetically smaller words.
+    fn get_access_words(&self) -> HashMap<String, String> {
+        let mut access_words = HashMap::new();
+        let mut states_to_visit = VecDeque::new();
+        access_words.insert(self.start_state.clone(), String::new());
+        states_to_visit.push_back(self.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in self.transitions.iter().filter(|transition| transition.state == state).sorted() {
+                if !access_words.contains_key(&transition.next_state) {
+                    let access_word = format!("{}{}", access_words[&state], transition.input);
+                    access_words.insert(transition.next_state.clone(), access_word);
+                    states_to_visit.push_back(transition.next_state.clone());
+                }
+            }
+        }
+        access_words
+    }
+
+    /// Maps every state from which an accept state can be reached to the shortest word leading into an accept state.
+    fn get_completion_words(&self) -> HashMap<String, String> {
+        let mut completion_words: HashMap<String, String> = self.accept_states.iter().map(|state| (state.clone(), String::new())).collect();
+        let mut states_to_visit: VecDeque<String> = self.accept_states.iter().sorted().cloned().collect();
+        while let Some(state) = states_to_visit.pop_front() {
+            for transition in self.transitions.iter().filter(|transition| transition.next_state == state).sorted() {
+                if !completion_words.contains_key(&transition.state) {
+                    let completion_word = format!("{}{}", transition.input, completion_words[&state]);
+                    completion_words.insert(transition.state.clone(), completion_word);
+                    states_to_visit.push_back(transition.state.clone());
+                }
+            }
+        }
+        completion_words
+    }
+}
+
+#[cfg(test)]
+mod test_vectors_tests {
+    use crate::{Dfa, RejectionReason};
+
+    #[test]
+    fn test_generate_test_vectors() {
+        // Accepts "ab" followed by any number of 'b's.
+        let dfa = Dfa::from_table(&["q0", "q1", "q2"], &['a', 'b'], &[&[Some(1), None], &[None, Some(2)], &[None, Some(2)]], 0, &[2]).unwrap();
+        let test_vectors = dfa.generate_test_vectors(6);
+        assert_eq!(test_vectors.len(), 6);
+        assert_eq!(test_vectors.iter().filter(|test_vector| test_vector.is_accepted).count(), 2, "there are only two accepted candidates");
+        for test_vector in &test_vectors {
+            assert_eq!(dfa.check(&test_vector.word).0, test_vector.is_accepted);
+        }
+        let empty_word = test_vectors.iter().find(|test_vector| test_vector.word.is_empty()).unwrap();
+        assert_eq!(empty_word.rejection_reason, Some(RejectionReason::RejectingState("q0".to_string())));
+        let missing_transition = test_vectors.iter().find(|test_vector| test_vector.word == "b").unwrap();
+        assert_eq!(missing_transition.rejection_reason, Some(RejectionReason::MissingTransition { state: "q0".to_string(), input: 'b' }));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{Dfa, EpsilonNfa, Nfa, Regex};
+
+impl Regex {
+    /// Converts the regex into an ε-NFA with [Thompson's construction](https://en.wikipedia.org/wiki/Thompson%27s_construction).
+    /// Every subexpression becomes a fragment with one entry and one exit state, which are glued together with ε-transitions.
+    /// The states are called "q0", "q1" and so on, and the resulting automaton has a single accept state.
+    pub fn to_epsilon_nfa(&self) -> EpsilonNfa {
+        let mut epsilon_nfa = EpsilonNfa::new(&self.to_string(), "");
+        let mut state_count = 0;
+        let (entry, exit) = self.build_fragment(&mut epsilon_nfa, &mut state_count);
+        epsilon_nfa.start_state = entry;
+        epsilon_nfa.add_accept_state(&exit);
+        epsilon_nfa
+    }
+
+    /// Converts the regex into an NFA by eliminating the ε-transitions of Thompson's construction (see `to_epsilon_nfa`).
+    pub fn to_nfa(&self) -> Nfa {
+        self.to_epsilon_nfa().to_nfa()
+    }
+
+    /// Converts the regex into a DFA by applying the subset construction to the result of Thompson's construction.
+    pub fn to_dfa(&self) -> Dfa {
+        self.to_epsilon_nfa().to_dfa()
+    }
+
+    /// Adds the fragment of the regex to the automaton and returns its entry and exit state.
+    fn build_fragment(&self, epsilon_nfa: &mut EpsilonNfa, state_count: &mut usize) -> (String, String) {
+        let mut new_state = || {
+            *state_count += 1;
+            format!("q{}", *state_count - 1)
+        };
+        let (entry, exit) = (new_state(), new_state());
+        match self {
+            Regex::Empty => {}
+            Regex::Epsilon => epsilon_nfa.add_epsilon_transition(&entry, &exit),
+            Regex::Symbol(symbol) => epsilon_nfa.add_transition(&entry, *symbol, &exit),
+            Regex::Concatenation(left, right) => {
+                let (left_entry, left_exit) = left.build_fragment(epsilon_nfa, state_count);
+                let (right_entry, right_exit) = right.build_fragment(epsilon_nfa, state_count);
+                epsilon_nfa.add_epsilon_transition(&entry, &left_entry);
+                epsilon_nfa.add_epsilon_transition(&left_exit, &right_entry);
+                epsilon_nfa.add_epsilon_transition(&right_exit, &exit);
+            }
+            Regex::Union(left, right) => {
+                for alternative in &[left, right] {
+                    let (alternative_entry, alternative_exit) = alternative.build_fragment(epsilon_nfa, state_count);
+                    epsilon_nfa.add_epsilon_transition(&entry, &alternative_entry);
+                    epsilon_nfa.add_epsilon_transition(&alternative_exit, &exit);
+                }
+            }
+            Regex::Star(inner) => {
+                let (inner_entry, inner_exit) = inner.build_fragment(epsilon_nfa, state_count);
+                epsilon_nfa.add_epsilon_transition(&entry, &inner_entry);
+                epsilon_nfa.add_epsilon_transition(&inner_exit, &inner_entry);
+                epsilon_nfa.add_epsilon_transition(&inner_exit, &exit);
+                epsilon_nfa.add_epsilon_transition(&entry, &exit);
+            }
+        }
+        (entry, exit)
+    }
+}
+
+#[cfg(test)]
+mod thompson_tests {
+    use crate::Regex;
+
+    #[test]
+    fn test_regex_to_dfa_pipeline() {
+        let regex = Regex::parse("(a|bc)*d+").unwrap();
+        let epsilon_nfa = regex.to_epsilon_nfa();
+        let nfa = regex.to_nfa();
+        let mut dfa = regex.to_dfa();
+        for word in &["d", "abcdd", "bcad", "", "bd", "abc", "dad"] {
+            assert_eq!(epsilon_nfa.check(word), regex.matches(word), "{}", word);
+            assert_eq!(nfa.check(word), regex.matches(word), "{}", word);
+            assert_eq!(dfa.check(word).0, regex.matches(word), "{}", word);
+        }
+        dfa.minimize();
+        assert_eq!(dfa.get_all_states().len(), 3);
+        assert!(!Regex::Empty.to_nfa().check(""));
+    }
+}
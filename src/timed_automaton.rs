@@ -0,0 +1,336 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+/// An atomic clock constraint like "x <= 3". Guards are conjunctions of these constraints.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct ClockConstraint {
+    clock: String,
+    comparison: Comparison,
+    constant: u32,
+}
+
+impl ClockConstraint {
+    pub fn new(clock: &str, comparison: Comparison, constant: u32) -> ClockConstraint {
+        ClockConstraint { clock: clock.to_string(), comparison, constant }
+    }
+
+    fn is_satisfied_by(&self, value: f64) -> bool {
+        let constant = f64::from(self.constant);
+        match self.comparison {
+            Comparison::Less => value < constant,
+            Comparison::LessOrEqual => value <= constant,
+            Comparison::Equal => (value - constant).abs() < f64::EPSILON,
+            Comparison::GreaterOrEqual => value >= constant,
+            Comparison::Greater => value > constant,
+        }
+    }
+}
+
+/// Describes to which next state a timed automaton may switch when it reads a certain input while being in a certain state
+/// and the guard is satisfied by the current clock values. The clocks listed in `resets` are set to zero afterwards.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct TimedTransition {
+    state: String,
+    input: char,
+    guard: Vec<ClockConstraint>,
+    resets: Vec<String>,
+    next_state: String,
+}
+
+/// # [Timed automaton](https://en.wikipedia.org/wiki/Timed_automaton)
+/// A nondeterministic automaton with real-valued clocks that all advance at the same rate. The clocks are
+/// implicitly declared by being mentioned in a guard or reset and all start at zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedAutomaton {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<TimedTransition>,
+}
+
+impl TimedAutomaton {
+    /// Creates a timed automaton without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> TimedAutomaton {
+        TimedAutomaton {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, input: char, guard: Vec<ClockConstraint>, resets: &[&str], next_state: &str) {
+        self.transitions.push(TimedTransition {
+            state: state.to_string(),
+            input,
+            guard,
+            resets: resets.iter().map(|clock| clock.to_string()).collect(),
+            next_state: next_state.to_string(),
+        });
+    }
+
+    /// Returns all clocks mentioned by guards or resets in sorted order.
+    pub fn get_all_clocks(&self) -> Vec<String> {
+        self.transitions.iter()
+            .flat_map(|transition| transition.guard.iter().map(|constraint| constraint.clock.clone()).chain(transition.resets.iter().cloned()))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// Checks whether a timed word is accepted. The timed word consists of symbols together with the absolute points in time
+    /// at which they are read. The points in time must not decrease, otherwise the word is rejected.
+    /// As the automaton might be nondeterministic, all possible runs are simulated simultaneously.
+    pub fn check(&self, timed_word: &[(char, f64)]) -> bool {
+        let clocks = self.get_all_clocks();
+        // A configuration consists of the current state and the current value of every clock.
+        let mut configurations: Vec<(&str, Vec<f64>)> = vec![(&self.start_state, vec![0.0; clocks.len()])];
+        let mut current_time = 0.0;
+        for (input, time) in timed_word {
+            if *time < current_time {
+                return false;
+            }
+            let delay = time - current_time;
+            current_time = *time;
+            let mut next_configurations = Vec::new();
+            for (state, values) in &configurations {
+                let values = values.iter().map(|value| value + delay).collect_vec();
+                let enabled_transitions = self.transitions.iter().filter(|transition| {
+                    transition.state == *state && transition.input == *input && transition.guard.iter().all(|constraint| {
+                        constraint.is_satisfied_by(values[clocks.binary_search(&constraint.clock).unwrap()])
+                    })
+                });
+                for transition in enabled_transitions {
+                    let mut next_values = values.clone();
+                    for clock in &transition.resets {
+                        next_values[clocks.binary_search(clock).unwrap()] = 0.0;
+                    }
+                    next_configurations.push((&transition.next_state[..], next_values));
+                }
+            }
+            configurations = next_configurations;
+        }
+        configurations.iter().any(|(state, _)| self.accept_states.contains(*state))
+    }
+
+    /// Checks whether the automaton accepts no timed word at all.
+    pub fn is_empty(&self) -> bool {
+        self.find_accepted_untimed_word().is_none()
+    }
+
+    /// Explores the zone graph to find an accept state that is reachable with some timing. Returns the symbols read
+    /// along the way, i.e. the untimed version of an accepted timed word. Zones are represented by difference bound matrices
+    /// and are extrapolated with the maximal constant of each clock, which guarantees termination.
+    pub fn find_accepted_untimed_word(&self) -> Option<Vec<char>> {
+        let clocks = self.get_all_clocks();
+        let maximal_constants: Vec<i64> = clocks.iter().map(|clock| {
+            self.transitions.iter().flat_map(|transition| transition.guard.iter())
+                .filter(|constraint| constraint.clock == *clock)
+                .map(|constraint| i64::from(constraint.constant))
+                .max()
+                .unwrap_or(0)
+        }).collect();
+        let mut start_zone = Zone::zero(clocks.len());
+        start_zone.delay();
+        let mut explored_zones: HashMap<&str, Vec<Zone>> = HashMap::new();
+        let mut nodes_to_visit: VecDeque<(&str, Zone, Vec<char>)> = VecDeque::new();
+        nodes_to_visit.push_back((&self.start_state, start_zone, Vec::new()));
+        while let Some((state, zone, word)) = nodes_to_visit.pop_front() {
+            if self.accept_states.contains(state) {
+                return Some(word);
+            }
+            // Zones that are included in an already explored zone of the same state cannot reach anything new.
+            let explored_zones_of_state = explored_zones.entry(state).or_default();
+            if explored_zones_of_state.iter().any(|explored_zone| zone.is_included_in(explored_zone)) {
+                continue;
+            }
+            explored_zones_of_state.push(zone.clone());
+            for transition in self.transitions.iter().filter(|transition| transition.state == state) {
+                let mut next_zone = zone.clone();
+                for constraint in &transition.guard {
+                    next_zone.constrain(clocks.binary_search(&constraint.clock).unwrap() + 1, constraint.comparison, i64::from(constraint.constant));
+                }
+                if next_zone.is_empty() {
+                    continue;
+                }
+                for clock in &transition.resets {
+                    next_zone.reset(clocks.binary_search(clock).unwrap() + 1);
+                }
+                next_zone.delay();
+                next_zone.extrapolate(&maximal_constants);
+                let mut next_word = word.clone();
+                next_word.push(transition.input);
+                nodes_to_visit.push_back((&transition.next_state, next_zone, next_word));
+            }
+        }
+        None
+    }
+}
+
+/// Bounds of a difference bound matrix are encoded as integers so that they can be compared directly:
+/// "< c" is encoded as 2c and "<= c" as 2c + 1.
+const INFINITY: i64 = i64::MAX;
+const LESS_OR_EQUAL_ZERO: i64 = 1;
+
+fn bound(constant: i64, is_strict: bool) -> i64 {
+    2 * constant + if is_strict { 0 } else { 1 }
+}
+
+fn add_bounds(first: i64, second: i64) -> i64 {
+    if first == INFINITY || second == INFINITY {
+        return INFINITY;
+    }
+    ((first >> 1) + (second >> 1)) * 2 + (first & second & 1)
+}
+
+/// A [difference bound matrix](https://en.wikipedia.org/wiki/Difference_bound_matrix) describing a convex set of clock valuations.
+/// The entry (i, j) bounds the difference x_i - x_j, where x_0 is a reference clock that is always zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Zone {
+    bounds: Vec<Vec<i64>>,
+}
+
+impl Zone {
+    /// The zone in which all clocks are zero.
+    fn zero(clock_count: usize) -> Zone {
+        Zone { bounds: vec![vec![LESS_OR_EQUAL_ZERO; clock_count + 1]; clock_count + 1] }
+    }
+
+    fn size(&self) -> usize {
+        self.bounds.len()
+    }
+
+    /// Tightens all bounds with the Floyd-Warshall algorithm so that the zone has a unique representation.
+    fn canonicalize(&mut self) {
+        for k in 0..self.size() {
+            for i in 0..self.size() {
+                for j in 0..self.size() {
+                    let bound_via_k = add_bounds(self.bounds[i][k], self.bounds[k][j]);
+                    if bound_via_k < self.bounds[i][j] {
+                        self.bounds[i][j] = bound_via_k;
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        (0..self.size()).any(|i| self.bounds[i][i] < LESS_OR_EQUAL_ZERO)
+    }
+
+    /// Lets an arbitrary amount of time pass by removing the upper bounds of all clocks.
+    fn delay(&mut self) {
+        for i in 1..self.size() {
+            self.bounds[i][0] = INFINITY;
+        }
+    }
+
+    fn reset(&mut self, clock: usize) {
+        for i in 0..self.size() {
+            self.bounds[clock][i] = self.bounds[0][i];
+            self.bounds[i][clock] = self.bounds[i][0];
+        }
+    }
+
+    fn constrain(&mut self, clock: usize, comparison: Comparison, constant: i64) {
+        let mut tighten = |i: usize, j: usize, new_bound: i64| {
+            if new_bound < self.bounds[i][j] {
+                self.bounds[i][j] = new_bound;
+            }
+        };
+        match comparison {
+            Comparison::Less => tighten(clock, 0, bound(constant, true)),
+            Comparison::LessOrEqual => tighten(clock, 0, bound(constant, false)),
+            Comparison::Equal => {
+                tighten(clock, 0, bound(constant, false));
+                tighten(0, clock, bound(-constant, false));
+            }
+            Comparison::GreaterOrEqual => tighten(0, clock, bound(-constant, false)),
+            Comparison::Greater => tighten(0, clock, bound(-constant, true)),
+        }
+        self.canonicalize();
+    }
+
+    /// Widens bounds beyond the maximal constant of a clock, as guards cannot tell such values apart.
+    fn extrapolate(&mut self, maximal_constants: &[i64]) {
+        let maximal_constant = |i: usize| if i == 0 { 0 } else { maximal_constants[i - 1] };
+        for i in 0..self.size() {
+            for j in 0..self.size() {
+                if i == j {
+                    continue;
+                }
+                if i != 0 && self.bounds[i][j] != INFINITY && self.bounds[i][j] > bound(maximal_constant(i), false) {
+                    self.bounds[i][j] = INFINITY;
+                } else if j != 0 && self.bounds[i][j] < bound(-maximal_constant(j), true) {
+                    self.bounds[i][j] = bound(-maximal_constant(j), true);
+                }
+            }
+        }
+        self.canonicalize();
+    }
+
+    fn is_included_in(&self, other: &Zone) -> bool {
+        (0..self.size()).cartesian_product(0..self.size()).all(|(i, j)| self.bounds[i][j] <= other.bounds[i][j])
+    }
+}
+
+#[cfg(test)]
+mod timed_automaton_tests {
+    use crate::{ClockConstraint, Comparison, TimedAutomaton};
+
+    /// Creates a timed automaton accepting "ab" if 'b' is read at most two time units after 'a'.
+    fn create_example_timed_automaton() -> TimedAutomaton {
+        let mut automaton = TimedAutomaton::new("b follows a quickly", "q0");
+        automaton.add_transition("q0", 'a', vec![], &["x"], "q1");
+        automaton.add_transition("q1", 'b', vec![ClockConstraint::new("x", Comparison::LessOrEqual, 2)], &[], "q2");
+        automaton.add_accept_state("q2");
+        automaton
+    }
+
+    #[test]
+    fn test_check() {
+        let automaton = create_example_timed_automaton();
+        assert!(automaton.check(&[('a', 1.0), ('b', 2.5)]));
+        assert!(automaton.check(&[('a', 1.0), ('b', 3.0)]), "should accept 'b' exactly two time units after 'a'");
+        assert!(!automaton.check(&[('a', 1.0), ('b', 3.5)]), "should reject if 'b' comes too late");
+        assert!(!automaton.check(&[('a', 1.0), ('b', 0.5)]), "should reject decreasing points in time");
+    }
+
+    #[test]
+    fn test_emptiness() {
+        let automaton = create_example_timed_automaton();
+        assert_eq!(automaton.find_accepted_untimed_word(), Some(vec!['a', 'b']));
+        // 'a' must be read before time 1 and 'b' after time 2 but less than one time unit after 'a', which is impossible.
+        let mut impossible_automaton = TimedAutomaton::new("impossible timing", "q0");
+        impossible_automaton.add_transition("q0", 'a', vec![ClockConstraint::new("y", Comparison::LessOrEqual, 1)], &["x"], "q1");
+        impossible_automaton.add_transition("q1", 'b', vec![
+            ClockConstraint::new("x", Comparison::Less, 1),
+            ClockConstraint::new("y", Comparison::Greater, 2),
+        ], &[], "q2");
+        impossible_automaton.add_accept_state("q2");
+        assert!(impossible_automaton.is_empty());
+        // With a longer deadline, the same structure becomes possible.
+        let mut possible_automaton = impossible_automaton.clone();
+        possible_automaton.add_transition("q1", 'c', vec![
+            ClockConstraint::new("x", Comparison::Less, 2),
+            ClockConstraint::new("y", Comparison::Greater, 2),
+        ], &[], "q2");
+        assert_eq!(possible_automaton.find_accepted_untimed_word(), Some(vec!['a', 'c']));
+        assert!(possible_automaton.check(&[('a', 0.5), ('c', 2.1)]));
+    }
+}
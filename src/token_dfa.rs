@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Describes to which next state a `TokenDfa` switches when it reads a certain token while being in a certain state.
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct TokenTransition<T> {
+    state: String,
+    input: T,
+    next_state: String,
+}
+
+/// A deterministic finite acceptor whose input symbols are arbitrary values instead of characters, e.g. an enum
+/// of protocol events. Apart from the type of the input symbols, it is modelled just like `Dfa`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenDfa<T: Eq + Hash> {
+    name: String,
+    start_state: String,
+    accept_states: HashSet<String>,
+    transitions: Vec<TokenTransition<T>>,
+}
+
+impl<T: Eq + Hash + Clone> TokenDfa<T> {
+    /// Creates a DFA without transitions and accept states.
+    pub fn new(name: &str, start_state: &str) -> TokenDfa<T> {
+        TokenDfa {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            accept_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    /// Adds a transition. An existing transition for the same state and input is replaced so that the DFA stays deterministic.
+    pub fn add_transition(&mut self, state: &str, input: T, next_state: &str) {
+        self.transitions.retain(|transition| !(transition.state == state && transition.input == input));
+        self.transitions.push(TokenTransition { state: state.to_string(), input, next_state: next_state.to_string() });
+    }
+
+    /// Checks whether a certain sequence of tokens is accepted by the DFA.
+    /// Additionally returns a list of the states that have been traversed while processing the input.
+    /// The start_state is included in that list of traversed states.
+    pub fn check(&self, input: &[T]) -> (bool, Vec<String>) {
+        let mut traversed_states = vec![self.start_state.clone()];
+        for token in input {
+            match self.get_transition(traversed_states.last().unwrap(), token) {
+                Some(transition) => traversed_states.push(transition.next_state.clone()),
+                // The next state cannot be determined, which means that we are in an error state.
+                None => return (false, traversed_states),
+            }
+        }
+        (self.accept_states.contains(traversed_states.last().unwrap()), traversed_states)
+    }
+
+    pub fn get_transition(&self, state: &str, input: &T) -> Option<&TokenTransition<T>> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.input == *input)
+    }
+
+    /// Returns the tokens read by the transitions in the order of their first appearance.
+    pub fn get_all_input_symbols(&self) -> Vec<T> {
+        let mut input_symbols: Vec<T> = Vec::new();
+        for transition in &self.transitions {
+            if !input_symbols.contains(&transition.input) {
+                input_symbols.push(transition.input.clone());
+            }
+        }
+        input_symbols
+    }
+
+    pub fn get_all_states(&self) -> HashSet<String> {
+        let mut all_states = HashSet::from_iter(self.transitions.iter().flat_map(|transition| vec![transition.state.clone(), transition.next_state.clone()]));
+        all_states.insert(self.start_state.clone());
+        all_states
+    }
+
+    /// Minimizes the DFA via partition refinement. Just like `Dfa::minimize`, the states of an equivalence class are
+    /// merged into the state with the smallest name and the renaming operations are returned.
+    pub fn minimize(&mut self) -> HashMap<String, String> {
+        self.remove_inaccessible_states();
+        let all_input_symbols = self.get_all_input_symbols();
+        let all_states = self.get_all_states().into_iter().sorted().collect_vec();
+        // Initially, states are only distinguished by whether they accept.
+        let mut class_of_state: HashMap<String, usize> = all_states.iter()
+            .map(|state| (state.clone(), if self.accept_states.contains(state) { 1 } else { 0 }))
+            .collect();
+        // Split the classes by the classes their transitions lead into, until no split occurs any more.
+        loop {
+            let mut classes_by_signature: HashMap<(usize, Vec<Option<usize>>), usize> = HashMap::new();
+            let mut new_class_of_state = HashMap::new();
+            for state in &all_states {
+                let signature = all_input_symbols.iter()
+                    .map(|input| self.get_transition(state, input).map(|transition| class_of_state[&transition.next_state]))
+                    .collect_vec();
+                let class_count = classes_by_signature.len();
+                let class = *classes_by_signature.entry((class_of_state[state], signature)).or_insert(class_count);
+                new_class_of_state.insert(state.clone(), class);
+            }
+            let has_split_occurred = new_class_of_state.values().unique().count() > class_of_state.values().unique().count();
+            class_of_state = new_class_of_state;
+            if !has_split_occurred {
+                break;
+            }
+        }
+        // Every state is renamed to the smallest state of its class. States in singleton classes keep their name.
+        let mut renaming_operations = HashMap::new();
+        for class in class_of_state.values().unique() {
+            let members = all_states.iter().filter(|state| class_of_state[*state] == *class).collect_vec();
+            if members.len() <= 1 {
+                continue;
+            }
+            for member in &members {
+                renaming_operations.insert((*member).clone(), members[0].clone());
+            }
+        }
+        let rename = |state: &String| renaming_operations.get(state).unwrap_or(state).clone();
+        let mut transitions: Vec<TokenTransition<T>> = Vec::new();
+        for transition in &self.transitions {
+            let renamed_transition = TokenTransition { state: rename(&transition.state), input: transition.input.clone(), next_state: rename(&transition.next_state) };
+            if !transitions.contains(&renamed_transition) {
+                transitions.push(renamed_transition);
+            }
+        }
+        self.transitions = transitions;
+        self.start_state = rename(&self.start_state);
+        self.accept_states = self.accept_states.iter().map(rename).collect();
+        renaming_operations
+    }
+
+    /// Builds the product automaton of both DFAs. The states of the product are named "(p, q)" after the pair of states
+    /// they stand for. The predicate decides whether a pair is accepting, given whether p and q are accepting.
+    /// For example, `|a, b| a && b` yields the intersection and `|a, b| a || b` the union of both languages.
+    /// For the union, missing transitions are treated as transitions into a rejecting trap state.
+    pub fn product(&self, other: &TokenDfa<T>, is_accepting: impl Fn(bool, bool) -> bool) -> TokenDfa<T> {
+        let mut all_input_symbols = self.get_all_input_symbols();
+        for input in other.get_all_input_symbols() {
+            if !all_input_symbols.contains(&input) {
+                all_input_symbols.push(input);
+            }
+        }
+        let name_of_pair = |pair: &(Option<String>, Option<String>)| {
+            format!("({}, {})", pair.0.as_deref().unwrap_or("∅"), pair.1.as_deref().unwrap_or("∅"))
+        };
+        let start_pair = (Some(self.start_state.clone()), Some(other.start_state.clone()));
+        let mut product = TokenDfa::new(&format!("{} × {}", self.name, other.name), &name_of_pair(&start_pair));
+        let mut visited_pairs = HashSet::new();
+        let mut pairs_to_visit = VecDeque::new();
+        visited_pairs.insert(start_pair.clone());
+        pairs_to_visit.push_back(start_pair);
+        while let Some(pair) = pairs_to_visit.pop_front() {
+            let is_self_accepting = pair.0.as_ref().map_or(false, |state| self.accept_states.contains(state));
+            let is_other_accepting = pair.1.as_ref().map_or(false, |state| other.accept_states.contains(state));
+            if is_accepting(is_self_accepting, is_other_accepting) {
+                product.add_accept_state(&name_of_pair(&pair));
+            }
+            for input in &all_input_symbols {
+                let next_pair = (
+                    pair.0.as_ref().and_then(|state| self.get_transition(state, input)).map(|transition| transition.next_state.clone()),
+                    pair.1.as_ref().and_then(|state| other.get_transition(state, input)).map(|transition| transition.next_state.clone()),
+                );
+                // Once both automata are in their error state, nothing can be accepted any more.
+                if next_pair == (None, None) {
+                    continue;
+                }
+                product.add_transition(&name_of_pair(&pair), input.clone(), &name_of_pair(&next_pair));
+                if visited_pairs.insert(next_pair.clone()) {
+                    pairs_to_visit.push_back(next_pair);
+                }
+            }
+        }
+        product
+    }
+
+    /// Removes all states that cannot be reached from the start state, together with their transitions.
+    fn remove_inaccessible_states(&mut self) {
+        let mut visited_states: HashSet<String> = HashSet::new();
+        let mut states_to_visit = VecDeque::new();
+        states_to_visit.push_back(self.start_state.clone());
+        while let Some(state) = states_to_visit.pop_front() {
+            if !visited_states.insert(state.clone()) {
+                continue;
+            }
+            for transition in self.transitions.iter().filter(|transition| transition.state == state) {
+                states_to_visit.push_back(transition.next_state.clone());
+            }
+        }
+        self.transitions.retain(|transition| visited_states.contains(&transition.state));
+        self.accept_states.retain(|state| visited_states.contains(state));
+    }
+}
+
+#[cfg(test)]
+mod token_dfa_tests {
+    use crate::TokenDfa;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Event {
+        Open,
+        Read,
+        Close,
+    }
+
+    /// Creates a DFA accepting sessions that open a file, read it arbitrarily often and close it.
+    /// The states "reading" and "idle" are equivalent.
+    fn create_example_token_dfa() -> TokenDfa<Event> {
+        let mut dfa = TokenDfa::new("file session", "closed");
+        dfa.add_transition("closed", Event::Open, "idle");
+        dfa.add_transition("idle", Event::Read, "reading");
+        dfa.add_transition("reading", Event::Read, "reading");
+        dfa.add_transition("idle", Event::Close, "done");
+        dfa.add_transition("reading", Event::Close, "done");
+        dfa.add_accept_state("done");
+        dfa
+    }
+
+    #[test]
+    fn test_check() {
+        let dfa = create_example_token_dfa();
+        assert!(dfa.check(&[Event::Open, Event::Read, Event::Read, Event::Close]).0);
+        assert_eq!(dfa.check(&[Event::Open, Event::Close]).1, vec!["closed", "idle", "done"]);
+        assert!(!dfa.check(&[Event::Read]).0);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let mut dfa = create_example_token_dfa();
+        let renaming_operations = dfa.minimize();
+        assert_eq!(dfa.get_all_states().len(), 3);
+        assert_eq!(renaming_operations["reading"], "idle");
+        assert!(dfa.check(&[Event::Open, Event::Read, Event::Close]).0);
+    }
+
+    #[test]
+    fn test_product() {
+        let dfa = create_example_token_dfa();
+        let mut at_most_one_read = TokenDfa::new("at most one read", "r0");
+        at_most_one_read.add_transition("r0", Event::Open, "r0");
+        at_most_one_read.add_transition("r0", Event::Close, "r0");
+        at_most_one_read.add_transition("r0", Event::Read, "r1");
+        at_most_one_read.add_transition("r1", Event::Open, "r1");
+        at_most_one_read.add_transition("r1", Event::Close, "r1");
+        at_most_one_read.add_accept_state("r0");
+        at_most_one_read.add_accept_state("r1");
+        let intersection = dfa.product(&at_most_one_read, |a, b| a && b);
+        assert!(intersection.check(&[Event::Open, Event::Read, Event::Close]).0);
+        assert!(!intersection.check(&[Event::Open, Event::Read, Event::Read, Event::Close]).0);
+        let union = dfa.product(&at_most_one_read, |a, b| a || b);
+        assert!(union.check(&[Event::Read]).0);
+        assert_eq!(union.check(&[Event::Read]).1, vec!["(closed, r0)", "(∅, r1)"]);
+    }
+}
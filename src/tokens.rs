@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{AcceptTag, Dfa, Location, MissingTransitionPolicy};
+
+/// Determines how the tokenizer continues when no token can be recognized at the current position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorRecovery {
+    /// The error is reported and tokenizing continues after the character that could not be recognized.
+    SkipOneChar,
+    /// The error is reported and tokenizing stops.
+    FailFast,
+}
+
+/// A token recognized by the tokenizer. Its kind is the kind of the accept tag of the state the DFA ended up in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: &'a str,
+    /// Byte range of the token within the input.
+    pub span: Range<usize>,
+    pub slice: &'a str,
+    /// Location of the first character of the token.
+    pub location: Location,
+}
+
+/// Reports that no token starts at the given location, where the character `input` is found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub location: Location,
+    pub input: char,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: no token starts with the character {}", self.location, self.input)
+    }
+}
+
+impl std::error::Error for LexError {}
+
+/// Iterator over the tokens of an input, created by `Dfa::tokens`.
+pub struct Tokens<'a> {
+    dfa: &'a Dfa,
+    input: &'a str,
+    location: Location,
+    recovery: ErrorRecovery,
+    alphabet: HashSet<char>,
+    has_failed: bool,
+}
+
+impl Dfa {
+    /// Splits the input into tokens, using the DFA as a lexer whose tagged accept states recognize the token kinds (see `AcceptTag`).
+    /// Tokens are recognized by the maximal munch rule: At every position, the longest non-empty prefix of the remaining input
+    /// that ends in a tagged accept state becomes the next token. Accept states without a tag are ignored.
+    pub fn tokens<'a>(&'a self, input: &'a str, recovery: ErrorRecovery) -> Tokens<'a> {
+        Tokens { dfa: self, input, location: Location::start(), recovery, alphabet: self.get_alphabet(), has_failed: false }
+    }
+}
+
+impl<'a> Tokens<'a> {
+    /// Returns the end of the longest token starting at the current position, together with its tag.
+    fn find_longest_token(&self) -> Option<(usize, &'a AcceptTag)> {
+        let dfa = self.dfa;
+        let mut current_state = &dfa.start_state;
+        let mut longest_token = None;
+        let position = self.location.offset;
+        for (offset, char) in self.input[position..].char_indices() {
+            match dfa.get_transition(current_state, &char) {
+                Some(transition) => current_state = &transition.next_state,
+                None if dfa.missing_transitions == MissingTransitionPolicy::SelfLoop && self.alphabet.contains(&char) => {}
+                None => break,
+            }
+            if let Some(tag) = dfa.accept_tags.get(current_state) {
+                longest_token = Some((position + offset + char.len_utf8(), tag));
+            }
+        }
+        longest_token
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<Token<'a>, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let location = self.location;
+        if self.has_failed || location.offset >= self.input.len() {
+            return None;
+        }
+        match self.find_longest_token() {
+            Some((end, tag)) => {
+                let slice = &self.input[location.offset..end];
+                self.location = location.advance(slice);
+                Some(Ok(Token { kind: &tag.kind, span: location.offset..end, slice, location }))
+            }
+            None => {
+                let input = self.input[location.offset..].chars().next().unwrap();
+                let error = LexError { location, input };
+                match self.recovery {
+                    ErrorRecovery::SkipOneChar => self.location = location.advance(&input.to_string()),
+                    ErrorRecovery::FailFast => self.has_failed = true,
+                }
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tokens_tests {
+    use crate::{AcceptTag, Dfa, ErrorRecovery, LexError, Location, Token};
+
+    /// Creates a lexer for numbers over the digits 0 and 1, the operator "+" and spaces.
+    fn create_example_lexer() -> Dfa {
+        let mut dfa = Dfa::from_table(
+            &["start", "number", "plus", "space"],
+            &['0', '1', '+', ' '],
+            &[&[Some(1), Some(1), Some(2), Some(3)], &[Some(1), Some(1), None, None], &[None, None, None, None], &[None, None, None, Some(3)]],
+            0,
+            &[],
+        ).unwrap();
+        dfa.set_accept_tag("number", AcceptTag::new("number", 0));
+        dfa.set_accept_tag("plus", AcceptTag::new("operator", 0));
+        dfa.set_accept_tag("space", AcceptTag::new("whitespace", 0));
+        dfa
+    }
+
+    #[test]
+    fn test_tokens() {
+        let dfa = create_example_lexer();
+        let tokens: Vec<_> = dfa.tokens("10 + 1", ErrorRecovery::FailFast).collect();
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[0], Ok(Token { kind: "number", span: 0..2, slice: "10", location: Location::start() }));
+        assert_eq!(tokens[2], Ok(Token { kind: "operator", span: 3..4, slice: "+", location: Location { offset: 3, line: 1, column: 4 } }));
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let dfa = create_example_lexer();
+        let skipping: Vec<_> = dfa.tokens("1?+", ErrorRecovery::SkipOneChar).collect();
+        assert_eq!(skipping.len(), 3);
+        assert_eq!(skipping[1], Err(LexError { location: Location { offset: 1, line: 1, column: 2 }, input: '?' }));
+        assert_eq!(skipping[2], Ok(Token { kind: "operator", span: 2..3, slice: "+", location: Location { offset: 2, line: 1, column: 3 } }));
+        let failing: Vec<_> = dfa.tokens("1?+", ErrorRecovery::FailFast).collect();
+        assert_eq!(failing.len(), 2, "should stop after the first error");
+    }
+}
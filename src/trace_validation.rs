@@ -0,0 +1,89 @@
+use std::fmt;
+
+use crate::{Dfa, Location, MissingTransitionError};
+
+/// Describes the first point at which a recorded trace of states diverges from the run of a DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceError {
+    /// The trace does not begin with the start state.
+    WrongStartState { expected: String, actual: String },
+    /// After reading the symbol at the location, the DFA switches to the expected state, but the trace records another one.
+    UnexpectedState { location: Location, input: char, expected: String, actual: String },
+    /// The trace continues, although the DFA has no transition for the next symbol.
+    MissingTransition(MissingTransitionError),
+    /// The trace ends before the whole input is read.
+    TooShort { expected_length: usize, actual_length: usize },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::WrongStartState { expected, actual } => write!(f, "the trace starts with {} instead of the start state {}", actual, expected),
+            TraceError::UnexpectedState { location, input, expected, actual } => {
+                write!(f, "{}: after reading {}, the trace records {} instead of {}", location, input, actual, expected)
+            }
+            TraceError::MissingTransition(error) => write!(f, "the trace continues although {}", error),
+            TraceError::TooShort { expected_length, actual_length } => write!(f, "the trace records {} states instead of {}", actual_length, expected_length),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl Dfa {
+    /// Checks that the states, e.g. logged by an external implementation, are a legal run of the DFA on the input, starting with
+    /// the start state and recording one state per symbol. Missing transitions are interpreted according to the `MissingTransitionPolicy`,
+    /// so a legal trace ends early where the DFA has no transition. Reports the first point at which the trace diverges.
+    pub fn validate_trace(&self, states: &[&str], input: &str) -> Result<(), TraceError> {
+        let (_, expected_states) = self.check(input);
+        let symbols: Vec<(usize, char)> = input.char_indices().collect();
+        for (index, (expected, actual)) in expected_states.iter().zip(states).enumerate() {
+            if expected == actual {
+                continue;
+            }
+            if index == 0 {
+                return Err(TraceError::WrongStartState { expected: expected.clone(), actual: actual.to_string() });
+            }
+            let (offset, symbol) = symbols[index - 1];
+            return Err(TraceError::UnexpectedState {
+                location: Location::in_text(input, offset),
+                input: symbol,
+                expected: expected.clone(),
+                actual: actual.to_string(),
+            });
+        }
+        if states.len() > expected_states.len() {
+            // The trace records more states than the DFA traversed, so the DFA stopped at a missing transition.
+            let (offset, symbol) = symbols[expected_states.len() - 1];
+            return Err(TraceError::MissingTransition(MissingTransitionError {
+                state: expected_states.last().unwrap().clone(),
+                input: symbol,
+                location: Location::in_text(input, offset),
+            }));
+        }
+        if states.len() < expected_states.len() {
+            return Err(TraceError::TooShort { expected_length: expected_states.len(), actual_length: states.len() });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod trace_validation_tests {
+    use crate::{Dfa, Location, TraceError};
+
+    #[test]
+    fn test_validate_trace() {
+        // Accepts words over 'a' and 'b' with an even number of 'a', without transitions for other symbols.
+        let dfa = Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(0)], &[Some(0), Some(1)]], 0, &[0]).unwrap();
+        assert_eq!(dfa.validate_trace(&["even", "odd", "odd", "even"], "aba"), Ok(()));
+        assert_eq!(dfa.validate_trace(&["odd"], ""), Err(TraceError::WrongStartState { expected: "even".to_string(), actual: "odd".to_string() }));
+        assert_eq!(
+            dfa.validate_trace(&["even", "odd", "even", "odd"], "ab\na"),
+            Err(TraceError::UnexpectedState { location: Location { offset: 1, line: 1, column: 2 }, input: 'b', expected: "odd".to_string(), actual: "even".to_string() }),
+        );
+        let error = dfa.validate_trace(&["even", "odd", "odd"], "ac").unwrap_err();
+        assert_eq!(error.to_string(), "the trace continues although 1:2: the state odd has no transition for input c");
+        assert_eq!(dfa.validate_trace(&["even", "odd"], "ab"), Err(TraceError::TooShort { expected_length: 3, actual_length: 2 }));
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bounded, Fuel};
+
+/// The direction in which the head of a Turing machine moves after writing.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum TapeMove {
+    Left,
+    Right,
+    Stay,
+}
+
+/// Describes that a Turing machine in `state` reading `read` under its head writes `write`, moves the head and switches to `next_state`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+pub struct TuringTransition {
+    state: String,
+    read: char,
+    write: char,
+    movement: TapeMove,
+    next_state: String,
+}
+
+/// How a bounded run of a Turing machine ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TuringOutcome {
+    /// The machine reached an accept state.
+    Accept,
+    /// The machine reached a reject state or had no transition for the current state and symbol.
+    Reject,
+    /// The machine did not halt within the maximum number of steps, which cannot be avoided in general, as halting is undecidable.
+    /// This is the outcome of `run` when the fuel of `run_with_fuel` would be exhausted.
+    Timeout,
+}
+
+/// The configuration of a Turing machine after some step, consisting of its state, the tape and the position of the head on the tape.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TapeSnapshot {
+    pub state: String,
+    /// The part of the tape the machine has visited or that holds the input, which may include blanks.
+    pub tape: String,
+    /// The index of the symbol under the head within `tape`, counted in characters.
+    pub head: usize,
+}
+
+impl fmt::Display for TapeSnapshot {
+    /// Renders the configuration like textbooks do, with the state in brackets in front of the symbol under the head, like "ab[q1]c".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before_head: String = self.tape.chars().take(self.head).collect();
+        let from_head: String = self.tape.chars().skip(self.head).collect();
+        write!(f, "{}[{}]{}", before_head, self.state, from_head)
+    }
+}
+
+/// # [Turing machine](https://en.wikipedia.org/wiki/Turing_machine)
+/// A deterministic machine with a tape that is unbounded in both directions and initially holds the input, followed and preceded by blanks.
+/// The head starts on the first symbol of the input. In every step, the machine writes a symbol under the head, moves the head
+/// and switches its state according to its transitions, until it reaches an accept or reject state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuringMachine {
+    name: String,
+    start_state: String,
+    blank: char,
+    accept_states: HashSet<String>,
+    reject_states: HashSet<String>,
+    transitions: Vec<TuringTransition>,
+}
+
+impl TuringMachine {
+    /// Creates a Turing machine without transitions, accept and reject states, using the blank symbol for empty cells of the tape.
+    pub fn new(name: &str, start_state: &str, blank: char) -> TuringMachine {
+        TuringMachine {
+            name: name.to_string(),
+            start_state: start_state.to_string(),
+            blank,
+            accept_states: HashSet::new(),
+            reject_states: HashSet::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn add_accept_state(&mut self, state: &str) {
+        self.accept_states.insert(state.to_string());
+    }
+
+    pub fn add_reject_state(&mut self, state: &str) {
+        self.reject_states.insert(state.to_string());
+    }
+
+    pub fn add_transition(&mut self, state: &str, read: char, write: char, movement: TapeMove, next_state: &str) {
+        self.transitions.push(TuringTransition {
+            state: state.to_string(),
+            read,
+            write,
+            movement,
+            next_state: next_state.to_string(),
+        });
+    }
+
+    /// Tries to find the transition for the state and the symbol under the head. If there is none, the machine rejects.
+    pub fn get_transition(&self, state: &str, read: char) -> Option<&TuringTransition> {
+        self.transitions.iter().find(|transition| transition.state == state && transition.read == read)
+    }
+
+    /// Runs the machine on the input for at most `max_steps` steps. Returns how the run ended
+    /// and the configurations after every step, starting with the initial one.
+    pub fn run(&self, input: &str, max_steps: usize) -> (TuringOutcome, Vec<TapeSnapshot>) {
+        match self.simulate(input, &mut Fuel::new(max_steps)) {
+            (Some(true), history) => (TuringOutcome::Accept, history),
+            (Some(false), history) => (TuringOutcome::Reject, history),
+            (None, history) => (TuringOutcome::Timeout, history),
+        }
+    }
+
+    /// Runs the machine like `run`, but takes at most as many steps as there is fuel. Returns whether the machine accepts
+    /// together with the configurations after every step, or `Bounded::Exhausted` if the fuel ran out before the machine halted.
+    pub fn run_with_fuel(&self, input: &str, fuel: &mut Fuel) -> Bounded<(bool, Vec<TapeSnapshot>)> {
+        match self.simulate(input, fuel) {
+            (Some(is_accepted), history) => Bounded::Done((is_accepted, history)),
+            (None, _) => Bounded::Exhausted,
+        }
+    }
+
+    /// Renders the configurations of the run on the input in the [DOT language](https://graphviz.org/doc/info/lang.html) of Graphviz.
+    /// Configurations are labeled like "ab[q1]c" (see `TapeSnapshot`), and edges with the symbol read, the symbol written
+    /// and the move of their transition, like "a → _, R". As the machine is deterministic, the graph is a single path,
+    /// which only closes into a cycle if a configuration repeats. If the machine accepts, the run is highlighted in red.
+    /// The run stops when the fuel runs out, so the graph may be partial.
+    pub fn configuration_graph_to_dot(&self, input: &str, fuel: &mut Fuel) -> String {
+        let (is_accepted, history) = self.simulate(input, fuel);
+        let is_accepted = is_accepted == Some(true);
+        let color = if is_accepted { ", color=red" } else { "" };
+        let mut indices: HashMap<&TapeSnapshot, usize> = HashMap::new();
+        let mut dot = format!("digraph \"{}\" {{\n    node [shape=box];\n", self.name.replace('"', "\\\""));
+        for (step, snapshot) in history.iter().enumerate() {
+            if indices.contains_key(snapshot) {
+                continue;
+            }
+            indices.insert(snapshot, indices.len());
+            let peripheries = if is_accepted && step == history.len() - 1 { ", peripheries=2" } else { "" };
+            dot += &format!("    c{} [label=\"{}\"{}{}];\n", indices[snapshot], snapshot.to_string().replace('"', "\\\""), color, peripheries);
+        }
+        let mut edges = HashSet::new();
+        for (snapshot, next_snapshot) in history.iter().zip(history.iter().skip(1)) {
+            if !edges.insert((indices[snapshot], indices[next_snapshot])) {
+                continue;
+            }
+            let read = snapshot.tape.chars().nth(snapshot.head).unwrap_or(self.blank);
+            let label = match self.get_transition(&snapshot.state, read) {
+                Some(transition) => {
+                    let movement = match transition.movement {
+                        TapeMove::Left => 'L',
+                        TapeMove::Right => 'R',
+                        TapeMove::Stay => 'S',
+                    };
+                    format!("{} → {}, {}", read, transition.write, movement)
+                }
+                None => String::new(),
+            };
+            dot += &format!("    c{} -> c{} [label=\"{}\"{}];\n", indices[snapshot], indices[next_snapshot], label.replace('"', "\\\""), color);
+        }
+        dot + "}\n"
+    }
+
+    /// Runs the machine until it halts or the fuel runs out. Returns whether it accepts, or None if the fuel ran out, together with the configurations.
+    fn simulate(&self, input: &str, fuel: &mut Fuel) -> (Option<bool>, Vec<TapeSnapshot>) {
+        let mut tape: Vec<char> = input.chars().collect();
+        if tape.is_empty() {
+            tape.push(self.blank);
+        }
+        let mut head = 0;
+        let mut state = self.start_state.as_str();
+        let snapshot = |state: &str, tape: &[char], head: usize| TapeSnapshot { state: state.to_string(), tape: tape.iter().collect(), head };
+        let mut history = vec![snapshot(state, &tape, head)];
+        loop {
+            if self.accept_states.contains(state) {
+                return (Some(true), history);
+            }
+            if self.reject_states.contains(state) {
+                return (Some(false), history);
+            }
+            let transition = match self.get_transition(state, tape[head]) {
+                Some(transition) => transition,
+                None => return (Some(false), history),
+            };
+            if !fuel.consume() {
+                return (None, history);
+            }
+            tape[head] = transition.write;
+            match transition.movement {
+                // The tape grows by a blank whenever the head leaves the part visited so far.
+                TapeMove::Left if head == 0 => tape.insert(0, self.blank),
+                TapeMove::Left => head -= 1,
+                TapeMove::Right => {
+                    head += 1;
+                    if head == tape.len() {
+                        tape.push(self.blank);
+                    }
+                }
+                TapeMove::Stay => {}
+            }
+            state = &transition.next_state;
+            history.push(snapshot(state, &tape, head));
+        }
+    }
+}
+
+#[cfg(test)]
+mod turing_machine_tests {
+    use crate::{Bounded, Fuel, TapeMove, TuringMachine, TuringOutcome};
+
+    #[test]
+    fn test_run() {
+        // Accepts the words a^n b^n by repeatedly crossing off the first 'a' and the last 'b'.
+        let mut machine = TuringMachine::new("anbn", "start", '_');
+        machine.add_transition("start", '_', '_', TapeMove::Stay, "accept");
+        machine.add_transition("start", 'a', '_', TapeMove::Right, "right");
+        machine.add_transition("right", 'a', 'a', TapeMove::Right, "right");
+        machine.add_transition("right", 'b', 'b', TapeMove::Right, "right");
+        machine.add_transition("right", '_', '_', TapeMove::Left, "last");
+        machine.add_transition("last", 'b', '_', TapeMove::Left, "left");
+        machine.add_transition("left", 'a', 'a', TapeMove::Left, "left");
+        machine.add_transition("left", 'b', 'b', TapeMove::Left, "left");
+        machine.add_transition("left", '_', '_', TapeMove::Right, "start");
+        machine.add_accept_state("accept");
+        let (outcome, history) = machine.run("ab", 100);
+        assert_eq!(outcome, TuringOutcome::Accept);
+        let history = history.iter().map(|snapshot| snapshot.to_string()).collect::<Vec<_>>();
+        assert_eq!(history, vec!["[start]ab", "_[right]b", "_b[right]_", "_[last]b_", "[left]___", "_[start]__", "_[accept]__"]);
+        assert_eq!(machine.run("aab", 100).0, TuringOutcome::Reject);
+        assert_eq!(machine.run("", 100).0, TuringOutcome::Accept);
+        assert_eq!(machine.run("aabb", 5).0, TuringOutcome::Timeout);
+        assert_eq!(machine.run_with_fuel("aabb", &mut Fuel::new(5)), Bounded::Exhausted);
+        assert_eq!(machine.run_with_fuel("ab", &mut Fuel::new(6)), Bounded::Done((true, machine.run("ab", 6).1)));
+        let dot = machine.configuration_graph_to_dot("ab", &mut Fuel::unlimited());
+        assert!(dot.contains("    c0 [label=\"[start]ab\", color=red];\n"));
+        assert!(dot.contains("    c0 -> c1 [label=\"a → _, R\", color=red];\n"));
+        assert!(dot.contains("    c6 [label=\"_[accept]__\", color=red, peripheries=2];\n"));
+        let dot = machine.configuration_graph_to_dot("aab", &mut Fuel::unlimited());
+        assert!(dot.contains("    c0 [label=\"[start]aab\"];\n"), "aab is rejected, so the run is not highlighted");
+    }
+}
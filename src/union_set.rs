@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::matcher::CompiledDfa;
+use crate::Dfa;
+
+/// The part of the product of all members that has been explored so far. A joint state consists of the current state
+/// of every member, or None for a member that is already in its error state.
+#[derive(Debug, Default)]
+struct JointStates {
+    states: Vec<Vec<Option<usize>>>,
+    indices: HashMap<Vec<Option<usize>>, usize>,
+    transitions: HashMap<(usize, char), usize>,
+}
+
+impl JointStates {
+    fn get_index(&mut self, state: Vec<Option<usize>>) -> usize {
+        if let Some(index) = self.indices.get(&state) {
+            return *index;
+        }
+        self.states.push(state.clone());
+        self.indices.insert(state, self.states.len() - 1);
+        self.states.len() - 1
+    }
+}
+
+/// The union of many DFAs, which accepts a word if at least one member accepts it.
+///
+/// Building the product of all members eagerly can result in a huge DFA. Instead, the members are run jointly, and the joint states
+/// and transitions that inputs actually lead through are cached, which amounts to a lazy product construction.
+/// Repeated inputs with similar prefixes therefore get faster over time. The cache is protected by a lock, so the set can be shared between threads.
+#[derive(Debug, Default)]
+pub struct UnionSet {
+    members: Vec<CompiledDfa>,
+    joint_states: Mutex<JointStates>,
+}
+
+impl UnionSet {
+    pub fn new() -> UnionSet {
+        UnionSet::default()
+    }
+
+    /// Adds a member to the union. This clears the cache.
+    pub fn add_member(&mut self, dfa: &Dfa) {
+        self.members.push(dfa.compile_table());
+        self.joint_states = Mutex::new(JointStates::default());
+    }
+
+    pub fn get_member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns the number of joint states that have been explored and cached so far.
+    pub fn get_cached_state_count(&self) -> usize {
+        self.joint_states.lock().unwrap().states.len()
+    }
+
+    /// Checks whether at least one member accepts the input.
+    pub fn contains(&self, input: &str) -> bool {
+        !self.get_accepting_members(input).is_empty()
+    }
+
+    /// Returns the indices of the members accepting the input, in the order in which they were added.
+    pub fn get_accepting_members(&self, input: &str) -> Vec<usize> {
+        let mut joint_states = self.joint_states.lock().unwrap();
+        let start = self.members.iter().map(|member| Some(member.start_state)).collect();
+        let mut index = joint_states.get_index(start);
+        for char in input.chars() {
+            index = match joint_states.transitions.get(&(index, char)) {
+                Some(next_index) => *next_index,
+                None => {
+                    let next_state = joint_states.states[index].iter()
+                        .zip(&self.members)
+                        .map(|(state, member)| state.and_then(|state| member.transitions[state].get(&char).cloned()))
+                        .collect();
+                    let next_index = joint_states.get_index(next_state);
+                    joint_states.transitions.insert((index, char), next_index);
+                    next_index
+                }
+            };
+        }
+        joint_states.states[index].iter()
+            .zip(&self.members)
+            .enumerate()
+            .filter(|(_, (state, member))| state.map_or(false, |state| member.is_accepting[state]))
+            .map(|(member_index, _)| member_index)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod union_set_tests {
+    use crate::{Dfa, UnionSet};
+
+    #[test]
+    fn test_contains() {
+        let mut union_set = UnionSet::new();
+        // Accepts words over 'a' and 'b' of even length.
+        union_set.add_member(&Dfa::from_table(&["even", "odd"], &['a', 'b'], &[&[Some(1), Some(1)], &[Some(0), Some(0)]], 0, &[0]).unwrap());
+        // Accepts words over 'a' and 'b' ending with 'b'.
+        union_set.add_member(&Dfa::from_table(&["q0", "q1"], &['a', 'b'], &[&[Some(0), Some(1)], &[Some(0), Some(1)]], 0, &[1]).unwrap());
+        assert_eq!(union_set.get_member_count(), 2);
+        assert!(union_set.contains("ab"));
+        assert!(union_set.contains("aab"));
+        assert!(!union_set.contains("a"));
+        assert_eq!(union_set.get_accepting_members("ab"), vec![0, 1]);
+        assert_eq!(union_set.get_accepting_members("aa"), vec![0]);
+        assert!(union_set.get_cached_state_count() <= 4, "at most the four joint states of the product are explored");
+    }
+}